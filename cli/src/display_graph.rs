@@ -252,6 +252,8 @@ impl<'a> DisplayGraph<'a> {
             SomeGraphDef::Onnx(onnx, _) => self.with_onnx_model(onnx),
             #[cfg(feature = "tf")]
             SomeGraphDef::Tf(tf) => self.with_tf_graph_def(tf),
+            #[cfg(feature = "tflite")]
+            SomeGraphDef::Tflite(_) => Ok(self),
         }
     }
 
@@ -340,7 +342,7 @@ impl<'a> DisplayGraph<'a> {
             if let Ok(id) = self.model.borrow().node_id_by_name(&*node_name) {
                 let mut v = vec![];
                 for a in gnode.attribute.iter() {
-                    let value = if let Some(t) =  &a.t {
+                    let value = if let Some(t) = &a.t {
                         format!("{:?}", Tensor::try_from(t)?)
                     } else {
                         format!("{:?}", a)
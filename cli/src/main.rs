@@ -22,10 +22,10 @@ use itertools::Itertools;
 use std::process;
 use std::str::FromStr;
 
-#[cfg(feature = "tf")]
-use tract_tensorflow::tfpb::tensorflow::GraphDef;
 use tract_core::internal::*;
 use tract_core::model::{NormalizedModel, TypedModel};
+#[cfg(feature = "tf")]
+use tract_tensorflow::tfpb::tensorflow::GraphDef;
 
 use crate::display_graph::DisplayOptions;
 use crate::errors::*;
@@ -42,6 +42,7 @@ mod profile;
 mod run;
 mod rusage;
 mod stream_check;
+mod stream_info;
 mod tensor;
 mod utils;
 
@@ -74,7 +75,7 @@ fn main() {
         (@arg model: +takes_value "Sets the model to use")
 
         (@arg format: -f +takes_value
-            "Hint the model format ('kaldi', 'onnx' or 'tf') instead of guess from extension.")
+            "Hint the model format ('kaldi', 'onnx', 'tf' or 'tflite') instead of guess from extension.")
 
         (@arg input: -i --input +takes_value +multiple number_of_values(1)
             "Set input shape and type (@file.pb or @file.npz:thing.npy or 3x4xi32).")
@@ -91,6 +92,18 @@ fn main() {
         (@arg kaldi_adjust_final_offset: --("kaldi-adjust-final-offset") +takes_value
             "Adjust value of final offset in network (for reproducibility)")
 
+        (@arg kaldi_subtract_priors: --("kaldi-subtract-priors")
+            "Subtract the log priors of an acoustic model (.mdl) from its output")
+
+        (@arg kaldi_input_rate: --("kaldi-input-rate") +takes_value +multiple number_of_values(1)
+            "Rate of a Kaldi input node: name=constant, or name=period for a subsampled input")
+
+        (@arg saved_model_tag: --("saved-model-tag") +takes_value +multiple number_of_values(1)
+            "Tag of the MetaGraph to load from a TensorFlow SavedModel directory (default: serve)")
+
+        (@arg saved_model_signature: --("saved-model-signature") +takes_value
+            "Signature to load from a TensorFlow SavedModel directory (default: serving_default)")
+
         (@arg kaldi_downsample: --("kaldi-downsample") +takes_value
             "Add a subsampling to output on axis 0")
 
@@ -266,6 +279,27 @@ fn main() {
         .long_about("Compare output of streamed and regular exec");
     app = app.subcommand(output_options(stream_check));
 
+    let stream_info = clap::SubCommand::with_name("stream-info")
+        .long_about("Show delay, latency, receptive field and buffered memory of a pulsed network")
+        .arg(
+            Arg::with_name("assert-max-delay")
+                .takes_value(true)
+                .long("assert-max-delay")
+                .help("Checks outputs delay does not exceed this (in frames of each output)"),
+        )
+        .arg(
+            Arg::with_name("assert-max-latency").takes_value(true).long("assert-max-latency").help(
+                "Checks outputs latency does not exceed this (in frames of the S-rate inputs)",
+            ),
+        )
+        .arg(
+            Arg::with_name("assert-max-buffer")
+                .takes_value(true)
+                .long("assert-max-buffer")
+                .help("Checks delay buffers do not exceed this (in bytes)"),
+        );
+    app = app.subcommand(output_options(stream_info));
+
     let matches = app.get_matches();
 
     if ::std::env::var("RUST_LOG").is_err() {
@@ -340,6 +374,8 @@ pub enum SomeGraphDef {
     Tf(GraphDef),
     #[cfg(feature = "onnx")]
     Onnx(tract_onnx::pb::ModelProto, tract_onnx::model::ParseResult),
+    #[cfg(feature = "tflite")]
+    Tflite(tract_tflite::TfliteProtoModel),
 }
 
 /// Structure holding the parsed parameters.
@@ -371,6 +407,8 @@ impl Parameters {
         let name = matches.value_of("model").ok_or("Model argument required")?;
         let format = matches.value_of("format").unwrap_or(if name.ends_with(".onnx") {
             "onnx"
+        } else if name.ends_with(".tflite") {
+            "tflite"
         } else {
             "tf"
         });
@@ -383,6 +421,26 @@ impl Parameters {
                 if let Some(i) = matches.value_of("kaldi_adjust_final_offset") {
                     graph.adjust_final_offset = i.parse()?;
                 }
+                if matches.is_present("kaldi_subtract_priors") {
+                    if graph.priors.is_none() {
+                        bail!("--kaldi-subtract-priors requires a model with priors")
+                    }
+                    graph.subtract_priors = true;
+                }
+                if let Some(rates) = matches.values_of("kaldi_input_rate") {
+                    for rate in rates {
+                        let mut tokens = rate.splitn(2, '=');
+                        let name = tokens.next().unwrap();
+                        let rate = match tokens.next() {
+                            Some("constant") => tract_kaldi::model::InputRate::Constant,
+                            Some(period) => {
+                                tract_kaldi::model::InputRate::Subsampled(period.parse()?)
+                            }
+                            None => bail!("Expected name=rate in --kaldi-input-rate: {}", rate),
+                        };
+                        graph.input_rates.insert(name.to_string(), rate);
+                    }
+                }
                 let parsed = kaldi.model_for_proto_model(&graph)?;
                 (SomeGraphDef::Kaldi(graph), parsed)
             }
@@ -399,12 +457,35 @@ impl Parameters {
             "tf" => {
                 let tf = tract_tensorflow::tensorflow();
                 info_usage("load framework (tf)");
-                let mut graph = tf.proto_model_for_path(&name)?;
-                if matches.is_present("determinize") {
-                    tract_tensorflow::Tensorflow::determinize(&mut graph)?;
+                if std::path::Path::new(&name).is_dir() {
+                    let tags: Vec<&str> = matches
+                        .values_of("saved_model_tag")
+                        .map(|tags| tags.collect())
+                        .unwrap_or(vec!["serve"]);
+                    let signature =
+                        matches.value_of("saved_model_signature").unwrap_or("serving_default");
+                    let mut frozen = tf.read_saved_model_dir(&name, &tags, signature)?;
+                    if matches.is_present("determinize") {
+                        tract_tensorflow::Tensorflow::determinize(&mut frozen.graph)?;
+                    }
+                    let tract = tf.model_for_frozen_signature(&frozen)?;
+                    (SomeGraphDef::Tf(frozen.graph), tract)
+                } else {
+                    let mut graph = tf.proto_model_for_path(&name)?;
+                    if matches.is_present("determinize") {
+                        tract_tensorflow::Tensorflow::determinize(&mut graph)?;
+                    }
+                    let tract = tf.model_for_proto_model(&graph)?;
+                    (SomeGraphDef::Tf(graph), tract)
                 }
-                let tract = tf.model_for_proto_model(&graph)?;
-                (SomeGraphDef::Tf(graph), tract)
+            }
+            #[cfg(feature = "tflite")]
+            "tflite" => {
+                let tflite = tract_tflite::tflite();
+                info_usage("load framework (tflite)");
+                let graph = tflite.proto_model_for_path(&name)?;
+                let tract = tflite.model_for_proto_model(&graph)?;
+                (SomeGraphDef::Tflite(graph), tract)
             }
             _ => bail!(
                 "Format {} not supported. You may need to recompile tract with the right features.",
@@ -580,11 +661,16 @@ impl Parameters {
 
         let pulse: Option<usize> = matches.value_of("pulse").map(|s| s.parse()).transpose()?;
         let mut typed_model = None;
-        let normalized_model: Option<NormalizedModel> = None;
+        let mut normalized_model: Option<NormalizedModel> = None;
 
         let tract_model: Box<dyn Model> = {
             let stop_at = matches.value_of("pass").unwrap_or(if matches.is_present("optimize") {
                 "optimize"
+            } else if pulse.is_some()
+                && (matches.subcommand_name() == Some("stream-info")
+                    || matches.subcommand_name() == Some("stream-check"))
+            {
+                "pulse"
             } else if pulse.is_some() {
                 "pulse-declutter"
             } else {
@@ -626,13 +712,14 @@ impl Parameters {
                 info_usage("after declutter");
                 if let Some(pulse) = pulse {
                     info!("Running 'pulse-normalize'");
-                    let normalized_model = model.clone().into_normalized()?;
+                    let normalized = model.clone().into_normalized()?;
+                    normalized_model = Some(normalized.clone());
                     if stop_at == "pulse-normalize" {
-                        return Ok(Box::new(normalized_model) as _);
+                        return Ok(Box::new(normalized) as _);
                     }
                     info_usage("after pulse-normalize");
                     info!("Running 'pulse' ({})", pulse);
-                    let pulsed = ::tract_core::pulse::PulsedModel::new(&normalized_model, pulse)?;
+                    let pulsed = ::tract_core::pulse::PulsedModel::new(&normalized, pulse)?;
                     if stop_at == "pulse" {
                         return Ok(Box::new(pulsed) as _);
                     }
@@ -765,10 +852,29 @@ fn handle(matches: clap::ArgMatches) -> CliResult<()> {
         #[cfg(feature = "onnx")]
         {
             let onnx = tract_onnx::onnx();
-            let names = onnx.op_register.0.keys().sorted().into_iter().join(", ");
-            println!("Onnx:\n");
-            println!("{}", names);
-            println!("\n");
+            let ops = onnx.op_register.ops();
+            for (domain, ops) in &ops.iter().group_by(|op| op.0) {
+                let names = ops
+                    .map(|(_, op, versions)| {
+                        let versions = versions
+                            .iter()
+                            .map(|(since, until)| match until {
+                                Some(until) if until == since => format!("{}", since),
+                                Some(until) => format!("{}-{}", since, until),
+                                None => format!("{}+", since),
+                            })
+                            .join(",");
+                        format!("{} ({})", op, versions)
+                    })
+                    .join(", ");
+                if domain.is_empty() {
+                    println!("Onnx:\n");
+                } else {
+                    println!("Onnx ({}):\n", domain);
+                }
+                println!("{}", names);
+                println!("\n");
+            }
         }
         #[cfg(feature = "tf")]
         {
@@ -822,6 +928,10 @@ fn handle(matches: clap::ArgMatches) -> CliResult<()> {
             stream_check::handle(params, display_options_from_clap(&matches, m)?)
         }
 
+        ("stream-info", Some(m)) => {
+            stream_info::handle(params, display_options_from_clap(&matches, m)?, m)
+        }
+
         ("cost", Some(m)) => {
             crate::cost::handle(params, display_options_from_clap(&matches, m)?, m)
         }
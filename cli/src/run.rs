@@ -46,52 +46,51 @@ fn run_regular(tract: &dyn Model, params: &Parameters) -> CliResult<TVec<Arc<Ten
 }
 
 fn run_pulse_t(model: &PulsedModel, params: &Parameters) -> CliResult<TVec<Arc<Tensor>>> {
-    let input_fact = model.input_fact(0)?;
-    let output_fact = model.output_fact(0)?;
+    let input_facts = model
+        .input_outlets()?
+        .iter()
+        .map(|o| Ok(model.outlet_fact(*o)?.clone()))
+        .collect::<TractResult<TVec<_>>>()?;
+    let inputs = (0..input_facts.len())
+        .map(|ix| {
+            let value = params.input_values.get(ix).and_then(|v| v.as_ref());
+            let value = value.ok_or_else(|| format!("Input #{} value is required", ix))?;
+            Ok(value.to_array_view::<f32>()?)
+        })
+        .collect::<CliResult<Vec<_>>>()?;
 
-    let output_pulse = output_fact.pulse();
-    //    println!("output_fact: {:?}", output_fact);
-    let axis = input_fact.axis;
-    let input: &Tensor = &params.input_values[0].as_ref().unwrap();
-    //    println!("input_shape: {:?}", input.shape());
-    let input_dim = input.shape()[axis];
-    //    println!("output_fact: {:?}", output_fact);
-    let output_dim = output_fact.dim.eval(input_dim as i32).unwrap() as i32;
-    let mut output_shape = output_fact.shape.to_vec();
-    output_shape[output_fact.axis] =
-        output_dim as usize + output_fact.delay + 4 * output_fact.pulse();
     let plan = SimplePlan::new(model)?;
-    let mut state = ::tract_core::plan::SimpleState::new(&plan)?;
-    //    println!("output_shape: {:?}", output_shape);
-    let pulse = input_fact.pulse();
-    let mut result = ndarray::ArrayD::<f32>::default(output_shape);
-    let input = input.to_array_view::<f32>()?;
-    for ix in 0..input_dim.div_ceil(pulse) {
-        let chunk = input.slice_axis(ndarray::Axis(axis), (ix * pulse..(ix + 1) * pulse).into());
-        let input = if chunk.shape()[input_fact.axis] < pulse {
-            let mut chunk_shape = chunk.shape().to_vec();
-            chunk_shape[input_fact.axis] = pulse;
-            let mut padded_chunk = ndarray::ArrayD::<f32>::default(chunk_shape);
-            padded_chunk
-                .slice_axis_mut(
-                    ndarray::Axis(input_fact.axis),
-                    (..chunk.shape()[input_fact.axis]).into(),
-                )
-                .assign(&chunk);
-            padded_chunk
-        } else {
-            chunk.to_owned()
-        };
-        let outputs = state.run(tvec!(input.into()))?;
-        let result_chunk = outputs[0].to_array_view::<f32>()?;
-        result
-            .slice_axis_mut(
-                ndarray::Axis(output_fact.axis),
-                ((output_pulse * ix)..(output_pulse * (ix + 1))).into(),
-            )
-            .assign(&result_chunk);
+    let mut session = ::tract_core::pulse::StreamingSession::new(&plan)?;
+    let chunk = |i: usize, last: bool| -> TVec<Tensor> {
+        inputs
+            .iter()
+            .zip(input_facts.iter())
+            .map(|(input, fact)| {
+                let start = (i * fact.pulse()).min(input.shape()[fact.axis]);
+                let end = if last { input.shape()[fact.axis] } else { start + fact.pulse() };
+                input.slice_axis(ndarray::Axis(fact.axis), (start..end).into()).to_owned().into()
+            })
+            .collect()
+    };
+    // the first input of length S paces the stream
+    let (reference, fact) = inputs
+        .iter()
+        .zip(input_facts.iter())
+        .find(|(_, fact)| fact.dim == TDim::s())
+        .ok_or("Expected an input of length S")?;
+    let pulses = reference.shape()[fact.axis] / fact.pulse();
+    let mut chunks: Vec<TVec<Tensor>> = vec![];
+    for i in 0..pulses {
+        chunks.push(session.push_inputs(chunk(i, false))?);
+    }
+    chunks.push(session.finish_inputs(chunk(pulses, true))?);
+
+    let mut outputs = tvec!();
+    for (ix, output) in model.output_outlets()?.iter().enumerate() {
+        let output_axis = model.outlet_fact(*output)?.axis;
+        let views =
+            chunks.iter().map(|c| c[ix].to_array_view::<f32>()).collect::<TractResult<Vec<_>>>()?;
+        outputs.push(ndarray::stack(ndarray::Axis(output_axis), &views)?.into_arc_tensor());
     }
-    result.slice_axis_inplace(ndarray::Axis(output_fact.axis), (output_fact.delay..).into());
-    result.slice_axis_inplace(ndarray::Axis(output_fact.axis), (..output_dim as usize).into());
-    Ok(tvec!(result.into_arc_tensor()))
+    Ok(outputs)
 }
@@ -1,11 +1,12 @@
 use std::sync::Arc;
 
 use itertools::Itertools;
-use tract_core::ndarray::{ArrayD, Axis};
+use tract_core::ndarray::{ArrayD, ArrayViewD, Axis};
 
+use tract_core::internal::*;
 use tract_core::model::{Fact, OutletId};
-use tract_core::plan::{SimplePlan, SimpleState};
-use tract_core::pulse::PulsedModel;
+use tract_core::plan::SimplePlan;
+use tract_core::pulse::{PulsedModel, StreamingSession};
 
 use crate::display_graph;
 use crate::{CliResult, Parameters};
@@ -15,10 +16,6 @@ pub fn handle(params: Parameters, options: display_graph::DisplayOptions) -> Cli
 
     let fixed = params.normalized_model.clone().unwrap();
 
-    let fixed_input_fact = fixed.input_fact(0)?;
-    let pulsed_input_fact = pulsed.input_fact(0)?;
-    let input_pulse = pulsed_input_fact.pulse();
-
     let display_graph = display_graph::DisplayGraph::from_model_and_options(
         &*params.tract_model,
         Arc::new(options),
@@ -39,92 +36,82 @@ pub fn handle(params: Parameters, options: display_graph::DisplayOptions) -> Cli
 
             let mut pulsed = pulsed.clone();
             pulsed.set_output_outlets(&[pulsed_outlet])?;
-
-            let pulsed_output_fact = pulsed.output_fact(0)?;
-            let output_pulse = pulsed_output_fact.pulse();
+            let pulsed_output_fact = pulsed.output_fact(0)?.clone();
             let output_axis = pulsed_output_fact.axis;
-            let delay = pulsed_output_fact.delay;
-
-            let stream_dim = delay + 3 * input_pulse + input_pulse / 2;
 
-            let fixed_input = crate::tensor::tensor_for_fact(
-                &fixed_input_fact.to_tensor_fact(),
-                Some(stream_dim),
-            )?;
+            let plan = SimplePlan::new(&pulsed)?;
+            let mut session = StreamingSession::new(&plan)?;
+            // frames past the end of the inputs must not leak into the output
+            session.set_filler(tensor0(std::f32::NAN))?;
+            let pulse = session.pulse();
+            let stream_dim = pulsed_output_fact.delay + 3 * pulse + pulse / 2;
+
+            // every input gets its own length and pulse, if it streams at a
+            // fraction of S
+            let mut fixed_inputs = tvec!();
+            let mut pulsed_input_facts = tvec!();
+            for (ix, input) in fixed.input_outlets()?.iter().enumerate() {
+                let pulsed_input_fact = pulsed.input_fact(ix)?.clone();
+                let len = pulsed_input_fact
+                    .dim
+                    .eval(stream_dim as i32)
+                    .ok_or_else(|| format!("Can not compute length of input {}", ix))?;
+                fixed_inputs.push(crate::tensor::tensor_for_fact(
+                    &fixed.outlet_fact(*input)?.to_tensor_fact(),
+                    Some(len as usize),
+                )?);
+                pulsed_input_facts.push(pulsed_input_fact);
+            }
 
             let mut fixed = fixed.clone();
             fixed.set_output_outlets(&[fixed_outlet])?;
-            let fixed_result = SimplePlan::new(&fixed)?.run(tvec!(fixed_input.clone()))?.remove(0);
+            let fixed_result = SimplePlan::new(&fixed)?.run(fixed_inputs.clone())?.remove(0);
             let fixed_result = fixed_result.to_array_view::<f32>()?;
-            let fixed_output_len = fixed_result.shape()[output_axis];
 
-            let plan = SimplePlan::new(&pulsed)?;
-            let mut state = SimpleState::new(&plan)?;
-
-            for i in 0.. {
-                let mut pulsed_input = ArrayD::from_elem(&*pulsed_input_fact.shape, std::f32::NAN);
-                let offset = i * input_pulse;
-                if offset < stream_dim {
-                    let count = input_pulse.min(stream_dim - offset);
-                    pulsed_input
-                        .slice_axis_mut(Axis(pulsed_input_fact.axis), (0..count).into())
-                        .assign(&fixed_input.to_array_view::<f32>()?.slice_axis(
-                            Axis(pulsed_input_fact.axis),
-                            (offset..offset + count).into(),
-                        ));
-                };
-                if offset + input_pulse > stream_dim {
-                    debug!("Set known_stream_len: {}", stream_dim);
-                    state.session_state.known_stream_len = Some(stream_dim)
-                };
-
-                let output = state.run(tvec!(pulsed_input.into()))?.remove(0);
-
-                let output = output.to_array_view::<f32>()?;
-                let output_offset = i * output_pulse;
-                let (f_o, p_o, count) = if output_offset + output_pulse <= delay {
-                    // entire pulse before signal, wait
-                    continue;
-                } else if output_offset >= delay + fixed_output_len {
-                    // entire pulse after signal, we stop
-                    break;
-                } else if output_offset < delay {
-                    // beginning of signal
-                    let count = output_pulse + output_offset - delay;
-                    (0, output_pulse - count, count)
-                } else if output_offset + output_pulse > delay + fixed_output_len {
-                    // end of signal
-                    let count = fixed_output_len + delay - output_offset;
-                    (output_offset - delay, 0, count)
-                } else {
-                    (output_offset - delay, 0, output_pulse)
-                };
-                let valid_pulse_result =
-                    output.slice_axis(Axis(output_axis), (p_o..p_o + count).into());
-                let valid_fixed_result =
-                    fixed_result.slice_axis(Axis(output_axis), (f_o..f_o + count).into());
-                if valid_pulse_result != valid_fixed_result {
-                    display_graph.render_node(pulsed_node)?;
-                    println!("pulse: {} ({}..{})", i, i * output_pulse, (i + 1) * output_pulse);
-                    println!(
-                        "expected: {}",
-                        valid_fixed_result
-                            .axis_iter(Axis(output_axis))
-                            .map(|s| *s.iter().next().unwrap())
-                            .join(" ")
-                    );
-                    println!(
-                        "got: {}",
-                        valid_pulse_result
-                            .axis_iter(Axis(output_axis))
-                            .map(|s| *s.iter().next().unwrap())
-                            .join(" ")
-                    );
-                    bail!("Pulse check failed")
-                }
+            let chunk = |i: usize, last: bool| -> TractResult<TVec<Tensor>> {
+                fixed_inputs
+                    .iter()
+                    .zip(pulsed_input_facts.iter())
+                    .map(|(input, fact)| {
+                        if !fact.is_streaming() {
+                            return Ok(input.clone());
+                        }
+                        let input = input.to_array_view::<f32>()?;
+                        let start = i * fact.pulse();
+                        let end =
+                            if last { input.shape()[fact.axis] } else { start + fact.pulse() };
+                        let view = input.slice_axis(Axis(fact.axis), (start..end).into());
+                        // ndarray 0.12 to_owned() panics on some empty strided views
+                        Ok(ArrayD::from_shape_vec(view.shape(), view.iter().cloned().collect())?
+                            .into())
+                    })
+                    .collect()
+            };
+            let pulses = stream_dim / pulse;
+            let mut outputs = vec![];
+            for i in 0..pulses {
+                outputs.push(session.push_inputs(chunk(i, false)?)?.remove(0));
+            }
+            outputs.push(session.finish_inputs(chunk(pulses, true)?)?.remove(0));
+            let views = outputs
+                .iter()
+                .map(|o| o.to_array_view::<f32>())
+                .collect::<TractResult<Vec<_>>>()?;
+            let pulsed_result = tract_core::ndarray::stack(Axis(output_axis), &views)?;
+
+            if pulsed_result != fixed_result {
+                display_graph.render_node(pulsed_node)?;
+                println!("expected: {}", frames(&fixed_result, output_axis));
+                println!("got: {}", frames(&pulsed_result.view(), output_axis));
+                bail!("Pulse check failed")
             }
         }
     }
 
     Ok(())
 }
+
+/// First value of each frame along the streaming axis.
+fn frames(array: &ArrayViewD<f32>, axis: usize) -> String {
+    array.axis_iter(Axis(axis)).map(|s| s.iter().next().cloned().unwrap_or(std::f32::NAN)).join(" ")
+}
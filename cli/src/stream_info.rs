@@ -0,0 +1,86 @@
+use crate::display_graph::*;
+use crate::errors::*;
+use crate::{Model, Parameters};
+use tract_core::internal::*;
+use tract_core::pulse::latency::OutletLatency;
+
+fn context(latency: &OutletLatency) -> String {
+    let left = latency.left_context.map(|l| l.to_string()).unwrap_or("∞".to_string());
+    format!("-{}/+{}", left, latency.right_context)
+}
+
+pub fn handle(params: Parameters, options: DisplayOptions, m: &clap::ArgMatches) -> CliResult<()> {
+    let max_delay = m.value_of("assert-max-delay").map(|s| s.parse::<usize>()).transpose()?;
+    let max_latency = m.value_of("assert-max-latency").map(|s| s.parse::<usize>()).transpose()?;
+    let max_buffer = m.value_of("assert-max-buffer").map(|s| s.parse::<usize>()).transpose()?;
+    let model = params
+        .tract_model
+        .downcast_ref::<PulsedModel>()
+        .ok_or("stream-info works on a pulsed model (use --pulse)")?;
+    let latencies = model.latency()?;
+    let mut display_graph =
+        DisplayGraph::from_model_and_options(model as &dyn Model, options.into())?
+            .with_graph_def(&params.graph)?;
+    for node in model.nodes() {
+        for slot in 0..node.outputs.len() {
+            if let Some(latency) = latencies.get(&OutletId::new(node.id, slot)) {
+                display_graph.add_node_section(
+                    &[node.id],
+                    vec![
+                        format!(
+                            "delay: {} latency: {} context: {}",
+                            latency.delay,
+                            latency.latency,
+                            context(latency)
+                        ),
+                        format!("buffered: {} bytes", latency.buffered_bytes),
+                    ],
+                )?;
+            }
+        }
+    }
+    display_graph.render()?;
+
+    let buffered: usize = model
+        .nodes()
+        .iter()
+        .filter_map(|n| n.op_as::<tract_core::pulse::delay::Delay>())
+        .map(|d| d.buffer_bytes())
+        .sum();
+    let (mut delay, mut latency) = (0, 0);
+    for (ix, output) in model.output_outlets()?.iter().enumerate() {
+        let outlet = &latencies[output];
+        println!(
+            "output #{} ({}): delay: {} output frames, latency: {} input frames, context: {}",
+            ix,
+            model.node(output.node).name,
+            outlet.delay,
+            outlet.latency,
+            context(outlet)
+        );
+        delay = delay.max(outlet.delay);
+        latency = latency.max(outlet.latency);
+    }
+    println!("buffered: {} bytes", buffered);
+
+    if let Some(max) = max_delay {
+        if delay > max {
+            bail!("Delay assertion not met: expected at most {} output frames got {}", max, delay);
+        }
+    }
+    if let Some(max) = max_latency {
+        if latency > max {
+            bail!(
+                "Latency assertion not met: expected at most {} input frames got {}",
+                max,
+                latency
+            );
+        }
+    }
+    if let Some(max) = max_buffer {
+        if buffered > max {
+            bail!("Buffer assertion not met: expected at most {} bytes got {}", max, buffered);
+        }
+    }
+    Ok(())
+}
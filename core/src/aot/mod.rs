@@ -0,0 +1,559 @@
+//! Ahead-of-time compilation of a NormalizedModel to standalone Rust code.
+//!
+//! The generated source has no interpreter: weights are static byte arrays,
+//! activations live in statically allocated buffers, reused once their
+//! consumers have run, and convolutions and matrix products call directly
+//! into tract-linalg kernels. It expects to be compiled as a module of a crate
+//! depending on tract-linalg.
+//!
+//! Only f32 models with fully determined shapes are supported. Operators opt
+//! in by implementing `TypedOp::emit_rust`.
+
+use std::fmt::Write;
+
+use crate::internal::*;
+use crate::ops::source::TypedSource;
+
+const RUNTIME: &str = include_str!("runtime.rs");
+
+/// Widest tile (mr or nr) of the tract-linalg f32 kernels, on every
+/// architecture. Packing buffers are sized with it, so that the generated code
+/// does not depend on the kernel picked on the generating machine.
+pub const MAX_TILE: usize = 16;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Storage {
+    Input(usize),
+    Buffer(usize),
+}
+
+#[derive(Clone, Debug, Default)]
+struct Buffer {
+    size: usize,
+    users: usize,
+}
+
+/// Code generation context handed over to `TypedOp::emit_rust`.
+///
+/// It gives access to the current node inputs and outputs as Rust
+/// expressions, and collects the static items and the statements of the
+/// generated `run` function.
+#[derive(Debug, Default)]
+pub struct Emitter {
+    items: Vec<String>,
+    code: Vec<String>,
+    buffers: Vec<Buffer>,
+    storage: HashMap<OutletId, Storage>,
+    node_inputs: TVec<(Storage, TVec<usize>)>,
+    node_outputs: TVec<(Option<Storage>, TVec<usize>)>,
+    held: TVec<usize>,
+    im2col: usize,
+    packed_a: usize,
+    packed_b: usize,
+}
+
+impl Emitter {
+    fn expr(&self, storage: Storage, shape: &[usize], mutable: bool) -> String {
+        let len = shape.iter().product::<usize>();
+        match storage {
+            Storage::Input(ix) => format!("input_{}", ix),
+            Storage::Buffer(ix) if mutable => format!("&mut B{}.0[..{}]", ix, len),
+            Storage::Buffer(ix) => format!("&B{}.0[..{}]", ix, len),
+        }
+    }
+
+    /// Shape of the ix-th input of the current node.
+    pub fn input_shape(&self, ix: usize) -> &[usize] {
+        &self.node_inputs[ix].1
+    }
+
+    /// Shape of the ix-th output of the current node.
+    pub fn output_shape(&self, ix: usize) -> &[usize] {
+        &self.node_outputs[ix].1
+    }
+
+    /// Expression of the ix-th input of the current node, as a `&[f32]`.
+    pub fn input(&self, ix: usize) -> String {
+        let (storage, ref shape) = self.node_inputs[ix];
+        self.expr(storage, shape, false)
+    }
+
+    /// Expression of the ix-th output of the current node, as a `&mut [f32]`.
+    ///
+    /// The buffer is allocated on first call.
+    pub fn output(&mut self, ix: usize) -> TractResult<String> {
+        let storage = match self.node_outputs[ix].0 {
+            Some(Storage::Input(_)) => bail!("Output {} is an alias of a model input", ix),
+            Some(storage) => storage,
+            None => {
+                let size = self.node_outputs[ix].1.iter().product::<usize>();
+                let buffer = self.allocate(size);
+                self.node_outputs[ix].0 = Some(Storage::Buffer(buffer));
+                Storage::Buffer(buffer)
+            }
+        };
+        Ok(self.expr(storage, &self.node_outputs[ix].1, true))
+    }
+
+    /// Makes the ix-th output of the current node share the storage of one
+    /// of its inputs, for operators that only reinterpret their input shape.
+    pub fn alias(&mut self, output: usize, input: usize) -> TractResult<()> {
+        if self.node_outputs[output].0.is_some() {
+            bail!("Output {} already has a storage", output)
+        }
+        self.node_outputs[output].0 = Some(self.node_inputs[input].0);
+        Ok(())
+    }
+
+    /// Stores a tensor as a static item, returning a `&'static [f32]`
+    /// expression.
+    pub fn weight(&mut self, tensor: &Tensor) -> TractResult<String> {
+        let tensor = tensor.cast_to::<f32>()?;
+        let values = tensor.as_slice::<f32>()?;
+        let mut literal = String::with_capacity(values.len() * 8);
+        for (ix, value) in values.iter().enumerate() {
+            let bytes = value.to_bits().to_le_bytes();
+            // line continuations swallow leading spaces
+            if ix % 24 == 23 && bytes[0] != b' ' {
+                literal.push_str("\\\n");
+            }
+            for &byte in &bytes {
+                match byte {
+                    b'"' | b'\\' => write!(literal, "\\{}", byte as char)?,
+                    0x20..=0x7e => literal.push(byte as char),
+                    _ => write!(literal, "\\x{:02x}", byte)?,
+                }
+            }
+        }
+        let name = format!("W{}", self.items.len());
+        self.items.push(format!(
+            "static {}: Aligned<[u8; {}]> = Aligned(*b\"{}\");",
+            name,
+            values.len() * 4,
+            literal
+        ));
+        Ok(format!("weights(&{}.0)", name))
+    }
+
+    /// Declares a constant item of type `ty`, returning its name.
+    pub fn constant(&mut self, ty: &str, value: String) -> String {
+        let name = format!("C{}", self.items.len());
+        self.items.push(format!("const {}: {} = {};", name, ty, value));
+        name
+    }
+
+    /// Reserves the im2col scratch buffer for `len` values.
+    pub fn reserve_im2col(&mut self, len: usize) {
+        self.im2col = self.im2col.max(len);
+    }
+
+    /// Reserves the packing scratch buffers for a m×k by k×n product, panels
+    /// being padded to at most `MAX_TILE`.
+    pub fn reserve_matmul(&mut self, m: usize, k: usize, n: usize) {
+        self.packed_a = self.packed_a.max((m + MAX_TILE - 1) * k);
+        self.packed_b = self.packed_b.max((n + MAX_TILE - 1) * k);
+    }
+
+    /// Appends a statement to the generated `run` function.
+    pub fn push<S: Into<String>>(&mut self, statement: S) {
+        self.code.push(statement.into())
+    }
+
+    fn allocate(&mut self, size: usize) -> usize {
+        let free = (0..self.buffers.len()).filter(|&b| self.buffers[b].users == 0);
+        let best = free
+            .clone()
+            .filter(|&b| self.buffers[b].size >= size)
+            .min_by_key(|&b| self.buffers[b].size)
+            .or_else(|| free.max_by_key(|&b| self.buffers[b].size));
+        let buffer = best.unwrap_or_else(|| {
+            self.buffers.push(Buffer::default());
+            self.buffers.len() - 1
+        });
+        self.buffers[buffer].size = self.buffers[buffer].size.max(size);
+        // held until the current node outputs are bound
+        self.buffers[buffer].users = 1;
+        self.held.push(buffer);
+        buffer
+    }
+
+    fn release(&mut self, storage: Storage, users: usize) {
+        if let Storage::Buffer(b) = storage {
+            self.buffers[b].users -= users;
+        }
+    }
+
+    fn acquire(&mut self, storage: Storage, users: usize) {
+        if let Storage::Buffer(b) = storage {
+            self.buffers[b].users += users;
+        }
+    }
+
+    fn emit_node(&mut self, model: &NormalizedModel, node: &NormalizedNode) -> TractResult<()> {
+        let shape = |outlet: OutletId| -> TractResult<TVec<usize>> {
+            let fact = model.outlet_fact(outlet)?;
+            if fact.datum_type != f32::datum_type() {
+                bail!("{} can not be compiled ahead of time: only f32 is supported", node.name)
+            }
+            fact.shape.as_finite().map(|s| s.into()).ok_or_else(|| {
+                format!("{} can not be compiled ahead of time: streaming shape", node.name).into()
+            })
+        };
+        self.node_inputs = node
+            .inputs
+            .iter()
+            .map(|i| Ok((self.storage[i], shape(*i)?)))
+            .collect::<TractResult<_>>()?;
+        self.node_outputs = (0..node.outputs.len())
+            .map(|slot| Ok((None, shape(OutletId::new(node.id, slot))?)))
+            .collect::<TractResult<_>>()?;
+        self.code.push(format!("// {} ({})", node.name, node.op.name()));
+        node.op.emit_rust(model, node, self)?;
+        let outputs = std::mem::replace(&mut self.node_outputs, tvec!());
+        let pinned = model.output_outlets()?;
+        for (slot, (storage, _)) in outputs.into_iter().enumerate() {
+            let outlet = OutletId::new(node.id, slot);
+            let storage = storage.ok_or_else(|| {
+                format!("{} did not bind its output {} in generated code", node.name, slot)
+            })?;
+            let users = node.outputs[slot].successors.len()
+                + pinned.iter().filter(|o| **o == outlet).count();
+            self.acquire(storage, users);
+            self.storage.insert(outlet, storage);
+        }
+        for buffer in std::mem::replace(&mut self.held, tvec!()) {
+            self.release(Storage::Buffer(buffer), 1);
+        }
+        for input in &node.inputs {
+            self.release(self.storage[input], 1);
+        }
+        Ok(())
+    }
+}
+
+/// Formats a f32 as a Rust expression.
+pub fn f32_literal(value: f32) -> String {
+    if value.is_nan() {
+        "std::f32::NAN".to_string()
+    } else if value.is_infinite() {
+        format!("std::f32::{}INFINITY", if value < 0.0 { "NEG_" } else { "" })
+    } else {
+        format!("{:?}f32", value)
+    }
+}
+
+/// Formats a list of integers as a Rust slice expression.
+pub fn usize_slice(values: &[usize]) -> String {
+    format!("&[{}]", values.iter().map(|v| v.to_string()).collect::<Vec<_>>().join(", "))
+}
+
+/// Row-major strides of a shape.
+pub fn strides(shape: &[usize]) -> TVec<usize> {
+    let mut strides: TVec<usize> = tvec!(1; shape.len());
+    for ax in (0..shape.len().saturating_sub(1)).rev() {
+        strides[ax] = strides[ax + 1] * shape[ax + 1];
+    }
+    strides
+}
+
+/// Strides reading a tensor broadcast to a higher rank or larger shape.
+pub fn broadcast_strides(shape: &[usize], target: &[usize]) -> TVec<usize> {
+    let mut result: TVec<usize> = tvec!(0; target.len() - shape.len());
+    for (&d, s) in shape.iter().zip(strides(shape)) {
+        result.push(if d == 1 { 0 } else { s });
+    }
+    result
+}
+
+/// Generates a Rust module evaluating the model.
+///
+/// The module exposes `pub unsafe fn run(input_0: &[f32], ..., output_0: &mut [f32], ...)`
+/// and the `INPUT_SHAPES` and `OUTPUT_SHAPES` constants.
+pub fn generate(model: &NormalizedModel) -> TractResult<String> {
+    let mut emitter = Emitter::default();
+    let inputs = model.input_outlets()?;
+    for (ix, input) in inputs.iter().enumerate() {
+        emitter.storage.insert(*input, Storage::Input(ix));
+    }
+    for n in model.eval_order()? {
+        let node = model.node(n);
+        if !node.op_is::<TypedSource>() {
+            emitter.emit_node(model, node)?;
+        }
+    }
+    let shapes = |outlets: &[OutletId]| -> TractResult<Vec<TVec<usize>>> {
+        outlets
+            .iter()
+            .map(|o| {
+                Ok(model
+                    .outlet_fact(*o)?
+                    .shape
+                    .as_finite()
+                    .ok_or("Model inputs and outputs must have finite shapes")?
+                    .into())
+            })
+            .collect()
+    };
+    let input_shapes = shapes(inputs)?;
+    let output_shapes = shapes(model.output_outlets()?)?;
+
+    let mut code = String::new();
+    writeln!(code, "// Generated by tract. Do not edit.")?;
+    writeln!(code, "#![allow(dead_code, unused_unsafe, unknown_lints, static_mut_refs)]")?;
+    writeln!(code, "#![allow(clippy::all)]")?;
+    writeln!(code)?;
+    code.push_str(RUNTIME);
+    writeln!(code)?;
+    for (name, shapes) in &[("INPUT_SHAPES", &input_shapes), ("OUTPUT_SHAPES", &output_shapes)] {
+        let shapes = shapes.iter().map(|s| usize_slice(s)).collect::<Vec<_>>().join(", ");
+        writeln!(code, "pub const {}: &[&[usize]] = &[{}];", name, shapes)?;
+    }
+    let scratch = [
+        ("IM2COL", emitter.im2col),
+        ("PACKED_A", emitter.packed_a),
+        ("PACKED_B", emitter.packed_b),
+    ];
+    let buffers = emitter.buffers.iter().enumerate().map(|(ix, b)| (format!("B{}", ix), b.size));
+    for (name, size) in scratch.iter().map(|(n, s)| (n.to_string(), *s)).chain(buffers) {
+        writeln!(
+            code,
+            "static mut {}: Aligned<[f32; {}]> = Aligned([0.0; {}]);",
+            name, size, size
+        )?;
+    }
+    for item in &emitter.items {
+        writeln!(code, "{}", item)?;
+    }
+    writeln!(code)?;
+    writeln!(code, "/// Runs the model.")?;
+    writeln!(code, "///")?;
+    writeln!(code, "/// # Safety")?;
+    writeln!(code, "///")?;
+    writeln!(code, "/// Works in static buffers: calls must not overlap.")?;
+    let args = (0..inputs.len())
+        .map(|ix| format!("input_{}: &[f32]", ix))
+        .chain((0..output_shapes.len()).map(|ix| format!("output_{}: &mut [f32]", ix)))
+        .collect::<Vec<_>>();
+    writeln!(code, "pub unsafe fn run({}) {{", args.join(", "))?;
+    for (ix, shape) in input_shapes.iter().enumerate() {
+        writeln!(code, "    assert_eq!(input_{}.len(), {});", ix, shape.iter().product::<usize>())?;
+    }
+    for (ix, shape) in output_shapes.iter().enumerate() {
+        writeln!(
+            code,
+            "    assert_eq!(output_{}.len(), {});",
+            ix,
+            shape.iter().product::<usize>()
+        )?;
+    }
+    for statement in &emitter.code {
+        writeln!(code, "    {}", statement)?;
+    }
+    for (ix, (output, shape)) in model.output_outlets()?.iter().zip(&output_shapes).enumerate() {
+        let source = emitter.expr(emitter.storage[output], shape, false);
+        writeln!(code, "    output_{}.copy_from_slice({});", ix, source)?;
+    }
+    writeln!(code, "}}")?;
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::cnn::*;
+    use crate::ops::nn::{DataFormat, LayerSoftmax};
+    use crate::ops::{array, math};
+    use std::io::Write as _;
+    use std::path::PathBuf;
+    use std::process::{Command, Stdio};
+
+    // builds the generated code in a scratch crate and runs it on the inputs
+    fn run_generated(name: &str, model: &NormalizedModel, inputs: &[Tensor]) -> Vec<Vec<f32>> {
+        let root = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("..");
+        let dir = root.join("target").join("aot").join(name);
+        std::fs::create_dir_all(dir.join("src")).unwrap();
+        let manifest = format!(
+            "[package]\nname = \"aot-{}\"\nversion = \"0.0.0\"\nedition = \"2018\"\n\n\
+             [dependencies]\ntract-linalg = {{ path = {:?} }}\n\n[workspace]\n",
+            name,
+            root.join("linalg")
+        );
+        std::fs::write(dir.join("Cargo.toml"), manifest).unwrap();
+        std::fs::copy(root.join("Cargo.lock"), dir.join("Cargo.lock")).unwrap();
+        std::fs::write(dir.join("src/model.rs"), generate(model).unwrap()).unwrap();
+        let n_outputs = model.output_outlets().unwrap().len();
+        let args = (0..inputs.len())
+            .map(|ix| format!("&inputs[{}]", ix))
+            .chain((0..n_outputs).map(|ix| format!("&mut outputs[{}]", ix)))
+            .collect::<Vec<_>>();
+        let main = format!(
+            "mod model;\n\
+             use std::io::{{Read, Write}};\n\
+             fn main() {{\n\
+                 let mut bytes = vec![];\n\
+                 std::io::stdin().read_to_end(&mut bytes).unwrap();\n\
+                 let mut values = bytes.chunks(4)\n\
+                     .map(|b| f32::from_bits(u32::from_le_bytes([b[0], b[1], b[2], b[3]])));\n\
+                 let inputs: Vec<Vec<f32>> = model::INPUT_SHAPES.iter()\n\
+                     .map(|s| values.by_ref().take(s.iter().product()).collect()).collect();\n\
+                 let mut outputs: Vec<Vec<f32>> = model::OUTPUT_SHAPES.iter()\n\
+                     .map(|s| vec![0.0; s.iter().product()]).collect();\n\
+                 unsafe {{ model::run({}) }};\n\
+                 for v in outputs.iter().flatten() {{\n\
+                     std::io::stdout().write_all(&v.to_bits().to_le_bytes()).unwrap();\n\
+                 }}\n\
+             }}\n",
+            args.join(", ")
+        );
+        std::fs::write(dir.join("src/main.rs"), main).unwrap();
+        let cargo = std::env::var("CARGO").unwrap_or("cargo".to_string());
+        let build = Command::new(cargo)
+            .args(&["build", "--offline", "--release"])
+            .current_dir(&dir)
+            .env("CARGO_TARGET_DIR", root.join("target").join("aot").join("target"))
+            .output()
+            .unwrap();
+        assert!(build.status.success(), "{}", String::from_utf8_lossy(&build.stderr));
+        let binary = root.join("target/aot/target/release").join(format!("aot-{}", name));
+        let mut child =
+            Command::new(binary).stdin(Stdio::piped()).stdout(Stdio::piped()).spawn().unwrap();
+        {
+            let stdin = child.stdin.as_mut().unwrap();
+            for input in inputs {
+                for v in input.as_slice::<f32>().unwrap() {
+                    stdin.write_all(&v.to_bits().to_le_bytes()).unwrap();
+                }
+            }
+        }
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success(), "generated code for {} failed", name);
+        let mut values = output
+            .stdout
+            .chunks(4)
+            .map(|b| f32::from_bits(u32::from_le_bytes([b[0], b[1], b[2], b[3]])));
+        model
+            .output_outlets()
+            .unwrap()
+            .iter()
+            .map(|o| {
+                let len =
+                    model.outlet_fact(*o).unwrap().shape.as_finite().unwrap().iter().product();
+                values.by_ref().take(len).collect()
+            })
+            .collect()
+    }
+
+    fn check(name: &str, model: NormalizedModel, inputs: Vec<Tensor>) {
+        let expected =
+            SimplePlan::new(&model).unwrap().run(inputs.iter().cloned().collect()).unwrap();
+        let found = run_generated(name, &model, &inputs);
+        assert_eq!(found.len(), expected.len());
+        for (found, expected) in found.into_iter().zip(expected) {
+            let found =
+                Tensor::from(ndarray::ArrayD::from_shape_vec(expected.shape(), found).unwrap());
+            found.close_enough(&expected, true).unwrap();
+        }
+    }
+
+    fn values(shape: &[usize], seed: usize) -> Tensor {
+        let len = shape.iter().product::<usize>();
+        let values =
+            (0..len).map(|i| ((i * 7 + seed * 13) % 23) as f32 / 23.0 - 0.5).collect::<Vec<_>>();
+        Tensor::from(ndarray::ArrayD::from_shape_vec(shape, values).unwrap())
+    }
+
+    #[test]
+    fn mobilenet_like() {
+        let mut model = InferenceModel::default();
+        let fact = InferenceFact::dt_shape(f32::datum_type(), shapefact!(1, 8, 8, 3));
+        let input = model.add_source("input", fact).unwrap();
+        let conv = Conv::default().nhwc().hwio().padding(PaddingSpec::SameUpper);
+        let ker = model.add_const("k0", values(&[3, 3, 3, 8], 1)).unwrap();
+        let c0 = model.wire_node("c0", conv.clone().strides(tvec!(2, 2)), &[input, ker]).unwrap();
+        let scale = model.add_const("scale", values(&[8], 2)).unwrap();
+        let bn = model.wire_node("bn.mul", math::mul::bin(), &[c0[0], scale]).unwrap();
+        let shift = model.add_const("shift", values(&[8], 3)).unwrap();
+        let bn = model.wire_node("bn.add", math::add::bin(), &[bn[0], shift]).unwrap();
+        let r0 = model
+            .wire_node(
+                "relu6",
+                math::scalar_min_max(
+                    rctensor0(6.0f32).into_tensor(),
+                    rctensor0(0.0f32).into_tensor(),
+                ),
+                &bn,
+            )
+            .unwrap();
+        let ker = model.add_const("k1", values(&[8, 1, 3, 3], 4)).unwrap();
+        let dw_conv = Conv::default().nhwc().padding(PaddingSpec::SameUpper).group(8);
+        let dw = model.wire_node("dw", dw_conv, &[r0[0], ker]).unwrap();
+        let ker = model.add_const("k2", values(&[1, 1, 8, 8], 5)).unwrap();
+        let pw = model.wire_node("pw", conv.clone(), &[dw[0], ker]).unwrap();
+        let res = model.wire_node("res", math::add::bin(), &[pw[0], r0[0]]).unwrap();
+        let pool_spec =
+            PoolSpec::new(DataFormat::NHWC, tvec!(4, 4), PaddingSpec::Valid, None, None, None);
+        let pool = model.wire_node("pool", AvgPool::new(pool_spec, false), &res).unwrap();
+        let rm = model.wire_node("rm", array::RmDims::new(vec![1, 2]), &pool).unwrap();
+        model.wire_node("softmax", LayerSoftmax::new(1), &rm).unwrap();
+        model.auto_outputs().unwrap();
+        check("mobilenet_like", model.into_normalized().unwrap(), vec![values(&[1, 8, 8, 3], 0)]);
+    }
+
+    #[test]
+    fn max_tile_bounds_packing() {
+        for &(m, k, n) in &[(1, 1, 1), (5, 3, 7), (17, 9, 33), (64, 27, 100)] {
+            let mut emitter = Emitter::default();
+            emitter.reserve_matmul(m, k, n);
+            let mmm = (tract_linalg::ops().smmm)(m, k, n);
+            assert!(mmm.a_pack().len() <= emitter.packed_a);
+            assert!(mmm.b_pack().len() <= emitter.packed_b);
+        }
+    }
+
+    #[test]
+    fn tf_depthwise() {
+        let mut model = TypedModel::default();
+        let fact = TypedFact::dt_shape(f32::datum_type(), [1, 7, 7, 4].as_ref()).unwrap();
+        let input = model.add_source("input", fact.clone()).unwrap();
+        let kernel = TypedFact::from(values(&[3, 3, 4, 1], 1));
+        let conv = Conv::default()
+            .nhwc()
+            .hwio()
+            .group(4)
+            .padding(PaddingSpec::SameUpper)
+            .strides(tvec!(2, 2));
+        let conv = conv.to_unary(&[&fact, &kernel]).unwrap().unwrap();
+        model.wire_node("dw", conv, &[input]).unwrap();
+        model.auto_outputs().unwrap();
+        check("tf_depthwise", model.into_normalized().unwrap(), vec![values(&[1, 7, 7, 4], 0)]);
+    }
+
+    #[test]
+    fn nchw_conv_pool_matmul() {
+        let mut model = InferenceModel::default();
+        let fact = InferenceFact::dt_shape(f32::datum_type(), shapefact!(2, 3, 7, 6));
+        let input = model.add_source("input", fact).unwrap();
+        let ker = model.add_const("k", values(&[4, 3, 3, 2], 1)).unwrap();
+        let bias = model.add_const("b", values(&[4], 2)).unwrap();
+        let conv = Conv::default().bias_input(2).dilations(tvec!(1, 2));
+        let c = model.wire_node("conv", conv, &[input, ker, bias]).unwrap();
+        let pool_spec = PoolSpec::new(
+            DataFormat::NCHW,
+            tvec!(2, 2),
+            PaddingSpec::SameUpper,
+            None,
+            Some(tvec!(2, 2)),
+            None,
+        );
+        let pool = model.wire_node("pool", MaxPool::new(pool_spec, None), &c).unwrap();
+        let a = model.add_const("a", values(&[5, 3], 3)).unwrap();
+        let mm = model.wire_node("mm", math::MatMul::default(), &[a, pool[0]]).unwrap();
+        model.wire_node("tanh", math::tanh(), &mm).unwrap();
+        model.auto_outputs().unwrap();
+        check(
+            "nchw_conv_pool_matmul",
+            model.into_normalized().unwrap(),
+            vec![values(&[2, 3, 7, 6], 0)],
+        );
+    }
+}
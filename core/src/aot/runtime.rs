@@ -0,0 +1,234 @@
+// Support code copied at the top of every source generated by
+// tract_core::aot. It is not compiled as part of tract-core.
+
+#[cfg(target_endian = "big")]
+compile_error!("tract generated code stores its weights little-endian");
+
+#[repr(C, align(64))]
+struct Aligned<T>(T);
+
+fn weights(bytes: &'static [u8]) -> &'static [f32] {
+    assert!(bytes.as_ptr() as usize % std::mem::align_of::<f32>() == 0);
+    unsafe { std::slice::from_raw_parts(bytes.as_ptr() as *const f32, bytes.len() / 4) }
+}
+
+// calls f with the offsets of every element of shape, in row major order,
+// along the a and b strides
+fn walk(shape: &[usize], a: &[usize], b: &[usize], mut f: impl FnMut(usize, usize)) {
+    let mut coords = [0usize; 8];
+    assert!(shape.len() <= coords.len());
+    let (mut ia, mut ib) = (0, 0);
+    for _ in 0..shape.iter().product::<usize>() {
+        f(ia, ib);
+        for ax in (0..shape.len()).rev() {
+            coords[ax] += 1;
+            ia += a[ax];
+            ib += b[ax];
+            if coords[ax] < shape[ax] {
+                break;
+            }
+            coords[ax] = 0;
+            ia -= a[ax] * shape[ax];
+            ib -= b[ax] * shape[ax];
+        }
+    }
+}
+
+fn map(input: &[f32], output: &mut [f32], f: impl Fn(f32) -> f32) {
+    for (o, i) in output.iter_mut().zip(input) {
+        *o = f(*i)
+    }
+}
+
+fn zip(
+    shape: &[usize],
+    a: &[f32],
+    a_strides: &[usize],
+    b: &[f32],
+    b_strides: &[usize],
+    c: &mut [f32],
+    f: impl Fn(f32, f32) -> f32,
+) {
+    let mut ic = 0;
+    walk(shape, a_strides, b_strides, |ia, ib| {
+        c[ic] = f(a[ia], b[ib]);
+        ic += 1;
+    })
+}
+
+fn copy(shape: &[usize], input: &[f32], in_strides: &[usize], output: &mut [f32], out_strides: &[usize]) {
+    walk(shape, in_strides, out_strides, |i, o| output[o] = input[i])
+}
+
+fn softmax(len: usize, input: &[f32], output: &mut [f32]) {
+    for (input, output) in input.chunks(len).zip(output.chunks_mut(len)) {
+        let max = input.iter().cloned().fold(f32::MIN, f32::max);
+        map(input, output, |x| (x - max).exp());
+        let sum: f32 = output.iter().sum();
+        output.iter_mut().for_each(|x| *x /= sum);
+    }
+}
+
+// geometry of a convolution or pooling over NCHW or NHWC data
+struct Geo {
+    batch: usize,
+    nhwc: bool,
+    c_in: usize,
+    c_out: usize,
+    input: &'static [usize],
+    output: &'static [usize],
+    kernel: &'static [usize],
+    strides: &'static [usize],
+    dilations: &'static [usize],
+    pad_before: &'static [usize],
+}
+
+impl Geo {
+    fn input_len(&self) -> usize {
+        self.input.iter().product()
+    }
+
+    fn output_len(&self) -> usize {
+        self.output.iter().product()
+    }
+
+    fn kernel_len(&self) -> usize {
+        self.kernel.iter().product()
+    }
+
+    fn offset(&self, n: usize, c: usize, pos: usize, channels: usize, len: usize) -> usize {
+        if self.nhwc {
+            (n * len + pos) * channels + c
+        } else {
+            (n * channels + c) * len + pos
+        }
+    }
+
+    // input position read by kernel position k for output position o, None
+    // in the padding
+    fn tap(&self, mut o: usize, mut k: usize) -> Option<usize> {
+        let mut pos = 0;
+        let mut stride = 1;
+        for ax in (0..self.input.len()).rev() {
+            let x = (o % self.output[ax]) * self.strides[ax] + (k % self.kernel[ax]) * self.dilations[ax];
+            o /= self.output[ax];
+            k /= self.kernel[ax];
+            if x < self.pad_before[ax] || x - self.pad_before[ax] >= self.input[ax] {
+                return None;
+            }
+            pos += (x - self.pad_before[ax]) * stride;
+            stride *= self.input[ax];
+        }
+        Some(pos)
+    }
+
+    fn is_pointwise(&self) -> bool {
+        self.kernel_len() == 1
+            && self.strides.iter().all(|&s| s == 1)
+            && self.pad_before.iter().all(|&p| p == 0)
+    }
+}
+
+unsafe fn matmul(
+    (m, k, n): (usize, usize, usize),
+    a: &[f32],
+    (rsa, csa): (usize, usize),
+    b: &[f32],
+    (rsb, csb): (usize, usize),
+    c: &mut [f32],
+    (rsc, csc): (usize, usize),
+) {
+    let mut mmm = (tract_linalg::ops().smmm)(m, k, n);
+    let packer = mmm.a_pack();
+    assert!(packer.len() <= PACKED_A.0.len(), "kernel tile wider than the generator MAX_TILE");
+    packer.pack(PACKED_A.0.as_mut_ptr(), a.as_ptr(), rsa as isize, csa as isize);
+    let packer = mmm.b_pack();
+    assert!(packer.len() <= PACKED_B.0.len(), "kernel tile wider than the generator MAX_TILE");
+    packer.pack(PACKED_B.0.as_mut_ptr(), b.as_ptr(), rsb as isize, csb as isize);
+    mmm.c_from_data_and_strides(rsc as isize, csc as isize);
+    mmm.run(PACKED_A.0.as_ptr(), PACKED_B.0.as_ptr(), c.as_mut_ptr(), &[]);
+}
+
+// kernel is laid out as [group, c_out / group, c_in / group, kernel positions]
+unsafe fn conv(geo: &Geo, group: usize, input: &[f32], kernel: &[f32], bias: Option<&[f32]>, output: &mut [f32]) {
+    let (ilen, olen, klen) = (geo.input_len(), geo.output_len(), geo.kernel_len());
+    let (ci, co) = (geo.c_in / group, geo.c_out / group);
+    let c_strides = if geo.nhwc { (1, geo.c_out) } else { (olen, 1) };
+    for n in 0..geo.batch {
+        for g in 0..group {
+            let c = &mut output[geo.offset(n, g * co, 0, geo.c_out, olen)..];
+            let kernel = &kernel[g * co * ci * klen..];
+            if ci == 1 && co == 1 {
+                for o in 0..olen {
+                    let mut sum = 0.0;
+                    for k in 0..klen {
+                        if let Some(pos) = geo.tap(o, k) {
+                            sum += kernel[k] * input[geo.offset(n, g, pos, geo.c_in, ilen)];
+                        }
+                    }
+                    c[o * c_strides.1] = sum;
+                }
+            } else if geo.is_pointwise() {
+                let b = &input[geo.offset(n, g * ci, 0, geo.c_in, ilen)..];
+                let b_strides = if geo.nhwc { (1, geo.c_in) } else { (ilen, 1) };
+                matmul((co, ci, olen), kernel, (ci, 1), b, b_strides, c, c_strides);
+            } else {
+                let cols = &mut IM2COL.0[..ci * klen * olen];
+                for i in 0..ci {
+                    for k in 0..klen {
+                        for o in 0..olen {
+                            cols[(i * klen + k) * olen + o] = match geo.tap(o, k) {
+                                Some(pos) => input[geo.offset(n, g * ci + i, pos, geo.c_in, ilen)],
+                                None => 0.0,
+                            }
+                        }
+                    }
+                }
+                matmul((co, ci * klen, olen), kernel, (ci * klen, 1), cols, (olen, 1), c, c_strides);
+            }
+        }
+        if let Some(bias) = bias {
+            for c in 0..geo.c_out {
+                for o in 0..olen {
+                    output[geo.offset(n, c, o, geo.c_out, olen)] += bias[c];
+                }
+            }
+        }
+    }
+}
+
+fn max_pool(geo: &Geo, input: &[f32], output: &mut [f32]) {
+    let (ilen, olen, klen) = (geo.input_len(), geo.output_len(), geo.kernel_len());
+    for n in 0..geo.batch {
+        for c in 0..geo.c_in {
+            for o in 0..olen {
+                let mut max = f32::MIN;
+                for k in 0..klen {
+                    if let Some(pos) = geo.tap(o, k) {
+                        max = max.max(input[geo.offset(n, c, pos, geo.c_in, ilen)]);
+                    }
+                }
+                output[geo.offset(n, c, o, geo.c_in, olen)] = max;
+            }
+        }
+    }
+}
+
+fn avg_pool(geo: &Geo, count_include_pad: bool, input: &[f32], output: &mut [f32]) {
+    let (ilen, olen, klen) = (geo.input_len(), geo.output_len(), geo.kernel_len());
+    for n in 0..geo.batch {
+        for c in 0..geo.c_in {
+            for o in 0..olen {
+                let (mut sum, mut count) = (0.0, 0);
+                for k in 0..klen {
+                    if let Some(pos) = geo.tap(o, k) {
+                        sum += input[geo.offset(n, c, pos, geo.c_in, ilen)];
+                        count += 1;
+                    }
+                }
+                let count = if count_include_pad { klen } else { count };
+                output[geo.offset(n, c, o, geo.c_in, olen)] = sum * (count as f32).recip();
+            }
+        }
+    }
+}
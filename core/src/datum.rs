@@ -135,6 +135,14 @@ impl DatumType {
         }
     }
 
+    /// Whether values of this type are plain bytes, with no heap allocation.
+    pub fn is_copy(&self) -> bool {
+        match self {
+            DatumType::Blob | DatumType::TDim | DatumType::String => false,
+            _ => true,
+        }
+    }
+
     pub fn alignment(&self) -> usize {
         match self {
             DatumType::TDim => std::mem::size_of::<usize>(),
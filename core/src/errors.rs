@@ -6,6 +6,7 @@ error_chain! {
         TractError, TractErrorKind, TractResultExt, TractResult;
     }
     foreign_links {
+        Fmt(::std::fmt::Error);
         Io(::std::io::Error);
         NdarrayShape(::ndarray::ShapeError);
         StrUtf8(::std::str::Utf8Error);
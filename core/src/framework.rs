@@ -39,3 +39,13 @@ where
         self.model_for_read(&mut r)
     }
 }
+
+/// A set of operators, registered on a framework instance from outside tract.
+///
+/// `Register` is the operator register of the framework: `OnnxOpRegister`,
+/// `TfOpRegister` or `KaldiOpRegister`. A plugin can implement this trait for
+/// several frameworks.
+pub trait OpPlugin<Register> {
+    /// Add the plugin operator builders to the register.
+    fn register_ops(&self, register: &mut Register);
+}
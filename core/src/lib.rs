@@ -83,6 +83,7 @@ pub mod analyser;
 #[macro_use]
 pub mod ops;
 
+pub mod aot;
 pub mod broadcast;
 pub mod datum;
 pub mod dim;
@@ -104,9 +105,9 @@ pub mod prelude {
     pub use crate::errors::*;
     pub use crate::framework::Framework;
     pub use crate::model::*;
-    pub use crate::plan::{SimplePlan, SimpleState};
+    pub use crate::plan::{SimplePlan, SimpleState, StateSnapshot};
     pub use crate::tensor::litteral::*;
-    pub use crate::tensor::{IntoArcTensor, IntoTensor, Tensor};
+    pub use crate::tensor::{IntoArcTensor, IntoTensor, SharedBytes, Tensor};
     pub use crate::tvec;
     pub use std::sync::Arc;
 }
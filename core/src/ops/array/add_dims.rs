@@ -95,6 +95,15 @@ impl TypedOp for AddDims {
         let input = mapping[&node.inputs[0]];
         target.wire_node(&*node.name, self.clone(), &[input])
     }
+
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        _node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        emitter.alias(0, 0)
+    }
 }
 
 impl PulsedOp for AddDims {
@@ -204,7 +204,7 @@ impl Op for NormConcat {
     }
 
     op_as_typed_op!();
-    not_a_pulsed_op!();
+    op_as_pulsed_op!();
 }
 
 impl TypedOp for NormConcat {
@@ -240,10 +240,6 @@ impl TypedOp for NormConcat {
         mapping: &HashMap<OutletId, OutletId>,
         _pulse: usize,
     ) -> TractResult<TVec<OutletId>> {
-        if node.inputs.len() > 1 {
-            bail!("Pulsification not implemented for more than one input to Concat")
-        }
-
         let input = mapping[&node.inputs[0]];
         let fact = target.outlet_fact(input)?;
 
@@ -252,7 +248,7 @@ impl TypedOp for NormConcat {
                 self, source, node, target, mapping
             ))
         } else {
-            bail!("Pulsify for Concat on a separate axis is not implemented (but possible)");
+            self.pulsify_along_other_axis(node, target, mapping)
         }
     }
 
@@ -284,6 +280,38 @@ impl TypedOp for NormConcat {
         patch.shunt_outside(OutletId::new(node.id, 0), OutletId::new(node_id, 0))?;
         return Ok(Some(patch));
     }
+
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        _node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        let output_strides = crate::aot::strides(emitter.output_shape(0));
+        let output = emitter.output(0)?;
+        let mut offset = 0;
+        let mut input_idx = 0;
+        for slice in &self.slices {
+            let (shape, input) = match slice {
+                NormConcatSlice::Const(c) => (c.shape().to_vec(), emitter.weight(c)?),
+                NormConcatSlice::Var => {
+                    input_idx += 1;
+                    (emitter.input_shape(input_idx - 1).to_vec(), emitter.input(input_idx - 1))
+                }
+            };
+            emitter.push(format!(
+                "copy({}, {}, {}, &mut {}[{}..], {});",
+                crate::aot::usize_slice(&shape),
+                input,
+                crate::aot::usize_slice(&crate::aot::strides(&shape)),
+                output,
+                offset * output_strides[self.axis],
+                crate::aot::usize_slice(&output_strides)
+            ));
+            offset += shape[self.axis];
+        }
+        Ok(())
+    }
 }
 
 impl StatelessOp for NormConcat {
@@ -300,6 +328,43 @@ impl StatelessOp for NormConcat {
 }
 
 impl NormConcat {
+    fn pulsify_along_other_axis(
+        &self,
+        node: &NormalizedNode,
+        target: &mut PulsedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        if self.slices.iter().any(|s| !s.is_var()) {
+            bail!("Concat of constants and streams on a separate axis can not be pulsified")
+        }
+        let facts = node
+            .inputs
+            .iter()
+            .map(|i| Ok(target.outlet_fact(mapping[i])?.clone()))
+            .collect::<TractResult<TVec<_>>>()?;
+        if facts
+            .iter()
+            .any(|f| !f.is_streaming() || f.axis != facts[0].axis || f.pulse() != facts[0].pulse())
+        {
+            bail!("Concat inputs must stream along the same axis with the same pulse")
+        }
+        // align all inputs on the most delayed one
+        let delay = facts.iter().map(|f| f.delay).max().unwrap();
+        let mut inputs = tvec!();
+        for (ix, fact) in facts.iter().enumerate() {
+            let mut input = mapping[&node.inputs[ix]];
+            if fact.delay < delay {
+                input = target.wire_node(
+                    format!("{}/Delay-{}", node.name, ix),
+                    Delay::new(&fact, delay - fact.delay, 0),
+                    &[input],
+                )?[0];
+            }
+            inputs.push(input);
+        }
+        target.wire_node(&*node.name, self.clone(), &*inputs)
+    }
+
     fn pulsify_along_concat_axis_t<T: Datum>(
         &self,
         _source: &NormalizedModel,
@@ -348,6 +413,17 @@ impl NormConcat {
     }
 }
 
+impl PulsedOp for NormConcat {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.shape[self.axis] = inputs.iter().map(|f| f.shape[self.axis]).sum();
+        Ok(tvec!(fact))
+    }
+
+    pulsed_op_as_op!();
+    pulsed_op_to_typed_op!();
+}
+
 #[derive(Copy, Clone, Debug)]
 enum RangeInRange {
     /// ----[--]<-->HHHH----
@@ -516,6 +592,16 @@ impl<T: Datum> OpState for PulsedSameAxisConcatState<T> {
 
         return Ok(tvec!(data.into_arc_tensor()));
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!(tensor0(self.current_pos as i64)))
+    }
+
+    fn load(&mut self, mut tensors: TVec<Tensor>) -> TractResult<()> {
+        let current_pos = args_1!(tensors);
+        self.current_pos = *current_pos.to_scalar::<i64>()? as usize;
+        Ok(())
+    }
 }
 
 ////////////////////////////////////////////////
@@ -1,7 +1,5 @@
 use crate::internal::*;
 
-// TODO: canonicalize as Reshape
-
 #[derive(Debug, Clone, new, Default)]
 pub struct Flatten {
     axis: usize,
@@ -68,4 +66,17 @@ impl TypedOp for Flatten {
             self.compute_shape(&*inputs[0].shape.to_tvec()).as_ref(),
         )?))
     }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let shape = self.compute_shape(&*model.outlet_fact(node.inputs[0])?.shape.to_tvec());
+        Ok(Some(TypedModelPatch::single_unary_op(
+            model,
+            node,
+            super::reshape::TypedReshape::new(shape.iter().cloned().collect()),
+        )?))
+    }
 }
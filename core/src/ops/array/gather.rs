@@ -91,6 +91,23 @@ impl TypedOp for Gather {
                 .compute_output_shape(&*inputs[0].shape.to_tvec(), &*inputs[1].shape.to_tvec())?
         )?))
     }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        if let Some(ref indices) = model.outlet_fact(node.inputs[1])?.konst {
+            let rank = model.outlet_fact(node.inputs[0])?.shape.rank();
+            let op = TypedGather::new(self.resolved_axis(rank)?, indices.clone());
+            let mut patch = TypedModelPatch::default();
+            let tap = patch.tap_model(model, node.inputs[0])?;
+            let wire = patch.wire_node(&*node.name, op, &[tap])?[0];
+            patch.shunt_outside(OutletId::new(node.id, 0), wire)?;
+            return Ok(Some(patch));
+        }
+        Ok(None)
+    }
 }
 
 impl StatelessOp for Gather {
@@ -125,6 +142,81 @@ impl InferenceRulesOp for Gather {
     to_typed!();
 }
 
+/// Gather with constant indices.
+#[derive(Debug, Clone, new)]
+pub struct TypedGather {
+    axis: usize,
+    indices: Arc<Tensor>,
+}
+
+impl TypedGather {
+    fn as_gather(&self) -> Gather {
+        Gather::new(self.axis as i64)
+    }
+}
+
+impl Op for TypedGather {
+    fn name(&self) -> Cow<str> {
+        "TypedGather".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axis: {}, indices: {:?}", self.axis, self.indices)])
+    }
+
+    op_as_typed_op!();
+    op_as_pulsed_op!();
+}
+
+impl StatelessOp for TypedGather {
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        self.as_gather().eval(tvec!(inputs[0].clone(), self.indices.clone()))
+    }
+}
+
+impl TypedOp for TypedGather {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let indices_shape: TVec<TDim> = self.indices.shape().iter().map(|d| d.to_dim()).collect();
+        Ok(tvec!(TypedFact::dt_shape(
+            inputs[0].datum_type,
+            &*self
+                .as_gather()
+                .compute_output_shape(&*inputs[0].shape.to_tvec(), &*indices_shape)?
+        )?))
+    }
+
+    fn pulsify(
+        &self,
+        _source: &NormalizedModel,
+        node: &NormalizedNode,
+        target: &mut PulsedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        _pulse: usize,
+    ) -> TractResult<TVec<OutletId>> {
+        let input = mapping[&node.inputs[0]];
+        if target.outlet_fact(input)?.axis == self.axis {
+            bail!("{} can not be pulsified: gathering along the streaming axis", node.name)
+        }
+        target.wire_node(&*node.name, self.clone(), &[input])
+    }
+}
+
+impl PulsedOp for TypedGather {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.shape = self.as_gather().compute_output_shape(&*fact.shape, self.indices.shape())?;
+        if fact.axis > self.axis {
+            fact.axis = fact.axis + self.indices.rank() - 1;
+        }
+        Ok(tvec!(fact))
+    }
+
+    pulsed_op_as_op!();
+    pulsed_op_to_typed_op!();
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
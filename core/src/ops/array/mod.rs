@@ -52,6 +52,7 @@ mod gather;
 mod pad;
 mod permute_axes;
 mod reshape;
+mod resize;
 mod rm_dims;
 mod shape;
 mod size;
@@ -69,10 +70,11 @@ pub use self::constant_like::EyeLike;
 pub use self::constant_of_shape::ConstantOfShape;
 pub use self::crop::Crop;
 pub use self::flatten::Flatten;
-pub use self::gather::Gather;
+pub use self::gather::{Gather, TypedGather};
 pub use self::pad::{Pad, PadMode};
 pub use self::permute_axes::PermuteAxes;
-pub use self::reshape::{FiniteReshape, Reshape, TypedReshape};
+pub use self::reshape::{FiniteReshape, PulsedReshape, Reshape, TypedReshape};
+pub use self::resize::{CoordTransformer, Interpolator, NearestMode, Resize};
 pub use self::rm_dims::RmDims;
 pub use self::shape::Shape;
 pub use self::size::Size;
@@ -80,4 +82,4 @@ pub use self::slice::Slice;
 pub use self::split::Split;
 pub use self::squeeze::Squeeze;
 pub use self::strided_slice::StridedSlice;
-pub use self::tile::Tile;
+pub use self::tile::{Tile, TypedTile};
@@ -5,7 +5,11 @@ use ndarray::*;
 #[derive(Debug, Clone, PartialEq)]
 pub enum PadMode {
     Constant(Arc<Tensor>),
+    /// Mirror the input, excluding the edge: [1, 2, 3] -> [3, 2, 1, 2, 3, 2, 1]
     Reflect,
+    /// Mirror the input, including the edge: [1, 2, 3] -> [2, 1, 1, 2, 3, 3, 2]
+    Symmetric,
+    /// Repeat the edge: [1, 2, 3] -> [1, 1, 1, 2, 3, 3, 3]
     Edge,
 }
 
@@ -22,10 +26,36 @@ pub struct Pad {
 }
 
 impl Pad {
+    // mirror and edge pads are read from the input, which must be long enough
+    fn check(&self, shape: &[usize]) -> TractResult<()> {
+        if shape.len() != self.pads.len() {
+            bail!("Expected pads for {} axes, got {:?}", shape.len(), self.pads)
+        }
+        for (&dim, &(bef, aft)) in shape.iter().zip(self.pads.iter()) {
+            let max = match self.mode {
+                PadMode::Constant(_) => continue,
+                PadMode::Reflect => dim.saturating_sub(1),
+                PadMode::Symmetric => dim,
+                PadMode::Edge if dim == 0 => 0,
+                PadMode::Edge => continue,
+            };
+            if bef > max || aft > max {
+                bail!(
+                    "Pads {:?} too large for {:?} padding of a dim of {}",
+                    (bef, aft),
+                    self.mode,
+                    dim
+                )
+            }
+        }
+        Ok(())
+    }
+
     fn eval_t<T>(&self, input: Arc<Tensor>) -> TractResult<Arc<Tensor>>
     where
         T: Copy + Datum,
     {
+        self.check(input.shape())?;
         let input = input.to_array_view::<T>()?;
         let output_shape: Vec<usize> =
             input.shape().iter().zip(self.pads.iter()).map(|(&d, &(a, b))| d + a + b).collect();
@@ -45,7 +75,10 @@ impl Pad {
             .collect();
         let slice_info = SliceInfo::<_, IxDyn>::new(slice_spec).unwrap();
         output.slice_mut(slice_info.as_ref()).assign(&input);
-        if self.mode == PadMode::Reflect || self.mode == PadMode::Edge {
+        if self.mode == PadMode::Reflect
+            || self.mode == PadMode::Symmetric
+            || self.mode == PadMode::Edge
+        {
             for (ax, &(bef, aft)) in self.pads.iter().enumerate() {
                 let axis = Axis(ax);
                 let dim = output.shape()[ax];
@@ -56,6 +89,7 @@ impl Pad {
                         let source_slice = match self.mode {
                             PadMode::Edge => 0,
                             PadMode::Reflect => bef - i,
+                            PadMode::Symmetric => bef - 1 - i,
                             _ => panic!(),
                         };
                         let source =
@@ -70,6 +104,7 @@ impl Pad {
                         let source_slice = match self.mode {
                             PadMode::Edge => dim - aft - 1,
                             PadMode::Reflect => dim - aft - 2 - i,
+                            PadMode::Symmetric => dim - aft - 1 - i,
                             _ => panic!(),
                         };
                         let source =
@@ -158,7 +193,9 @@ impl TypedOp for Pad {
                 }
             },
             PadMode::Edge => bail!("Edge padding mode needs pulse strictly bigger than left padding (pulse={} padding={})", pulse, before),
-            PadMode::Reflect => bail!("Reflect padding mode pulsing is not supported")
+            PadMode::Reflect | PadMode::Symmetric => {
+                bail!("{:?} padding mode pulsing is not supported", self.mode)
+            }
         };
         if extra_delay > 0 {
             input = target.wire_node(
@@ -265,6 +302,20 @@ impl<T: Datum + Copy> OpState for PulsePadOpState<T> {
 
         Ok(tvec!(data.into_arc_tensor()))
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        let mut tensors = tvec!(tensor0(self.current_pos as i64));
+        tensors.extend(self.last_valid_frame.clone());
+        Ok(tensors)
+    }
+
+    fn load(&mut self, tensors: TVec<Tensor>) -> TractResult<()> {
+        let mut tensors = tensors.into_iter();
+        let current_pos = tensors.next().ok_or("Pad state expects a position")?;
+        self.current_pos = *current_pos.to_scalar::<i64>()? as usize;
+        self.last_valid_frame = tensors.next();
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, Default, new)]
@@ -317,3 +368,44 @@ impl<T: Datum + Copy> PulsedOp for PulsePad<T> {
     pulsed_op_as_op!();
     pulsed_op_to_typed_op!();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pad(mode: PadMode) -> Arc<Tensor> {
+        let op = Pad::new(vec![(2, 2)], mode);
+        op.eval(tvec!(rctensor1(&[1, 2, 3]))).unwrap().remove(0)
+    }
+
+    #[test]
+    fn reflect() {
+        assert_eq!(pad(PadMode::Reflect), rctensor1(&[3, 2, 1, 2, 3, 2, 1]));
+    }
+
+    #[test]
+    fn symmetric() {
+        assert_eq!(pad(PadMode::Symmetric), rctensor1(&[2, 1, 1, 2, 3, 3, 2]));
+    }
+
+    #[test]
+    fn edge() {
+        assert_eq!(pad(PadMode::Edge), rctensor1(&[1, 1, 1, 2, 3, 3, 3]));
+    }
+
+    #[test]
+    fn mirror_pads_out_of_range() {
+        let input = tvec!(rctensor1(&[1, 2, 3]));
+        assert!(Pad::new(vec![(3, 0)], PadMode::Reflect).eval(input.clone()).is_err());
+        assert!(Pad::new(vec![(0, 4)], PadMode::Symmetric).eval(input.clone()).is_err());
+        let output = Pad::new(vec![(3, 0)], PadMode::Symmetric).eval(input).unwrap().remove(0);
+        assert_eq!(output, rctensor1(&[3, 2, 1, 1, 2, 3]));
+    }
+
+    #[test]
+    fn symmetric_2d() {
+        let op = Pad::new(vec![(1, 0), (0, 1)], PadMode::Symmetric);
+        let output = op.eval(tvec!(rctensor2(&[[1, 2], [3, 4]]))).unwrap().remove(0);
+        assert_eq!(output, rctensor2(&[[1, 2, 2], [1, 2, 2], [3, 4, 4]]));
+    }
+}
@@ -118,6 +118,26 @@ impl TypedOp for PermuteAxes {
         target.wire_node(&*node.name, self.clone(), &[input])
     }
 
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        _node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        let input_strides = self.compute_shape(&crate::aot::strides(emitter.input_shape(0)));
+        let shape = emitter.output_shape(0).to_vec();
+        let output = emitter.output(0)?;
+        emitter.push(format!(
+            "copy({}, {}, {}, {}, {});",
+            crate::aot::usize_slice(&shape),
+            emitter.input(0),
+            crate::aot::usize_slice(&input_strides),
+            output,
+            crate::aot::usize_slice(&crate::aot::strides(&shape))
+        ));
+        Ok(())
+    }
+
     typed_op_as_op!();
 }
 
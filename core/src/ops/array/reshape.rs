@@ -126,6 +126,15 @@ impl TypedOp for TypedReshape {
         Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, &*self.shape)?))
     }
 
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        _node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        emitter.alias(0, 0)
+    }
+
     fn codegen(
         &self,
         model: &TypedModel,
@@ -145,6 +154,91 @@ impl TypedOp for TypedReshape {
         }
         Ok(None)
     }
+
+    fn pulsify(
+        &self,
+        source: &NormalizedModel,
+        node: &NormalizedNode,
+        target: &mut PulsedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        _pulse: usize,
+    ) -> TractResult<TVec<OutletId>> {
+        let input = mapping[&node.inputs[0]];
+        let fact = target.outlet_fact(input)?.clone();
+        let input_shape = source.outlet_fact(node.inputs[0])?.shape.to_tvec();
+        let product = |dims: &[TDim]| -> Option<i32> {
+            dims.iter().try_fold(1, |acc, d| d.to_integer().ok().map(|d| acc * d))
+        };
+        // the streaming axis must be left whole, with the same number of
+        // elements before it: only the axes around it can be reshaped
+        let axis = self.shape.iter().position(|d| *d == input_shape[fact.axis]).filter(|&axis| {
+            product(&self.shape[..axis]).is_some()
+                && product(&self.shape[..axis]) == product(&input_shape[..fact.axis])
+                && product(&self.shape[axis + 1..]).is_some()
+        });
+        let axis = if let Some(axis) = axis {
+            axis
+        } else {
+            bail!(
+                "{} can not be pulsified: reshaping from {:?} to {:?} involves the streaming axis",
+                node.name,
+                input_shape,
+                self.shape
+            )
+        };
+        let shape = self
+            .shape
+            .iter()
+            .enumerate()
+            .map(|(ix, d)| Ok(if ix == axis { fact.pulse() } else { d.to_integer()? as usize }))
+            .collect::<TractResult<_>>()?;
+        target.wire_node(&*node.name, PulsedReshape::new(shape, axis), &[input])
+    }
+}
+
+/// Pulsed form of TypedReshape, moving the streaming axis to `axis`.
+#[derive(Debug, Clone, new, Default)]
+pub struct PulsedReshape {
+    pub shape: TVec<usize>,
+    pub axis: usize,
+}
+
+impl Op for PulsedReshape {
+    fn name(&self) -> Cow<str> {
+        "PulsedReshape".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "to shape: {}, streaming axis: {}",
+            self.shape.iter().join("x"),
+            self.axis
+        )])
+    }
+
+    not_a_typed_op!();
+    op_as_pulsed_op!();
+}
+
+impl StatelessOp for PulsedReshape {
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        FiniteReshape::new(self.shape.clone()).eval(inputs)
+    }
+}
+
+impl PulsedOp for PulsedReshape {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.shape = self.shape.clone();
+        fact.axis = self.axis;
+        Ok(tvec!(fact))
+    }
+
+    fn to_typed(&self) -> Box<dyn TypedOp> {
+        Box::new(FiniteReshape::new(self.shape.clone()))
+    }
+
+    pulsed_op_as_op!();
 }
 
 #[derive(Debug, Clone, new, Default)]
@@ -178,5 +272,14 @@ impl TypedOp for FiniteReshape {
         Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, &*self.shape)?))
     }
 
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        _node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        emitter.alias(0, 0)
+    }
+
     typed_op_as_op!();
 }
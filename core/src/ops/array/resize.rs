@@ -0,0 +1,233 @@
+use crate::internal::*;
+use ndarray::*;
+
+/// How an output coordinate maps back to the input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CoordTransformer {
+    /// Pixel centers are aligned: `(x + 0.5) * in / out - 0.5`
+    HalfPixel,
+    /// Corner pixels are aligned: `x * (in - 1) / (out - 1)`
+    AlignCorners,
+    /// `x * in / out`
+    Asymmetric,
+    /// TensorFlow half pixel centers for nearest neighbor: `(x + 0.5) * in / out`
+    TfHalfPixelForNN,
+}
+
+impl Default for CoordTransformer {
+    fn default() -> CoordTransformer {
+        CoordTransformer::Asymmetric
+    }
+}
+
+impl CoordTransformer {
+    fn transform(&self, x: usize, input: usize, output: usize) -> f32 {
+        let scale = input as f32 / output as f32;
+        match self {
+            CoordTransformer::HalfPixel => (x as f32 + 0.5) * scale - 0.5,
+            CoordTransformer::AlignCorners if output == 1 => 0.0,
+            CoordTransformer::AlignCorners => x as f32 * (input - 1) as f32 / (output - 1) as f32,
+            CoordTransformer::Asymmetric => x as f32 * scale,
+            CoordTransformer::TfHalfPixelForNN => (x as f32 + 0.5) * scale,
+        }
+    }
+}
+
+/// Rounding of the input coordinate for nearest neighbor interpolation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NearestMode {
+    Floor,
+    Ceil,
+    RoundPreferFloor,
+    RoundPreferCeil,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Interpolator {
+    /// Linear interpolation, computed in f32
+    Linear,
+    Nearest(NearestMode),
+}
+
+impl Default for Interpolator {
+    fn default() -> Interpolator {
+        Interpolator::Nearest(NearestMode::Floor)
+    }
+}
+
+/// Resample some axes of the input to fixed sizes.
+#[derive(Debug, Clone, new, Default)]
+pub struct Resize {
+    axes: Vec<usize>,
+    sizes: Vec<usize>,
+    coord_transformer: CoordTransformer,
+    interpolator: Interpolator,
+}
+
+impl Resize {
+    fn output_datum_type(&self, input: DatumType) -> DatumType {
+        match self.interpolator {
+            Interpolator::Linear => f32::datum_type(),
+            Interpolator::Nearest(_) => input,
+        }
+    }
+
+    fn eval_nearest<T: Datum + Copy>(
+        &self,
+        mode: NearestMode,
+        input: &Tensor,
+    ) -> TractResult<Arc<Tensor>> {
+        let mut data = input.to_array_view::<T>()?.to_owned();
+        for (&axis, &size) in self.axes.iter().zip(self.sizes.iter()) {
+            let input_len = data.shape()[axis];
+            let indices: Vec<usize> = (0..size)
+                .map(|x| {
+                    let x = self.coord_transformer.transform(x, input_len, size);
+                    let x = match mode {
+                        NearestMode::Floor => x.floor(),
+                        NearestMode::Ceil => x.ceil(),
+                        NearestMode::RoundPreferFloor => (x - 0.5).ceil(),
+                        NearestMode::RoundPreferCeil => (x + 0.5).floor(),
+                    };
+                    (x.max(0.0) as usize).min(input_len - 1)
+                })
+                .collect();
+            data = data.select(Axis(axis), &indices);
+        }
+        Ok(data.into_arc_tensor())
+    }
+
+    fn eval_linear(&self, input: &Tensor) -> TractResult<Arc<Tensor>> {
+        let input = input.cast_to::<f32>()?;
+        let mut data = input.to_array_view::<f32>()?.to_owned();
+        for (&axis, &size) in self.axes.iter().zip(self.sizes.iter()) {
+            let input_len = data.shape()[axis];
+            let mut shape = data.shape().to_vec();
+            shape[axis] = size;
+            let mut output = ArrayD::<f32>::zeros(shape);
+            for x in 0..size {
+                let coord = self.coord_transformer.transform(x, input_len, size);
+                let coord = coord.max(0.0).min((input_len - 1) as f32);
+                let lo = coord as usize;
+                let hi = (lo + 1).min(input_len - 1);
+                let w = coord - lo as f32;
+                let mut target = output.index_axis_mut(Axis(axis), x);
+                target.assign(&data.index_axis(Axis(axis), lo));
+                target *= 1.0 - w;
+                target.scaled_add(w, &data.index_axis(Axis(axis), hi));
+            }
+            data = output;
+        }
+        Ok(data.into_arc_tensor())
+    }
+}
+
+impl Op for Resize {
+    fn name(&self) -> Cow<str> {
+        "Resize".into()
+    }
+
+    op_as_typed_op!();
+    not_a_pulsed_op!();
+}
+
+impl StatelessOp for Resize {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let output = match self.interpolator {
+            Interpolator::Linear => self.eval_linear(&input)?,
+            Interpolator::Nearest(mode) => {
+                dispatch_copy!(Self::eval_nearest(input.datum_type())(self, mode, &input))?
+            }
+        };
+        Ok(tvec!(output))
+    }
+}
+
+impl InferenceRulesOp for Resize {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.given(&inputs[0].datum_type, move |s, dt| {
+            s.equals(&outputs[0].datum_type, self.output_datum_type(dt))
+        })?;
+        s.equals(&inputs[0].rank, &outputs[0].rank)?;
+        s.given(&inputs[0].rank, move |s, rank| {
+            for ax in 0..rank as usize {
+                if let Some(ix) = self.axes.iter().position(|&a| a == ax) {
+                    s.equals(&outputs[0].shape[ax], self.sizes[ix].to_dim())?;
+                } else {
+                    s.equals(&inputs[0].shape[ax], &outputs[0].shape[ax])?;
+                }
+            }
+            Ok(())
+        })
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Resize {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.datum_type = self.output_datum_type(fact.datum_type);
+        for (&axis, &size) in self.axes.iter().zip(self.sizes.iter()) {
+            fact.shape.set_dim(axis, size.to_dim())?
+        }
+        Ok(tvec!(fact))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn resize(coord: CoordTransformer, interpolator: Interpolator) -> Arc<Tensor> {
+        let op = Resize::new(vec![0], vec![4], coord, interpolator);
+        op.eval(tvec!(rctensor1(&[1.0f32, 2.0]))).unwrap().remove(0)
+    }
+
+    #[test]
+    fn linear_asymmetric() {
+        let r = resize(CoordTransformer::Asymmetric, Interpolator::Linear);
+        r.close_enough(&tensor1(&[1.0f32, 1.5, 2.0, 2.0]), true).unwrap();
+    }
+
+    #[test]
+    fn linear_align_corners() {
+        let r = resize(CoordTransformer::AlignCorners, Interpolator::Linear);
+        r.close_enough(&tensor1(&[1.0f32, 4.0 / 3.0, 5.0 / 3.0, 2.0]), true).unwrap();
+    }
+
+    #[test]
+    fn linear_half_pixel() {
+        let r = resize(CoordTransformer::HalfPixel, Interpolator::Linear);
+        r.close_enough(&tensor1(&[1.0f32, 1.25, 1.75, 2.0]), true).unwrap();
+    }
+
+    #[test]
+    fn nearest_asymmetric() {
+        let r = resize(CoordTransformer::Asymmetric, Interpolator::Nearest(NearestMode::Floor));
+        assert_eq!(r, rctensor1(&[1.0f32, 1.0, 2.0, 2.0]));
+    }
+
+    #[test]
+    fn nearest_2d_keeps_type() {
+        let op = Resize::new(
+            vec![0, 1],
+            vec![2, 4],
+            CoordTransformer::Asymmetric,
+            Interpolator::Nearest(NearestMode::Floor),
+        );
+        let r = op.eval(tvec!(rctensor2(&[[1, 2]]))).unwrap().remove(0);
+        assert_eq!(r, rctensor2(&[[1, 1, 2, 2], [1, 1, 2, 2]]));
+    }
+}
@@ -142,9 +142,21 @@ impl TypedOp for RmDims {
         _pulse: usize,
     ) -> TractResult<TVec<OutletId>> {
         let input = mapping[&node.inputs[0]];
+        if self.axes.contains(&target.outlet_fact(input)?.axis) {
+            bail!("{} can not be pulsified: removing the streaming axis", node.name)
+        }
         target.wire_node(&*node.name, self.clone(), &[input])
     }
 
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        _node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        emitter.alias(0, 0)
+    }
+
     typed_op_as_op!();
 }
 
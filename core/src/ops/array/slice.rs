@@ -155,14 +155,36 @@ impl<D: DimLike + ToDim> TypedOp for Slice<D> {
         target.wire_node(&*node.name, op, &[input])
     }
 
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        _node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        let input_strides = crate::aot::strides(emitter.input_shape(0));
+        let offset = self.start.to_integer()? as usize * input_strides[self.axis];
+        let shape = emitter.output_shape(0).to_vec();
+        let output = emitter.output(0)?;
+        emitter.push(format!(
+            "copy({}, &{}[{}..], {}, {}, {});",
+            crate::aot::usize_slice(&shape),
+            emitter.input(0),
+            offset,
+            crate::aot::usize_slice(&input_strides),
+            output,
+            crate::aot::usize_slice(&crate::aot::strides(&shape))
+        ));
+        Ok(())
+    }
+
     typed_op_as_op!();
 }
 
 impl<D: DimLike + ToDim> PulsedOp for Slice<D> {
     fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        // slicing along the streaming axis goes through PulsedAxisSlice
         let mut fact = inputs[0].clone();
-        fact.delay += self.start.to_integer()? as usize;
-        fact.dim = (self.end.clone() - &self.start).to_dim();
+        fact.shape[self.axis] = (self.end.clone() - &self.start).to_integer()? as usize;
         Ok(tvec!(fact))
     }
 
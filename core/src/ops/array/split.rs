@@ -43,7 +43,7 @@ impl Op for Split {
     }
 
     op_as_typed_op!();
-    not_a_pulsed_op!();
+    op_as_pulsed_op!();
 }
 
 impl StatelessOp for Split {
@@ -100,4 +100,35 @@ impl TypedOp for Split {
             })
             .collect()
     }
+
+    fn pulsify(
+        &self,
+        _source: &NormalizedModel,
+        node: &NormalizedNode,
+        target: &mut PulsedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        _pulse: usize,
+    ) -> TractResult<TVec<OutletId>> {
+        let input = mapping[&node.inputs[0]];
+        if target.outlet_fact(input)?.axis == self.axis {
+            bail!("{} can not be pulsified: split along the streaming axis", node.name)
+        }
+        target.wire_node(&*node.name, self.clone(), &[input])
+    }
+}
+
+impl PulsedOp for Split {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        self.split_dims(inputs[0].shape[self.axis])?
+            .into_iter()
+            .map(|d| {
+                let mut fact = inputs[0].clone();
+                fact.shape[self.axis] = d;
+                Ok(fact)
+            })
+            .collect()
+    }
+
+    pulsed_op_as_op!();
+    pulsed_op_to_typed_op!();
 }
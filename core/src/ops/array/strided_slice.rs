@@ -315,9 +315,9 @@ impl InferenceRulesOp for StridedSlice {
             for (ix, &axis) in axes.iter().enumerate() {
                 let d = input_shape.dim(axis);
                 let preped = self.prepare_one_dim(ix, &d, &begin, &end, &strides);
-                if preped.begin != 0.to_dim() || preped.end != input.shape.dim(ix) {
+                if preped.begin != 0.to_dim() || preped.end != input.shape.dim(axis) {
                     wire = target.wire_node(
-                        format!("{}-Slice", node.name),
+                        format!("{}-Slice-{}", node.name, ix),
                         crate::ops::array::Slice::new(axis, preped.begin, preped.end),
                         [wire].as_ref(),
                     )?[0];
@@ -325,7 +325,7 @@ impl InferenceRulesOp for StridedSlice {
                 if preped.stride != 1 {
                     wire = target.wire_node(
                         format!("{}-Stride-{}", node.name, ix),
-                        crate::ops::downsample::Downsample::new(ix, preped.stride as usize, 0),
+                        crate::ops::downsample::Downsample::new(axis, preped.stride as usize, 0),
                         [wire].as_ref(),
                     )?[0];
                 }
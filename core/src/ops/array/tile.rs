@@ -110,7 +110,7 @@ impl Op for TypedTile {
     }
 
     op_as_typed_op!();
-    not_a_pulsed_op!();
+    op_as_pulsed_op!();
 }
 
 impl StatelessOp for TypedTile {
@@ -132,4 +132,37 @@ impl TypedOp for TypedTile {
             .collect::<TVec<_>>();
         Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, &*shape)?))
     }
+
+    fn pulsify(
+        &self,
+        _source: &NormalizedModel,
+        node: &NormalizedNode,
+        target: &mut PulsedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        _pulse: usize,
+    ) -> TractResult<TVec<OutletId>> {
+        let input = mapping[&node.inputs[0]];
+        let fact = target.outlet_fact(input)?;
+        if self.multipliers[fact.axis] != 1 {
+            bail!(
+                "{} can not be pulsified: tiling along the streaming axis (axis {})",
+                node.name,
+                fact.axis
+            )
+        }
+        target.wire_node(&*node.name, self.clone(), &[input])
+    }
+}
+
+impl PulsedOp for TypedTile {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        for (d, &m) in fact.shape.iter_mut().zip(self.multipliers.iter()) {
+            *d *= m;
+        }
+        Ok(tvec!(fact))
+    }
+
+    pulsed_op_as_op!();
+    pulsed_op_to_typed_op!();
 }
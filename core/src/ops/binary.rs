@@ -51,6 +51,11 @@ pub trait BinMiniOp: fmt::Debug + objekt::Clone + Send + Sync + 'static + Downca
     fn cost_per_element(&self, dt: DatumType) -> TVec<(Cost, usize)> {
         tvec!()
     }
+    /// Rust expression computing the op on f32 `a` and `b`, for ahead-of-time
+    /// compilation.
+    fn rust_expr(&self) -> Option<&'static str> {
+        None
+    }
 }
 clone_trait_object!(BinMiniOp);
 downcast_rs::impl_downcast!(BinMiniOp);
@@ -388,6 +393,17 @@ impl TypedOp for TypedBinOp {
         pulsify_bin(node, self, target, mapping)
     }
 
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        let a = (emitter.input(0), emitter.input_shape(0).into());
+        let b = (emitter.input(1), emitter.input_shape(1).into());
+        emit_rust_bin(&*self.0, node, emitter, a, b)
+    }
+
     typed_op_as_op!();
 }
 
@@ -404,6 +420,42 @@ impl PulsedOp for TypedBinOp {
     pulsed_op_to_typed_op!();
 }
 
+fn emit_rust_bin(
+    mini_op: &dyn BinMiniOp,
+    node: &NormalizedNode,
+    emitter: &mut crate::aot::Emitter,
+    a: (String, TVec<usize>),
+    b: (String, TVec<usize>),
+) -> TractResult<()> {
+    use crate::aot::{broadcast_strides, usize_slice};
+    let expr = mini_op.rust_expr().ok_or_else(|| {
+        format!(
+            "{} can not be compiled ahead of time: no Rust form for {}",
+            node.name,
+            mini_op.name()
+        )
+    })?;
+    let shape: TVec<usize> = emitter.output_shape(0).into();
+    let (shape, a_strides, b_strides) = if a.1 == shape && b.1 == shape {
+        let len = shape.iter().product::<usize>();
+        (tvec!(len), tvec!(1), tvec!(1))
+    } else {
+        (shape.clone(), broadcast_strides(&a.1, &shape), broadcast_strides(&b.1, &shape))
+    };
+    let output = emitter.output(0)?;
+    emitter.push(format!(
+        "zip({}, {}, {}, {}, {}, {}, |a, b| {});",
+        usize_slice(&shape),
+        a.0,
+        usize_slice(&a_strides),
+        b.0,
+        usize_slice(&b_strides),
+        output,
+        expr
+    ));
+    Ok(())
+}
+
 fn pulsify_bin(
     node: &NormalizedNode,
     op: &dyn PulsedOp,
@@ -411,19 +463,30 @@ fn pulsify_bin(
     mapping: &HashMap<OutletId, OutletId>,
 ) -> TractResult<TVec<OutletId>> {
     use crate::pulse::delay::Delay;
-    let delay = (0..2)
-        .map(|ix| target.outlet_fact(mapping[&node.inputs[ix]]).unwrap().delay)
-        .max()
-        .unwrap();
+    let facts = (0..2)
+        .map(|ix| Ok(target.outlet_fact(mapping[&node.inputs[ix]])?.clone()))
+        .collect::<TractResult<TVec<_>>>()?;
+    if facts.iter().any(|f| !f.is_streaming())
+        || facts[0].axis != facts[1].axis
+        || facts[0].pulse() != facts[1].pulse()
+    {
+        bail!(
+            "{} can not be pulsified: inputs must stream along the same axis with the same pulse ({:?} and {:?})",
+            node.name,
+            facts[0],
+            facts[1]
+        )
+    }
+    // align both inputs on the most delayed one
+    let delay = facts.iter().map(|f| f.delay).max().unwrap();
     let mut inputs = tvec!();
-    for ix in 0..2 {
+    for (ix, fact) in facts.iter().enumerate() {
         let mut input = mapping[&node.inputs[ix]];
-        let fact = target.outlet_fact(input)?.clone();
         if fact.delay < delay {
             let add_delay = delay - fact.delay;
             input = target.wire_node(
                 format!("{}/Delay", &*node.name),
-                Delay::new(&fact, add_delay, 0),
+                Delay::new(fact, add_delay, 0),
                 &[input],
             )?[0];
         }
@@ -534,6 +597,17 @@ impl TypedOp for UnaryOp {
         target.wire_node(&*node.name, self.clone(), &[input])
     }
 
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        let a = (emitter.weight(&self.a)?, self.a.shape().into());
+        let b = (emitter.input(0), emitter.input_shape(0).into());
+        emit_rust_bin(&*self.mini_op, node, emitter, a, b)
+    }
+
     typed_op_as_op!();
 }
 
@@ -633,6 +707,17 @@ impl TypedOp for MergeOp {
         pulsify_bin(node, self, target, mapping)
     }
 
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        let a = (emitter.input(0), emitter.input_shape(0).into());
+        let b = (emitter.input(1), emitter.input_shape(1).into());
+        emit_rust_bin(&*self.0, node, emitter, a, b)
+    }
+
     typed_op_as_op!();
 }
 
@@ -685,6 +770,17 @@ impl TypedOp for MergeOpUnicast {
             .collect())
     }
 
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        let a = (emitter.input(0), emitter.input_shape(0).into());
+        let b = (emitter.input(1), emitter.input_shape(1).into());
+        emit_rust_bin(&*self.0, node, emitter, a, b)
+    }
+
     typed_op_as_op!();
 }
 
@@ -707,6 +803,7 @@ macro_rules! bin_to_super_type {
      $(flip: $flip:expr,)?
      $(declutter: $declutter:expr,)?
      $(validation: $validation:expr,)?
+     $(rust: $rust:expr,)?
      $( [$($typ:ident),*] => $cab:expr),*) => {
         #[derive(Debug, Clone)]
         pub struct $Op;
@@ -779,6 +876,11 @@ macro_rules! bin_to_super_type {
                 $validation
             }
             )?
+            $(
+            fn rust_expr(&self) -> Option<&'static str> {
+                Some($rust)
+            }
+            )?
         }
 
         pub mod $func {
@@ -90,6 +90,25 @@ impl TypedOp for AvgPool {
         self.pool_spec.pulsify(source, node, self, target, mapping)
     }
 
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        _node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        let input_shape: TVec<usize> = emitter.input_shape(0).into();
+        let geo = self.pool_spec.emit_rust_geo(emitter, &input_shape);
+        let output = emitter.output(0)?;
+        emitter.push(format!(
+            "avg_pool(&{}, {}, {}, {});",
+            geo,
+            self.count_include_pad,
+            emitter.input(0),
+            output
+        ));
+        Ok(())
+    }
+
     fn codegen(
         &self,
         model: &TypedModel,
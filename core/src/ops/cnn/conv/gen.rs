@@ -269,7 +269,8 @@ impl TypedOp for Conv {
             let kshape: TVec<usize> =
                 inputs[1].shape.iter().map(|d| d.to_integer().unwrap() as _).collect();
             let oshape = self.output_shape(&*inputs[0].shape.to_tvec(), &*kshape);
-            Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, &*oshape)?))
+            let dt = self.override_output_datum_type.unwrap_or(inputs[0].datum_type);
+            Ok(tvec!(TypedFact::dt_shape(dt, &*oshape)?))
         } else {
             bail!("Streaming on kernel is not typeable")
         }
@@ -135,9 +135,7 @@ impl ConvUnary {
         Ok(packed_as.insert_axis(Axis(0)))
     }
 
-    fn bias_as_non_linear<T>(
-        &self,
-    ) -> TractResult<Option<ArrayD<Vec<FusedSpec<T>>>>>
+    fn bias_as_non_linear<T>(&self) -> TractResult<Option<ArrayD<Vec<FusedSpec<T>>>>>
     where
         T: Datum + Copy,
     {
@@ -336,7 +334,11 @@ impl StatelessOp for ConvUnary {
 
 impl TypedOp for ConvUnary {
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
-        self.pool_spec.output_facts(inputs)
+        let mut facts = self.pool_spec.output_facts(inputs)?;
+        if let Some(q) = &self.q_params {
+            facts[0].datum_type = q.c_datum_type;
+        }
+        Ok(facts)
     }
 
     fn axes_info(&self, model: &TypedModel, node: &TypedNode) -> TractResult<AxesInfo> {
@@ -409,6 +411,50 @@ impl TypedOp for ConvUnary {
         )))
     }
 
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        if self.q_params.is_some() {
+            bail!("{} can not be compiled ahead of time: quantized convolution", node.name)
+        }
+        let input_shape: TVec<usize> = emitter.input_shape(0).into();
+        let (input, patch, output) = self.pool_spec.compute_geo(&input_shape);
+        let ci = input.c() / self.group;
+        let co = output.c() / self.group;
+        let k = ci * patch.standard_layout_data_field.len();
+        let n = output.hw_dims().iter().product::<usize>();
+        let pointwise = k == ci
+            && patch.spec.strides.iter().all(|&s| s == 1)
+            && patch.pad_before.iter().all(|&p| p == 0);
+        if ci != 1 || co != 1 {
+            emitter.reserve_matmul(co, k, n);
+            if !pointwise {
+                emitter.reserve_im2col(k * n);
+            }
+        }
+        let geo = self.pool_spec.emit_rust_geo(emitter, &input_shape);
+        let kernel = emitter.weight(&self.kernel_as_group_o_ihw::<f32>()?.into_tensor())?;
+        let bias = match &self.bias {
+            Some(bias) if bias.len() == *output.c() => format!("Some({})", emitter.weight(bias)?),
+            Some(_) => bail!("{} can not be compiled ahead of time: bias shape", node.name),
+            None => "None".to_string(),
+        };
+        let output = emitter.output(0)?;
+        emitter.push(format!(
+            "conv(&{}, {}, {}, {}, {}, {});",
+            geo,
+            self.group,
+            emitter.input(0),
+            kernel,
+            bias,
+            output
+        ));
+        Ok(())
+    }
+
     fn dispose_dummy_axis(
         &self,
         model: &TypedModel,
@@ -518,6 +564,13 @@ impl TypedOp for ConvUnary {
                             ),
                             &[wire],
                         )?[0];
+                        if let Some(ref bias) = self.bias {
+                            wire = patch.wire_node(
+                                format!("{}-Bias", node.name),
+                                crate::ops::math::add::unary(bias.clone()),
+                                &[wire],
+                            )?[0];
+                        }
                         wire = patch.wire_node(
                             &*node.name,
                             TypedReshape::new(node.outputs[0].fact.shape.to_tvec()),
@@ -529,13 +582,17 @@ impl TypedOp for ConvUnary {
                 }
                 if (0..spatial_rank).all(|ax| self.pool_spec.padding.valid_dim(ax))
                     && self.group == 1
+                    && self.q_params.is_none()
                 {
                     let mut patch = TypedModelPatch::default();
                     let wire = patch.tap_model(model, node.inputs[0])?;
                     let wire = self.wire_as_im2col_pair(&mut patch, &*node.name, wire, true)?;
                     patch.shunt_outside(OutletId::new(node.id, 0), wire)?;
                     return Ok(Some(patch));
-                } else if self.group != 1 && self.group == self.output_channels() {
+                } else if self.group != 1
+                    && self.group == self.output_channels()
+                    && self.q_params.is_none()
+                {
                     return Ok(Some(TypedModelPatch::single_unary_op(
                         model,
                         node,
@@ -90,6 +90,22 @@ impl TypedOp for MaxPool {
         self.pool_spec.pulsify(source, node, self, target, mapping)
     }
 
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        if self.with_index_outputs.is_some() {
+            bail!("{} can not be compiled ahead of time: index output is not supported", node.name)
+        }
+        let input_shape: TVec<usize> = emitter.input_shape(0).into();
+        let geo = self.pool_spec.emit_rust_geo(emitter, &input_shape);
+        let output = emitter.output(0)?;
+        emitter.push(format!("max_pool(&{}, {}, {});", geo, emitter.input(0), output));
+        Ok(())
+    }
+
     fn codegen(
         &self,
         model: &TypedModel,
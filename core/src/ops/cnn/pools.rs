@@ -57,6 +57,31 @@ impl PoolSpec {
         (input_shape, patch, output_shape)
     }
 
+    /// Declares the geometry of the op over input_full_shape as a constant of
+    /// the generated code, returning its name.
+    pub fn emit_rust_geo(
+        &self,
+        emitter: &mut crate::aot::Emitter,
+        input_full_shape: &[usize],
+    ) -> String {
+        use crate::aot::usize_slice;
+        let (input_shape, patch, output_shape) = self.compute_geo(input_full_shape);
+        let geo = format!(
+            "Geo {{ batch: {}, nhwc: {}, c_in: {}, c_out: {}, input: {}, output: {}, kernel: {}, strides: {}, dilations: {}, pad_before: {} }}",
+            input_shape.n(),
+            self.data_format == DataFormat::NHWC,
+            input_shape.c(),
+            output_shape.c(),
+            usize_slice(input_shape.hw_dims()),
+            usize_slice(output_shape.hw_dims()),
+            usize_slice(&patch.spec.kernel_shape),
+            usize_slice(&patch.spec.strides),
+            usize_slice(&patch.spec.dilations),
+            usize_slice(&patch.pad_before),
+        );
+        emitter.constant("Geo", geo)
+    }
+
     pub fn rules_for_shape<'r, 'p: 'r, 's: 'r>(
         &'s self,
         s: &mut Solver<'r>,
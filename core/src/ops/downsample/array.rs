@@ -1,4 +1,4 @@
-use super::Downsample;
+use super::{Downsample, Upsample};
 use crate::internal::*;
 use crate::ops;
 
@@ -64,6 +64,26 @@ pub fn pull_downsample_over_rmdims(
     return Ok(Some(patch));
 }
 
+pub fn push_upsample_over_rmdims(
+    model: &TypedModel,
+    rm_node: &TypedNode,
+    rm_op: &ops::array::RmDims,
+    up_node: &TypedNode,
+    up_op: &Upsample,
+) -> TractResult<Option<TypedModelPatch>> {
+    if rm_op.axes.contains(&up_op.axis) {
+        return Ok(None);
+    }
+    let mut patch = TypedModelPatch::default();
+    let tap = patch.tap_model(model, up_node.inputs[0])?;
+    let rm = patch.wire_node(&*rm_node.name, rm_op.clone(), &[tap])?;
+    let mut new_up = up_op.clone();
+    new_up.axis -= rm_op.axes.iter().filter(|&ax| *ax < up_op.axis).count();
+    let up = patch.wire_node(&*up_node.name, new_up, &*rm)?;
+    patch.shunt_outside(OutletId::new(rm_node.id, 0), up[0])?;
+    return Ok(Some(patch));
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
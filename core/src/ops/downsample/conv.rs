@@ -1,4 +1,4 @@
-use super::Downsample;
+use super::{Downsample, Upsample};
 use crate::internal::*;
 use crate::ops;
 
@@ -32,3 +32,130 @@ pub fn fuse_downsample_into_conv(
     patch.shunt_outside(OutletId::new(down_node.id, 0), new_output)?;
     return Ok(Some(patch));
 }
+
+// upsampling along a geometric axis commutes with a convolution having a
+// kernel of 1, or a dilation multiple of the factor, along this axis. the
+// convolution stride is folded with the upsampling factor.
+pub fn push_upsample_over_conv(
+    model: &TypedModel,
+    conv_node: &TypedNode,
+    conv_op: &ops::cnn::conv::ConvUnary,
+    up_node: &TypedNode,
+    up_op: &Upsample,
+) -> TractResult<Option<TypedModelPatch>> {
+    let input_fact = model.outlet_fact(up_node.inputs[0])?;
+    let input_shape =
+        conv_op.pool_spec.data_format.shape(input_fact.shape.iter().collect::<TVec<_>>());
+    if up_op.axis < input_shape.h_axis() {
+        return Ok(None);
+    }
+    let geo_axis = up_op.axis - input_shape.h_axis();
+    if geo_axis >= input_shape.hw_rank() {
+        return Ok(None);
+    }
+    let kernel = conv_op.pool_spec.kernel_shape[geo_axis];
+    let dilation = conv_op.pool_spec.dilation(geo_axis);
+    let stride = conv_op.pool_spec.stride(geo_axis);
+    if !conv_op.pool_spec.padding.valid_dim(geo_axis)
+        || (kernel > 1 && dilation % up_op.factor != 0)
+    {
+        return Ok(None);
+    }
+    let (stride, factor) = if up_op.factor % stride == 0 {
+        (1, up_op.factor / stride)
+    } else if stride % up_op.factor == 0 {
+        (stride / up_op.factor, 1)
+    } else {
+        return Ok(None);
+    };
+    let mut new_conv = conv_op.clone();
+    let hw_rank = input_shape.hw_rank();
+    if kernel > 1 {
+        new_conv.pool_spec.dilations.get_or_insert_with(|| tvec!(1; hw_rank))[geo_axis] =
+            dilation / up_op.factor;
+    }
+    new_conv.pool_spec.strides.get_or_insert_with(|| tvec!(1; hw_rank))[geo_axis] = stride;
+
+    let mut patch = TypedModelPatch::default();
+    let tap = patch.tap_model(model, up_node.inputs[0])?;
+    let mut wire = patch.wire_node(&*conv_node.name, new_conv, [tap].as_ref())?[0];
+    if factor > 1 {
+        wire = patch.wire_node(&*up_node.name, Upsample::new(up_op.axis, factor), &[wire])?[0];
+    }
+    patch.shunt_outside(OutletId::new(conv_node.id, 0), wire)?;
+    Ok(Some(patch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::cnn::Conv;
+    use ndarray::Array3;
+    use proptest::prelude::*;
+    use proptest::test_runner::TestCaseResult;
+
+    fn up_then_conv(
+        len: usize,
+        factor: usize,
+        kernel: usize,
+        dilation: usize,
+        stride: usize,
+    ) -> TestCaseResult {
+        let model = {
+            let mut model = InferenceModel::default();
+            let fact = InferenceFact::dt_shape(f32::datum_type(), &[1, 1, len]);
+            let input = model.add_source("input", fact)?;
+            let up = model.wire_node("up", Upsample::new(2, factor), &[input])?;
+            let weights = (1..=kernel).map(|x| x as f32).collect::<Vec<_>>();
+            let ker = model.add_const(
+                "kernel",
+                Array3::from_shape_vec((1, 1, kernel), weights)?.into_tensor(),
+            )?;
+            let conv = Conv::default().dilations(tvec!(dilation)).strides(tvec!(stride));
+            let conv = model.wire_node("conv", conv, &[up[0], ker])?;
+            model.set_output_outlets(&conv)?;
+            model
+        };
+        let typed = model.into_typed()?;
+        let input = Array3::from_shape_fn((1, 1, len), |(_, _, x)| x as f32).into_tensor();
+        let expected = SimplePlan::new(&typed)?.run(tvec!(input.clone()))?;
+        let typed = typed.declutter()?;
+        let found = SimplePlan::new(&typed)?.run(tvec!(input))?;
+        prop_assert_eq!(found, expected);
+        Ok(())
+    }
+
+    fn up_then_conv_strat() -> BoxedStrategy<(usize, usize, usize, usize, usize)> {
+        (1usize..4, 1usize..4, 1usize..5, 1usize..4, 0usize..4)
+            .prop_map(|(factor, kernel, dilation, stride, extra)| {
+                let len = ((kernel - 1) * dilation + factor) / factor + extra;
+                (len, factor, kernel, dilation, stride)
+            })
+            .boxed()
+    }
+
+    proptest! {
+        #[test]
+        fn up_then_conv_prop((len, factor, kernel, dilation, stride) in up_then_conv_strat()) {
+            up_then_conv(len, factor, kernel, dilation, stride).unwrap()
+        }
+    }
+
+    #[test]
+    fn upsample_is_pushed_over_dilated_conv() {
+        let mut model = InferenceModel::default();
+        let fact = InferenceFact::dt_shape(f32::datum_type(), &[1, 1, 4]);
+        let input = model.add_source("input", fact).unwrap();
+        let up = model.wire_node("up", Upsample::new(2, 2), &[input]).unwrap();
+        let ker = model.add_const("kernel", tensor3(&[[[1f32, 1.0]]])).unwrap();
+        let conv = Conv::default().dilations(tvec!(2));
+        model.wire_node("conv", conv, &[up[0], ker]).unwrap();
+        model.auto_outputs().unwrap();
+        let model = model.into_typed().unwrap().declutter().unwrap();
+        let output = model.node(model.output_outlets().unwrap()[0].node);
+        assert!(output.op_is::<Upsample>());
+        let input = tensor3(&[[[1f32, 2.0, 3.0, 4.0]]]);
+        let output = SimplePlan::new(&model).unwrap().run(tvec!(input)).unwrap();
+        assert_eq!(*output[0], tensor3(&[[[3f32, 3.0, 5.0, 5.0, 7.0, 7.0]]]));
+    }
+}
@@ -4,7 +4,12 @@ use ndarray::prelude::*;
 
 mod array;
 mod conv;
+mod resample;
 mod scan;
+mod upsample;
+
+pub use self::resample::Resample;
+pub use self::upsample::Upsample;
 
 #[derive(Debug, Clone, new, Default, PartialEq)]
 pub struct Downsample {
@@ -110,7 +115,7 @@ impl TypedOp for Downsample {
         model: &TypedModel,
         node: &TypedNode,
     ) -> TractResult<Option<TypedModelPatch>> {
-        if self.stride == 1 {
+        if self.stride == 1 && self.modulo == 0 {
             return Ok(Some(TypedModelPatch::shunt_one_op(model, node)?));
         }
         pull_downsample_up(model, node)
@@ -125,21 +130,56 @@ impl TypedOp for Downsample {
         _pulse: usize,
     ) -> TractResult<TVec<OutletId>> {
         let input = mapping[&node.inputs[0]];
-        let pulse = target.outlet_fact(input)?.pulse();
-        if pulse % self.stride != 0 {
-            bail!("Pulsificaton requires pulse to be a stride multiple")
+        let fact = target.outlet_fact(input)?;
+        if fact.axis == self.axis && fact.pulse() % self.stride != 0 {
+            bail!(
+                "{} can not be pulsified: pulse ({}) must be a multiple of the stride ({})",
+                node.name,
+                fact.pulse(),
+                self.stride
+            )
         }
         target.wire_node(&*node.name, self.clone(), &[input])
     }
 
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        _node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        let mut input_strides = crate::aot::strides(emitter.input_shape(0));
+        let offset = self.modulo * input_strides[self.axis];
+        input_strides[self.axis] *= self.stride;
+        let shape = emitter.output_shape(0).to_vec();
+        let output = emitter.output(0)?;
+        if shape.iter().product::<usize>() > 0 {
+            emitter.push(format!(
+                "copy({}, &{}[{}..], {}, {}, {});",
+                crate::aot::usize_slice(&shape),
+                emitter.input(0),
+                offset,
+                crate::aot::usize_slice(&input_strides),
+                output,
+                crate::aot::usize_slice(&crate::aot::strides(&shape))
+            ));
+        }
+        Ok(())
+    }
+
     typed_op_as_op!();
 }
 
 impl PulsedOp for Downsample {
     fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
         let mut fact = inputs[0].clone();
-        fact.shape[self.axis] /= self.stride;
-        fact.dim = fact.dim.div_ceil(self.stride.to_dim());
+        if fact.axis == self.axis {
+            fact.shape[self.axis] /= self.stride;
+            fact.dim = fact.dim.div_ceil(self.stride.to_dim());
+        } else {
+            fact.shape[self.axis] =
+                (fact.shape[self.axis].saturating_sub(self.modulo)).div_ceil(self.stride);
+        }
         Ok(tvec!(fact))
     }
 
@@ -180,6 +220,8 @@ fn pull_downsample_up(
             return conv::fuse_downsample_into_conv(model, prec, conv_op, down_node, down_op);
         } else if let Some(other_op) = prec.op_as::<ops::scan::Typed>() {
             return scan::pull_downsample_over_scan(model, prec, other_op, down_node, down_op);
+        } else if let Some(up_op) = prec.op_as::<Upsample>() {
+            return upsample::pull_downsample_over_upsample(model, prec, up_op, down_node, down_op);
         }
     }
     Ok(None)
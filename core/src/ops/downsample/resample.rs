@@ -0,0 +1,132 @@
+use super::{Downsample, Upsample};
+use crate::internal::*;
+
+/// Nearest neighbour resampling along `axis` by a rational factor `up/down`:
+/// frames are repeated `up` times, then one out of `down` is kept, starting at
+/// `modulo`.
+///
+/// Decluttered to an Upsample followed by a Downsample.
+#[derive(Debug, Clone, new, Default, PartialEq)]
+pub struct Resample {
+    axis: usize,
+    up: usize,
+    down: usize,
+    modulo: usize,
+}
+
+impl Resample {
+    fn upsample(&self) -> Upsample {
+        Upsample::new(self.axis, self.up)
+    }
+
+    fn downsample(&self) -> Downsample {
+        Downsample::new(self.axis, self.down, self.modulo)
+    }
+
+    pub(crate) fn transform_dim(&self, input_dim: &TDim) -> TDim {
+        self.downsample().transform_dim(&self.upsample().transform_dim(input_dim))
+    }
+}
+
+impl Op for Resample {
+    fn name(&self) -> Cow<str> {
+        "Resample".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "axis:{} up:{} down:{} modulo:{}",
+            self.axis, self.up, self.down, self.modulo
+        )])
+    }
+
+    impl_op_same_as!();
+    op_as_typed_op!();
+    not_a_pulsed_op!();
+}
+
+impl StatelessOp for Resample {
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let upped = self.upsample().eval(inputs)?;
+        self.downsample().eval(upped)
+    }
+}
+
+impl InferenceRulesOp for Resample {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].rank, &outputs[0].rank)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.given(&inputs[0].rank, move |s, r| {
+            for i in 0..(r as usize) {
+                if i == self.axis {
+                    s.given(&inputs[0].shape[i], move |s, d| {
+                        s.equals(&outputs[0].shape[i], self.transform_dim(&d))
+                    })?
+                } else {
+                    s.equals(&inputs[0].shape[i], &outputs[0].shape[i])?
+                }
+            }
+            Ok(())
+        })
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Resample {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let mut fact = inputs[0].clone();
+        let len = self.transform_dim(&fact.shape.dim(self.axis));
+        fact.shape.set_dim(self.axis, len)?;
+        Ok(tvec!(fact))
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let mut patch = TypedModelPatch::default();
+        let tap = patch.tap_model(model, node.inputs[0])?;
+        let up = patch.wire_node(format!("{}-Upsample", node.name), self.upsample(), &[tap])?;
+        let down = patch.wire_node(&*node.name, self.downsample(), &up)?;
+        patch.shunt_outside(OutletId::new(node.id, 0), down[0])?;
+        Ok(Some(patch))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pulse::PulsedModel;
+
+    #[test]
+    fn three_halves() {
+        let op = Resample::new(0, 3, 2, 0);
+        let output = op.eval(tvec!(rctensor1(&[0i32, 1, 2, 3]))).unwrap();
+        assert_eq!(*output[0], tensor1(&[0i32, 0, 1, 2, 2, 3]));
+    }
+
+    #[test]
+    fn pulsify_three_halves() {
+        let mut model = InferenceModel::default();
+        let fact = InferenceFact::dt_shape(f32::datum_type(), vec![TDim::s()]);
+        let a = model.add_source("a", fact).unwrap();
+        model.wire_node("resample", Resample::new(0, 3, 2, 0), &[a]).unwrap();
+        model.auto_outputs().unwrap();
+        let pulsed = PulsedModel::new(&model.into_normalized().unwrap(), 4).unwrap();
+        let fact = pulsed.output_fact(0).unwrap();
+        assert_eq!(fact.pulse(), 6);
+        assert_eq!(fact.dim, (TDim::s() * 3).div_ceil(2.to_dim()));
+    }
+}
@@ -1,4 +1,4 @@
-use super::Downsample;
+use super::{Downsample, Upsample};
 use crate::internal::*;
 use crate::ops;
 use crate::ops::scan::*;
@@ -89,3 +89,153 @@ pub fn pull_downsample_over_scan(
     }
     Ok(Some(patch))
 }
+
+// upsampled inputs can be scanned at the lower rate if the upsampling can be
+// pushed through the body down to all its outputs
+pub fn push_upsample_over_scan(
+    model: &TypedModel,
+    scan_node: &TypedNode,
+    scan_op: &ops::scan::Typed,
+    up_node: &TypedNode,
+    up_op: &Upsample,
+) -> TractResult<Option<TypedModelPatch>> {
+    let mut sources = tvec!();
+    for input in &scan_node.inputs {
+        let prec = model.node(input.node);
+        if !prec.op().same_as(up_op) {
+            return Ok(None);
+        }
+        sources.push(prec.inputs[0]);
+    }
+
+    let mut inner_model = scan_op.body.clone();
+    let inputs = inner_model.input_outlets()?.to_vec();
+    for (ix, input) in inputs.into_iter().enumerate() {
+        let fact = inner_model.outlet_fact(input)?.clone();
+        if fact.shape.rank() <= up_op.axis {
+            return Ok(None);
+        }
+        let dim = match fact.shape.dim(up_op.axis).to_integer() {
+            Ok(dim) if dim as usize % up_op.factor == 0 => dim as usize / up_op.factor,
+            _ => return Ok(None),
+        };
+        let mut low = fact.clone();
+        low.shape.set_dim(up_op.axis, dim.to_dim())?;
+        inner_model.set_outlet_fact(input, low)?;
+        let name = format!("{}-{}", up_node.name, ix);
+        TypedModelPatch::intercept(&inner_model, input, name, up_op.clone(), fact)?
+            .apply(&mut inner_model)?;
+    }
+    let mut inner_model = inner_model.declutter()?;
+
+    let mut outputs = tvec!();
+    for output in inner_model.output_outlets()? {
+        let node = inner_model.node(output.node);
+        if !node.op().same_as(up_op) {
+            return Ok(None);
+        }
+        outputs.push(node.inputs[0]);
+    }
+    inner_model.set_output_outlets(&outputs)?;
+    let inner_model = crate::model::compact::compact(&inner_model.declutter()?)?;
+
+    let down_op = Downsample::new(up_op.axis, up_op.factor, 0);
+    let mut new_scan = scan_op.clone();
+    new_scan.body = inner_model;
+    for input in &mut new_scan.input_mapping {
+        match input {
+            InputMapping::State { ref mut initializer } => {
+                if let StateInitializer::Value(ref v) = initializer {
+                    if v.rank() <= up_op.axis {
+                        return Ok(None);
+                    }
+                    let new_v = down_op.as_stateless().unwrap().eval(tvec!(v.clone()))?;
+                    if up_op.as_stateless().unwrap().eval(new_v.clone())?[0] != *v {
+                        return Ok(None);
+                    }
+                    *initializer = StateInitializer::Value(new_v[0].clone())
+                }
+            }
+            InputMapping::Scan { axis, ref mut chunk, .. } if *axis == up_op.axis => {
+                if chunk.to_integer()? as usize % up_op.factor != 0 {
+                    return Ok(None);
+                }
+                *chunk = chunk.div_ceil(up_op.factor.to_dim())
+            }
+            _ => (),
+        }
+    }
+    for output in &mut new_scan.output_mapping {
+        if output.full_slot.is_none() || output.axis != up_op.axis {
+            continue;
+        }
+        if output.chunk.to_integer()? as usize % up_op.factor != 0 {
+            return Ok(None);
+        }
+        output.chunk = output.chunk.div_ceil(up_op.factor.to_dim());
+        if let Some(ref mut hint) = output.full_dim_hint {
+            if hint.to_integer().map(|h| h as usize % up_op.factor != 0).unwrap_or(false) {
+                return Ok(None);
+            }
+            *hint = hint.div_ceil(up_op.factor.to_dim());
+        }
+    }
+
+    let mut patch = TypedModelPatch::default();
+    let mut inputs = tvec!();
+    for &source in &sources {
+        inputs.push(patch.tap_model(model, source)?);
+    }
+    let scan = patch.wire_node(&*scan_node.name, new_scan, &inputs)?;
+    for (ix, &wire) in scan.iter().enumerate() {
+        let name = format!("{}-{}", scan_node.name, ix);
+        let up = patch.wire_node(name, up_op.clone(), &[wire])?[0];
+        patch.shunt_outside(OutletId::new(scan_node.id, ix), up)?;
+    }
+    Ok(Some(patch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn upsample_is_pushed_over_scan() {
+        let fact = TypedFact::dt_shape(f32::datum_type(), [2].as_ref()).unwrap();
+        let mut body = TypedModel::default();
+        let s = body.add_source("s", fact.clone()).unwrap();
+        let x = body.add_source("x", fact).unwrap();
+        let abs = body.wire_node("abs", ops::math::abs(), &[x]).unwrap();
+        body.set_output_outlets(&[s, abs[0]]).unwrap();
+        let scan = ops::scan::Typed::new(
+            body,
+            vec![
+                InputMapping::State {
+                    initializer: StateInitializer::Value(rctensor1(&[3f32, 3.0])),
+                },
+                InputMapping::Scan { slot: 0, axis: 0, chunk: 2.to_dim() },
+            ],
+            vec![
+                OutputMapping::new(None, 0, 1.to_dim(), None, Some(1), true),
+                OutputMapping::new(Some(0), 0, 2.to_dim(), None, None, false),
+            ],
+            None,
+        )
+        .unwrap();
+        let mut model = TypedModel::default();
+        let fact = TypedFact::dt_shape(f32::datum_type(), [3].as_ref()).unwrap();
+        let input = model.add_source("input", fact).unwrap();
+        let up = model.wire_node("up", Upsample::new(0, 2), &[input]).unwrap();
+        let scan = model.wire_node("scan", scan, &up).unwrap();
+        model.set_output_outlets(&scan).unwrap();
+
+        let input = tensor1(&[-1f32, 2.0, -3.0]);
+        let expected = SimplePlan::new(&model).unwrap().run(tvec!(input.clone())).unwrap();
+        let model = model.declutter().unwrap();
+        for output in model.output_outlets().unwrap() {
+            assert!(model.node(output.node).op_is::<Upsample>());
+        }
+        let found = SimplePlan::new(&model).unwrap().run(tvec!(input)).unwrap();
+        assert_eq!(found, expected);
+    }
+}
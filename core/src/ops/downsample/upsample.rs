@@ -0,0 +1,285 @@
+use super::Downsample;
+use crate::internal::*;
+use crate::ops;
+use ndarray::prelude::*;
+
+/// Repeats every frame `factor` times along `axis`.
+#[derive(Debug, Clone, new, Default, PartialEq)]
+pub struct Upsample {
+    pub(super) axis: usize,
+    pub(super) factor: usize,
+}
+
+impl Upsample {
+    fn eval_t<T: Datum>(&self, input: &Tensor) -> TractResult<Arc<Tensor>> {
+        let input = input.to_array_view::<T>()?;
+        let mut shape = input.shape().to_vec();
+        shape[self.axis] *= self.factor;
+        let mut output = unsafe { T::uninitialized_array(&*shape) };
+        for (i, mut frame) in output.axis_iter_mut(Axis(self.axis)).enumerate() {
+            frame.assign(&input.index_axis(Axis(self.axis), i / self.factor));
+        }
+        Ok(output.into_arc_tensor())
+    }
+
+    pub(crate) fn transform_dim(&self, input_dim: &TDim) -> TDim {
+        input_dim.clone() * self.factor
+    }
+}
+
+impl Op for Upsample {
+    fn name(&self) -> Cow<str> {
+        "Upsample".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("axis:{} factor:{}", self.axis, self.factor)])
+    }
+
+    impl_op_same_as!();
+    op_as_typed_op!();
+    op_as_pulsed_op!();
+}
+
+impl StatelessOp for Upsample {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        Ok(tvec!(dispatch_datum!(Self::eval_t(input.datum_type())(self, &*input))?))
+    }
+}
+
+impl InferenceRulesOp for Upsample {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].rank, &outputs[0].rank)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.given(&inputs[0].rank, move |s, r| {
+            for i in 0..(r as usize) {
+                if i == self.axis {
+                    s.given(&inputs[0].shape[i], move |s, d| {
+                        s.equals(&outputs[0].shape[i], d * self.factor)
+                    })?
+                } else {
+                    s.equals(&inputs[0].shape[i], &outputs[0].shape[i])?
+                }
+            }
+            Ok(())
+        })
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Upsample {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let mut upped = inputs[0].clone();
+        let up_len = self.transform_dim(&upped.shape.dim(self.axis));
+        upped.shape.set_dim(self.axis, up_len)?;
+        Ok(tvec!(upped))
+    }
+
+    fn axes_info(&self, _model: &TypedModel, node: &TypedNode) -> TractResult<AxesInfo> {
+        let rank = node.outputs[0].fact.shape.rank();
+        Ok((0..rank).filter(|&ax| ax != self.axis).map(AxisInfo::simple).collect())
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        if self.factor == 1 {
+            return Ok(Some(TypedModelPatch::shunt_one_op(model, node)?));
+        }
+        push_upsample_down(model, node)
+    }
+
+    fn pulsify(
+        &self,
+        _source: &NormalizedModel,
+        node: &NormalizedNode,
+        target: &mut PulsedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        _pulse: usize,
+    ) -> TractResult<TVec<OutletId>> {
+        let input = mapping[&node.inputs[0]];
+        target.wire_node(&*node.name, self.clone(), &[input])
+    }
+
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        _node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        // walks the input with an extra repeat axis after the upsampled one
+        let mut shape: TVec<usize> = emitter.input_shape(0).into();
+        let mut input_strides = crate::aot::strides(&shape);
+        shape.insert(self.axis + 1, self.factor);
+        input_strides.insert(self.axis + 1, 0);
+        let output = emitter.output(0)?;
+        emitter.push(format!(
+            "copy({}, {}, {}, {}, {});",
+            crate::aot::usize_slice(&shape),
+            emitter.input(0),
+            crate::aot::usize_slice(&input_strides),
+            output,
+            crate::aot::usize_slice(&crate::aot::strides(&shape))
+        ));
+        Ok(())
+    }
+}
+
+impl PulsedOp for Upsample {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.shape[self.axis] *= self.factor;
+        if fact.axis == self.axis {
+            fact.dim = self.transform_dim(&fact.dim);
+            fact.delay *= self.factor;
+        }
+        Ok(tvec!(fact))
+    }
+
+    pulsed_op_as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+// moves upsampling as late as possible, so that the ops in between run at the
+// lower rate
+fn push_upsample_down(
+    model: &TypedModel,
+    up_node: &TypedNode,
+) -> TractResult<Option<TypedModelPatch>> {
+    let up_op = up_node.op_as::<Upsample>().unwrap();
+    if let Some(succ) = model.single_succ(up_node.id)? {
+        if let Some(scan_op) = succ.op_as::<ops::scan::Typed>() {
+            return super::scan::push_upsample_over_scan(model, succ, scan_op, up_node, up_op);
+        }
+        if succ.inputs.len() != 1 || succ.outputs.len() != 1 {
+            return Ok(None);
+        }
+        let invariants = succ.op.axes_info(model, succ)?;
+        debug!("Consider push {:?} over {:?} (invariants: {:?})", up_op, succ, invariants);
+        if let Some(below_axis) = invariants.unary_track_axis_down(up_op.axis, true) {
+            let mut patch = TypedModelPatch::default();
+            let tap = patch.tap_model(model, up_node.inputs[0])?;
+            let other = patch.wire_node(&*succ.name, succ.op.clone(), &[tap])?;
+            let mut op = up_op.clone();
+            op.axis = below_axis;
+            let up = patch.wire_node(&*up_node.name, op, &*other)?;
+            patch.shunt_outside(OutletId::new(succ.id, 0), up[0])?;
+            return Ok(Some(patch));
+        } else if let Some(other_op) = succ.op_as::<ops::array::RmDims>() {
+            return super::array::push_upsample_over_rmdims(model, succ, other_op, up_node, up_op);
+        } else if let Some(conv_op) = succ.op_as::<ops::cnn::conv::ConvUnary>() {
+            return super::conv::push_upsample_over_conv(model, succ, conv_op, up_node, up_op);
+        }
+    }
+    Ok(None)
+}
+
+// downsampling an upsampled signal by a multiple or a divisor of the
+// upsampling factor can be done with one single op
+pub fn pull_downsample_over_upsample(
+    model: &TypedModel,
+    up_node: &TypedNode,
+    up_op: &Upsample,
+    down_node: &TypedNode,
+    down_op: &Downsample,
+) -> TractResult<Option<TypedModelPatch>> {
+    if up_op.axis != down_op.axis {
+        return Ok(None);
+    }
+    let mut patch = TypedModelPatch::default();
+    let tap = patch.tap_model(model, up_node.inputs[0])?;
+    let wire = if down_op.stride % up_op.factor == 0 {
+        let stride = down_op.stride / up_op.factor;
+        let modulo = down_op.modulo / up_op.factor;
+        if stride == 1 && modulo == 0 {
+            tap
+        } else if stride == 1 {
+            return Ok(None);
+        } else {
+            let op = Downsample::new(down_op.axis, stride, modulo);
+            patch.wire_node(&*down_node.name, op, &[tap])?[0]
+        }
+    } else if up_op.factor % down_op.stride == 0 && down_op.modulo < down_op.stride {
+        let op = Upsample::new(up_op.axis, up_op.factor / down_op.stride);
+        patch.wire_node(&*up_node.name, op, &[tap])?[0]
+    } else {
+        return Ok(None);
+    };
+    patch.shunt_outside(OutletId::new(down_node.id, 0), wire)?;
+    Ok(Some(patch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use proptest::test_runner::TestCaseResult;
+
+    fn up_then_down(len: usize, factor: usize, stride: usize, modulo: usize) -> TestCaseResult {
+        let model = {
+            let mut model = InferenceModel::default();
+            let input =
+                model.add_source("input", InferenceFact::dt_shape(i32::datum_type(), &[len]))?;
+            let up = model.wire_node("up", Upsample::new(0, factor), &[input])?;
+            let down = model.wire_node("down", Downsample::new(0, stride, modulo), &up)?;
+            model.set_output_outlets(&down)?;
+            model
+        };
+        let typed = model.into_typed()?;
+        let input = tensor1(&(0i32..len as _).collect::<Vec<_>>());
+        let expected = SimplePlan::new(&typed)?.run(tvec!(input.clone()))?;
+        let typed = typed.declutter()?;
+        let found = SimplePlan::new(&typed)?.run(tvec!(input))?;
+        prop_assert_eq!(found, expected);
+        Ok(())
+    }
+
+    fn up_then_down_strat() -> BoxedStrategy<(usize, usize, usize, usize)> {
+        (4usize..10, 1usize..5, 1usize..5)
+            .prop_flat_map(|(len, factor, stride)| {
+                (Just(len), Just(factor), Just(stride), 0..stride)
+            })
+            .boxed()
+    }
+
+    proptest! {
+        #[test]
+        fn up_then_down_prop((len, factor, stride, modulo) in up_then_down_strat()) {
+            up_then_down(len, factor, stride, modulo).unwrap()
+        }
+    }
+
+    #[test]
+    fn upsample() {
+        let up = Upsample::new(0, 2);
+        let output = up.eval(tvec!(rctensor1(&[1i32, 2, 3]))).unwrap();
+        assert_eq!(*output[0], tensor1(&[1i32, 1, 2, 2, 3, 3]));
+    }
+
+    #[test]
+    fn upsample_is_pushed_down() {
+        let mut model = TypedModel::default();
+        let fact = TypedFact::dt_shape(f32::datum_type(), [3].as_ref()).unwrap();
+        let input = model.add_source("input", fact).unwrap();
+        let up = model.wire_node("up", Upsample::new(0, 2), &[input]).unwrap();
+        model.wire_node("abs", ops::math::abs(), &up).unwrap();
+        model.auto_outputs().unwrap();
+        let model = model.declutter().unwrap();
+        let output = model.node(model.output_outlets().unwrap()[0].node);
+        assert!(output.op_is::<Upsample>());
+    }
+}
@@ -26,6 +26,11 @@ pub trait ElementWiseMiniOp: fmt::Debug + objekt::Clone + Send + Sync + 'static
     fn cost_per_element(&self, dt: DatumType) -> TVec<(Cost, usize)> {
         tvec!()
     }
+    /// Rust expression computing the op on a f32 `x`, for ahead-of-time
+    /// compilation.
+    fn rust_expr(&self) -> TractResult<Option<String>> {
+        Ok(None)
+    }
 }
 
 clone_trait_object!(ElementWiseMiniOp);
@@ -119,6 +124,24 @@ impl TypedOp for ElementWiseOp {
         target.wire_node(&*node.name, self.clone(), &[input])
     }
 
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        let expr = self.0.rust_expr()?.ok_or_else(|| {
+            format!(
+                "{} can not be compiled ahead of time: no Rust form for {}",
+                node.name,
+                self.name()
+            )
+        })?;
+        let output = emitter.output(0)?;
+        emitter.push(format!("map({}, {}, |x| {});", emitter.input(0), output, expr));
+        Ok(())
+    }
+
     typed_op_as_op!();
 }
 
@@ -142,6 +165,7 @@ macro_rules! element_wise {
         $(; cost: $cost:expr )?
         $(; prefix: $prefix:expr )?
         $(; validation: $validation:expr )?
+        $(; rust: $rust:expr )?
     ) => {
         #[derive(Debug, Clone)]
         pub struct $Op { $( $(pub $var: $var_typ),* )? }
@@ -176,6 +200,12 @@ macro_rules! element_wise {
                 $validation
             }
             )?
+            $(
+            fn rust_expr(&self) -> TractResult<Option<String>> {
+                let f: fn(&Self) -> TractResult<String> = $rust;
+                Ok(Some(f(self)?))
+            }
+            )?
         }
         pub fn $func($( $($var: $var_typ),* )?) -> $crate::ops::element_wise::ElementWiseOp {
             $crate::ops::element_wise::ElementWiseOp(Box::new($Op { $( $($var),* )? } ))
@@ -6,9 +6,9 @@ use crate::internal::*;
 use ndarray::*;
 
 use super::mat_mul::MMMWrapper;
-use tract_linalg::mmm::FusedSpec;
+use tract_linalg::mmm::{FusedSpec, MatrixStoreSpec};
 
-use tract_linalg::frame::PackB;
+use tract_linalg::frame::{PackA, PackB};
 
 #[derive(Debug, Clone)]
 pub struct MatMatMulPackB<T>
@@ -73,6 +73,65 @@ where
     typed_op_as_op!();
 }
 
+#[derive(Debug, Clone)]
+pub struct MatMatMulPackA<T>
+where
+    T: Copy + Datum + Zero,
+{
+    pub(crate) pack_a: PackA<T>,
+    pub(crate) row_stride: isize,
+    pub(crate) col_stride: isize,
+    pub(crate) output_shape: TVec<usize>,
+}
+
+impl<T> Op for MatMatMulPackA<T>
+where
+    T: Copy + Datum + Zero,
+{
+    fn name(&self) -> Cow<str> {
+        "MatMatMulPackA".into()
+    }
+
+    op_as_typed_op!();
+    not_a_pulsed_op!();
+}
+
+impl<T> StatelessOp for MatMatMulPackA<T>
+where
+    T: Copy + Datum + Zero,
+{
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let a = args_1!(inputs);
+        let mut packed = unsafe {
+            Tensor::uninitialized_aligned::<T>(&*self.output_shape, self.pack_a.alignment())
+                .unwrap()
+        };
+        let a = a.to_array_view::<T>()?;
+        let a_prefix = &a.shape()[..a.shape().len() - 2];
+        for prefix in indices(a_prefix).into_iter() {
+            let mut a = a.view();
+            let mut p = packed.to_array_view_mut()?;
+            for &dim in prefix.slice() {
+                a.index_axis_inplace(Axis(0), dim);
+                p.index_axis_inplace(Axis(0), dim);
+            }
+            self.pack_a.pack(p.as_mut_ptr(), a.as_ptr(), self.row_stride, self.col_stride)
+        }
+        Ok(tvec!(packed.into_arc_tensor()))
+    }
+}
+
+impl<T> TypedOp for MatMatMulPackA<T>
+where
+    T: Copy + Datum + Zero,
+{
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, &*self.output_shape)?))
+    }
+
+    typed_op_as_op!();
+}
+
 #[derive(Debug, Clone)]
 pub(crate) struct MatMatMulUnaryFinite<TA, TB, TC, TI>
 where
@@ -181,51 +240,25 @@ where
             let mut c = Tensor::uninitialized::<TC>(&*self.c_shape)?;
             if let Some((prefix_dim, prefix_strides)) = &self.c_prefix_dim_and_stride {
                 let b = b.to_array_view::<TB>()?;
-                let mut c = c.to_array_view_mut::<TC>()?;
-                for prefix in indices(&**prefix_dim).into_iter() {
-                    let mut a = self.packed_as.view();
-                    let mut b = b.view();
-                    let mut c: *mut TC = c.as_mut_ptr();
-                    for (ix, &dim) in prefix.slice().iter().enumerate() {
-                        let d = dim.min(a.shape()[0] - 1);
-                        a.index_axis_inplace(Axis(0), d);
-                        let d = dim.min(b.shape()[0] - 1);
-                        b.index_axis_inplace(Axis(0), d);
-                        c = c.offset(prefix_strides[ix] * dim as isize);
-                    }
-                    let pa: &Tensor = a.iter().next().unwrap();
-                    if let Some(fused) = &self.fused_ops {
-                        let mut fused = fused.view();
-                        for &dim in prefix.slice() {
-                            let d = dim.min(fused.shape()[0] - 1);
-                            fused.index_axis_inplace(Axis(0), d);
-                        }
-                        self.mmm.run(
-                            pa.as_ptr()?,
-                            b.as_ptr(),
-                            c,
-                            &fused.as_slice().unwrap()[0],
-                        );
-                    } else {
-                        self.mmm.run(pa.as_ptr()?, b.as_ptr(), c, &[]);
-                    }
-                }
+                run_batched(
+                    &self.mmm,
+                    prefix_dim,
+                    prefix_strides,
+                    |prefix| {
+                        let pa = broadcast_prefix(self.packed_as.view(), prefix, 0);
+                        pa.iter().next().unwrap().as_ptr()
+                    },
+                    &b,
+                    c.as_ptr_mut()?,
+                    self.fused_ops.as_ref(),
+                )?;
             } else {
-                if let Some(fused) = &self.fused_ops {
-                    self.mmm.run(
-                        self.packed_as.as_slice().unwrap()[0].as_ptr()?,
-                        b.as_ptr()?,
-                        c.as_ptr_mut()?,
-                        &fused.as_slice().unwrap()[0],
-                    );
-                } else {
-                    self.mmm.run(
-                        self.packed_as.as_slice().unwrap()[0].as_ptr()?,
-                        b.as_ptr()?,
-                        c.as_ptr_mut()?,
-                        &[],
-                    );
-                }
+                self.mmm.run(
+                    self.packed_as.as_slice().unwrap()[0].as_ptr()?,
+                    b.as_ptr()?,
+                    c.as_ptr_mut()?,
+                    self.fused_ops.as_ref().map(|f| &**f.iter().next().unwrap()).unwrap_or(&[]),
+                );
             }
             Ok(tvec!(c.into_arc_tensor()))
         }
@@ -239,8 +272,8 @@ where
     TC: Datum + Copy,
     TI: Datum + Copy + Add + Mul + Zero + fmt::Debug,
 {
-    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
-        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, &*self.c_shape)?))
+    fn output_facts(&self, _inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(TypedFact::dt_shape(TC::datum_type(), &*self.c_shape)?))
     }
 
     /*
@@ -255,3 +288,164 @@ where
 
     typed_op_as_op!();
 }
+
+/// Matrix multiplication of two packed operands, the binary counterpart of
+/// MatMatMulUnaryFinite.
+///
+/// Inputs are the outputs of MatMatMulPackA and MatMatMulPackB (or the raw B
+/// in the matrix-vector case), batch prefixes broadcasting against c's.
+#[derive(Debug, Clone)]
+pub(crate) struct MatMatMulFinite<TA, TB, TC, TI>
+where
+    TA: Datum + Copy + Zero,
+    TB: Datum + Copy + Zero,
+    TC: Datum + Copy,
+    TI: Datum + Copy + Add + Mul + Zero + fmt::Debug,
+{
+    pub(crate) c_shape: TVec<usize>,
+    pub(crate) c_prefix_dim_and_stride: Option<(TVec<usize>, TVec<isize>)>,
+    pub(crate) mmm: MMMWrapper<TA, TB, TC, TI>,
+}
+
+impl<TA, TB, TC, TI> Op for MatMatMulFinite<TA, TB, TC, TI>
+where
+    TA: Datum + Copy + Zero,
+    TB: Datum + Copy + Zero,
+    TC: Datum + Copy,
+    TI: Datum + Copy + Add + Mul + Zero + fmt::Debug,
+{
+    fn name(&self) -> Cow<str> {
+        "MatMatMulFinite".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![
+            format!(
+                "c_prefix: {:?} m:{} k:{} n:{}",
+                self.c_prefix_dim_and_stride,
+                self.mmm.as_mmm().m(),
+                self.mmm.as_mmm().k(),
+                self.mmm.as_mmm().n(),
+            ),
+            format!("{}", self.mmm),
+        ])
+    }
+
+    op_as_typed_op!();
+    not_a_pulsed_op!();
+}
+
+impl<TA, TB, TC, TI> StatelessOp for MatMatMulFinite<TA, TB, TC, TI>
+where
+    TA: Datum + Copy + Zero,
+    TB: Datum + Copy + Zero,
+    TC: Datum + Copy,
+    TI: Datum + Copy + Add + Mul + Zero + fmt::Debug,
+{
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        unsafe {
+            let (a, b) = args_2!(inputs);
+            let mut c = Tensor::uninitialized::<TC>(&*self.c_shape)?;
+            if let Some((prefix_dim, prefix_strides)) = &self.c_prefix_dim_and_stride {
+                let a = a.to_array_view::<TA>()?;
+                let b = b.to_array_view::<TB>()?;
+                run_batched(
+                    &self.mmm,
+                    prefix_dim,
+                    prefix_strides,
+                    |prefix| Ok(broadcast_prefix(a.view(), prefix, 1).as_ptr()),
+                    &b,
+                    c.as_ptr_mut()?,
+                    None,
+                )?;
+            } else {
+                self.mmm.run(a.as_ptr()?, b.as_ptr()?, c.as_ptr_mut()?, &[]);
+            }
+            Ok(tvec!(c.into_arc_tensor()))
+        }
+    }
+}
+
+impl<TA, TB, TC, TI> TypedOp for MatMatMulFinite<TA, TB, TC, TI>
+where
+    TA: Datum + Copy + Zero,
+    TB: Datum + Copy + Zero,
+    TC: Datum + Copy,
+    TI: Datum + Copy + Add + Mul + Zero + fmt::Debug,
+{
+    fn output_facts(&self, _inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(TypedFact::dt_shape(TC::datum_type(), &*self.c_shape)?))
+    }
+
+    typed_op_as_op!();
+}
+
+/// Select the slice of `view` matching the c batch `prefix`, broadcasting
+/// (clamping to 0) over the axes of size 1 and over missing leading axes.
+/// `inner` is the minimum number of non-batch axes at the end of `view`.
+pub(crate) fn broadcast_prefix<'a, T>(
+    mut view: ArrayViewD<'a, T>,
+    prefix: &[usize],
+    inner: usize,
+) -> ArrayViewD<'a, T> {
+    let own_prefix_len = (view.ndim() - inner).min(prefix.len());
+    for &dim in &prefix[prefix.len() - own_prefix_len..] {
+        let d = dim.min(view.shape()[0] - 1);
+        view.index_axis_inplace(Axis(0), d);
+    }
+    view
+}
+
+/// Run the multiplication for every matrix in the c batch prefix, picking the
+/// broadcasted packed A, B and fused specs for each of them.
+///
+/// All slices share the same MatMatMul instance. They are independent, so they
+/// are dispatched to rayon when the `batch-parallel` feature is enabled.
+pub(crate) unsafe fn run_batched<TA, TB, TC, TI>(
+    mmm: &MMMWrapper<TA, TB, TC, TI>,
+    prefix_dim: &[usize],
+    prefix_strides: &[isize],
+    a: impl Fn(&[usize]) -> TractResult<*const TA> + Sync,
+    b: &ArrayViewD<TB>,
+    c: *mut TC,
+    fused: Option<&ArrayD<Vec<FusedSpec<TI>>>>,
+) -> TractResult<()>
+where
+    TA: Datum + Copy + Zero,
+    TB: Datum + Copy + Zero,
+    TC: Datum + Copy,
+    TI: Datum + Copy + Add + Mul + Zero + fmt::Debug,
+{
+    // trailing axes of b that are not part of the batch
+    let b_inner = match mmm.as_mmm().b_storage() {
+        MatrixStoreSpec::Packed { .. } => 1,
+        MatrixStoreSpec::VecStride { .. } => 2,
+        // direct convolution: b is the raw input, batched on its leading axes
+        _ => 0,
+    };
+    // raw pointers are not Sync, so smuggle c as an address
+    let c = c as usize;
+    let run_one = |prefix: &[usize]| -> TractResult<()> {
+        let pa = a(prefix)?;
+        let pb = broadcast_prefix(b.view(), prefix, b_inner).as_ptr();
+        let offset: isize = prefix.iter().zip(prefix_strides).map(|(&d, &s)| d as isize * s).sum();
+        let pc = (c as *mut TC).offset(offset);
+        let fused: &[FusedSpec<TI>] = match fused {
+            Some(f) => broadcast_prefix(f.view(), prefix, 0).into_iter().next().unwrap(),
+            None => &[],
+        };
+        mmm.run(pa, pb, pc, fused);
+        Ok(())
+    };
+    #[cfg(feature = "batch-parallel")]
+    {
+        use rayon::prelude::*;
+        let prefixes: Vec<Vec<usize>> =
+            indices(prefix_dim).into_iter().map(|p| p.slice().to_vec()).collect();
+        prefixes.par_iter().try_for_each(|p| run_one(p))
+    }
+    #[cfg(not(feature = "batch-parallel"))]
+    {
+        indices(prefix_dim).into_iter().try_for_each(|p| run_one(p.slice()))
+    }
+}
@@ -3,7 +3,10 @@ use std::fmt;
 use std::ops::{Add, Deref, Mul};
 
 use crate::internal::*;
-use crate::ops::math::mat_mat_mul::{MatMatMulPackB, MatMatMulUnaryFinite};
+use crate::ops::math::mat_mat_mul::{
+    broadcast_prefix, run_batched, MatMatMulFinite, MatMatMulPackA, MatMatMulPackB,
+    MatMatMulUnaryFinite,
+};
 use crate::ops::quant::QParams;
 use ndarray::*;
 
@@ -75,48 +78,60 @@ where
     }
     let a = a.into_shape(&*geo.bc_a_shape)?;
     let b = b.into_shape(&*geo.bc_b_shape)?;
-    let mut c = unsafe { Array::<TC, IxDyn>::uninitialized(&*geo.c_shape) };
+    let mut c = unsafe { Tensor::uninitialized::<TC>(&*geo.c_shape)? };
 
+    // pack each operand matrix once, batch slices then only pick (and
+    // broadcast) the packed matrices they need
+    let a_pack = geo.mm.as_mmm().a_pack();
+    let pa = pack_prefixed(&a, a_pack.len(), a_pack.alignment(), a_trans, |p, a, rs, cs| {
+        a_pack.pack(p, a, rs, cs)
+    })?;
     let b_pack = geo.mm.as_mmm().b_pack();
+    let pb = pack_prefixed(&b, b_pack.len(), b_pack.alignment(), b_trans, |p, b, rs, cs| {
+        b_pack.pack(p, b, rs, cs)
+    })?;
+    let pa = pa.to_array_view::<TA>()?;
+    let pb = pb.to_array_view::<TB>()?;
 
-    let mut pa = unsafe {
-        Tensor::uninitialized_aligned::<TA>(
-            &[geo.mm.as_mmm().a_pack().len()],
-            geo.mm.as_mmm().a_pack().alignment(),
-        )?
-    };
-    let mut pb =
-        unsafe { Tensor::uninitialized_aligned::<TB>(&[b_pack.len()], b_pack.alignment())? };
-
-    for prefix in indices(&*geo.c_shape_prefix).into_iter() {
-        let mut a = a.view();
-        let mut b = b.view();
-        let mut c = c.view_mut();
-        for (axis, &dim) in prefix.slice().iter().enumerate() {
-            let d = dim.min(a.shape()[axis] - 1);
-            a.slice_axis_inplace(Axis(axis), (d..=d).into());
-            let d = dim.min(b.shape()[axis] - 1);
-            b.slice_axis_inplace(Axis(axis), (d..=d).into());
-            c.slice_axis_inplace(Axis(axis), (dim..=dim).into());
-        }
+    let (prefix_dim, prefix_strides) = geo.c_prefix_dim_and_stride();
+    unsafe {
+        run_batched(
+            &geo.mm,
+            &prefix_dim,
+            &prefix_strides,
+            |prefix| Ok(broadcast_prefix(pa.view(), prefix, 1).as_ptr()),
+            &pb,
+            c.as_ptr_mut()?,
+            None,
+        )?;
+    }
+    Ok(c)
+}
 
-        geo.mm.as_mmm().a_pack().pack(
-            pa.as_ptr_mut()?,
-            a.as_ptr(),
-            a.strides()[prefix.ndim() + a_trans as usize],
-            a.strides()[prefix.ndim() + !a_trans as usize],
-        );
-        b_pack.pack(
-            pb.as_ptr_mut()?,
-            b.as_ptr(),
-            b.strides()[prefix.ndim() + b_trans as usize],
-            b.strides()[prefix.ndim() + !b_trans as usize],
-        );
-        unsafe {
-            geo.mm.run(pa.as_ptr()?, pb.as_ptr()?, c.as_mut_ptr(), &[]);
+/// Pack every matrix of a (batch prefix + 2D) array, producing a tensor with
+/// the same batch prefix and a flat packed matrix as last axis.
+fn pack_prefixed<T: Datum + Copy>(
+    m: &ArrayViewD<T>,
+    packed_len: usize,
+    alignment: usize,
+    trans: bool,
+    pack: impl Fn(*mut T, *const T, isize, isize),
+) -> TractResult<Tensor> {
+    let prefix_shape = &m.shape()[..m.ndim() - 2];
+    let mut packed_shape: TVec<usize> = prefix_shape.into();
+    packed_shape.push(packed_len);
+    let mut packed = unsafe { Tensor::uninitialized_aligned::<T>(&packed_shape, alignment)? };
+    let mut packed_view = packed.to_array_view_mut::<T>()?;
+    for prefix in indices(prefix_shape).into_iter() {
+        let mut m = m.view();
+        let mut p = packed_view.view_mut();
+        for &dim in prefix.slice() {
+            m.index_axis_inplace(Axis(0), dim);
+            p.index_axis_inplace(Axis(0), dim);
         }
+        pack(p.as_mut_ptr(), m.as_ptr(), m.strides()[trans as usize], m.strides()[!trans as usize]);
     }
-    Ok(c.into_tensor())
+    Ok(packed)
 }
 
 pub fn infer_shapes<D: DimLike>(
@@ -362,6 +377,47 @@ where
             c_trans,
         })
     }
+
+    /// Batch prefix of c, with the matching element strides in c.
+    fn c_prefix_dim_and_stride(&self) -> (TVec<usize>, TVec<isize>) {
+        let c_prefix_strides: TVec<isize> = self
+            .c_shape
+            .iter()
+            .rev()
+            .scan(1isize, |s, &d| {
+                let now: isize = *s;
+                *s *= d as isize;
+                Some(now)
+            })
+            .collect::<TVec<_>>()
+            .into_iter()
+            .skip(2)
+            .rev()
+            .collect::<TVec<_>>();
+        (self.c_shape_prefix.clone(), c_prefix_strides)
+    }
+
+    /// Storage for the codegen'd ops: B is a plain vector when n == 1,
+    /// packed otherwise.
+    unsafe fn configure_storage(&mut self) {
+        if self.n == 1 {
+            self.mm.as_mmm_mut().b_vec_from_data_and_stride(if self.b_trans {
+                1
+            } else {
+                *self.b_shape.last().unwrap() as isize
+            });
+            self.mm.as_mmm_mut().c_vec_from_data_and_stride(if self.c_trans {
+                1
+            } else {
+                *self.c_shape.last().unwrap() as isize
+            });
+        } else {
+            self.mm.as_mmm_mut().c_from_data_and_strides(
+                if self.c_trans { 1 } else { *self.c_shape.last().unwrap() as isize },
+                if !self.c_trans { 1 } else { *self.c_shape.last().unwrap() as isize },
+            );
+        };
+    }
 }
 
 #[derive(Debug, Clone, Default)]
@@ -422,8 +478,13 @@ impl InferenceRulesOp for MatMul {
     ) -> InferenceResult {
         check_input_arity(&inputs, 2)?;
         check_output_arity(&outputs, 1)?;
-        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
-        s.equals(&inputs[1].datum_type, &outputs[0].datum_type)?;
+        if let Some(q) = &self.q_params {
+            s.equals(&inputs[0].datum_type, &inputs[1].datum_type)?;
+            s.equals(&outputs[0].datum_type, q.c_datum_type)?;
+        } else {
+            s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+            s.equals(&inputs[1].datum_type, &outputs[0].datum_type)?;
+        }
         s.given_2(&inputs[0].shape, &inputs[1].shape, move |s, ashape, bshape| {
             let (_, _, cshape) =
                 infer_shapes(ashape, bshape, self.a_trans, self.b_trans, self.c_trans)?;
@@ -439,7 +500,7 @@ impl InferenceRulesOp for MatMul {
 impl TypedOp for MatMul {
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
         Ok(tvec!(TypedFact::dt_shape(
-            inputs[0].datum_type,
+            self.q_params.as_ref().map(|q| q.c_datum_type).unwrap_or(inputs[0].datum_type),
             &*infer_shapes(
                 inputs[0].shape.to_tvec(),
                 inputs[1].shape.to_tvec(),
@@ -471,11 +532,15 @@ impl TypedOp for MatMul {
         let t_konst = [self.a_trans, self.b_trans][konst_ix] ^ flip;
         let t_var = [self.b_trans, self.a_trans][konst_ix] ^ flip;
         let konst = model.outlet_fact(node.inputs[konst_ix])?.konst.clone().unwrap();
+        let mut q_params = self.q_params.clone();
+        if let (Some(q), true) = (q_params.as_mut(), flip) {
+            std::mem::swap(&mut q.zero_point_a, &mut q.zero_point_b);
+        }
         let patch = TypedModelPatch::replace_single_op(
             model,
             node,
             &node.inputs[var_ix..][..1],
-            MatMulUnary::new(konst, t_konst, t_var, self.c_trans ^ flip, self.q_params.clone()),
+            MatMulUnary::new(konst, t_konst, t_var, self.c_trans ^ flip, q_params),
         )?;
         return Ok(Some(patch));
     }
@@ -490,6 +555,36 @@ impl TypedOp for MatMul {
         )
     }
 
+    fn codegen(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let mut inputs = model.node_input_facts(node.id)?;
+        let (a, b) = args_2!(inputs);
+        if let (Some(a_shape), Some(b_shape)) = (a.shape.as_finite(), b.shape.as_finite()) {
+            if a_shape.len() < 2
+                || b_shape.len() < 2
+                || (a.datum_type, b.datum_type) != (f32::datum_type(), f32::datum_type())
+            {
+                return Ok(None);
+            }
+            let patch = new_mat_mul_finite(
+                model,
+                node,
+                a_shape,
+                b_shape,
+                self.a_trans,
+                self.b_trans,
+                self.c_trans,
+                self.q_params.as_ref(),
+                &|m, k, n| MMMWrapper::Plain((tract_linalg::ops().smmm)(m, k, n)),
+            )?;
+            return Ok(Some(patch));
+        }
+        Ok(None)
+    }
+
     typed_op_as_op!();
 }
 
@@ -539,7 +634,7 @@ impl StatelessOp for MatMulUnary {
 impl TypedOp for MatMulUnary {
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
         Ok(tvec!(TypedFact::dt_shape(
-            inputs[0].datum_type,
+            self.q_params.as_ref().map(|q| q.c_datum_type).unwrap_or(inputs[0].datum_type),
             &*infer_shapes(
                 self.a.shape().into_iter().map(|d| d.to_dim()).collect::<TVec<_>>(),
                 inputs[0].shape.to_tvec(),
@@ -600,12 +695,63 @@ impl TypedOp for MatMulUnary {
         target.wire_node(&*node.name, self.clone(), &[input])
     }
 
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        if self.q_params.is_some() {
+            bail!("{} can not be compiled ahead of time: quantized product", node.name)
+        }
+        let (a_shape, b_shape, c_shape) = infer_shapes(
+            self.a.shape().into(),
+            emitter.input_shape(0).into(),
+            self.a_trans,
+            self.b_trans,
+            self.c_trans,
+        )?;
+        let rank = c_shape.len();
+        if a_shape[..rank - 2].iter().any(|&d| d != 1) {
+            bail!("{} can not be compiled ahead of time: batched constant operand", node.name)
+        }
+        let (ar, ac) = (a_shape[rank - 2], a_shape[rank - 1]);
+        let (br, bc) = (b_shape[rank - 2], b_shape[rank - 1]);
+        let (m, k, a_strides) = if self.a_trans { (ac, ar, (1, ac)) } else { (ar, ac, (ac, 1)) };
+        let (n, b_strides) = if self.b_trans { (br, (1, bc)) } else { (bc, (bc, 1)) };
+        let c_strides = if self.c_trans { (1, m) } else { (n, 1) };
+        let batch = c_shape[..rank - 2].iter().product::<usize>();
+        emitter.reserve_matmul(m, k, n);
+        let a = emitter.weight(&self.a)?;
+        let c = emitter.output(0)?;
+        emitter.push(format!(
+            "{{ let (b, c) = ({}, {}); for i in 0..{} {{ matmul(({}, {}, {}), {}, {:?}, &b[i * {}..], {:?}, &mut c[i * {}..], {:?}); }} }}",
+            emitter.input(0),
+            c,
+            batch,
+            m,
+            k,
+            n,
+            a,
+            a_strides,
+            br * bc,
+            b_strides,
+            m * n,
+            c_strides
+        ));
+        Ok(())
+    }
+
     fn codegen(
         &self,
         model: &TypedModel,
         node: &TypedNode,
     ) -> TractResult<Option<TypedModelPatch>> {
         let b = args_1!(model.node_input_facts(node.id)?);
+        if self.q_params.is_some() {
+            // quantized products are evaluated as is
+            return Ok(None);
+        }
         if let Some(b_shape) = b.shape.as_finite() {
             let patch =
                 if (self.a.datum_type(), b.datum_type) == (f32::datum_type(), f32::datum_type()) {
@@ -699,23 +845,7 @@ where
         pa.into_arc_tensor()
     });
     unsafe {
-        if geo.n == 1 {
-            geo.mm.as_mmm_mut().b_vec_from_data_and_stride(if b_trans {
-                1
-            } else {
-                *geo.b_shape.last().unwrap() as isize
-            });
-            geo.mm.as_mmm_mut().c_vec_from_data_and_stride(if c_trans {
-                1
-            } else {
-                *geo.c_shape.last().unwrap() as isize
-            });
-        } else {
-            geo.mm.as_mmm_mut().c_from_data_and_strides(
-                if c_trans { 1 } else { *geo.c_shape.last().unwrap() as isize },
-                if !c_trans { 1 } else { *geo.c_shape.last().unwrap() as isize },
-            );
-        };
+        geo.configure_storage();
         if let Some(q) = q_params {
             geo.mm.set_quant_params(q)?;
         }
@@ -735,21 +865,7 @@ where
         )?[0];
     }
     let c_prefix_dim_and_stride = if geo.c_shape_prefix.iter().any(|d| *d > 1) {
-        let c_prefix_strides: TVec<isize> = geo
-            .c_shape
-            .iter()
-            .rev()
-            .scan(1isize, |s, &d| {
-                let now: isize = *s;
-                *s *= d as isize;
-                Some(now)
-            })
-            .collect::<TVec<_>>()
-            .into_iter()
-            .skip(2)
-            .rev()
-            .collect::<TVec<_>>();
-        Some((geo.c_shape_prefix.clone(), c_prefix_strides))
+        Some(geo.c_prefix_dim_and_stride())
     } else {
         None
     };
@@ -768,6 +884,73 @@ where
     Ok(patch)
 }
 
+fn new_mat_mul_finite<TA, TB, TC, TI>(
+    model: &TypedModel,
+    node: &TypedNode,
+    a_shape: &[usize],
+    b_shape: &[usize],
+    a_trans: bool,
+    b_trans: bool,
+    c_trans: bool,
+    q_params: Option<&QParams>,
+    mmm: &impl Fn(usize, usize, usize) -> MMMWrapper<TA, TB, TC, TI>,
+) -> TractResult<TypedModelPatch>
+where
+    TA: Datum + Copy + Zero,
+    TB: Datum + Copy + Zero,
+    TC: Datum + Copy,
+    TI: Datum + Copy + Add + Mul + Zero + fmt::Debug,
+{
+    let mut patch = TypedModelPatch::default();
+    let mut a = patch.tap_model(model, node.inputs[0])?;
+    let mut b = patch.tap_model(model, node.inputs[1])?;
+    let mut geo = Geo::<TA, TB, TC, TI>::new(a_shape, b_shape, a_trans, b_trans, c_trans, mmm)?;
+    unsafe {
+        geo.configure_storage();
+        if let Some(q) = q_params {
+            geo.mm.set_quant_params(q)?;
+        }
+    }
+    let mut packed_a_shape: TVec<usize> = a_shape[..a_shape.len() - 2].into();
+    packed_a_shape.push(geo.mm.as_mmm().a_pack().len());
+    a = patch.wire_node(
+        format!("{}-pack-a", &*node.name),
+        MatMatMulPackA {
+            pack_a: geo.mm.as_mmm().a_pack().clone(),
+            row_stride: if a_trans { 1 } else { *a_shape.last().unwrap() as isize },
+            col_stride: if a_trans { *a_shape.last().unwrap() as isize } else { 1 },
+            output_shape: packed_a_shape,
+        },
+        &[a],
+    )?[0];
+    if geo.n > 1 {
+        let mut packed_b_shape: TVec<usize> = b_shape[..b_shape.len() - 2].into();
+        packed_b_shape.push(geo.mm.as_mmm().b_pack().len());
+        b = patch.wire_node(
+            format!("{}-pack-b", &*node.name),
+            MatMatMulPackB {
+                pack_b: geo.mm.as_mmm().b_pack().clone(),
+                col_stride: if b_trans { *b_shape.last().unwrap() as isize } else { 1 },
+                row_stride: if b_trans { 1 } else { *b_shape.last().unwrap() as isize },
+                output_shape: packed_b_shape,
+            },
+            &[b],
+        )?[0];
+    }
+    let c_prefix_dim_and_stride = if geo.c_shape_prefix.iter().any(|d| *d > 1) {
+        Some(geo.c_prefix_dim_and_stride())
+    } else {
+        None
+    };
+    let wire = patch.wire_node(
+        format!("{}-matmatmul", &*node.name),
+        MatMatMulFinite { c_shape: geo.c_shape, c_prefix_dim_and_stride, mmm: geo.mm },
+        &[a, b],
+    )?[0];
+    patch.shunt_outside(OutletId::new(node.id, 0), wire)?;
+    Ok(patch)
+}
+
 fn cost<A: ToDim + Clone, B: ToDim + Clone>(
     a: &[A],
     b: &[B],
@@ -812,4 +995,91 @@ mod test {
         let c_found = op.eval(tvec!(b, a)).unwrap().pop().unwrap();
         c.close_enough(&c_found, true).unwrap();
     }
+
+    fn reference(a: &ArrayD<f32>, b: &ArrayD<f32>, a_trans: bool, b_trans: bool) -> ArrayD<f32> {
+        let (_, _, c_shape) =
+            infer_shapes::<usize>(a.shape().into(), b.shape().into(), a_trans, b_trans, false)
+                .unwrap();
+        let rank = c_shape.len();
+        ArrayD::from_shape_fn(&*c_shape, |coords| {
+            let (m, n) = (coords[rank - 2], coords[rank - 1]);
+            let k = a.shape()[a.ndim() - 1 - a_trans as usize];
+            (0..k)
+                .map(|k| {
+                    let mut a_coords: Vec<usize> = coords.slice()[rank - a.ndim()..].into();
+                    let mut b_coords: Vec<usize> = coords.slice()[rank - b.ndim()..].into();
+                    for (ix, d) in a.shape()[..a.ndim() - 2].iter().enumerate() {
+                        a_coords[ix] = a_coords[ix].min(d - 1);
+                    }
+                    for (ix, d) in b.shape()[..b.ndim() - 2].iter().enumerate() {
+                        b_coords[ix] = b_coords[ix].min(d - 1);
+                    }
+                    let (ar, ac) = if a_trans { (k, m) } else { (m, k) };
+                    let (br, bc) = if b_trans { (n, k) } else { (k, n) };
+                    a_coords[a.ndim() - 2] = ar;
+                    a_coords[a.ndim() - 1] = ac;
+                    b_coords[b.ndim() - 2] = br;
+                    b_coords[b.ndim() - 1] = bc;
+                    a[&*a_coords] * b[&*b_coords]
+                })
+                .sum()
+        })
+    }
+
+    fn check_batched(a_shape: &[usize], b_shape: &[usize], a_trans: bool, b_trans: bool) {
+        let a = ArrayD::from_shape_fn(a_shape, |c| c.slice().iter().sum::<usize>() as f32);
+        let b = ArrayD::from_shape_fn(b_shape, |c| c.slice().iter().product::<usize>() as f32);
+        let expected = reference(&a, &b, a_trans, b_trans).into_arc_tensor();
+        let op = MatMul::default().with_a_trans(a_trans).with_b_trans(b_trans);
+
+        let found = op.eval(tvec!(a.clone().into_arc_tensor(), b.clone().into_arc_tensor()));
+        expected.close_enough(&found.unwrap()[0], true).unwrap();
+
+        let mut model = TypedModel::default();
+        let sa = model.add_source("a", TypedFact::dt_shape(f32::datum_type(), a_shape).unwrap());
+        let sb = model.add_source("b", TypedFact::dt_shape(f32::datum_type(), b_shape).unwrap());
+        let c = model.wire_node("c", op, &[sa.unwrap(), sb.unwrap()]).unwrap();
+        model.set_output_outlets(&c).unwrap();
+        let model = model.into_optimized().unwrap();
+        assert!(model.nodes().iter().any(|n| n.op_is::<MatMatMulFinite<f32, f32, f32, f32>>()));
+        let found =
+            SimplePlan::new(&model).unwrap().run(tvec!(a.into_tensor(), b.into_tensor())).unwrap();
+        expected.close_enough(&found[0], true).unwrap();
+    }
+
+    #[test]
+    fn batched() {
+        check_batched(&[3, 2, 4], &[3, 4, 5], false, false);
+    }
+
+    #[test]
+    fn batched_broadcast() {
+        check_batched(&[2, 1, 2, 3], &[3, 3, 4], false, false);
+    }
+
+    #[test]
+    fn batched_adj() {
+        check_batched(&[2, 3, 2], &[2, 4, 3], true, true);
+    }
+
+    #[test]
+    fn batched_mat_vec() {
+        check_batched(&[2, 3, 4], &[2, 4, 1], false, false);
+    }
+
+    #[test]
+    fn batched_unary_broadcast() {
+        let a = ArrayD::from_shape_fn(&[2, 3, 4][..], |c| c.slice().iter().sum::<usize>() as f32);
+        let b = ArrayD::from_shape_fn(&[4, 5][..], |c| c.slice().iter().product::<usize>() as f32);
+        let expected = reference(&a, &b, false, false).into_arc_tensor();
+        let mut model = TypedModel::default();
+        let sb = model
+            .add_source("b", TypedFact::dt_shape(f32::datum_type(), &[4usize, 5][..]).unwrap());
+        let op = MatMulUnary::new(a.into_arc_tensor(), false, false, false, None);
+        let c = model.wire_node("c", op, &[sb.unwrap()]).unwrap();
+        model.set_output_outlets(&c).unwrap();
+        let model = model.into_optimized().unwrap();
+        let found = SimplePlan::new(&model).unwrap().run(tvec!(b.into_tensor())).unwrap();
+        expected.close_enough(&found[0], true).unwrap();
+    }
 }
@@ -10,26 +10,29 @@ use super::binary::*;
 bin_to_super_type!(add, Add,
         flip:commute,
         validation: Validation::Rounding,
+        rust: "a + b",
      [f32, i8, i16, i32, i64, u8, u16, f16, f64, TDim] => |c, a, b| *c = a.clone() + b);
-bin_to_super_type!(sub, Sub, flip:flip_sub,
+bin_to_super_type!(sub, Sub, flip:flip_sub, rust: "a - b",
      [f32, i8, i16, i32, i64, u8, u16, f16, f64, TDim] => |c, a, b| *c = a.clone() - b);
 #[inline]
 bin_to_super_type!(mul, Mul,
         cost: |dt| tvec!((Cost::FMA(dt), 1)),
         flip:commute,
+        rust: "a * b",
      [f32, i8, i16, i32, i64, u8, u16, f16, f64, TDim] => |c, a, b| *c = a.clone() * b);
 bin_to_super_type!(div, Div,
         cost: |dt| tvec!((Cost::Div(dt), 1)),
+        rust: "a / b",
      [f32, i8, i16, i32, i64, u8, u16, f16, f64, TDim] => |c, a, b| *c = a.clone() / b);
 bin_to_super_type!(rem, Rem,
      [f32, i8, i16, i32, i64, u8, u16, f16, f64, TDim] => |c, a, b| *c = a.clone() % b);
-bin_to_super_type!(min, Min, flip:commute,
+bin_to_super_type!(min, Min, flip:commute, rust: "a.min(b)",
      [f32, f64] => |c,a,b| *c = a.min(*b),
      [i8, i16, i32, i64, u8, u16] => |c, a, b| *c = *a.min(b));
-bin_to_super_type!(max, Max, flip:commute,
+bin_to_super_type!(max, Max, flip:commute, rust: "a.max(b)",
      [f32, f64] => |c,a,b| *c = a.max(*b),
      [i8, i16, i32, i64, u8, u16] => |c, a, b| *c = *a.max(b));
-bin_to_super_type!(pow, Pow,
+bin_to_super_type!(pow, Pow, rust: "a.powf(b)",
      [f32, f64] => |c,a,b| *c = a.powf(*b));
 
 fn flip_sub(_op: &dyn BinMiniOp, t: &Arc<Tensor>) -> Option<UnaryOp> {
@@ -48,52 +51,63 @@ fn flip_sub(_op: &dyn BinMiniOp, t: &Arc<Tensor>) -> Option<UnaryOp> {
 element_wise!(abs, Abs, [f16, f32, i32] => |_, xs| {
     xs.iter_mut().for_each(|x| *x = x.abs());
     Ok(())
-});
+};
+    rust: |_| Ok("x.abs()".into())
+);
 
 element_wise!(exp, Exp, [f16, f32, f64] => |_, xs| {
     xs.iter_mut().for_each(|x| *x = x.exp());
     Ok(())
 };
-    validation: Validation::Rounding
+    validation: Validation::Rounding;
+    rust: |_| Ok("x.exp()".into())
 );
 
 element_wise!(ln, Ln, [f16, f32, f64] => |_, xs| {
     xs.iter_mut().for_each(|x| *x = x.ln());
     Ok(())
 };
-    validation: Validation::Rounding
+    validation: Validation::Rounding;
+    rust: |_| Ok("x.ln()".into())
 );
 
 element_wise!(sqrt, Sqrt, [f16, f32, f64] => |_, xs| {
     xs.iter_mut().for_each(|x| *x = x.sqrt());
     Ok(())
 };
-    validation: Validation::Rounding
+    validation: Validation::Rounding;
+    rust: |_| Ok("x.sqrt()".into())
 );
 
 element_wise!(recip, Recip, [f16, f32, f64] => |_, xs| {
     xs.iter_mut().for_each(|x| *x = x.recip());
     Ok(())
 };
-    validation: Validation::Rounding
+    validation: Validation::Rounding;
+    rust: |_| Ok("x.recip()".into())
 );
 
 element_wise!(rsqrt, Rsqrt, [f16, f32, f64] => |_, xs| {
     xs.iter_mut().for_each(|x| *x = x.sqrt().recip());
     Ok(())
 };
-    validation: Validation::Rounding
+    validation: Validation::Rounding;
+    rust: |_| Ok("x.sqrt().recip()".into())
 );
 
 element_wise!(ceil, Ceil, [f16, f32, f64] => |_, xs| {
     xs.iter_mut().for_each(|x| *x = x.ceil());
     Ok(())
-});
+};
+    rust: |_| Ok("x.ceil()".into())
+);
 
 element_wise!(floor, Floor, [f16, f32, f64] => |_, xs| {
     xs.iter_mut().for_each(|x| *x = x.floor());
     Ok(())
-});
+};
+    rust: |_| Ok("x.floor()".into())
+);
 
 element_wise!(scalar_min_max, ScalarMinMax { min: Tensor, max: Tensor },
    [f32, f64] => |m, xs| {
@@ -101,82 +115,164 @@ element_wise!(scalar_min_max, ScalarMinMax { min: Tensor, max: Tensor },
         let min = m.min.cast_to_scalar()?;
         xs.iter_mut().for_each(|x| { *x = x.max(max).min(min) });
         Ok(())
-});
+};
+    rust: |m| Ok(format!("x.max({}).min({})",
+        crate::aot::f32_literal(m.max.cast_to_scalar()?),
+        crate::aot::f32_literal(m.min.cast_to_scalar()?)))
+);
 
 element_wise!(scalar_min, ScalarMin { min: Tensor },
    [f32, f64] => |m, xs| {
         let min = m.min.cast_to_scalar()?;
         xs.iter_mut().for_each(|x| *x = x.min(min));
         Ok(())
-});
+};
+    rust: |m| Ok(format!("x.min({})", crate::aot::f32_literal(m.min.cast_to_scalar()?)))
+);
 
 element_wise!(scalar_max, ScalarMax { max: Tensor },
    [f32, f64] => |m, xs| {
         let max = m.max.cast_to_scalar()?;
         xs.iter_mut().for_each(|x| *x = x.max(max));
         Ok(())
-});
+};
+    rust: |m| Ok(format!("x.max({})", crate::aot::f32_literal(m.max.cast_to_scalar()?)))
+);
 
 element_wise!(cos, Cos, [f16, f32, f64] => |_, xs| {
     xs.iter_mut().for_each(|x| *x = x.cos());
     Ok(())
-});
+};
+    rust: |_| Ok("x.cos()".into())
+);
 
 element_wise!(sin, Sin, [f16, f32, f64] => |_, xs| {
     xs.iter_mut().for_each(|x| *x = x.sin());
     Ok(())
-});
+};
+    rust: |_| Ok("x.sin()".into())
+);
 
 element_wise!(tan, Tan, [f16, f32, f64] => |_, xs| {
     xs.iter_mut().for_each(|x| *x = x.tan());
     Ok(())
-});
+};
+    rust: |_| Ok("x.tan()".into())
+);
 
 element_wise!(acos, Acos, [f16, f32, f64] => |_, xs| {
     xs.iter_mut().for_each(|x| *x = x.acos());
     Ok(())
-});
+};
+    rust: |_| Ok("x.acos()".into())
+);
 
 element_wise!(asin, Asin, [f16, f32, f64] => |_, xs| {
     xs.iter_mut().for_each(|x| *x = x.asin());
     Ok(())
-});
+};
+    rust: |_| Ok("x.asin()".into())
+);
 
 element_wise!(atan, Atan, [f16, f32, f64] => |_, xs| {
     xs.iter_mut().for_each(|x| *x = x.atan());
     Ok(())
-});
+};
+    rust: |_| Ok("x.atan()".into())
+);
 
 element_wise!(cosh, Cosh, [f16, f32, f64] => |_, xs| {
     xs.iter_mut().for_each(|x| *x = x.cosh());
     Ok(())
-});
+};
+    rust: |_| Ok("x.cosh()".into())
+);
 
 element_wise!(sinh, Sinh, [f16, f32, f64] => |_, xs| {
     xs.iter_mut().for_each(|x| *x = x.sinh());
     Ok(())
-});
+};
+    rust: |_| Ok("x.sinh()".into())
+);
 
 element_wise!(tanh, Tanh,
    [f32] => |_, xs| { (tract_linalg::ops().stanh)().run(xs); Ok(()) },
    [f16, f64] => |_, xs| { xs.iter_mut().for_each(|x| *x = x.tanh()); Ok(()) };
-   cost: |dt| {tvec!((Cost::FMA(dt), 11), (Cost::Div(dt), 1))}
+   cost: |dt| {tvec!((Cost::FMA(dt), 11), (Cost::Div(dt), 1))};
+   rust: |_| Ok("x.tanh()".into())
 );
 
-element_wise!(acosh, Acosh, [f16, f32, f64] => |_, xs| { xs.iter_mut().for_each(|x| *x = x.acosh()); Ok(()) });
-element_wise!(asinh, Asinh, [f16, f32, f64] => |_, xs| { xs.iter_mut().for_each(|x| *x = x.asinh()); Ok(()) });
-element_wise!(atanh, Atanh, [f16, f32, f64] => |_, xs| { xs.iter_mut().for_each(|x| *x = x.atanh()); Ok(()) });
+element_wise!(acosh, Acosh, [f16, f32, f64] => |_, xs| { xs.iter_mut().for_each(|x| *x = x.acosh()); Ok(()) };
+    rust: |_| Ok("x.acosh()".into())
+);
+element_wise!(asinh, Asinh, [f16, f32, f64] => |_, xs| { xs.iter_mut().for_each(|x| *x = x.asinh()); Ok(()) };
+    rust: |_| Ok("x.asinh()".into())
+);
+element_wise!(atanh, Atanh, [f16, f32, f64] => |_, xs| { xs.iter_mut().for_each(|x| *x = x.atanh()); Ok(()) };
+    rust: |_| Ok("x.atanh()".into())
+);
 
 element_wise!(neg, Neg, [i8, i16, i32, i64, f16, f32, f64, TDim] => |_, xs| {
     xs.iter_mut().for_each(|x| *x = -x.clone());
     Ok(())
-});
+};
+    rust: |_| Ok("-x".into())
+);
 
 element_wise!(sign, Sign, [f16, f32, f64] => |_, xs| {
     xs.iter_mut().for_each(|x| *x = if x.is_zero() { *x } else { x.signum() });
     Ok(())
+};
+    rust: |_| Ok("if x == 0.0 { x } else { x.signum() }".into())
+);
+
+element_wise!(square, Square, [i8, i16, i32, i64, f16, f32, f64] => |_, xs| {
+    xs.iter_mut().for_each(|x| *x = x.clone() * x.clone());
+    Ok(())
+};
+    rust: |_| Ok("x * x".into())
+);
+
+element_wise!(round_half_to_even, RoundHalfToEven, [f32] => |_, xs| {
+    xs.iter_mut().for_each(|x| *x = round_half_to_even_f32(*x));
+    Ok(())
+});
+
+fn round_half_to_even_f32(x: f32) -> f32 {
+    if (x - x.trunc()).abs() == 0.5 {
+        2.0 * (x / 2.0).round()
+    } else {
+        x.round()
+    }
+}
+
+element_wise!(erf, Erf, [f32] => |_, xs| {
+    xs.iter_mut().for_each(|x| *x = erf_f32(*x));
+    Ok(())
 });
 
+#[allow(non_upper_case_globals)]
+fn erf_f32(x: f32) -> f32 {
+    const a1: f32 = 0.0705230784;
+    const a2: f32 = 0.0422820123;
+    const a3: f32 = 0.0092705272;
+    const a4: f32 = 0.0001520143;
+    const a5: f32 = 0.0002765672;
+    const a6: f32 = 0.0000430638;
+
+    let signum = x.signum();
+    let x = x.abs();
+    let y = a6 * x;
+    let y = (a5 + y) * x;
+    let y = (a4 + y) * x;
+    let y = (a3 + y) * x;
+    let y = (a2 + y) * x;
+    let y = (a1 + y) * x;
+    let y = 1.0 - (y + 1.0).powi(16).recip();
+
+    y.copysign(signum)
+}
+
 #[cfg(test)]
 mod tests {
     use ndarray::arr2;
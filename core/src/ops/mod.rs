@@ -30,7 +30,7 @@ pub mod source;
 pub mod unimpl;
 
 pub use axis::{AxesInfo, AxisInfo};
-pub use downsample::Downsample;
+pub use downsample::{Downsample, Resample, Upsample};
 
 pub fn check_input_arity(inputs: &[TensorProxy], expected: usize) -> TractResult<()> {
     if inputs.len() != expected {
@@ -75,6 +75,17 @@ pub trait OpState: fmt::Debug + Send + objekt::Clone {
         op: &dyn Op,
         inputs: TVec<Arc<Tensor>>,
     ) -> TractResult<TVec<Arc<Tensor>>>;
+
+    /// Dumps the state as plain tensors, for snapshots.
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        bail!("{:?} can not be saved", self)
+    }
+
+    /// Restores a state dumped by `save`.
+    #[allow(unused_variables)]
+    fn load(&mut self, tensors: TVec<Tensor>) -> TractResult<()> {
+        bail!("{:?} can not be loaded", self)
+    }
 }
 
 pub trait StatelessOp: Op {
@@ -238,7 +249,11 @@ pub trait TypedOp:
         _pulse: usize,
     ) -> TractResult<TVec<OutletId>> {
         debug!("{:?}", node);
-        bail!("Operator {} do not support pulsification", self.name())
+        bail!(
+            "{} can not be pulsified: operator {} does not support pulsification",
+            node.name,
+            self.name()
+        )
     }
 
     /// Translate the op into the most efficient form possible for execution.
@@ -253,6 +268,20 @@ pub trait TypedOp:
         Ok(None)
     }
 
+    /// Emit the Rust code computing the op, for ahead-of-time compilation.
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        node: &NormalizedNode,
+        _emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        bail!(
+            "{} can not be compiled ahead of time: operator {} is not supported",
+            node.name,
+            self.name()
+        )
+    }
+
     /// Nested model multipliers, with label (for profiling).
     #[allow(unused_variables)]
     fn nested_model_multipliers(&self, inputs: &[&TypedFact]) -> Vec<(Cow<str>, f32)> {
@@ -70,7 +70,7 @@ impl InferenceRulesOp for ArgMaxMin {
                 s.equals(&outputs[0].shape[i], &inputs[0].shape[i])?;
             }
             s.given(&inputs[0].rank, move |s, rank| {
-                for i in (self.axis + 1)..(rank as usize - 1) {
+                for i in self.axis..(rank as usize - 1) {
                     s.equals(&outputs[0].shape[i], &inputs[0].shape[i + 1])?;
                 }
                 Ok(())
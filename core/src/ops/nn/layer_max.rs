@@ -273,6 +273,21 @@ impl TypedOp for LayerSoftmax {
         pulsify(self, self.axis, node, target, mapping)
     }
 
+    fn emit_rust(
+        &self,
+        _model: &NormalizedModel,
+        _node: &NormalizedNode,
+        emitter: &mut crate::aot::Emitter,
+    ) -> TractResult<()> {
+        let shape = emitter.input_shape(0);
+        let axis =
+            if self.axis < 0 { shape.len() as isize + self.axis } else { self.axis } as usize;
+        let len = shape[axis..].iter().product::<usize>();
+        let output = emitter.output(0)?;
+        emitter.push(format!("softmax({}, {}, {});", len, emitter.input(0), output));
+        Ok(())
+    }
+
     typed_op_as_op!();
 }
 
@@ -1,29 +1,40 @@
 use crate::internal::*;
 use ndarray::prelude::*;
 
-#[derive(Debug, Clone, new, Default)]
+/// Local response normalization, across the channels found on `axis` (1 by
+/// default, as in NCHW).
+#[derive(Debug, Clone, Default)]
 pub struct Lrn {
     alpha: f32,
     beta: f32,
     bias: f32,
     size: usize,
+    axis: usize,
 }
 
 impl Lrn {
+    pub fn new(alpha: f32, beta: f32, bias: f32, size: usize) -> Lrn {
+        Lrn { alpha, beta, bias, size, axis: 1 }
+    }
+
+    pub fn with_axis(self, axis: usize) -> Lrn {
+        Lrn { axis, ..self }
+    }
+
     fn eval_t<T: Datum + ::num_traits::Float + ::num_traits::FromPrimitive + ::std::iter::Sum>(
         &self,
         input: Arc<Tensor>,
     ) -> TractResult<TVec<Arc<Tensor>>> {
         let input = input.to_array_view::<T>()?;
-        let channels = input.shape()[1];
+        let channels = input.shape()[self.axis];
         let output = Array::from_shape_fn(input.shape(), |mut coords| {
-            let c = coords[1];
+            let c = coords[self.axis];
             let x = input[&coords];
             let c_min = c.saturating_sub((self.size - 1) / 2);
             let c_max = (c + ((self.size - 1).div_ceil(2))).min(channels - 1);
             let square_sum: T = (c_min..=c_max)
                 .map(|c| {
-                    coords[1] = c;
+                    coords[self.axis] = c;
                     input[&coords].powi(2)
                 })
                 .sum();
@@ -30,7 +30,8 @@ element_wise!(sigmoid, Sigmoid, [f32] => |_, xs| {
     (tract_linalg::ops().ssigmoid)().run(xs);
     Ok(())
 };
-    cost: |dt| {tvec!((Cost::FMA(dt), 11), (Cost::Div(dt), 1))}
+    cost: |dt| {tvec!((Cost::FMA(dt), 11), (Cost::Div(dt), 1))};
+    rust: |_| Ok("(1.0 + (-x).exp()).recip()".into())
 );
 
 element_wise!(elu, Elu { alpha: f32 },
@@ -49,7 +50,9 @@ element_wise!(leaky_relu, LeakyRelu { alpha: f32 },
     [f32, f64] => |e, xs| {
         xs.iter_mut().for_each(|x| { *x = x.leaky_relu(e.alpha); });
         Ok(())
-});
+};
+    rust: |e| Ok(format!("if x < 0.0 {{ {} * x }} else {{ x }}", crate::aot::f32_literal(e.alpha)))
+);
 
 element_wise!(parametric_softplus, ParametricSoftplus { alpha: f32, beta: f32 },
     [f32, f64] => |e, xs| {
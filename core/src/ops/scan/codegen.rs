@@ -40,9 +40,16 @@ impl StatefullOp for Codegen {
         _session: &mut SessionState,
         _node_id: usize,
     ) -> TractResult<Option<Box<dyn OpState>>> {
+        let mut hidden_facts = tvec!();
+        for (ix, input) in self.input_mapping.iter().enumerate() {
+            if let InputMapping::State { .. } = input {
+                hidden_facts.push(self.plan.model().input_fact(ix)?.clone());
+            }
+        }
         Ok(Some(Box::new(State {
             position: 0,
             hidden_state: tvec!(),
+            hidden_facts,
             model_state: TypedSimpleState::new(Arc::clone(&self.plan))?,
         })))
     }
@@ -52,6 +59,7 @@ impl StatefullOp for Codegen {
 struct State {
     position: usize,
     hidden_state: TVec<Tensor>,
+    hidden_facts: TVec<TypedFact>,
     model_state: TypedSimpleState<TypedModel, Arc<TypedSimplePlan<TypedModel>>>,
 }
 
@@ -229,6 +237,47 @@ impl OpState for State {
 
         Ok(outputs.into_iter().map(Arc::new).collect())
     }
+
+    /// The position (number of iterations run) as an i64 scalar, followed by
+    /// the hidden states, in input mapping order. Hidden states are omitted
+    /// before the first iteration, they will come from the initializers.
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        let mut tensors = tvec!(tensor0(self.position as i64));
+        tensors.extend(self.hidden_state.iter().cloned());
+        Ok(tensors)
+    }
+
+    fn load(&mut self, tensors: TVec<Tensor>) -> TractResult<()> {
+        let mut tensors = tensors.into_iter();
+        let position = tensors.next().ok_or("Scan state expects a position")?;
+        self.position = *position.to_scalar::<i64>()? as usize;
+        let hidden_state: TVec<Tensor> = tensors.collect();
+        if !hidden_state.is_empty() && hidden_state.len() != self.hidden_facts.len() {
+            bail!(
+                "Scan state expects {} hidden states, got {}",
+                self.hidden_facts.len(),
+                hidden_state.len()
+            )
+        }
+        for (ix, (tensor, fact)) in hidden_state.iter().zip(self.hidden_facts.iter()).enumerate() {
+            let shape_ok = fact
+                .shape
+                .as_finite()
+                .map(|shape| shape == tensor.shape())
+                .unwrap_or(fact.shape.rank() == tensor.rank());
+            if tensor.datum_type() != fact.datum_type || !shape_ok {
+                bail!(
+                    "Scan hidden state #{} expected to be {:?}, got {:?} {:?}",
+                    ix,
+                    fact,
+                    tensor.shape(),
+                    tensor.datum_type()
+                )
+            }
+        }
+        self.hidden_state = hidden_state;
+        Ok(())
+    }
 }
 
 impl TypedOp for Codegen {
@@ -59,3 +59,12 @@ pub enum StateInitializer {
     FromInput(usize),
     Value(Arc<Tensor>),
 }
+
+impl StateInitializer {
+    pub fn from_input(&self) -> Option<usize> {
+        match self {
+            StateInitializer::FromInput(slot) => Some(*slot),
+            _ => None,
+        }
+    }
+}
@@ -70,6 +70,93 @@ impl Typed {
             seq_length_input_slot,
         })
     }
+
+    /// Moves a constant outer input inside the body, or in the state
+    /// initializer. Scanned inputs are left alone.
+    fn absorb_const_input(&self, slot: usize, konst: Arc<Tensor>) -> TractResult<Option<Typed>> {
+        let mut new = self.clone();
+        let mut body_inputs = self.body.input_outlets()?.to_vec();
+        for ix in (0..self.input_mapping.len()).rev() {
+            match &self.input_mapping[ix] {
+                InputMapping::Scan { slot: s, .. } if *s == slot => return Ok(None),
+                InputMapping::Full { slot: s } if *s == slot => {
+                    let outlet = body_inputs.remove(ix);
+                    new.body.node_mut(outlet.node).op =
+                        Box::new(crate::ops::konst::Const::new(konst.clone()));
+                    new.body.set_outlet_fact(outlet, konst.clone().into())?;
+                    new.input_mapping.remove(ix);
+                }
+                InputMapping::State { initializer: StateInitializer::FromInput(s) }
+                    if *s == slot =>
+                {
+                    new.input_mapping[ix] =
+                        InputMapping::State { initializer: StateInitializer::Value(konst.clone()) };
+                }
+                _ => (),
+            }
+        }
+        new.body.set_input_outlets(&body_inputs)?;
+        if new.seq_length_input_slot == Some(slot) {
+            new.seq_length_input_slot = None;
+        }
+        let renumber = |s: &mut usize| {
+            if *s > slot {
+                *s -= 1
+            }
+        };
+        for im in &mut new.input_mapping {
+            match im {
+                InputMapping::Full { slot } | InputMapping::Scan { slot, .. } => renumber(slot),
+                InputMapping::State { initializer: StateInitializer::FromInput(slot) } => {
+                    renumber(slot)
+                }
+                _ => (),
+            }
+        }
+        if let Some(s) = new.seq_length_input_slot.as_mut() {
+            renumber(s)
+        }
+        new.decluttered = false;
+        Ok(Some(new))
+    }
+
+    /// Stops computing the outer outputs nobody is using.
+    fn prune_unused_outputs(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let model_outputs = model.output_outlets()?;
+        let used: Vec<bool> = (0..node.outputs.len())
+            .map(|ix| {
+                !node.outputs[ix].successors.is_empty()
+                    || model_outputs.contains(&OutletId::new(node.id, ix))
+            })
+            .collect();
+        if used.iter().all(|u| *u) || !used.iter().any(|u| *u) {
+            return Ok(None);
+        }
+        let renumbered = |slot: Option<usize>| {
+            slot.filter(|s| used[*s]).map(|s| used[..s].iter().filter(|u| **u).count())
+        };
+        let mut new = self.clone();
+        for om in &mut new.output_mapping {
+            om.full_slot = renumbered(om.full_slot);
+            om.last_value_slot = renumbered(om.last_value_slot);
+        }
+        let mut patch = TypedModelPatch::default();
+        let inputs = node
+            .inputs
+            .iter()
+            .map(|i| patch.tap_model(model, *i))
+            .collect::<TractResult<TVec<_>>>()?;
+        let wires = patch.wire_node(&*node.name, new, &inputs)?;
+        let kept = (0..node.outputs.len()).filter(|ix| used[*ix]);
+        for (ix, wire) in kept.zip(wires.iter()) {
+            patch.shunt_outside(OutletId::new(node.id, ix), *wire)?;
+        }
+        Ok(Some(patch))
+    }
 }
 
 impl Op for Typed {
@@ -156,7 +243,16 @@ impl TypedOp for Typed {
             new.decluttered = true;
             return Ok(Some(TypedModelPatch::replace_single_op(model, node, &node.inputs, new)?));
         }
-        Ok(None)
+        for (slot, input) in node.inputs.iter().enumerate() {
+            if let Some(konst) = model.outlet_fact(*input)?.konst.clone() {
+                if let Some(op) = self.absorb_const_input(slot, konst)? {
+                    let mut inputs = node.inputs.clone();
+                    inputs.remove(slot);
+                    return Ok(Some(TypedModelPatch::replace_single_op(model, node, &inputs, op)?));
+                }
+            }
+        }
+        self.prune_unused_outputs(model, node)
     }
 
     fn pulsify(
@@ -167,31 +263,52 @@ impl TypedOp for Typed {
         mapping: &HashMap<OutletId, OutletId>,
         _pulse: usize,
     ) -> TractResult<TVec<OutletId>> {
-        if node.inputs.len() > 1 || node.outputs.len() > 1 {
-            bail!("Scan pulsificiaton limited to single streaming input and output case");
+        let mut skip = None;
+        for (slot, input) in node.inputs.iter().enumerate() {
+            let input_fact = target.outlet_fact(mapping[input])?;
+            if self.seq_length_input_slot == Some(slot) {
+                bail!("Can not pulsify a Scan with a non-constant sequence length input")
+            }
+            let scan = self
+                .input_mapping
+                .iter()
+                .filter_map(InputMapping::as_scan)
+                .find(|mapping| mapping.0 == slot);
+            let (axis, chunk) = if let Some((_slot, axis, chunk)) = scan {
+                (axis, chunk.to_integer()? as usize)
+            } else if self
+                .input_mapping
+                .iter()
+                .any(|im| im.as_state().map(|s| s.from_input() == Some(slot)).unwrap_or(false))
+            {
+                bail!("Can not pulsify a Scan with a non-constant initial state (input #{})", slot)
+            } else {
+                bail!("Can not pulsify a Scan with a non-constant full input (input #{})", slot)
+            };
+            if input_fact.axis != axis {
+                bail!("Scan pulsification limited to scanning axis");
+            }
+            if input_fact.pulse() % chunk != 0 || input_fact.delay % chunk != 0 {
+                bail!(
+                    "Scan chunk ({}) must divide the pulse ({}) and the delay ({})",
+                    chunk,
+                    input_fact.pulse(),
+                    input_fact.delay
+                )
+            }
+            let input_skip = (input_fact.delay / chunk, input_fact.pulse() / chunk);
+            if skip.get_or_insert(input_skip) != &input_skip {
+                bail!("Scanned inputs must be pulsed at the same rate and delay")
+            }
         }
-        let input = mapping[&node.inputs[0]];
-        let input_fact = target.outlet_fact(input)?;
-        let (_slot, axis, _chunk) = self
-            .input_mapping
-            .iter()
-            .filter_map(InputMapping::as_scan)
-            .find(|mapping| mapping.0 == 0)
-            .unwrap();
-        if input_fact.axis != axis {
-            bail!("Scan pulsification limited to scanning axis");
+        if self.output_mapping.iter().any(|om| om.last_value_slot.is_some()) {
+            bail!("Can not pulsify a Scan exposing the last value of its states: only full outputs can be streamed")
         }
-
-        let mut output_fact = crate::pulse::PulsedFact::from_tensor_fact_pulse(
-            &node.outputs[0].fact,
-            input_fact.pulse(),
-        )?;
-        output_fact.delay = input_fact.delay;
         let mut op = self.clone();
-        op.skip = input_fact.delay;
-        op.output_mapping.iter_mut().find(|om| om.full_slot == Some(0)).unwrap().full_dim_hint =
-            None;
-        target.wire_node(&*node.name, op, &[input])
+        op.skip = skip.map(|s| s.0).unwrap_or(0);
+        op.output_mapping.iter_mut().for_each(|om| om.full_dim_hint = None);
+        let inputs: TVec<OutletId> = node.inputs.iter().map(|i| mapping[i]).collect();
+        target.wire_node(&*node.name, op, &inputs)
     }
 
     fn nested_model_multipliers(&self, inputs: &[&TypedFact]) -> Vec<(Cow<str>, f32)> {
@@ -219,33 +336,38 @@ impl TypedOp for Typed {
 
 impl PulsedOp for Typed {
     fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
-        let (output_body_ix, output_mapping) = self
-            .output_mapping
-            .iter()
-            .enumerate()
-            .find(|(_ix, om)| om.full_slot == Some(0))
-            .unwrap();
-        let output_body_fact = self.body.output_fact(output_body_ix)?;
-        let shape = output_body_fact
-            .shape
-            .iter()
-            .enumerate()
-            .map(|(axis, d)| {
-                if axis == output_mapping.axis {
-                    inputs[0].pulse()
+        let (slot, _axis, chunk) =
+            self.input_mapping.iter().filter_map(InputMapping::as_scan).next().unwrap();
+        let chunk = chunk.to_integer()? as usize;
+        let input = inputs[slot];
+        let mut outputs = tvec!();
+        for (ix, om) in self.output_mapping.iter().enumerate() {
+            if let Some(slot) = om.full_slot {
+                let output_chunk = om.chunk.to_integer()? as usize;
+                let body_fact = self.body.output_fact(ix)?;
+                let mut shape: TVec<usize> = body_fact
+                    .shape
+                    .iter()
+                    .map(|d| Ok(d.to_integer()? as usize))
+                    .collect::<TractResult<_>>()?;
+                shape[om.axis] = input.pulse() / chunk * output_chunk;
+                let dim = if chunk == output_chunk {
+                    input.dim.clone()
                 } else {
-                    d.to_integer().unwrap() as usize
-                }
-            })
-            .collect();
-        let fact = PulsedFact {
-            datum_type: output_body_fact.datum_type,
-            shape,
-            axis: output_mapping.axis,
-            dim: inputs[0].dim.clone(),
-            delay: inputs[0].delay,
-        };
-        Ok(tvec!(fact))
+                    input.dim.clone().div_ceil(chunk.to_dim()) * output_chunk
+                };
+                let fact = PulsedFact {
+                    datum_type: body_fact.datum_type,
+                    shape,
+                    axis: om.axis,
+                    dim,
+                    delay: input.delay / chunk * output_chunk,
+                };
+                outputs.push((slot, fact));
+            }
+        }
+        outputs.sort_by_key(|a| a.0);
+        Ok(outputs.into_iter().map(|(_slot, v)| v).collect())
     }
 
     pulsed_op_as_op!();
@@ -12,6 +12,14 @@ impl OpState for SourceState {
     ) -> TractResult<TVec<Arc<Tensor>>> {
         Ok(tvec!(session.inputs[&self.0].clone()))
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!())
+    }
+
+    fn load(&mut self, _tensors: TVec<Tensor>) -> TractResult<()> {
+        Ok(())
+    }
 }
 
 #[derive(Debug, Clone, new)]
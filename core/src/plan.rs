@@ -6,13 +6,161 @@ use crate::internal::*;
 use crate::model::order::eval_order_for_nodes;
 use crate::model::{Fact, ModelImpl, OutletId};
 
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub struct SessionState {
     pub inputs: HashMap<usize, Arc<Tensor>>,
     pub known_stream_len: Option<usize>,
     pub tensors: HashMap<String, Tensor>,
 }
 
+/// Internal state of a SimpleState, made of plain tensors so that it can be
+/// stored away, or sent to another process running the same model (see
+/// `write` and `read`).
+#[derive(Debug, Default, Clone)]
+pub struct StateSnapshot {
+    pub known_stream_len: Option<usize>,
+    /// Session tensors (like tensorflow variables), by id.
+    pub tensors: HashMap<String, Tensor>,
+    /// Op states, as dumped by `OpState::save`, by node name.
+    pub op_states: HashMap<String, TVec<Tensor>>,
+}
+
+// datum types a snapshot can hold, the index being the type code
+const SNAPSHOT_DATUM_TYPES: [DatumType; 10] = [
+    DatumType::Bool,
+    DatumType::U8,
+    DatumType::U16,
+    DatumType::I8,
+    DatumType::I16,
+    DatumType::I32,
+    DatumType::I64,
+    DatumType::F16,
+    DatumType::F32,
+    DatumType::F64,
+];
+
+impl StateSnapshot {
+    /// Serializes the snapshot.
+    ///
+    /// All integers are little endian. The format is the "TRSS" magic, a u32
+    /// version (1), a u8 endianness flag for tensor data (1 for little, 2 for
+    /// big), `known_stream_len` as u64 (`u64::MAX` for none), then a u32
+    /// count of session tensors, each one a name and a tensor, then a u32
+    /// count of op states, each one a name, a u32 count of tensors, and the
+    /// tensors. Names are a u32 length followed by utf-8 bytes. Tensors are a
+    /// u8 datum type code, a u32 rank, the dimensions as u64, and the raw
+    /// data in the writer native byte order. Entries are sorted by name.
+    pub fn write<W: std::io::Write>(&self, w: &mut W) -> TractResult<()> {
+        w.write_all(b"TRSS")?;
+        w.write_all(&1u32.to_le_bytes())?;
+        w.write_all(&[if cfg!(target_endian = "little") { 1 } else { 2 }])?;
+        let len = self.known_stream_len.map(|l| l as u64).unwrap_or(std::u64::MAX);
+        w.write_all(&len.to_le_bytes())?;
+        let mut tensors: Vec<_> = self.tensors.iter().collect();
+        tensors.sort_by_key(|pair| pair.0);
+        w.write_all(&(tensors.len() as u32).to_le_bytes())?;
+        for (name, tensor) in tensors {
+            write_name(w, name)?;
+            write_tensor(w, tensor)?;
+        }
+        let mut op_states: Vec<_> = self.op_states.iter().collect();
+        op_states.sort_by_key(|pair| pair.0);
+        w.write_all(&(op_states.len() as u32).to_le_bytes())?;
+        for (name, tensors) in op_states {
+            write_name(w, name)?;
+            w.write_all(&(tensors.len() as u32).to_le_bytes())?;
+            for tensor in tensors {
+                write_tensor(w, tensor)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Deserializes a snapshot written by `write`.
+    pub fn read<R: std::io::Read>(r: &mut R) -> TractResult<StateSnapshot> {
+        let mut magic = [0u8; 4];
+        r.read_exact(&mut magic)?;
+        if &magic != b"TRSS" {
+            bail!("Not a state snapshot")
+        }
+        let version = read_u32(r)?;
+        if version != 1 {
+            bail!("Unsupported state snapshot version {}", version)
+        }
+        let mut endian = [0u8; 1];
+        r.read_exact(&mut endian)?;
+        if endian[0] != if cfg!(target_endian = "little") { 1 } else { 2 } {
+            bail!("State snapshot was written with a different byte order")
+        }
+        let len = read_u64(r)?;
+        let known_stream_len = if len == std::u64::MAX { None } else { Some(len as usize) };
+        let mut tensors = HashMap::new();
+        for _ in 0..read_u32(r)? {
+            let name = read_name(r)?;
+            tensors.insert(name, read_tensor(r)?);
+        }
+        let mut op_states = HashMap::new();
+        for _ in 0..read_u32(r)? {
+            let name = read_name(r)?;
+            let count = read_u32(r)?;
+            let states = (0..count).map(|_| read_tensor(r)).collect::<TractResult<TVec<_>>>()?;
+            op_states.insert(name, states);
+        }
+        Ok(StateSnapshot { known_stream_len, tensors, op_states })
+    }
+}
+
+fn write_name<W: std::io::Write>(w: &mut W, name: &str) -> TractResult<()> {
+    w.write_all(&(name.len() as u32).to_le_bytes())?;
+    w.write_all(name.as_bytes())?;
+    Ok(())
+}
+
+fn write_tensor<W: std::io::Write>(w: &mut W, tensor: &Tensor) -> TractResult<()> {
+    let code = SNAPSHOT_DATUM_TYPES
+        .iter()
+        .position(|dt| *dt == tensor.datum_type())
+        .ok_or_else(|| format!("Can not serialize {:?} tensor", tensor.datum_type()))?;
+    w.write_all(&[code as u8])?;
+    w.write_all(&(tensor.rank() as u32).to_le_bytes())?;
+    for d in tensor.shape() {
+        w.write_all(&(*d as u64).to_le_bytes())?;
+    }
+    w.write_all(tensor.as_bytes()?)?;
+    Ok(())
+}
+
+fn read_u32<R: std::io::Read>(r: &mut R) -> TractResult<u32> {
+    let mut bytes = [0u8; 4];
+    r.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64<R: std::io::Read>(r: &mut R) -> TractResult<u64> {
+    let mut bytes = [0u8; 8];
+    r.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+fn read_name<R: std::io::Read>(r: &mut R) -> TractResult<String> {
+    let mut bytes = vec![0u8; read_u32(r)? as usize];
+    r.read_exact(&mut bytes)?;
+    Ok(std::str::from_utf8(&bytes)?.to_string())
+}
+
+fn read_tensor<R: std::io::Read>(r: &mut R) -> TractResult<Tensor> {
+    let mut code = [0u8; 1];
+    r.read_exact(&mut code)?;
+    let dt = *SNAPSHOT_DATUM_TYPES
+        .get(code[0] as usize)
+        .ok_or_else(|| format!("Unknown datum type code {} in state snapshot", code[0]))?;
+    let rank = read_u32(r)?;
+    let shape = (0..rank).map(|_| Ok(read_u64(r)? as usize)).collect::<TractResult<TVec<_>>>()?;
+    let mut data = vec![0u8; shape.iter().product::<usize>() * dt.size_of()];
+    r.read_exact(&mut data)?;
+    unsafe { Tensor::from_raw_dt(dt, &shape, &data) }
+}
+
 #[derive(Debug, Clone)]
 pub struct SimplePlan<TI, O, M>
 where
@@ -114,7 +262,7 @@ where
         SimpleState {
             plans: self.plans.clone(),
             states,
-            session_state: SessionState::default(),
+            session_state: self.session_state.clone(),
             values: self.values.clone(),
             _phantom: PhantomData,
         }
@@ -150,7 +298,7 @@ where
         Ok(())
     }
 
-    /// Reset wires state.
+    /// Reset op states.
     pub fn reset_op_states(&mut self) -> TractResult<()> {
         let &mut SimpleState { ref plans, ref mut session_state, ref mut states, .. } = self;
         *states = plans[0]
@@ -163,6 +311,67 @@ where
         Ok(())
     }
 
+    /// Reset wires, session and op states, as if the state was just built.
+    pub fn reset(&mut self) -> TractResult<()> {
+        self.reset_wires()?;
+        self.session_state = SessionState::default();
+        self.reset_op_states()
+    }
+
+    /// Saves the session and op states.
+    pub fn snapshot(&self) -> TractResult<StateSnapshot> {
+        let mut op_states = HashMap::new();
+        for (node, state) in self.model().nodes().iter().zip(self.states.iter()) {
+            if let Some(state) = state {
+                let tensors = state.save().chain_err(|| format!("Saving state of {}", node))?;
+                op_states.insert(node.name.clone(), tensors);
+            }
+        }
+        Ok(StateSnapshot {
+            known_stream_len: self.session_state.known_stream_len,
+            tensors: self.session_state.tensors.clone(),
+            op_states,
+        })
+    }
+
+    /// Restores the session and op states from a snapshot. Every stateful
+    /// node must be found in the snapshot.
+    pub fn restore(&mut self, snapshot: &StateSnapshot) -> TractResult<()> {
+        self.reset()?;
+        self.session_state.known_stream_len = snapshot.known_stream_len;
+        self.session_state.tensors = snapshot.tensors.clone();
+        let SimpleState { ref plans, ref mut states, .. } = self;
+        for (node, state) in plans[0].borrow().model().nodes().iter().zip(states.iter_mut()) {
+            if let Some(state) = state {
+                let tensors = snapshot
+                    .op_states
+                    .get(&node.name)
+                    .ok_or_else(|| format!("No state for {} in snapshot", node))?;
+                state.load(tensors.clone()).chain_err(|| format!("Loading state of {}", node))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Saves the state of one op.
+    pub fn op_state(&self, node: usize) -> TractResult<TVec<Tensor>> {
+        let node = &self.model().nodes()[node];
+        let state =
+            self.states[node.id].as_ref().ok_or_else(|| format!("{} has no state", node))?;
+        state.save().chain_err(|| format!("Saving state of {}", node))
+    }
+
+    /// Sets the state of one op, in the format of its `OpState::save`.
+    ///
+    /// Scan states start with the position as an i64 scalar, followed by the
+    /// hidden states: this is how recurrent states can be seeded.
+    pub fn set_op_state(&mut self, node: usize, tensors: TVec<Tensor>) -> TractResult<()> {
+        let SimpleState { ref plans, ref mut states, .. } = self;
+        let node = &plans[0].borrow().model().nodes()[node];
+        let state = states[node.id].as_mut().ok_or_else(|| format!("{} has no state", node))?;
+        state.load(tensors).chain_err(|| format!("Loading state of {}", node))
+    }
+
     pub fn run(&mut self, inputs: TVec<Tensor>) -> TractResult<TVec<Arc<Tensor>>> {
         self.run_plan(inputs, 0)
     }
@@ -398,3 +607,133 @@ where
         self.plan().model()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::scan::*;
+    use crate::pulse::PulsedModel;
+
+    // running sum of a vector, as a scan with one hidden state
+    fn cumsum_model() -> TypedModel {
+        let fact = TypedFact::dt_shape(f32::datum_type(), [1].as_ref()).unwrap();
+        let mut body = TypedModel::default();
+        let s = body.add_source("s", fact.clone()).unwrap();
+        let x = body.add_source("x", fact).unwrap();
+        let add = crate::ops::binary::TypedBinOp(Box::new(crate::ops::math::Add));
+        body.wire_node("add", add, &[s, x]).unwrap();
+        body.auto_outputs().unwrap();
+        let scan = Typed::new(
+            body,
+            vec![
+                InputMapping::State { initializer: StateInitializer::Value(rctensor1(&[0f32])) },
+                InputMapping::Scan { slot: 0, axis: 0, chunk: 1.to_dim() },
+            ],
+            vec![OutputMapping::new(Some(0), 0, 1.to_dim(), None, None, true)],
+            None,
+        )
+        .unwrap();
+        let mut model = TypedModel::default();
+        let fact = TypedFact::dt_shape(f32::datum_type(), [3].as_ref()).unwrap();
+        let x = model.add_source("x", fact).unwrap();
+        model.wire_node("scan", scan, &[x]).unwrap();
+        model.auto_outputs().unwrap();
+        model
+    }
+
+    fn run<M, P>(state: &mut TypedSimpleState<M, P>, input: &[f32]) -> Tensor
+    where
+        M: Borrow<TypedModel>,
+        P: Borrow<TypedSimplePlan<M>> + Clone,
+    {
+        state.run(tvec!(tensor1(input))).unwrap().remove(0).into_tensor()
+    }
+
+    #[test]
+    fn scan_snapshot_and_restore() {
+        let plan = SimplePlan::new(cumsum_model()).unwrap();
+        let mut state = SimpleState::new(&plan).unwrap();
+        assert_eq!(run(&mut state, &[1.0, 2.0, 3.0]), tensor1(&[1f32, 3.0, 6.0]));
+        let snapshot = state.snapshot().unwrap();
+        assert_eq!(run(&mut state, &[1.0, 1.0, 1.0]), tensor1(&[7f32, 8.0, 9.0]));
+
+        let mut other = SimpleState::new(&plan).unwrap();
+        other.restore(&snapshot).unwrap();
+        assert_eq!(run(&mut other, &[1.0, 1.0, 1.0]), tensor1(&[7f32, 8.0, 9.0]));
+    }
+
+    #[test]
+    fn scan_snapshot_bytes() {
+        let plan = SimplePlan::new(cumsum_model()).unwrap();
+        let mut state = SimpleState::new(&plan).unwrap();
+        run(&mut state, &[1.0, 2.0, 3.0]);
+        state.session_state.known_stream_len = Some(12);
+        let mut bytes = vec![];
+        state.snapshot().unwrap().write(&mut bytes).unwrap();
+        let snapshot = StateSnapshot::read(&mut &*bytes).unwrap();
+        assert_eq!(snapshot.known_stream_len, Some(12));
+
+        let mut other = SimpleState::new(&plan).unwrap();
+        other.restore(&snapshot).unwrap();
+        assert_eq!(run(&mut other, &[1.0, 1.0, 1.0]), tensor1(&[7f32, 8.0, 9.0]));
+        assert!(StateSnapshot::read(&mut &bytes[..bytes.len() - 1]).is_err());
+    }
+
+    #[test]
+    fn scan_bad_state() {
+        let plan = SimplePlan::new(cumsum_model()).unwrap();
+        let mut state = SimpleState::new(&plan).unwrap();
+        let scan = state.model().node_by_name("scan").unwrap().id;
+        assert!(state.set_op_state(scan, tvec!(tensor0(0i64), tensor1(&[1f32, 2.0]))).is_err());
+        assert!(state.set_op_state(scan, tvec!(tensor0(0i64), tensor1(&[1i32]))).is_err());
+        let two = tvec!(tensor0(0i64), tensor1(&[1f32]), tensor1(&[1f32]));
+        assert!(state.set_op_state(scan, two).is_err());
+    }
+
+    #[test]
+    fn scan_reset() {
+        let plan = SimplePlan::new(cumsum_model()).unwrap();
+        let mut state = SimpleState::new(&plan).unwrap();
+        assert_eq!(run(&mut state, &[1.0, 2.0, 3.0]), tensor1(&[1f32, 3.0, 6.0]));
+        state.reset().unwrap();
+        assert_eq!(run(&mut state, &[1.0, 2.0, 3.0]), tensor1(&[1f32, 3.0, 6.0]));
+    }
+
+    #[test]
+    fn scan_seeded_state() {
+        let plan = SimplePlan::new(cumsum_model()).unwrap();
+        let mut state = SimpleState::new(&plan).unwrap();
+        let scan = state.model().node_by_name("scan").unwrap().id;
+        state.set_op_state(scan, tvec!(tensor0(0i64), tensor1(&[10f32]))).unwrap();
+        assert_eq!(run(&mut state, &[1.0, 2.0, 3.0]), tensor1(&[11f32, 13.0, 16.0]));
+    }
+
+    #[test]
+    fn delay_snapshot_and_clone() {
+        use crate::ops::cnn::Conv;
+        let mut model = InferenceModel::default();
+        let ker = model.add_const("kernel", tensor3(&[[[1.0f32, 1.0, 1.0]]])).unwrap();
+        let fact =
+            InferenceFact::dt_shape(f32::datum_type(), vec![1.to_dim(), 1.to_dim(), TDim::s()]);
+        let a = model.add_source("a", fact).unwrap();
+        model.wire_node("conv", Conv::default(), &[a, ker]).unwrap();
+        model.auto_outputs().unwrap();
+        let pulsed = PulsedModel::new(&model.into_normalized().unwrap(), 2).unwrap();
+        let plan = SimplePlan::new(&pulsed).unwrap();
+        let chunk = |a: f32, b: f32| tensor3(&[[[a, b]]]);
+
+        let mut state = SimpleState::new(&plan).unwrap();
+        state.run(tvec!(chunk(1.0, 2.0))).unwrap();
+        state.session_state.known_stream_len = Some(6);
+        let snapshot = state.snapshot().unwrap();
+        let cloned = state.clone();
+        assert_eq!(cloned.session_state.known_stream_len, Some(6));
+        let expected = state.run(tvec!(chunk(3.0, 4.0))).unwrap();
+
+        for mut other in vec![cloned, SimpleState::new(&plan).unwrap()] {
+            other.restore(&snapshot).unwrap();
+            assert_eq!(other.session_state.known_stream_len, Some(6));
+            assert_eq!(other.run(tvec!(chunk(3.0, 4.0))).unwrap(), expected);
+        }
+    }
+}
@@ -0,0 +1,580 @@
+use crate::internal::*;
+use crate::ops::scan;
+use crate::plan::SimpleState;
+use crate::pulse::delay::Delay;
+use crate::pulse::session::{empty, slice, OutputStream};
+use crate::pulse::PulsedFact;
+use ndarray::*;
+
+type BatchedPlan = Arc<SimplePlan<PulsedFact, Box<dyn PulsedOp>, PulsedModel>>;
+
+/// Layout of the state of a stateful node along the batch axis.
+#[derive(Debug, Clone)]
+enum StateRows {
+    /// Delay buffer, batched along `axis`.
+    Delay { axis: usize },
+    /// Scan position, then hidden states batched along `axes`. `init` are the
+    /// initial hidden states of one slot. The first `skip_pulses` pulses of a
+    /// slot are delay, its hidden states are reset after them.
+    Scan { axes: Vec<usize>, init: Vec<Tensor>, skip_pulses: usize },
+}
+
+impl StateRows {
+    // rebuilds saved state for `capacity` slots, keeping the existing rows but
+    // for the `fresh` ones
+    fn rebuild(
+        &self,
+        saved: &TVec<Tensor>,
+        capacity: usize,
+        fresh: &dyn Fn(usize) -> bool,
+    ) -> TractResult<TVec<Tensor>> {
+        let rebuild_one = |t: &Tensor, axis: usize, init: &Tensor| -> TractResult<Tensor> {
+            let dt = t.datum_type();
+            let old = t.shape()[axis];
+            let rows = (0..capacity)
+                .map(|ix| {
+                    if ix < old && !fresh(ix) {
+                        dispatch_datum!(self::slice(dt)(t, axis, ix, ix + 1))
+                    } else {
+                        Ok(init.clone())
+                    }
+                })
+                .collect::<TractResult<Vec<_>>>()?;
+            dispatch_datum!(self::stack(dt)(axis, &rows))
+        };
+        match self {
+            StateRows::Delay { axis } => {
+                let buffer = &saved[0];
+                let mut shape = buffer.shape().to_vec();
+                shape[*axis] = 1;
+                let zeros = dispatch_datum!(self::empty(buffer.datum_type())(&*shape))?;
+                Ok(tvec!(rebuild_one(buffer, *axis, &zeros)?))
+            }
+            StateRows::Scan { axes, init, .. } => {
+                let mut tensors = tvec!(saved[0].clone());
+                for ((hidden, axis), init) in saved[1..].iter().zip(axes.iter()).zip(init.iter()) {
+                    tensors.push(rebuild_one(hidden, *axis, init)?);
+                }
+                Ok(tensors)
+            }
+        }
+    }
+}
+
+fn stack<T: Datum>(axis: usize, rows: &[Tensor]) -> TractResult<Tensor> {
+    let mut shape = rows[0].shape().to_vec();
+    shape[axis] = rows.iter().map(|t| t.shape()[axis]).sum();
+    let mut output = ArrayD::<T>::default(shape);
+    let mut offset = 0;
+    for row in rows {
+        let len = row.shape()[axis];
+        output
+            .slice_axis_mut(Axis(axis), (offset..offset + len).into())
+            .assign(&row.to_array_view::<T>()?);
+        offset += len;
+    }
+    Ok(output.into_tensor())
+}
+
+// the only axis that goes from 1 to 2 when the batch does
+fn batch_axis(one: &[usize], two: &[usize]) -> Option<usize> {
+    if one.len() != two.len() {
+        return None;
+    }
+    let mut diffs = (0..one.len()).filter(|&ax| one[ax] != two[ax]);
+    match (diffs.next(), diffs.next()) {
+        (Some(ax), None) if one[ax] == 1 && two[ax] == 2 => Some(ax),
+        _ => None,
+    }
+}
+
+fn outlet_facts(model: &PulsedModel, outlets: &[OutletId]) -> TractResult<TVec<PulsedFact>> {
+    outlets.iter().map(|o| Ok(model.outlet_fact(*o)?.clone())).collect()
+}
+
+fn batch_axes(one: &[PulsedFact], two: &[PulsedFact]) -> TractResult<TVec<usize>> {
+    one.iter()
+        .zip(two.iter())
+        .map(|(a, b)| {
+            let axis = batch_axis(&a.shape, &b.shape);
+            Ok(axis.filter(|&ax| !a.is_streaming() || ax != a.axis).ok_or_else(|| {
+                format!("Could not find the batch axis of {:?} (batch of two: {:?})", a, b)
+            })?)
+        })
+        .collect()
+}
+
+#[derive(Debug)]
+struct Slot {
+    written: usize,
+    pulses: usize,
+    finishing: bool,
+    outputs: TVec<OutputStream>,
+}
+
+impl Slot {
+    fn is_complete(&self) -> TractResult<bool> {
+        for output in &self.outputs {
+            if !output.is_complete(self.written)? {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Runs independent streams through one pulsed model, batched along an axis
+/// other than the streaming one.
+///
+/// This is not shape-polymorphic pulsing: TDim has no symbol but S, so a
+/// pulsed model has a concrete batch size and `factory` builds one for each
+/// size. It is called for sizes 1 and 2 to locate the batch axis of the
+/// inputs, outputs and op states, then for each new size the batch goes
+/// through: it doubles when the session runs out of slots, and is halved when
+/// the running streams fit in its first quarter. Plans are kept for the sizes
+/// already built, the op states are moved over by snapshot and restore.
+///
+/// Streams join and leave between pulses. Each one gets its own rows in the
+/// Delay buffers and Scan hidden states, starting from a fresh state, and its
+/// outputs with its own delay dropped. `finish` flushes the frames still in
+/// the pipeline before freeing the slot, `leave` drops them. Other op states
+/// (like pulsed padding or concatenation on the streaming axis) depend on the
+/// stream position and are rejected.
+pub struct BatchedSession<F>
+where
+    F: Fn(usize) -> TractResult<PulsedModel>,
+{
+    factory: F,
+    pulse: usize,
+    input_facts: TVec<PulsedFact>,
+    input_axes: TVec<usize>,
+    output_facts: TVec<PulsedFact>,
+    output_axes: TVec<usize>,
+    rows: HashMap<String, StateRows>,
+    plans: HashMap<usize, BatchedPlan>,
+    state: SimpleState<PulsedFact, Box<dyn PulsedOp>, PulsedModel, BatchedPlan>,
+    slots: Vec<Option<Slot>>,
+}
+
+impl<F> BatchedSession<F>
+where
+    F: Fn(usize) -> TractResult<PulsedModel>,
+{
+    pub fn new(factory: F) -> TractResult<BatchedSession<F>> {
+        let one = factory(1)?;
+        let two = factory(2)?;
+        let input_facts = outlet_facts(&one, one.input_outlets()?)?;
+        let input_axes = batch_axes(&input_facts, &outlet_facts(&two, two.input_outlets()?)?)?;
+        let output_facts = outlet_facts(&one, one.output_outlets()?)?;
+        let output_axes = batch_axes(&output_facts, &outlet_facts(&two, two.output_outlets()?)?)?;
+        let pulse = input_facts
+            .iter()
+            .find(|f| f.dim == TDim::s())
+            .map(|f| f.pulse())
+            .ok_or("Batched session expects an input of length S")?;
+        let mut rows = HashMap::new();
+        for node in one.nodes() {
+            let other = two.node_by_name(&node.name)?;
+            if node.op_is::<Delay>() {
+                let axis = batch_axis(
+                    &one.outlet_fact(node.inputs[0])?.shape,
+                    &two.outlet_fact(other.inputs[0])?.shape,
+                )
+                .ok_or_else(|| format!("Could not find the batch axis of {}", node))?;
+                rows.insert(node.name.clone(), StateRows::Delay { axis });
+            } else if let Some(op) = node.op_as::<scan::Typed>() {
+                let other_op = other.op_as::<scan::Typed>().unwrap();
+                let (mut axes, mut init) = (vec![], vec![]);
+                for (im, other_im) in op.input_mapping.iter().zip(other_op.input_mapping.iter()) {
+                    match (im.as_state(), other_im.as_state()) {
+                        (
+                            Some(scan::StateInitializer::Value(v)),
+                            Some(scan::StateInitializer::Value(other_v)),
+                        ) => {
+                            let axis = batch_axis(v.shape(), other_v.shape()).ok_or_else(|| {
+                                format!("Could not find the batch axis of states of {}", node)
+                            })?;
+                            axes.push(axis);
+                            init.push(v.as_ref().clone());
+                        }
+                        (None, None) => (),
+                        _ => bail!("Batched scans need constant initial states ({})", node),
+                    }
+                }
+                let (slot, _axis, chunk) =
+                    op.input_mapping.iter().filter_map(scan::InputMapping::as_scan).next().unwrap();
+                let iters =
+                    one.outlet_fact(node.inputs[slot])?.pulse() / chunk.to_integer()? as usize;
+                if op.skip % iters != 0 {
+                    bail!("Batched scans need a delay of whole pulses ({})", node)
+                }
+                rows.insert(
+                    node.name.clone(),
+                    StateRows::Scan { axes, init, skip_pulses: op.skip / iters },
+                );
+            }
+        }
+        let plan = Arc::new(SimplePlan::new(one)?);
+        let mut plans = HashMap::new();
+        plans.insert(1, plan.clone());
+        plans.insert(2, Arc::new(SimplePlan::new(two)?));
+        let state = SimpleState::new(plan)?;
+        for (name, saved) in state.snapshot()?.op_states {
+            if !saved.is_empty() && !rows.contains_key(&name) {
+                bail!("State of {} can not be batched", name)
+            }
+        }
+        Ok(BatchedSession {
+            factory,
+            pulse,
+            input_facts,
+            input_axes,
+            output_facts,
+            output_axes,
+            rows,
+            plans,
+            state,
+            slots: vec![None],
+        })
+    }
+
+    /// Pulse, in frames of the S-rate inputs.
+    pub fn pulse(&self) -> usize {
+        self.pulse
+    }
+
+    /// Batch size of the model currently running.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Slots of the streams currently running.
+    pub fn slots(&self) -> Vec<usize> {
+        (0..self.slots.len()).filter(|&ix| self.slots[ix].is_some()).collect()
+    }
+
+    /// Starts a new stream, returns its slot.
+    pub fn join(&mut self) -> TractResult<usize> {
+        let (slot, capacity) = match self.slots.iter().position(|s| s.is_none()) {
+            Some(slot) => (slot, self.slots.len()),
+            None => (self.slots.len(), 2 * self.slots.len()),
+        };
+        self.relayout(capacity, &|_, ix| ix == slot)?;
+        let outputs =
+            self.output_facts.iter().map(OutputStream::new).collect::<TractResult<_>>()?;
+        self.slots[slot] = Some(Slot { written: 0, pulses: 0, finishing: false, outputs });
+        Ok(slot)
+    }
+
+    /// Ends the stream running in `slot`, freeing the slot. Frames still in
+    /// the pipeline are lost.
+    pub fn leave(&mut self, slot: usize) -> TractResult<()> {
+        match self.slots.get_mut(slot) {
+            Some(s) if s.is_some() => *s = None,
+            _ => bail!("No stream in slot {}", slot),
+        }
+        self.shrink()
+    }
+
+    /// Ends the input of the stream running in `slot`. Next pushes take no
+    /// input for it, and return its remaining output frames. The slot is freed
+    /// when they are all out.
+    pub fn finish(&mut self, slot: usize) -> TractResult<()> {
+        let complete = match self.slots.get_mut(slot) {
+            Some(Some(s)) if !s.finishing => {
+                s.finishing = true;
+                s.is_complete()?
+            }
+            _ => bail!("No running stream in slot {}", slot),
+        };
+        if complete {
+            self.leave(slot)?;
+        }
+        Ok(())
+    }
+
+    /// Feeds one full pulse of every input for each running stream, returns
+    /// the valid output frames of each (finishing streams included). Inputs
+    /// with no streaming dim are given whole with every pulse.
+    pub fn push(
+        &mut self,
+        inputs: HashMap<usize, TVec<Tensor>>,
+    ) -> TractResult<HashMap<usize, TVec<Tensor>>> {
+        for (slot, slot_inputs) in &inputs {
+            match self.slots.get(*slot) {
+                Some(Some(s)) if s.finishing => bail!("Stream in slot {} is finishing", slot),
+                Some(Some(_)) => (),
+                _ => bail!("No stream in slot {}", slot),
+            }
+            if slot_inputs.len() != self.input_facts.len() {
+                bail!(
+                    "Expected {} inputs for slot {}, got {}",
+                    self.input_facts.len(),
+                    slot,
+                    slot_inputs.len()
+                )
+            }
+        }
+        let mut batched = tvec!();
+        for (ix, (fact, &axis)) in self.input_facts.iter().zip(self.input_axes.iter()).enumerate() {
+            let rows = (0..self.slots.len())
+                .map(|slot| {
+                    if self.slots[slot].as_ref().map(|s| s.finishing).unwrap_or(true) {
+                        return dispatch_datum!(self::empty(fact.datum_type)(&*fact.shape));
+                    }
+                    let input = &inputs
+                        .get(&slot)
+                        .ok_or_else(|| format!("No input for slot {}", slot))?[ix];
+                    if input.shape() != &*fact.shape {
+                        bail!(
+                            "Expected a pulse of shape {:?} for slot {}, got {:?}",
+                            fact.shape,
+                            slot,
+                            input.shape()
+                        )
+                    }
+                    Ok(input.clone())
+                })
+                .collect::<TractResult<Vec<_>>>()?;
+            batched.push(dispatch_datum!(self::stack(fact.datum_type)(axis, &rows))?);
+        }
+        let outputs = self.state.run(batched)?;
+        let mut result = HashMap::new();
+        let mut complete = vec![];
+        for (ix, slot) in self.slots.iter_mut().enumerate() {
+            if let Some(slot) = slot {
+                if !slot.finishing {
+                    slot.written += self.pulse;
+                }
+                slot.pulses += 1;
+                let mut slot_outputs = tvec!();
+                for ((stream, output), &axis) in
+                    slot.outputs.iter_mut().zip(outputs.iter()).zip(self.output_axes.iter())
+                {
+                    let dt = output.datum_type();
+                    stream.push(&dispatch_datum!(self::slice(dt)(output, axis, ix, ix + 1))?)?;
+                    slot_outputs.push(stream.take_until(stream.len_for_stream(slot.written)?)?);
+                }
+                result.insert(ix, slot_outputs);
+                if slot.finishing && slot.is_complete()? {
+                    complete.push(ix);
+                }
+            }
+        }
+        for ix in complete {
+            self.slots[ix] = None;
+        }
+        self.shrink()?;
+        // scans must not see the delay of the streams that joined late
+        let pulses: Vec<Option<usize>> =
+            self.slots.iter().map(|s| s.as_ref().map(|s| s.pulses)).collect();
+        let skipping = |rows: &StateRows, ix: usize| match (rows, pulses[ix]) {
+            (StateRows::Scan { skip_pulses, .. }, Some(pulses)) => pulses <= *skip_pulses,
+            _ => false,
+        };
+        self.relayout(self.slots.len(), &skipping)?;
+        Ok(result)
+    }
+
+    // halves the batch while the running streams fit in its first quarter
+    fn shrink(&mut self) -> TractResult<()> {
+        let mut capacity = self.slots.len();
+        while capacity > 1 && self.slots[capacity / 4..capacity].iter().all(|s| s.is_none()) {
+            capacity /= 2;
+        }
+        if capacity != self.slots.len() {
+            self.relayout(capacity, &|_, _| false)?;
+        }
+        Ok(())
+    }
+
+    // moves the op states to a model for `capacity` slots, resetting the rows
+    // for which `fresh` is true
+    fn relayout(
+        &mut self,
+        capacity: usize,
+        fresh: &dyn Fn(&StateRows, usize) -> bool,
+    ) -> TractResult<()> {
+        if capacity == self.slots.len() {
+            // same model: only the states with fresh rows are touched
+            for (name, rows) in &self.rows {
+                if (0..capacity).any(|ix| fresh(rows, ix)) {
+                    let node = self.state.model().node_by_name(name)?.id;
+                    let saved = self.state.op_state(node)?;
+                    let rebuilt = rows.rebuild(&saved, capacity, &|ix| fresh(rows, ix))?;
+                    self.state.set_op_state(node, rebuilt)?;
+                }
+            }
+            return Ok(());
+        }
+        let mut snapshot = self.state.snapshot()?;
+        for (name, rows) in &self.rows {
+            let saved =
+                snapshot.op_states.get_mut(name).ok_or_else(|| format!("No state for {}", name))?;
+            *saved = rows.rebuild(saved, capacity, &|ix| fresh(rows, ix))?;
+        }
+        let plan = match self.plans.get(&capacity) {
+            Some(plan) => plan.clone(),
+            None => Arc::new(SimplePlan::new((self.factory)(capacity)?)?),
+        };
+        self.plans.insert(capacity, plan.clone());
+        self.state = SimpleState::new(plan)?;
+        self.slots.resize_with(capacity, || None);
+        self.state.restore(&snapshot)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::array::{Pad, PadMode};
+    use crate::ops::cnn::Conv;
+    use crate::ops::scan::*;
+    use crate::pulse::StreamingSession;
+
+    fn conv_typed(batch: usize) -> TractResult<TypedModel> {
+        let mut model = InferenceModel::default();
+        let ker = model.add_const("kernel", tensor3(&[[[1.0f32, 2.0, 3.0]]]))?;
+        let fact =
+            InferenceFact::dt_shape(f32::datum_type(), vec![batch.to_dim(), 1.to_dim(), TDim::s()]);
+        let a = model.add_source("a", fact)?;
+        model.wire_node("conv", Conv::default(), &[a, ker])?;
+        model.auto_outputs()?;
+        model.into_typed()
+    }
+
+    fn conv_model(batch: usize) -> TractResult<PulsedModel> {
+        PulsedModel::new(&conv_typed(batch)?.declutter()?.into_normalized()?, 2)
+    }
+
+    // cumulative sum of the conv output
+    fn conv_scan_model(batch: usize) -> TractResult<PulsedModel> {
+        let mut model = conv_typed(batch)?;
+        let fact = TypedFact::dt_shape(f32::datum_type(), [batch, 1, 1].as_ref())?;
+        let mut body = TypedModel::default();
+        let s = body.add_source("s", fact.clone())?;
+        let x = body.add_source("x", fact)?;
+        let add = crate::ops::binary::TypedBinOp(Box::new(crate::ops::math::Add));
+        body.wire_node("add", add, &[s, x])?;
+        body.auto_outputs()?;
+        let init = ArrayD::<f32>::zeros(vec![batch, 1, 1]).into_arc_tensor();
+        let scan = Typed::new(
+            body,
+            vec![
+                InputMapping::State { initializer: StateInitializer::Value(init) },
+                InputMapping::Scan { slot: 0, axis: 2, chunk: 1.to_dim() },
+            ],
+            vec![OutputMapping::new(Some(0), 2, 1.to_dim(), None, None, true)],
+            None,
+        )?;
+        let conv = model.output_outlets()?[0];
+        let scan = model.wire_node("scan", scan, &[conv])?;
+        model.set_output_outlets(&scan)?;
+        PulsedModel::new(&model.declutter()?.into_normalized()?, 2)
+    }
+
+    fn pulse(stream: usize, ix: usize) -> Tensor {
+        let frames = [(10 * stream + 2 * ix) as f32, (10 * stream + 2 * ix + 1) as f32];
+        tensor3(&[[frames]])
+    }
+
+    // runs streams 0 and 1, then 1 and 2 (in the slot stream 0 left), and
+    // checks them against the same streams ran alone
+    fn check_against_single_streams(factory: fn(usize) -> TractResult<PulsedModel>) {
+        let mut session = BatchedSession::new(factory).unwrap();
+        let mut streams: Vec<(usize, Vec<Tensor>, Vec<TVec<Tensor>>)> = vec![];
+        streams.push((session.join().unwrap(), vec![], vec![]));
+        for step in 0..6 {
+            if step == 1 {
+                streams.push((session.join().unwrap(), vec![], vec![]));
+                assert_eq!(session.capacity(), 2);
+            }
+            if step == 4 {
+                session.leave(streams[0].0).unwrap();
+                streams.push((session.join().unwrap(), vec![], vec![]));
+                assert_eq!(streams[2].0, streams[0].0);
+            }
+            let running = if step < 4 { 0..2 } else { 1..3 };
+            let running = running.filter(|&s| s < streams.len()).collect::<Vec<_>>();
+            let mut inputs = HashMap::new();
+            for &s in &running {
+                let input = pulse(s, streams[s].1.len());
+                streams[s].1.push(input.clone());
+                inputs.insert(streams[s].0, tvec!(input));
+            }
+            let mut outputs = session.push(inputs).unwrap();
+            for &s in &running {
+                let output = outputs.remove(&streams[s].0).unwrap();
+                streams[s].2.push(output);
+            }
+        }
+        let plan = SimplePlan::new(factory(1).unwrap()).unwrap();
+        for (_, inputs, outputs) in streams {
+            let mut single = StreamingSession::new(&plan).unwrap();
+            for (input, output) in inputs.into_iter().zip(outputs) {
+                assert_eq!(single.push(input).unwrap(), output);
+            }
+        }
+    }
+
+    #[test]
+    fn batched_conv() {
+        check_against_single_streams(conv_model);
+    }
+
+    #[test]
+    fn batched_conv_scan() {
+        check_against_single_streams(conv_scan_model);
+    }
+
+    #[test]
+    fn finish_flushes_and_shrinks() {
+        let mut session = BatchedSession::new(conv_model).unwrap();
+        let a = session.join().unwrap();
+        let b = session.join().unwrap();
+        assert_eq!(session.capacity(), 2);
+        let mut got = vec![];
+        for ix in 0..2 {
+            let mut inputs = HashMap::new();
+            inputs.insert(a, tvec!(pulse(0, ix)));
+            inputs.insert(b, tvec!(pulse(1, ix)));
+            got.push(session.push(inputs).unwrap().remove(&a).unwrap().remove(0));
+        }
+        session.finish(a).unwrap();
+        while session.slots().contains(&a) {
+            let mut inputs = HashMap::new();
+            inputs.insert(b, tvec!(pulse(1, 2)));
+            got.push(session.push(inputs).unwrap().remove(&a).unwrap().remove(0));
+        }
+
+        let plan = SimplePlan::new(conv_model(1).unwrap()).unwrap();
+        let mut single = StreamingSession::new(&plan).unwrap();
+        let mut expected = vec![];
+        for ix in 0..2 {
+            expected.push(single.push(pulse(0, ix)).unwrap().remove(0));
+        }
+        expected.push(single.finish(tensor3(&[[[0f32; 0]]])).unwrap().remove(0));
+        let flatten = |ts: Vec<Tensor>| {
+            ts.iter().flat_map(|t| t.as_slice::<f32>().unwrap().to_vec()).collect::<Vec<_>>()
+        };
+        assert_eq!(flatten(got), flatten(expected));
+
+        assert_eq!(session.capacity(), 2);
+        session.leave(b).unwrap();
+        assert_eq!(session.capacity(), 1);
+    }
+
+    #[test]
+    fn pad_is_rejected() {
+        let factory = |batch: usize| {
+            let mut model = InferenceModel::default();
+            let fact = InferenceFact::dt_shape(f32::datum_type(), vec![batch.to_dim(), TDim::s()]);
+            let a = model.add_source("a", fact)?;
+            let pad = Pad::new(vec![(0, 0), (1, 0)], PadMode::Constant(rctensor0(0f32)));
+            model.wire_node("pad", pad, &[a])?;
+            model.auto_outputs()?;
+            PulsedModel::new(&model.into_normalized()?, 2)
+        };
+        assert!(BatchedSession::new(factory).is_err());
+    }
+}
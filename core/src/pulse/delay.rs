@@ -57,6 +57,26 @@ impl OpState for DelayState {
         let op = op.downcast_ref::<Delay>().ok_or("Wrong Op type")?;
         Ok(tvec!(dispatch_datum!(Self::eval_t(input.datum_type())(self, op, input))?))
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!(self.buffer.clone()))
+    }
+
+    fn load(&mut self, mut tensors: TVec<Tensor>) -> TractResult<()> {
+        let buffer = args_1!(tensors);
+        if buffer.datum_type() != self.buffer.datum_type() || buffer.shape() != self.buffer.shape()
+        {
+            bail!(
+                "Delay buffer expected to be {:?} {:?}, got {:?} {:?}",
+                self.buffer.shape(),
+                self.buffer.datum_type(),
+                buffer.shape(),
+                buffer.datum_type()
+            )
+        }
+        self.buffer = buffer;
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, PartialEq)]
@@ -75,6 +95,19 @@ impl Delay {
         buffer_shape[axis] = delay + overlap;
         Delay { datum_type: input_fact.datum_type, buffer_shape, axis, delay, overlap }
     }
+
+    pub fn delay(&self) -> usize {
+        self.delay
+    }
+
+    pub fn overlap(&self) -> usize {
+        self.overlap
+    }
+
+    /// Size of the state buffer.
+    pub fn buffer_bytes(&self) -> usize {
+        self.buffer_shape.iter().product::<usize>() * self.datum_type.size_of()
+    }
 }
 
 impl Op for Delay {
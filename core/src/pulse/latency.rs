@@ -0,0 +1,227 @@
+use std::collections::HashSet;
+
+use crate::internal::*;
+use crate::pulse::delay::Delay;
+use crate::pulse::{PulsedFact, PulsedModel};
+
+/// Latency and receptive field of one outlet of a pulsed model.
+///
+/// Context and latency are counted in frames of the S-rate inputs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OutletLatency {
+    /// Delay of the outlet stream, in its own frames.
+    pub delay: usize,
+    /// Input frames to wait for before an output frame is emitted.
+    pub latency: usize,
+    /// Input frames needed before the one aligned with an output frame. None
+    /// if unbounded (behind a recurrent layer).
+    pub left_context: Option<usize>,
+    /// Input frames needed after the one aligned with an output frame.
+    pub right_context: usize,
+    /// Bytes held by the Delay buffers the outlet depends on.
+    pub buffered_bytes: usize,
+}
+
+// input stream positions an outlet depends on, back from its own position
+#[derive(Clone, Default)]
+struct Span {
+    near: isize,
+    far: isize,
+    recurrent: bool,
+    delays: HashSet<usize>,
+}
+
+impl Span {
+    fn union(&mut self, other: &Span) {
+        self.near = self.near.min(other.near);
+        self.far = self.far.max(other.far);
+        self.recurrent |= other.recurrent;
+        self.delays.extend(other.delays.iter().cloned());
+    }
+}
+
+/// Computes delay, latency, receptive field and buffered memory of every
+/// outlet of a pulsed model.
+pub fn analyse(model: &PulsedModel) -> TractResult<HashMap<OutletId, OutletLatency>> {
+    let pulse = model
+        .input_outlets()?
+        .iter()
+        .map(|i| model.outlet_fact(*i))
+        .collect::<TractResult<TVec<_>>>()?
+        .into_iter()
+        .find(|f| f.dim == TDim::s())
+        .map(|f| f.pulse())
+        .ok_or("Latency analysis expects an input of length S")?;
+    let to_input_frames = |fact: &PulsedFact, frames: usize| -> TractResult<isize> {
+        let once = fact.dim.eval(pulse as i32);
+        let twice = fact.dim.eval(2 * pulse as i32);
+        match (once, twice) {
+            (Some(once), Some(twice)) if twice > once => {
+                Ok((frames * pulse).div_ceil((twice - once) as usize) as isize)
+            }
+            _ => bail!("Can not compute the rate of a stream of length {:?}", fact.dim),
+        }
+    };
+    // inputs fed whole with every pulse have no stream position
+    let mut inputs = vec![];
+    for input in model.input_outlets()? {
+        if model.outlet_fact(*input)?.is_streaming() {
+            inputs.push(input.node);
+        }
+    }
+    let mut spans: Vec<Option<Span>> = vec![None; model.nodes().len()];
+    let mut result = HashMap::new();
+    for n in crate::model::eval_order(model)? {
+        let node = model.node(n);
+        let mut span: Option<Span> = if inputs.contains(&n) { Some(Span::default()) } else { None };
+        for input in &node.inputs {
+            if let Some(ref other) = spans[input.node] {
+                match span {
+                    Some(ref mut span) => span.union(other),
+                    None => span = Some(other.clone()),
+                }
+            }
+        }
+        let mut span = if let Some(span) = span { span } else { continue };
+        if let Some(delay) = node.op_as::<Delay>() {
+            let fact = model.outlet_fact(node.inputs[0])?;
+            span.near += to_input_frames(fact, delay.delay())?;
+            span.far += to_input_frames(fact, delay.delay() + delay.overlap())?;
+            span.delays.insert(n);
+        } else if let (Some(input), Some(output)) = (
+            node.inputs
+                .iter()
+                .map(|i| model.outlet_fact(*i))
+                .find(|f| f.as_ref().map(|f| f.is_streaming()).unwrap_or(true)),
+            node.outputs.get(0),
+        ) {
+            // a strided op reads up to the end of the stride after its position
+            let input_rate = to_input_frames(input?, 1)?;
+            let output_rate = to_input_frames(&output.fact, 1)?;
+            span.near -= (output_rate - input_rate).max(0);
+        }
+        if let Some(scan) = node.op_as::<crate::ops::scan::Typed>() {
+            span.recurrent |= scan.input_mapping.iter().any(|im| im.as_state().is_some());
+        }
+        let buffered_bytes = span
+            .delays
+            .iter()
+            .map(|&d| model.node(d).op_as::<Delay>().unwrap().buffer_bytes())
+            .sum();
+        for (slot, output) in node.outputs.iter().enumerate() {
+            let latency = to_input_frames(&output.fact, output.fact.delay)?;
+            result.insert(
+                OutletId::new(n, slot),
+                OutletLatency {
+                    delay: output.fact.delay,
+                    latency: latency as usize,
+                    left_context: if span.recurrent {
+                        None
+                    } else {
+                        Some((span.far - latency).max(0) as usize)
+                    },
+                    right_context: (latency - span.near).max(0) as usize,
+                    buffered_bytes,
+                },
+            );
+        }
+        spans[n] = Some(span);
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::array::{Pad, PadMode};
+    use crate::ops::cnn::Conv;
+
+    fn conv_model(conv: Conv, pulse: usize) -> PulsedModel {
+        let mut model = InferenceModel::default();
+        let ker = model.add_const("kernel", tensor3(&[[[1.0f32, 1.0, 1.0]]])).unwrap();
+        let fact =
+            InferenceFact::dt_shape(f32::datum_type(), vec![1.to_dim(), 1.to_dim(), TDim::s()]);
+        let a = model.add_source("a", fact).unwrap();
+        model.wire_node("conv", conv, &[a, ker]).unwrap();
+        model.auto_outputs().unwrap();
+        PulsedModel::new(&model.into_normalized().unwrap(), pulse).unwrap()
+    }
+
+    fn output_latency(model: &PulsedModel) -> OutletLatency {
+        model.latency().unwrap()[&model.output_outlets().unwrap()[0]].clone()
+    }
+
+    #[test]
+    fn valid_conv() {
+        let model = conv_model(Conv::default(), 2);
+        assert_eq!(
+            output_latency(&model),
+            OutletLatency {
+                delay: 2,
+                latency: 2,
+                left_context: Some(0),
+                right_context: 2,
+                buffered_bytes: 8
+            }
+        );
+    }
+
+    #[test]
+    fn padded_conv() {
+        let mut model = InferenceModel::default();
+        let ker = model.add_const("kernel", tensor3(&[[[1.0f32, 1.0, 1.0]]])).unwrap();
+        let fact =
+            InferenceFact::dt_shape(f32::datum_type(), vec![1.to_dim(), 1.to_dim(), TDim::s()]);
+        let a = model.add_source("a", fact).unwrap();
+        let pad = Pad::new(vec![(0, 0), (0, 0), (1, 1)], PadMode::Constant(rctensor0(0f32)));
+        let pad = model.wire_node("pad", pad, &[a]).unwrap();
+        model.wire_node("conv", Conv::default(), &[pad[0], ker]).unwrap();
+        model.auto_outputs().unwrap();
+        let model = PulsedModel::new(&model.into_normalized().unwrap(), 2).unwrap();
+        let latency = output_latency(&model);
+        assert_eq!(latency.latency, 2);
+        assert_eq!(latency.left_context, Some(1));
+        assert_eq!(latency.right_context, 1);
+    }
+
+    #[test]
+    fn strided_conv() {
+        let model = conv_model(Conv::default().strides(tvec!(2)), 4);
+        let latency = output_latency(&model);
+        assert_eq!(latency.delay, 1);
+        assert_eq!(latency.latency, 2);
+        assert_eq!(latency.left_context, Some(0));
+        assert_eq!(latency.right_context, 2);
+    }
+
+    #[test]
+    fn recurrent_scan() {
+        use crate::ops::scan::*;
+        let fact = TypedFact::dt_shape(f32::datum_type(), [1].as_ref()).unwrap();
+        let mut body = TypedModel::default();
+        let s = body.add_source("s", fact.clone()).unwrap();
+        let x = body.add_source("x", fact).unwrap();
+        let add = crate::ops::binary::TypedBinOp(Box::new(crate::ops::math::Add));
+        body.wire_node("add", add, &[s, x]).unwrap();
+        body.auto_outputs().unwrap();
+        let scan = Typed::new(
+            body,
+            vec![
+                InputMapping::State { initializer: StateInitializer::Value(rctensor1(&[0f32])) },
+                InputMapping::Scan { slot: 0, axis: 0, chunk: 1.to_dim() },
+            ],
+            vec![OutputMapping::new(Some(0), 0, 1.to_dim(), None, None, true)],
+            None,
+        )
+        .unwrap();
+        let mut model = TypedModel::default();
+        let fact = TypedFact::dt_shape(f32::datum_type(), [TDim::s()].as_ref()).unwrap();
+        let x = model.add_source("x", fact).unwrap();
+        model.wire_node("scan", scan, &[x]).unwrap();
+        model.auto_outputs().unwrap();
+        let model = PulsedModel::new(&model.into_normalized().unwrap(), 2).unwrap();
+        let latency = output_latency(&model);
+        assert_eq!(latency.left_context, None);
+        assert_eq!(latency.right_context, 0);
+    }
+}
@@ -3,7 +3,13 @@ use std::fmt;
 
 use std::convert::TryFrom;
 
+pub mod batch;
 pub mod delay;
+pub mod latency;
+pub mod session;
+
+pub use self::batch::BatchedSession;
+pub use self::session::StreamingSession;
 
 #[derive(Clone, PartialEq)]
 pub struct PulsedFact {
@@ -17,6 +23,14 @@ pub struct PulsedFact {
 impl fmt::Debug for PulsedFact {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
         use itertools::Itertools;
+        if !self.is_streaming() {
+            return write!(
+                fmt,
+                "{}x{:?} [not streaming]",
+                self.shape.iter().join("x"),
+                self.datum_type
+            );
+        }
         write!(
             fmt,
             "{}x{:?} [pulse axis:{} ∂:{} full dim:{:?}]",
@@ -45,10 +59,38 @@ impl TryFrom<PulsedFact> for TypedFact {
 impl PulsedFact {
     pub fn from_tensor_fact_pulse(tf: &NormalizedFact, pulse: usize) -> TractResult<PulsedFact> {
         let datum_type = tf.datum_type;
-        let stream =
-            tf.shape.stream_info.as_ref().ok_or("Can not pulse a tensor with no streaming dim")?;
+        let stream = if let Some(stream) = tf.shape.stream_info.as_ref() {
+            stream
+        } else {
+            // fed whole with every pulse
+            let shape: TVec<usize> = tf.shape.as_finite().unwrap().into();
+            if shape.is_empty() {
+                bail!("Can not pulse a scalar with no streaming dim")
+            }
+            let dim = shape[0].to_dim();
+            return Ok(PulsedFact { datum_type, shape, axis: 0, dim, delay: 0 });
+        };
+        // a stream at a fraction of the rate of S gets the same fraction of the pulse
+        let stream_pulse = stream.len.eval(pulse as i32);
+        if stream_pulse.is_none()
+            || stream.len.eval(2 * pulse as i32) != stream_pulse.map(|p| 2 * p)
+        {
+            bail!("Can not pulse a stream of length {:?} with a pulse of {}", stream.len, pulse)
+        }
+        let stream_pulse = stream_pulse.unwrap() as usize;
+        // the streaming dim is the only symbolic one
         let shape =
-            tf.shape.iter().map(|d| d.to_integer().map(|d| d as usize).unwrap_or(pulse)).collect();
+            tf.shape
+                .iter()
+                .enumerate()
+                .map(|(ix, d)| {
+                    if ix == stream.axis {
+                        stream_pulse
+                    } else {
+                        d.to_integer().unwrap() as usize
+                    }
+                })
+                .collect();
         Ok(PulsedFact { datum_type, shape, axis: stream.axis, dim: stream.len.clone(), delay: 0 })
     }
 
@@ -56,6 +98,12 @@ impl PulsedFact {
         self.shape[self.axis]
     }
 
+    /// False for a tensor with no streaming dim, fed whole with every pulse
+    /// (its `dim` is then the concrete length of its axis 0).
+    pub fn is_streaming(&self) -> bool {
+        self.dim.to_integer().is_err()
+    }
+
     pub fn to_pulse_fact(&self) -> NormalizedFact {
         NormalizedFact::dt_shape(self.datum_type, &*self.shape).unwrap()
     }
@@ -70,7 +118,9 @@ impl PulsedFact {
 
     pub fn to_streaming_fact(&self) -> NormalizedFact {
         let mut info = self.to_pulse_fact();
-        info.shape.stream_info = Some(StreamInfo { axis: self.axis, len: self.dim.clone() });
+        if self.is_streaming() {
+            info.shape.stream_info = Some(StreamInfo { axis: self.axis, len: self.dim.clone() });
+        }
         info
     }
 }
@@ -93,6 +143,11 @@ impl PulsedModel {
     pub fn into_typed(self) -> TractResult<TypedModel> {
         Ok(crate::model::compact::translate(&self, &())?.0)
     }
+
+    /// Delay, latency, receptive field and buffered memory of every outlet.
+    pub fn latency(&self) -> TractResult<HashMap<OutletId, latency::OutletLatency>> {
+        latency::analyse(self)
+    }
 }
 
 #[cfg(test)]
@@ -100,14 +155,16 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_source_must_stream() {
+    fn test_source_pulse() {
         let mut model = InferenceModel::default();
         let _a =
             model.add_source("a", InferenceFact::dt_shape(DatumType::F32, vec![1, 2, 3])).unwrap();
         model.auto_outputs().unwrap();
-        assert!(
-            PulsedModel::new(&model.into_typed().unwrap().into_normalized().unwrap(), 4).is_err()
-        );
+        let pulse =
+            PulsedModel::new(&model.into_typed().unwrap().into_normalized().unwrap(), 4).unwrap();
+        let fact = pulse.input_fact(0).unwrap();
+        assert!(!fact.is_streaming());
+        assert_eq!(fact.shape, tvec!(1, 2, 3));
 
         let mut model = InferenceModel::default();
         let _a = model
@@ -125,6 +182,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_source_at_fraction_of_stream_rate() {
+        let mut model = InferenceModel::default();
+        let _a = model
+            .add_source(
+                "a",
+                InferenceFact::dt_shape(
+                    DatumType::F32,
+                    vec![TDim::s().div_ceil(4.into()), 3.into()],
+                ),
+            )
+            .unwrap();
+        model.auto_outputs().unwrap();
+        let normalized = model.into_normalized().unwrap();
+        let pulse = PulsedModel::new(&normalized, 8).unwrap();
+        assert_eq!(
+            pulse.input_fact(0).unwrap().to_tensor_fact(),
+            InferenceFact::dt_shape(DatumType::F32, vec!(2, 3))
+        );
+        assert!(PulsedModel::new(&normalized, 6).is_err());
+    }
+
     #[test]
     fn test_immediate() {
         let mut model = InferenceModel::default();
@@ -147,4 +226,152 @@ mod tests {
             InferenceFact::dt_shape(DatumType::F32, vec!(4, 2, 3))
         );
     }
+
+    #[test]
+    fn test_concat_streams_on_other_axis() {
+        use crate::ops::array::Concat;
+        let mut model = InferenceModel::default();
+        let fact = InferenceFact::dt_shape(DatumType::F32, vec![TDim::s(), 2.to_dim()]);
+        let a = model.add_source("a", fact.clone()).unwrap();
+        let b = model.add_source("b", fact).unwrap();
+        model.wire_node("concat", Concat::new(1), &[a, b]).unwrap();
+        model.auto_outputs().unwrap();
+        let pulse = PulsedModel::new(&model.into_normalized().unwrap(), 4).unwrap();
+        assert_eq!(
+            pulse.output_fact(0).unwrap().to_tensor_fact(),
+            InferenceFact::dt_shape(DatumType::F32, vec!(4, 4))
+        );
+    }
+
+    #[test]
+    fn test_reshape_inner_axes() {
+        use crate::ops::array::TypedReshape;
+        let mut model = TypedModel::default();
+        let a = model
+            .add_source(
+                "a",
+                TypedFact::dt_shape(DatumType::F32, [TDim::s(), 6.to_dim()].as_ref()).unwrap(),
+            )
+            .unwrap();
+        let shape = tvec!(TDim::s(), 2.to_dim(), 3.to_dim());
+        model.wire_node("reshape", TypedReshape::new(shape), &[a]).unwrap();
+        model.auto_outputs().unwrap();
+        let pulse = PulsedModel::new(&model.clone().into_normalized().unwrap(), 4).unwrap();
+        assert_eq!(
+            pulse.output_fact(0).unwrap().to_tensor_fact(),
+            InferenceFact::dt_shape(DatumType::F32, vec!(4, 2, 3))
+        );
+
+        let mut model = TypedModel::default();
+        let a = model
+            .add_source(
+                "a",
+                TypedFact::dt_shape(DatumType::F32, [TDim::s(), 6.to_dim()].as_ref()).unwrap(),
+            )
+            .unwrap();
+        let shape = tvec!(TDim::s() * 6);
+        model.wire_node("reshape", TypedReshape::new(shape), &[a]).unwrap();
+        model.auto_outputs().unwrap();
+        assert!(PulsedModel::new(&model.into_normalized().unwrap(), 4).is_err());
+    }
+
+    #[test]
+    fn test_reshape_outer_axes() {
+        use crate::ops::array::TypedReshape;
+        let mut model = TypedModel::default();
+        let a = model
+            .add_source(
+                "a",
+                TypedFact::dt_shape(
+                    DatumType::F32,
+                    [2.to_dim(), 3.to_dim(), TDim::s(), 5.to_dim()].as_ref(),
+                )
+                .unwrap(),
+            )
+            .unwrap();
+        let shape = tvec!(6.to_dim(), TDim::s(), 5.to_dim());
+        model.wire_node("reshape", TypedReshape::new(shape), &[a]).unwrap();
+        model.auto_outputs().unwrap();
+        let pulse = PulsedModel::new(&model.into_normalized().unwrap(), 4).unwrap();
+        let fact = pulse.output_fact(0).unwrap();
+        assert_eq!(fact.axis, 1);
+        assert_eq!(fact.to_tensor_fact(), InferenceFact::dt_shape(DatumType::F32, vec!(6, 4, 5)));
+    }
+
+    fn pulse_unary_op(op: impl Into<Box<dyn TypedOp>>) -> TractResult<PulsedModel> {
+        let mut model = TypedModel::default();
+        let a = model.add_source(
+            "a",
+            TypedFact::dt_shape(DatumType::F32, [TDim::s(), 2.to_dim(), 3.to_dim()].as_ref())?,
+        )?;
+        model.wire_node("op", op, &[a])?;
+        model.auto_outputs()?;
+        PulsedModel::new(&model.declutter()?.into_normalized()?, 4)
+    }
+
+    #[test]
+    fn test_gather_non_streaming_axis() {
+        use crate::ops::array::Gather;
+        let mut model = TypedModel::default();
+        let a = model
+            .add_source(
+                "a",
+                TypedFact::dt_shape(DatumType::F32, [2.to_dim(), TDim::s(), 3.to_dim()].as_ref())
+                    .unwrap(),
+            )
+            .unwrap();
+        let indices = model.add_const("indices", rctensor2(&[[2i64, 0], [1, 1]])).unwrap();
+        model.wire_node("gather", Gather::new(0), &[a, indices]).unwrap();
+        model.auto_outputs().unwrap();
+        let pulse =
+            PulsedModel::new(&model.declutter().unwrap().into_normalized().unwrap(), 4).unwrap();
+        let fact = pulse.output_fact(0).unwrap();
+        assert_eq!(fact.axis, 2);
+        assert_eq!(
+            fact.to_tensor_fact(),
+            InferenceFact::dt_shape(DatumType::F32, vec!(2, 2, 4, 3))
+        );
+    }
+
+    #[test]
+    fn test_gather_streaming_axis() {
+        use crate::ops::array::TypedGather;
+        let err = pulse_unary_op(TypedGather::new(0, rctensor1(&[0i64]))).unwrap_err();
+        assert!(err.iter().any(
+            |e| e.to_string() == "op can not be pulsified: gathering along the streaming axis"
+        ));
+    }
+
+    #[test]
+    fn test_tile_non_streaming_axis() {
+        use crate::ops::array::TypedTile;
+        let pulse = pulse_unary_op(TypedTile::new(tvec!(1, 3, 2))).unwrap();
+        assert_eq!(
+            pulse.output_fact(0).unwrap().to_tensor_fact(),
+            InferenceFact::dt_shape(DatumType::F32, vec!(4, 6, 6))
+        );
+        assert!(pulse_unary_op(TypedTile::new(tvec!(2, 1, 1))).is_err());
+    }
+
+    #[test]
+    fn test_split_non_streaming_axis() {
+        use crate::ops::array::Split;
+        let pulse = pulse_unary_op(Split::new(2, 2, Some(vec![1, 2]))).unwrap();
+        assert_eq!(
+            pulse.output_fact(1).unwrap().to_tensor_fact(),
+            InferenceFact::dt_shape(DatumType::F32, vec!(4, 2, 2))
+        );
+        assert!(pulse_unary_op(Split::new(0, 2, None)).is_err());
+    }
+
+    #[test]
+    fn test_flatten_inner_axes() {
+        use crate::ops::array::Flatten;
+        let pulse = pulse_unary_op(Flatten::new(1)).unwrap();
+        assert_eq!(
+            pulse.output_fact(0).unwrap().to_tensor_fact(),
+            InferenceFact::dt_shape(DatumType::F32, vec!(4, 6))
+        );
+        assert!(pulse_unary_op(Flatten::new(2)).is_err());
+    }
 }
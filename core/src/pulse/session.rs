@@ -0,0 +1,604 @@
+use std::borrow::Borrow;
+
+use crate::internal::*;
+use crate::plan::SimpleState;
+use crate::pulse::PulsedFact;
+use ndarray::*;
+
+/// Tracks what has been emitted so far for one output of the pulsed model.
+#[derive(Debug, Clone)]
+pub(super) struct OutputStream {
+    axis: usize,
+    delay: usize,
+    dim: TDim,
+    skipped: usize,
+    emitted: usize,
+    pending: Tensor,
+}
+
+impl OutputStream {
+    pub(super) fn new(fact: &PulsedFact) -> TractResult<OutputStream> {
+        let mut shape = fact.shape.clone();
+        shape[fact.axis] = 0;
+        let pending = dispatch_datum!(self::empty(fact.datum_type)(&*shape))?;
+        Ok(OutputStream {
+            axis: fact.axis,
+            delay: fact.delay,
+            dim: fact.dim.clone(),
+            skipped: 0,
+            emitted: 0,
+            pending,
+        })
+    }
+
+    pub(super) fn len_for_stream(&self, stream_len: usize) -> TractResult<usize> {
+        let len = self
+            .dim
+            .eval(stream_len as i32)
+            .ok_or_else(|| format!("Can not compute output length {:?}", self.dim))?;
+        Ok(len.max(0) as usize)
+    }
+
+    /// Whether all the output frames of a stream of `stream_len` frames have
+    /// been taken.
+    pub(super) fn is_complete(&self, stream_len: usize) -> TractResult<bool> {
+        Ok(self.emitted >= self.len_for_stream(stream_len)?)
+    }
+
+    /// Number of valid frames received so far, emitted or not.
+    fn available(&self) -> usize {
+        self.emitted + self.pending.shape()[self.axis]
+    }
+
+    /// Appends a raw pulse, dropping the frames that are there before the
+    /// signal because of the delay.
+    pub(super) fn push(&mut self, pulse: &Tensor) -> TractResult<()> {
+        let len = pulse.shape()[self.axis];
+        let skip = (self.delay - self.skipped).min(len);
+        self.skipped += skip;
+        let dt = pulse.datum_type();
+        let valid = dispatch_datum!(self::slice(dt)(pulse, self.axis, skip, len))?;
+        self.pending = dispatch_datum!(self::concat(dt)(self.axis, &self.pending, &valid))?;
+        Ok(())
+    }
+
+    /// Takes the pending frames up to output length `limit`.
+    pub(super) fn take_until(&mut self, limit: usize) -> TractResult<Tensor> {
+        let pending = self.pending.shape()[self.axis];
+        let count = limit.saturating_sub(self.emitted).min(pending);
+        self.emitted += count;
+        let dt = self.pending.datum_type();
+        let taken = dispatch_datum!(self::slice(dt)(&self.pending, self.axis, 0, count))?;
+        self.pending = dispatch_datum!(self::slice(dt)(&self.pending, self.axis, count, pending))?;
+        Ok(taken)
+    }
+}
+
+pub(super) fn empty<T: Datum>(shape: &[usize]) -> TractResult<Tensor> {
+    Ok(ArrayD::<T>::default(shape).into_tensor())
+}
+
+fn filled<T: Datum>(shape: &[usize], filler: &Tensor) -> TractResult<Tensor> {
+    Ok(ArrayD::from_elem(shape, filler.to_scalar::<T>()?.clone()).into_tensor())
+}
+
+pub(super) fn slice<T: Datum>(
+    t: &Tensor,
+    axis: usize,
+    start: usize,
+    end: usize,
+) -> TractResult<Tensor> {
+    let view = t.to_array_view::<T>()?;
+    let view = view.slice_axis(Axis(axis), (start..end).into());
+    // ndarray 0.12 to_owned() panics on some empty strided views
+    Ok(ArrayD::from_shape_vec(view.shape(), view.iter().cloned().collect())?.into_tensor())
+}
+
+fn concat<T: Datum>(axis: usize, a: &Tensor, b: &Tensor) -> TractResult<Tensor> {
+    let (a, b) = (a.to_array_view::<T>()?, b.to_array_view::<T>()?);
+    let split = a.shape()[axis];
+    let mut shape = a.shape().to_vec();
+    shape[axis] += b.shape()[axis];
+    let mut output = ArrayD::<T>::default(shape);
+    output.slice_axis_mut(Axis(axis), (..split).into()).assign(&a);
+    output.slice_axis_mut(Axis(axis), (split..).into()).assign(&b);
+    Ok(output.into_tensor())
+}
+
+/// Runs a pulsed model on a stream of input chunks.
+///
+/// Chunks of exactly one pulse are fed with `push`, the last one (possibly
+/// shorter, or empty) with `finish`. Both return the output frames that are
+/// known to be valid, the delay being dropped and the tail flushed, so that
+/// the concatenation of all outputs matches the full-sequence model.
+///
+/// Models with several inputs are fed with `push_inputs` and `finish_inputs`.
+/// One of the inputs at least must stream at the rate of S, the others can
+/// stream at a fraction of it (and get the same fraction of the pulse). An
+/// input with no streaming dim is given whole with every chunk, the last one
+/// given is used for the pulses that flush the tail.
+///
+/// Chunks of any length can be fed with `feed` (or `feed_inputs`) instead:
+/// they are buffered until whole pulses are available, and `flush` ends the
+/// stream with what is left in the buffers.
+///
+/// The frames past the end of the stream are zeros, or the value given to
+/// `set_filler` (NaN makes any leak into the valid output frames visible).
+#[derive(Debug)]
+pub struct StreamingSession<M, P>
+where
+    M: Borrow<PulsedModel>,
+    P: Borrow<SimplePlan<PulsedFact, Box<dyn PulsedOp>, M>> + Clone,
+{
+    state: SimpleState<PulsedFact, Box<dyn PulsedOp>, M, P>,
+    input_facts: TVec<PulsedFact>,
+    buffers: TVec<Tensor>,
+    pulse: usize,
+    written: usize,
+    outputs: TVec<OutputStream>,
+    finished: bool,
+    filler: Option<Tensor>,
+}
+
+impl<M, P> StreamingSession<M, P>
+where
+    M: Borrow<PulsedModel>,
+    P: Borrow<SimplePlan<PulsedFact, Box<dyn PulsedOp>, M>> + Clone,
+{
+    pub fn new(plan: P) -> TractResult<StreamingSession<M, P>> {
+        let state = SimpleState::new(plan)?;
+        let model = state.model();
+        let input_facts: TVec<PulsedFact> = model
+            .input_outlets()?
+            .iter()
+            .map(|o| Ok(model.outlet_fact(*o)?.clone()))
+            .collect::<TractResult<_>>()?;
+        let pulse = if let Some(fact) = input_facts.iter().find(|f| f.dim == TDim::s()) {
+            fact.pulse()
+        } else {
+            bail!(
+                "Streaming session expects an input of length S, got {:?}",
+                input_facts.iter().map(|f| &f.dim).collect::<Vec<_>>()
+            )
+        };
+        for fact in input_facts.iter().filter(|f| f.is_streaming()) {
+            if fact.dim.eval(pulse as i32) != Some(fact.pulse() as i32) {
+                bail!("Input stream of length {:?} does not match a pulse of {}", fact.dim, pulse)
+            }
+        }
+        let buffers = input_facts
+            .iter()
+            .map(|fact| {
+                let mut shape = fact.shape.clone();
+                shape[fact.axis] = 0;
+                dispatch_datum!(self::empty(fact.datum_type)(&*shape))
+            })
+            .collect::<TractResult<_>>()?;
+        let outputs = model
+            .output_outlets()?
+            .iter()
+            .map(|o| OutputStream::new(model.outlet_fact(*o)?))
+            .collect::<TractResult<_>>()?;
+        Ok(StreamingSession {
+            state,
+            input_facts,
+            buffers,
+            pulse,
+            written: 0,
+            outputs,
+            finished: false,
+            filler: None,
+        })
+    }
+
+    /// Fills the frames past the end of the stream of the inputs of the same
+    /// type with the scalar `filler`.
+    pub fn set_filler(&mut self, filler: Tensor) -> TractResult<()> {
+        if filler.rank() != 0 {
+            bail!("Filler must be a scalar, got {:?}", filler)
+        }
+        self.filler = Some(filler);
+        Ok(())
+    }
+
+    /// Pulse, in frames of the S-rate inputs.
+    pub fn pulse(&self) -> usize {
+        self.pulse
+    }
+
+    /// Number of input frames fed so far, in frames of the S-rate inputs.
+    pub fn written(&self) -> usize {
+        self.written
+    }
+
+    /// Feeds one full pulse of input, returns the valid output frames.
+    pub fn push(&mut self, input: Tensor) -> TractResult<TVec<Tensor>> {
+        self.push_inputs(tvec!(input))
+    }
+
+    /// Feeds the last chunk of input, of at most one pulse, and flushes the
+    /// remaining output frames.
+    pub fn finish(&mut self, input: Tensor) -> TractResult<TVec<Tensor>> {
+        self.finish_inputs(tvec!(input))
+    }
+
+    /// Feeds a chunk of input of any length, returns the valid output frames.
+    pub fn feed(&mut self, input: Tensor) -> TractResult<TVec<Tensor>> {
+        self.feed_inputs(tvec!(input))
+    }
+
+    /// Feeds a chunk of every input, of any length, and runs as many pulses
+    /// as the buffered frames allow. Returns the valid output frames.
+    pub fn feed_inputs(&mut self, inputs: TVec<Tensor>) -> TractResult<TVec<Tensor>> {
+        self.check_inputs(&inputs)?;
+        for ((buffer, input), fact) in
+            self.buffers.iter_mut().zip(inputs.iter()).zip(self.input_facts.iter())
+        {
+            if fact.is_streaming() {
+                let dt = buffer.datum_type();
+                *buffer = dispatch_datum!(self::concat(dt)(fact.axis, buffer, input))?;
+            } else {
+                *buffer = input.clone();
+            }
+        }
+        let mut outputs: TVec<Tensor> = self
+            .outputs
+            .iter()
+            .map(|o| {
+                let dt = o.pending.datum_type();
+                dispatch_datum!(self::slice(dt)(&o.pending, o.axis, 0, 0))
+            })
+            .collect::<TractResult<_>>()?;
+        while self
+            .buffers
+            .iter()
+            .zip(self.input_facts.iter())
+            .all(|(b, fact)| b.shape()[fact.axis] >= fact.pulse())
+        {
+            let mut pulse = tvec!();
+            for (buffer, fact) in self.buffers.iter_mut().zip(self.input_facts.iter()) {
+                if !fact.is_streaming() {
+                    pulse.push(buffer.clone());
+                    continue;
+                }
+                let (dt, len) = (buffer.datum_type(), buffer.shape()[fact.axis]);
+                pulse.push(dispatch_datum!(self::slice(dt)(buffer, fact.axis, 0, fact.pulse()))?);
+                *buffer = dispatch_datum!(self::slice(dt)(buffer, fact.axis, fact.pulse(), len))?;
+            }
+            let got = self.push_pulse(pulse)?;
+            for ((output, got), stream) in outputs.iter_mut().zip(got.iter()).zip(&self.outputs) {
+                let dt = got.datum_type();
+                *output = dispatch_datum!(self::concat(dt)(stream.axis, output, got))?;
+            }
+        }
+        Ok(outputs)
+    }
+
+    /// Ends a stream fed with `feed`, running the frames left in the
+    /// buffers, and flushes the remaining output frames.
+    pub fn flush(&mut self) -> TractResult<TVec<Tensor>> {
+        let inputs = self
+            .buffers
+            .iter_mut()
+            .zip(self.input_facts.iter())
+            .map(|(buffer, fact)| {
+                if !fact.is_streaming() {
+                    return Ok(buffer.clone());
+                }
+                let dt = buffer.datum_type();
+                let left = dispatch_datum!(self::slice(dt)(buffer, fact.axis, 0, 0))?;
+                Ok(std::mem::replace(buffer, left))
+            })
+            .collect::<TractResult<_>>()?;
+        self.finish_inputs(inputs)
+    }
+
+    /// Feeds one full pulse of every input, returns the valid output frames.
+    pub fn push_inputs(&mut self, inputs: TVec<Tensor>) -> TractResult<TVec<Tensor>> {
+        self.check_inputs(&inputs)?;
+        self.check_no_buffered_frames()?;
+        self.push_pulse(inputs)
+    }
+
+    fn push_pulse(&mut self, inputs: TVec<Tensor>) -> TractResult<TVec<Tensor>> {
+        for (input, fact) in inputs.iter().zip(self.input_facts.iter()) {
+            if input.shape() != &*fact.shape {
+                bail!(
+                    "Expected a pulse of shape {:?}, got {:?} (use finish for the last chunk)",
+                    fact.shape,
+                    input.shape()
+                )
+            }
+        }
+        self.keep_invariant_inputs(&inputs);
+        self.written += self.pulse;
+        self.run(inputs)?;
+        // frames past the output length for the input seen so far may still
+        // be affected by the end of the stream
+        let written = self.written;
+        self.outputs.iter_mut().map(|o| o.take_until(o.len_for_stream(written)?)).collect()
+    }
+
+    /// Feeds the last chunk of every input, of at most one pulse, and
+    /// flushes the remaining output frames.
+    pub fn finish_inputs(&mut self, inputs: TVec<Tensor>) -> TractResult<TVec<Tensor>> {
+        self.check_inputs(&inputs)?;
+        self.check_no_buffered_frames()?;
+        let mut len = None;
+        for (input, fact) in inputs.iter().zip(self.input_facts.iter()) {
+            if !fact.is_streaming() {
+                if input.shape() != &*fact.shape {
+                    bail!("Expected an input of shape {:?}, got {:?}", fact.shape, input.shape())
+                }
+                continue;
+            }
+            let input_len = input.shape()[fact.axis];
+            if input_len > fact.pulse() {
+                bail!(
+                    "Last chunk of {} frames is longer than the pulse ({})",
+                    input_len,
+                    fact.pulse()
+                )
+            }
+            if fact.dim == TDim::s() {
+                if len.map(|l| l != input_len).unwrap_or(false) {
+                    bail!("Last chunks of the S-rate inputs have different lengths")
+                }
+                len = Some(input_len);
+            }
+        }
+        self.keep_invariant_inputs(&inputs);
+        self.finished = true;
+        self.written += len.unwrap();
+        let stream_len = self.written;
+        self.state.session_state.known_stream_len = Some(stream_len);
+        let limits: TVec<usize> = self
+            .outputs
+            .iter()
+            .map(|o| o.len_for_stream(stream_len))
+            .collect::<TractResult<_>>()?;
+        let mut inputs = Some(
+            inputs
+                .iter()
+                .zip(self.input_facts.iter())
+                .map(|(input, fact)| {
+                    let mut filler_shape = fact.shape.clone();
+                    filler_shape[fact.axis] -= input.shape()[fact.axis];
+                    let dt = input.datum_type();
+                    let filler = self.padding(dt, &filler_shape)?;
+                    dispatch_datum!(self::concat(dt)(fact.axis, input, &filler))
+                })
+                .collect::<TractResult<TVec<_>>>()?,
+        );
+        while self.outputs.iter().zip(limits.iter()).any(|(o, &limit)| o.available() < limit) {
+            let pulse = match inputs.take() {
+                Some(inputs) => inputs,
+                None => self
+                    .input_facts
+                    .iter()
+                    .zip(self.buffers.iter())
+                    .map(|(fact, buffer)| {
+                        if fact.is_streaming() {
+                            self.padding(fact.datum_type, &fact.shape)
+                        } else {
+                            Ok(buffer.clone())
+                        }
+                    })
+                    .collect::<TractResult<_>>()?,
+            };
+            self.run(pulse)?;
+        }
+        self.outputs.iter_mut().zip(limits.iter()).map(|(o, &limit)| o.take_until(limit)).collect()
+    }
+
+    fn check_inputs(&self, inputs: &[Tensor]) -> TractResult<()> {
+        if self.finished {
+            bail!("Stream is already finished")
+        }
+        if inputs.len() != self.input_facts.len() {
+            bail!("Expected {} inputs, got {}", self.input_facts.len(), inputs.len())
+        }
+        Ok(())
+    }
+
+    fn check_no_buffered_frames(&self) -> TractResult<()> {
+        if self
+            .buffers
+            .iter()
+            .zip(self.input_facts.iter())
+            .any(|(b, f)| f.is_streaming() && b.shape()[f.axis] > 0)
+        {
+            bail!("Frames fed with feed are still buffered (use flush to end the stream)")
+        }
+        Ok(())
+    }
+
+    // the buffers of the inputs with no streaming dim hold their last value
+    fn keep_invariant_inputs(&mut self, inputs: &[Tensor]) {
+        for ((buffer, input), fact) in
+            self.buffers.iter_mut().zip(inputs.iter()).zip(self.input_facts.iter())
+        {
+            if !fact.is_streaming() {
+                *buffer = input.clone();
+            }
+        }
+    }
+
+    fn padding(&self, dt: DatumType, shape: &[usize]) -> TractResult<Tensor> {
+        match self.filler {
+            Some(ref filler) if filler.datum_type() == dt => {
+                dispatch_datum!(self::filled(dt)(shape, filler))
+            }
+            _ => dispatch_datum!(self::empty(dt)(shape)),
+        }
+    }
+
+    fn run(&mut self, inputs: TVec<Tensor>) -> TractResult<()> {
+        let outputs = self.state.run(inputs)?;
+        for (stream, pulse) in self.outputs.iter_mut().zip(outputs.iter()) {
+            stream.push(pulse)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ops::cnn::Conv;
+
+    fn conv_model(pulse: usize) -> PulsedModel {
+        let mut model = InferenceModel::default();
+        let ker = model.add_const("kernel", tensor3(&[[[1.0f32, 1.0, 1.0]]])).unwrap();
+        let fact =
+            InferenceFact::dt_shape(f32::datum_type(), vec![1.to_dim(), 1.to_dim(), TDim::s()]);
+        let a = model.add_source("a", fact).unwrap();
+        model.wire_node("conv", Conv::default(), &[a, ker]).unwrap();
+        model.auto_outputs().unwrap();
+        PulsedModel::new(&model.into_normalized().unwrap(), pulse).unwrap()
+    }
+
+    // conv(a) + b[1..S-1]: b is delayed to meet the convolution output
+    fn two_inputs_model(pulse: usize) -> PulsedModel {
+        let mut model = InferenceModel::default();
+        let ker = model.add_const("kernel", tensor3(&[[[1.0f32, 1.0, 1.0]]])).unwrap();
+        let fact =
+            InferenceFact::dt_shape(f32::datum_type(), vec![1.to_dim(), 1.to_dim(), TDim::s()]);
+        let a = model.add_source("a", fact.clone()).unwrap();
+        let b = model.add_source("b", fact).unwrap();
+        let conv = model.wire_node("conv", Conv::default(), &[a, ker]).unwrap()[0];
+        let slice = crate::ops::array::Slice::new(2, 1.to_dim(), TDim::s() - 1);
+        let slice = model.wire_node("slice", slice, &[b]).unwrap()[0];
+        model.wire_node("add", crate::ops::math::add::bin(), &[conv, slice]).unwrap();
+        model.auto_outputs().unwrap();
+        PulsedModel::new(&model.into_normalized().unwrap(), pulse).unwrap()
+    }
+
+    fn chunk(values: &[f32]) -> Tensor {
+        arr1(values).into_shape((1, 1, values.len())).unwrap().into_tensor()
+    }
+
+    #[test]
+    fn delay_and_short_last_chunk() {
+        let plan = SimplePlan::new(conv_model(2)).unwrap();
+        let mut session = StreamingSession::new(&plan).unwrap();
+        let mut got = vec![];
+        for c in &[[1.0f32, 2.0], [3.0, 4.0]] {
+            got.extend(session.push(chunk(c)).unwrap()[0].as_slice::<f32>().unwrap().to_vec());
+        }
+        assert_eq!(session.written(), 4);
+        got.extend(session.finish(chunk(&[5.0])).unwrap()[0].as_slice::<f32>().unwrap().to_vec());
+        assert_eq!(got, vec![6.0, 9.0, 12.0]);
+    }
+
+    #[test]
+    fn nan_filler() {
+        let plan = SimplePlan::new(conv_model(2)).unwrap();
+        let mut session = StreamingSession::new(&plan).unwrap();
+        session.set_filler(tensor0(std::f32::NAN)).unwrap();
+        let mut got =
+            session.push(chunk(&[1.0, 2.0])).unwrap()[0].as_slice::<f32>().unwrap().to_vec();
+        got.extend(session.finish(chunk(&[3.0])).unwrap()[0].as_slice::<f32>().unwrap().to_vec());
+        assert_eq!(got, vec![6.0]);
+    }
+
+    #[test]
+    fn empty_last_chunk() {
+        let plan = SimplePlan::new(conv_model(2)).unwrap();
+        let mut session = StreamingSession::new(&plan).unwrap();
+        let mut got =
+            session.push(chunk(&[1.0, 2.0])).unwrap()[0].as_slice::<f32>().unwrap().to_vec();
+        got.extend(session.finish(chunk(&[])).unwrap()[0].as_slice::<f32>().unwrap().to_vec());
+        assert_eq!(got, Vec::<f32>::new());
+    }
+
+    #[test]
+    fn push_checks_pulse() {
+        let plan = SimplePlan::new(conv_model(2)).unwrap();
+        let mut session = StreamingSession::new(&plan).unwrap();
+        assert!(session.push(chunk(&[1.0])).is_err());
+        assert!(session.finish(chunk(&[1.0, 2.0, 3.0])).is_err());
+        session.finish(chunk(&[1.0])).unwrap();
+        assert!(session.push(chunk(&[1.0, 2.0])).is_err());
+    }
+
+    #[test]
+    fn two_inputs_with_different_delays() {
+        let plan = SimplePlan::new(two_inputs_model(2)).unwrap();
+        let mut session = StreamingSession::new(&plan).unwrap();
+        assert!(session.push(chunk(&[1.0, 2.0])).is_err());
+        let a = [1.0f32, 2.0, 3.0, 4.0, 5.0];
+        let b = [10.0f32, 20.0, 30.0, 40.0, 50.0];
+        let mut got = vec![];
+        for i in 0..2 {
+            let inputs = tvec!(chunk(&a[2 * i..2 * i + 2]), chunk(&b[2 * i..2 * i + 2]));
+            got.extend(session.push_inputs(inputs).unwrap()[0].as_slice::<f32>().unwrap().to_vec());
+        }
+        let output = session.finish_inputs(tvec!(chunk(&a[4..]), chunk(&b[4..]))).unwrap();
+        got.extend(output[0].as_slice::<f32>().unwrap().to_vec());
+        assert_eq!(got, vec![26.0, 39.0, 52.0]);
+    }
+
+    fn feed_all<M, P>(session: &mut StreamingSession<M, P>, chunks: &[&[f32]]) -> Vec<f32>
+    where
+        M: Borrow<PulsedModel>,
+        P: Borrow<SimplePlan<PulsedFact, Box<dyn PulsedOp>, M>> + Clone,
+    {
+        let mut got = vec![];
+        for c in chunks {
+            got.extend(session.feed(chunk(c)).unwrap()[0].as_slice::<f32>().unwrap().to_vec());
+        }
+        got.extend(session.flush().unwrap()[0].as_slice::<f32>().unwrap().to_vec());
+        got
+    }
+
+    #[test]
+    fn feed_irregular_chunks() {
+        let plan = SimplePlan::new(conv_model(2)).unwrap();
+        let mut session = StreamingSession::new(&plan).unwrap();
+        let got = feed_all(&mut session, &[&[1.0, 2.0, 3.0], &[], &[4.0], &[5.0, 6.0, 7.0]]);
+        assert_eq!(got, vec![6.0, 9.0, 12.0, 15.0, 18.0]);
+    }
+
+    #[test]
+    fn feed_then_push_is_refused() {
+        let plan = SimplePlan::new(conv_model(2)).unwrap();
+        let mut session = StreamingSession::new(&plan).unwrap();
+        session.feed(chunk(&[1.0])).unwrap();
+        assert!(session.push(chunk(&[2.0, 3.0])).is_err());
+    }
+
+    #[test]
+    fn feed_downsampled() {
+        use crate::ops::downsample::Downsample;
+        let mut model = InferenceModel::default();
+        let fact =
+            InferenceFact::dt_shape(f32::datum_type(), vec![1.to_dim(), 1.to_dim(), TDim::s()]);
+        let a = model.add_source("a", fact).unwrap();
+        model.wire_node("down", Downsample::new(2, 2, 0), &[a]).unwrap();
+        model.auto_outputs().unwrap();
+        let plan = SimplePlan::new(PulsedModel::new(&model.into_normalized().unwrap(), 4).unwrap())
+            .unwrap();
+        let mut session = StreamingSession::new(&plan).unwrap();
+        let input: Vec<f32> = (0..11).map(|i| i as f32).collect();
+        assert_eq!(session.feed(chunk(&input[..3])).unwrap()[0].shape(), &[1, 1, 0]);
+        let got = feed_all(&mut session, &[&input[3..4], &input[4..9], &input[9..]]);
+        assert_eq!(got, vec![0.0, 2.0, 4.0, 6.0, 8.0, 10.0]);
+    }
+
+    #[test]
+    fn feed_resampled() {
+        use crate::ops::downsample::Resample;
+        let mut model = InferenceModel::default();
+        let fact =
+            InferenceFact::dt_shape(f32::datum_type(), vec![1.to_dim(), 1.to_dim(), TDim::s()]);
+        let a = model.add_source("a", fact).unwrap();
+        model.wire_node("resample", Resample::new(2, 3, 2, 0), &[a]).unwrap();
+        model.auto_outputs().unwrap();
+        let plan = SimplePlan::new(PulsedModel::new(&model.into_normalized().unwrap(), 2).unwrap())
+            .unwrap();
+        let mut session = StreamingSession::new(&plan).unwrap();
+        let got = feed_all(&mut session, &[&[0.0, 1.0, 2.0], &[3.0, 4.0]]);
+        assert_eq!(got, vec![0.0, 0.0, 1.0, 2.0, 2.0, 3.0, 4.0, 4.0]);
+    }
+}
@@ -13,6 +13,10 @@ use std::sync::Arc;
 
 pub mod litteral;
 
+/// A read-only byte buffer that tensors can borrow their content from (a
+/// memory mapped file for instance).
+pub type SharedBytes = Arc<dyn AsRef<[u8]> + Send + Sync>;
+
 /// Tensor is a concrete tensor in tract.
 pub struct Tensor {
     null: bool,
@@ -20,6 +24,8 @@ pub struct Tensor {
     shape: TVec<usize>,
     layout: alloc::Layout,
     data: *mut u8,
+    /// When set, `data` points inside this buffer and is not owned.
+    shared: Option<SharedBytes>,
 }
 
 unsafe impl Send for Tensor {}
@@ -63,7 +69,7 @@ impl Drop for Tensor {
                     .for_each(|s| std::ptr::drop_in_place(s as *mut TDim));
             }
         }
-        if !self.data.is_null() && self.layout.size() > 0 {
+        if self.shared.is_none() && !self.data.is_null() && self.layout.size() > 0 {
             unsafe { alloc::dealloc(self.data, self.layout) }
         }
     }
@@ -108,7 +114,7 @@ impl Tensor {
             assert!(!ptr.is_null());
             ptr
         } as *mut u8;
-        Ok(Tensor { null: false, layout, dt, shape: shape.into(), data })
+        Ok(Tensor { null: false, layout, dt, shape: shape.into(), data, shared: None })
     }
 
     /// Create an tensor from raw data.
@@ -127,7 +133,57 @@ impl Tensor {
         let layout = alloc::Layout::from_size_align(bytes, dt.alignment())?;
         let data = alloc::alloc(layout);
         content.as_ptr().copy_to_nonoverlapping(data, bytes);
-        Ok(Tensor { null: false, dt, shape: shape.into(), data, layout })
+        Ok(Tensor { null: false, dt, shape: shape.into(), data, layout, shared: None })
+    }
+
+    /// Create a tensor borrowing its content from a shared buffer, starting at
+    /// `offset`.
+    ///
+    /// The buffer is not copied, unless the data is not properly aligned for
+    /// the datum type. Mutable accesses will copy the data first.
+    pub unsafe fn from_shared_dt(
+        dt: DatumType,
+        shape: &[usize],
+        buffer: SharedBytes,
+        offset: usize,
+    ) -> TractResult<Tensor> {
+        if !dt.is_copy() {
+            bail!("Can not build a {:?} tensor from a shared buffer", dt);
+        }
+        let bytes = shape.iter().cloned().product::<usize>() * dt.size_of();
+        let content = (*buffer).as_ref();
+        if offset + bytes > content.len() {
+            bail!(
+                "Shared buffer too short: {} bytes from {} needed, buffer is {} bytes",
+                bytes,
+                offset,
+                content.len()
+            );
+        }
+        let content = &content[offset..][..bytes];
+        if bytes == 0 || content.as_ptr() as usize % dt.alignment() != 0 {
+            return Self::from_raw_dt(dt, shape, content);
+        }
+        let layout = alloc::Layout::from_size_align(bytes, dt.alignment())?;
+        let data = content.as_ptr() as *mut u8;
+        Ok(Tensor { null: false, dt, shape: shape.into(), data, layout, shared: Some(buffer) })
+    }
+
+    /// Check whether the tensor content is borrowed from a shared buffer.
+    pub fn is_shared(&self) -> bool {
+        self.shared.is_some()
+    }
+
+    /// Copy the content of a shared tensor in its own buffer.
+    fn unshare(&mut self) {
+        if self.shared.is_some() {
+            unsafe {
+                let data = alloc::alloc(self.layout);
+                self.data.copy_to_nonoverlapping(data, self.layout.size());
+                self.data = data;
+            }
+            self.shared = None;
+        }
     }
 
     /// Creates a null tensor (this is rare, and should stay that way).
@@ -143,6 +199,7 @@ impl Tensor {
             shape: shape.into(),
             data: std::ptr::null::<u8>() as *mut u8,
             layout: alloc::Layout::from_size_align(0, dt.size_of())?,
+            shared: None,
         })
     }
 
@@ -167,10 +224,9 @@ impl Tensor {
     }
 
     /// Reshape the tensor to `shape`.
-    pub unsafe fn into_shape(self, shape: &[usize]) -> TractResult<Tensor> {
-        let t = Tensor { shape: shape.into(), ..self };
-        std::mem::forget(self);
-        Ok(t)
+    pub unsafe fn into_shape(mut self, shape: &[usize]) -> TractResult<Tensor> {
+        self.shape = shape.into();
+        Ok(self)
     }
 
     /// Get the datum type of the tensor.
@@ -282,7 +338,8 @@ impl Tensor {
     pub fn to_array_view_mut<'a, D: Datum>(&'a mut self) -> TractResult<ArrayViewMutD<'a, D>> {
         if self.len() != 0 {
             unsafe {
-                return Ok(ArrayViewMutD::from_shape_ptr(&*self.shape, self.data as *mut D));
+                let ptr = self.as_ptr_mut()?;
+                return Ok(ArrayViewMutD::from_shape_ptr(&*self.shape, ptr));
             }
         } else {
             return Ok(ArrayViewMutD::from_shape(&*self.shape, &mut [])?);
@@ -297,9 +354,21 @@ impl Tensor {
 
     /// Access the data as a mutable pointer.
     pub fn as_ptr_mut<D: Datum>(&mut self) -> TractResult<*mut D> {
+        self.unshare();
         self.as_ptr::<D>().map(|p| p as *mut D)
     }
 
+    /// Access the raw data of a tensor of a copy datum type.
+    pub(crate) fn as_bytes(&self) -> TractResult<&[u8]> {
+        if !self.dt.is_copy() {
+            bail!("Can not access {:?} tensor as bytes", self.dt)
+        }
+        if self.data.is_null() {
+            return Ok(&[]);
+        }
+        unsafe { Ok(std::slice::from_raw_parts(self.data, self.len() * self.dt.size_of())) }
+    }
+
     /// Access the data as a slice.
     pub fn as_slice<D: Datum>(&self) -> TractResult<&[D]> {
         unsafe { Ok(std::slice::from_raw_parts::<D>(self.as_ptr()?, self.len())) }
@@ -429,27 +498,37 @@ impl Tensor {
         let layout =
             alloc::Layout::from_size_align(vec.len() * size_of::<T>(), align_of::<T>()).unwrap();
         let data = Box::into_raw(vec) as *mut u8;
-        Tensor { null: false, dt: T::datum_type(), shape, layout, data }
+        Tensor { null: false, dt: T::datum_type(), shape, layout, data, shared: None }
     }
 
     pub fn deep_clone(&self) -> Tensor {
         if self.dt == DatumType::String {
             let data: Vec<String> = self.as_slice::<String>().unwrap().to_vec();
-            let t = Tensor { data: data.as_ptr() as *mut u8, shape: self.shape.clone(), ..*self };
+            let t = Tensor {
+                data: data.as_ptr() as *mut u8,
+                shape: self.shape.clone(),
+                shared: None,
+                ..*self
+            };
             std::mem::forget(data);
             t
         } else if self.dt == DatumType::TDim {
             let data: Vec<TDim> = self.as_slice::<TDim>().unwrap().to_vec();
-            let t = Tensor { data: data.as_ptr() as *mut u8, shape: self.shape.clone(), ..*self };
+            let t = Tensor {
+                data: data.as_ptr() as *mut u8,
+                shape: self.shape.clone(),
+                shared: None,
+                ..*self
+            };
             std::mem::forget(data);
             t
         } else if self.null {
-            Tensor { shape: self.shape.clone(), ..*self }
+            Tensor { shape: self.shape.clone(), shared: None, ..*self }
         } else {
             unsafe {
                 let data = alloc::alloc(self.layout) as *mut u8;
                 self.data.copy_to_nonoverlapping(data, self.layout.size());
-                Tensor { data, shape: self.shape.clone(), ..*self }
+                Tensor { data, shape: self.shape.clone(), shared: None, ..*self }
             }
         }
     }
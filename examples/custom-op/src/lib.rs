@@ -0,0 +1,118 @@
+//! A custom operator, plugged into the ONNX, TensorFlow and Kaldi front-ends.
+//!
+//! `Swish` computes `x * sigmoid(beta * x)`. It implements the same traits as
+//! tract own operators:
+//!
+//! * `Op` and `StatelessOp` for its name and evaluation,
+//! * `InferenceRulesOp` for type and shape inference on the frameworks models,
+//! * `TypedOp` for the output facts once the model is typed.
+//!
+//! `SwishPlugin` registers a builder for it in each framework operator
+//! register. The builders read the operator parameters from the framework
+//! node.
+
+#[macro_use]
+extern crate tract_core;
+
+use tract_core::internal::*;
+
+use tract_kaldi::model::KaldiOpRegister;
+use tract_kaldi::KaldiAttributeKind;
+use tract_onnx::model::OnnxOpRegister;
+use tract_tensorflow::model::TfOpRegister;
+
+/// ONNX domain of the operator.
+pub const DOMAIN: &'static str = "com.example";
+
+#[derive(Clone, Debug)]
+pub struct Swish {
+    pub beta: f32,
+}
+
+impl Op for Swish {
+    fn name(&self) -> Cow<str> {
+        "example.Swish".into()
+    }
+
+    op_as_typed_op!();
+}
+
+impl StatelessOp for Swish {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let mut output = input.into_tensor().into_array::<f32>()?;
+        output.mapv_inplace(|x| x / (1.0 + (-self.beta * x).exp()));
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Swish {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Swish {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+}
+
+/// Registers `Swish` as:
+///
+/// * ONNX `Swish` in the `com.example` domain, with a `beta` float attribute,
+/// * TensorFlow `Swish`, with a `beta` float attribute,
+/// * Kaldi `SwishComponent`, with a `Beta` float attribute.
+pub struct SwishPlugin;
+
+impl OpPlugin<OnnxOpRegister> for SwishPlugin {
+    fn register_ops(&self, register: &mut OnnxOpRegister) {
+        register.insert_domain(DOMAIN, "Swish", 1, |_ctx, node| {
+            let beta = node.get_attr_opt("beta")?.unwrap_or(1.0);
+            Ok((Box::new(Swish { beta }), vec![]))
+        });
+    }
+}
+
+impl OpPlugin<TfOpRegister> for SwishPlugin {
+    fn register_ops(&self, register: &mut TfOpRegister) {
+        register.insert("Swish", |_ctx, node| {
+            let beta = node.get_attr_opt_float("beta")?.unwrap_or(1.0);
+            Ok(Box::new(Swish { beta }))
+        });
+    }
+}
+
+impl OpPlugin<KaldiOpRegister> for SwishPlugin {
+    fn register_ops(&self, register: &mut KaldiOpRegister) {
+        register.insert_component(
+            "SwishComponent",
+            &[("Beta", KaldiAttributeKind::Float)],
+            |ctx, name| {
+                let component = &ctx.proto_model.components[name];
+                let beta = component
+                    .attributes
+                    .get("Beta")
+                    .map(|beta| beta.cast_to_scalar::<f32>())
+                    .transpose()?
+                    .unwrap_or(1.0);
+                Ok(Box::new(Swish { beta }))
+            },
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use tract_core::ndarray;
+use tract_core::prelude::*;
+
+use tract_custom_op_example::{SwishPlugin, DOMAIN};
+
+use tract_onnx::pb;
+use tract_tensorflow::tfpb;
+
+/// A one node ONNX model, using the custom domain.
+fn onnx_model() -> pb::ModelProto {
+    let tensor_type =
+        pb::type_proto::Tensor { elem_type: pb::tensor_proto::DataType::Float as i32, shape: None };
+    let value_info = |name: &str| pb::ValueInfoProto {
+        name: name.to_string(),
+        r#type: Some(pb::TypeProto {
+            value: Some(pb::type_proto::Value::TensorType(tensor_type.clone())),
+            ..pb::TypeProto::default()
+        }),
+        ..pb::ValueInfoProto::default()
+    };
+    let swish = pb::NodeProto {
+        op_type: "Swish".to_string(),
+        domain: DOMAIN.to_string(),
+        input: vec!["x".to_string()],
+        output: vec!["y".to_string()],
+        attribute: vec![pb::AttributeProto {
+            name: "beta".to_string(),
+            r#type: pb::attribute_proto::AttributeType::Float as i32,
+            f: 2.0,
+            ..pb::AttributeProto::default()
+        }],
+        ..pb::NodeProto::default()
+    };
+    pb::ModelProto {
+        opset_import: vec![
+            pb::OperatorSetIdProto { domain: String::new(), version: 10 },
+            pb::OperatorSetIdProto { domain: DOMAIN.to_string(), version: 1 },
+        ],
+        graph: Some(pb::GraphProto {
+            node: vec![swish],
+            input: vec![value_info("x")],
+            output: vec![value_info("y")],
+            ..pb::GraphProto::default()
+        }),
+        ..pb::ModelProto::default()
+    }
+}
+
+/// A one node TensorFlow graph.
+fn tf_model() -> tfpb::tensorflow::GraphDef {
+    tfpb::graph()
+        .node(
+            tfpb::node()
+                .name("x")
+                .op("Placeholder")
+                .attr("dtype", tfpb::tensorflow::DataType::DtFloat),
+        )
+        .node(tfpb::node().name("y").op("Swish").input("x").attr("beta", 2.0f32))
+}
+
+/// A one component Kaldi network, in text format.
+const KALDI_MODEL: &'static str = r#"<Nnet3>
+input-node name=input dim=3
+component-node name=swish input=input component=swish
+output-node name=output input=swish
+
+<NumComponents> 1
+<ComponentName> swish <SwishComponent> <Beta> 2.0 </SwishComponent>
+</Nnet3>"#;
+
+fn run(model: InferenceModel, input: Tensor) -> TractResult<Arc<Tensor>> {
+    let mut model = model;
+    model.set_input_fact(0, InferenceFact::dt_shape(f32::datum_type(), input.shape()))?;
+    let model = model.into_optimized()?;
+    let plan = SimplePlan::new(&model)?;
+    Ok(plan.run(tvec!(input))?.remove(0))
+}
+
+fn main() -> TractResult<()> {
+    let input: Tensor = ndarray::arr2(&[[-1.0f32, 0.0, 1.0]]).into();
+
+    let onnx = tract_onnx::onnx().with_plugin(&SwishPlugin);
+    let model = onnx.model_for_proto_model(&onnx_model())?;
+    println!("onnx: {:?}", run(model, input.clone())?);
+
+    let tf = tract_tensorflow::tensorflow().with_plugin(&SwishPlugin);
+    let model = tf.model_for_proto_model(&tf_model())?;
+    println!("tensorflow: {:?}", run(model, input.clone())?);
+
+    let kaldi = tract_kaldi::kaldi().with_plugin(&SwishPlugin);
+    let model = kaldi.model_for_read(&mut KALDI_MODEL.as_bytes())?;
+    println!("kaldi: {:?}", run(model, input.clone())?);
+
+    Ok(())
+}
@@ -7,6 +7,7 @@ use proptest::*;
 use tract_core::dimfact;
 use tract_core::internal::*;
 use tract_core::ndarray::*;
+use tract_core::pulse::StreamingSession;
 use tract_core::shapefact;
 
 mod conv_plus_conv;
@@ -33,57 +34,27 @@ fn proptest_regular_against_pulse(
 
     let model = model.into_normalized().unwrap();
     let pulsed = PulsedModel::new(&model, pulse).unwrap();
-    let output_fact = pulsed.output_fact(0).unwrap().clone();
-
-    let output_stream_axis = output_fact.axis;
-    let delay = output_fact.delay;
-    let mut initial_output_shape = output_fact.shape.clone();
-    initial_output_shape[output_stream_axis] = 0;
+    let output_axis = pulsed.output_fact(0).unwrap().axis;
 
     let pulsed_plan = SimplePlan::new(pulsed).unwrap();
-    let mut state = SimpleState::new(&pulsed_plan).unwrap();
-
-    let mut got: ArrayD<f32> = ArrayD::zeros(&*initial_output_shape);
-    let mut output_len = None;
+    let mut session = StreamingSession::new(&pulsed_plan).unwrap();
+    // any frame past the end of the input leaking into the output shows up
+    session.set_filler(tensor0(std::f32::NAN)).unwrap();
 
+    let mut chunks = vec![];
+    let input_len = input_array.shape()[axis];
     let mut written = 0;
-    loop {
-        let to_write_in_chunk = pulse.min(input_array.shape()[axis].saturating_sub(written));
-        let mut chunk: ArrayD<f32> = input_array
-            .slice_axis(Axis(axis), (written..written + to_write_in_chunk).into())
-            .to_owned();
-        written += to_write_in_chunk;
-        if to_write_in_chunk < pulse {
-            let mut filler_shape = input_array.shape().to_vec();
-            filler_shape[axis] = pulse - to_write_in_chunk;
-            chunk = stack(
-                Axis(axis),
-                &[chunk.view(), ArrayD::from_elem(filler_shape, std::f32::NAN).view()],
-            )
-            .unwrap();
-            output_len = output_fact.dim.eval(written as _);
-            state.session_state.known_stream_len = Some(written)
-        }
-        let mut outputs = state.run(tvec!(Tensor::from(chunk.to_owned()).into())).unwrap();
-        got = stack(
-            Axis(output_stream_axis),
-            &[got.view(), outputs.remove(0).to_array_view::<f32>().unwrap()],
-        )
-        .unwrap();
-        if let Some(output_len) = output_len {
-            if got.shape()[output_stream_axis] >= output_len as usize + delay {
-                break;
-            }
-        }
+    while written + pulse <= input_len {
+        let chunk = input_array.slice_axis(Axis(axis), (written..written + pulse).into());
+        chunks.push(session.push(chunk.to_owned().into_tensor()).unwrap().remove(0));
+        written += pulse;
     }
+    let last = input_array.slice_axis(Axis(axis), (written..).into());
+    chunks.push(session.finish(last.to_owned().into_tensor()).unwrap().remove(0));
 
-    let pulsed_output = got
-        .slice_axis(
-            Axis(output_stream_axis),
-            (output_fact.delay..output_fact.delay + output_len.unwrap() as usize).into(),
-        )
-        .to_owned()
-        .into_tensor();
+    let chunks: Vec<ArrayViewD<f32>> =
+        chunks.iter().map(|c| c.to_array_view::<f32>().unwrap()).collect();
+    let pulsed_output = stack(Axis(output_axis), &chunks).unwrap().into_tensor();
 
     prop_assert_eq!(&pulsed_output, &*outputs[0]);
     Ok(())
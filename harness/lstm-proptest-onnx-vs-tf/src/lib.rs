@@ -19,6 +19,18 @@ pub struct LstmProblem {
 
 impl LstmProblem {
     pub fn onnx_model(&self) -> TractResult<TypedModel> {
+        self.onnx_model_for_input(InferenceFact::dt_shape(self.x.datum_type(), self.x.shape()))
+    }
+
+    pub fn onnx_model_for_input(&self, x_fact: InferenceFact) -> TractResult<TypedModel> {
+        self.onnx_model_with_direction(x_fact, tract_onnx::ops::rec::Direction::Forward)
+    }
+
+    pub fn onnx_model_with_direction(
+        &self,
+        x_fact: InferenceFact,
+        direction: tract_onnx::ops::rec::Direction,
+    ) -> TractResult<TypedModel> {
         let mut model = InferenceModel::default();
         let s = self.cell_size;
         let mut w_iofc = Array2::zeros((s, 4 * s));
@@ -41,9 +53,9 @@ impl LstmProblem {
         let r_iofc = r_iofc.t().into_shape((1, 4 * s, s))?.to_owned();
         let b_iofc = b_iofc.into_shape((1, 8 * s))?;
 
-        let x =
-            model.add_source("x", InferenceFact::dt_shape(self.x.datum_type(), self.x.shape()))?;
+        let x = model.add_source("x", x_fact)?;
         let mut op = tract_onnx::ops::rec::lstm::LSTM::default();
+        op.direction = direction;
         op.optional_y_output = Some(0);
         op.optional_bias_input = Some(3);
         op.optional_initial_h_input = Some(4);
@@ -169,6 +181,72 @@ impl LstmProblem {
         Ok(model.into_typed()?)
     }
 
+    /// BlockLSTM with constant initial states, on a streaming input.
+    pub fn tf_streaming_model(&self) -> TractResult<TypedModel> {
+        let mut model = InferenceModel::default();
+        let x = model.add_source(
+            "x",
+            InferenceFact::dt_shape(
+                self.x.datum_type(),
+                tvec!(TDim::s(), self.batch_size.to_dim(), self.cell_size.to_dim()),
+            ),
+        )?;
+        let seq_length = model.add_const("seq_length", tensor0(i64::max_value()))?;
+        let cs = model.add_const("cs", self.c0.clone())?;
+        let h = model.add_const("h", self.h0.clone())?;
+        let w = model.add_const("w", self.w_xh_icfo.clone())?;
+        let wc = model.add_const("wc", tensor1(&[0f32]))?;
+        let b = model.add_const("b", self.b_icfo.clone())?;
+        let lstm = model.wire_node(
+            "lstm",
+            tract_tensorflow::ops::rec::block_lstm::BlockLSTM::new(
+                0.0,
+                -1.0,
+                f32::datum_type(),
+                false,
+            ),
+            &[seq_length, x, cs, h, w, wc, wc, wc, b],
+        )?;
+        model.set_output_outlets(&[lstm[6]])?;
+        model.analyse(false)?;
+        Ok(model.into_typed()?)
+    }
+
+    pub fn onnx_pulsed_run(&self, pulse: usize) -> TractResult<Arc<Tensor>> {
+        let x_fact = InferenceFact::dt_shape(
+            self.x.datum_type(),
+            tvec!(TDim::s(), self.batch_size.to_dim(), self.cell_size.to_dim()),
+        );
+        let y = self.pulsed_run(self.onnx_model_for_input(x_fact)?, pulse)?;
+        let y = y.into_array::<f32>()?;
+        let y = y.into_shape((self.length, self.batch_size, self.cell_size))?;
+        Ok(y.into_arc_tensor())
+    }
+
+    pub fn tf_pulsed_run(&self, pulse: usize) -> TractResult<Arc<Tensor>> {
+        Ok(self.pulsed_run(self.tf_streaming_model()?, pulse)?.into_arc_tensor())
+    }
+
+    fn pulsed_run(&self, model: TypedModel, pulse: usize) -> TractResult<Tensor> {
+        let pulsed = PulsedModel::new(&model.declutter()?.into_normalized()?, pulse)?;
+        let plan = SimplePlan::new(pulsed)?;
+        let mut session = tract_core::pulse::StreamingSession::new(&plan)?;
+        let x = self.x.to_array_view::<f32>()?;
+        let mut chunks = vec![];
+        let mut written = 0;
+        while written + pulse <= self.length {
+            let chunk = x.slice_axis(Axis(0), (written..written + pulse).into());
+            chunks.push(session.push(chunk.to_owned().into_tensor())?.remove(0));
+            written += pulse;
+        }
+        let last = x.slice_axis(Axis(0), (written..).into());
+        let last = ArrayD::from_shape_vec(last.shape(), last.iter().cloned().collect())?;
+        chunks.push(session.finish(last.into_tensor())?.remove(0));
+        let chunks: Vec<ArrayViewD<f32>> =
+            chunks.iter().map(|c| c.to_array_view::<f32>()).collect::<TractResult<_>>()?;
+        Ok(stack(Axis(0), &chunks)?.into_tensor())
+    }
+
     pub fn onnx_run(&self) -> TractResult<Arc<Tensor>> {
         let model = self.onnx_model()?;
         let plan = SimplePlan::new(model)?;
@@ -238,6 +316,53 @@ proptest::proptest! {
         let t = pb.tf_run().unwrap();
         prop_assert!(o.close_enough(&t, true).is_ok(), "\nonnx:{:?}\n tf :{:?}\n", o, t);
     }
+
+    #[test]
+    fn test_pulsed(pb in strat(), pulse in 1usize..3) {
+        let o = pb.onnx_run().unwrap();
+        let p = pb.onnx_pulsed_run(pulse).unwrap();
+        prop_assert!(o.close_enough(&p, true).is_ok(), "\nonnx:{:?}\npulsed:{:?}\n", o, p);
+        let t = pb.tf_pulsed_run(pulse).unwrap();
+        prop_assert!(o.close_enough(&t, true).is_ok(), "\nonnx:{:?}\ntf pulsed:{:?}\n", o, t);
+    }
+}
+
+#[test]
+fn test_reverse() {
+    use tract_onnx::ops::rec::Direction;
+    let pb = LstmProblem {
+        length: 3,
+        batch_size: 1,
+        cell_size: 1,
+        x: rctensor3(&[[[1f32]], [[-2.0]], [[3.0]]]),
+        w_xh_icfo: arr2(&[[1.0f32, -1.0, 0.5, 2.0], [0.5, 1.0, -1.0, 0.0]]),
+        b_icfo: arr1(&[0.0f32, 0.0, 0.0, 0.0]),
+        h0: arr2(&[[0.0f32]]),
+        c0: arr2(&[[0.0f32]]),
+    };
+    // a reverse layer is the forward one on the reversed sequence
+    let x = pb.x.to_array_view::<f32>().unwrap();
+    let reversed =
+        LstmProblem { x: x.slice(s![..;-1, .., ..]).to_owned().into_arc_tensor(), ..pb.clone() };
+    let expected = reversed.onnx_run().unwrap();
+    let expected =
+        expected.to_array_view::<f32>().unwrap().slice(s![..;-1, .., ..]).to_owned().into_dyn();
+    let x_fact = InferenceFact::dt_shape(f32::datum_type(), pb.x.shape());
+    let model = pb.onnx_model_with_direction(x_fact, Direction::Reverse).unwrap();
+    let y = SimplePlan::new(&model.into_optimized().unwrap())
+        .unwrap()
+        .run(tvec!(pb.x.clone().into_tensor()))
+        .unwrap()
+        .remove(0);
+    let y = y.into_tensor().into_array::<f32>().unwrap().into_shape(expected.shape()).unwrap();
+    assert_eq!(y, expected);
+    // but it needs the whole sequence
+    let x_fact =
+        InferenceFact::dt_shape(f32::datum_type(), tvec!(TDim::s(), 1.to_dim(), 1.to_dim()));
+    let model = pb.onnx_model_with_direction(x_fact, Direction::Reverse).unwrap();
+    let err =
+        PulsedModel::new(&model.declutter().unwrap().into_normalized().unwrap(), 1).unwrap_err();
+    assert!(err.iter().any(|e| e.to_string().contains("Reverse")), "{:?}", err);
 }
 
 #[test]
@@ -1,5 +1,8 @@
 use std::{fs, path};
 
+/// Onnx releases whose backend test data we run, with their operator set.
+const RELEASES: &[(&str, i64)] = &[("1.4.1", 9), ("1.5.0", 10), ("1.6.0", 11), ("1.8.0", 13)];
+
 pub fn dir() -> path::PathBuf {
     let cache = ::std::env::var("CACHEDIR").ok().unwrap_or("../../.cached".to_string());
     fs::create_dir_all(&cache).unwrap();
@@ -14,7 +17,7 @@ pub fn ensure_onnx_git_checkout() {
         fs::create_dir_all(dir()).unwrap();
         let lockfile = dir().join(".lock");
         let _lock = fs::File::create(lockfile).unwrap().lock_exclusive();
-        for v in &["1.4.1", "1.5.0"] {
+        for (v, _) in RELEASES {
             let wanted = dir().join(format!("onnx-{}", v));
             if !wanted.join("onnx/backend/test/data").exists() {
                 let tmp = wanted.with_extension("tmp");
@@ -46,14 +49,14 @@ pub fn ensure_onnx_git_checkout() {
     });
 }
 
-pub fn make_test_file(root: &mut fs::File, tests_set: &str, onnx_tag: &str) {
+pub fn make_test_file(root: &mut fs::File, tests_set: &str, onnx_tag: &str, opset: i64) {
     use std::io::Write;
     ensure_onnx_git_checkout();
     let node_tests =
         dir().join(format!("onnx-{}", onnx_tag)).join("onnx/backend/test/data").join(tests_set);
     assert!(node_tests.exists());
     let working_list_file =
-        path::PathBuf::from(".").join(format!("{}-{}.txt", tests_set, onnx_tag));
+        path::PathBuf::from(".").join(format!("{}-opset{}.txt", tests_set, opset));
     println!("cargo:rerun-if-changed={}", working_list_file.to_str().unwrap());
     let working_list: Vec<(String, bool)> = fs::read_to_string(&working_list_file)
         .unwrap()
@@ -68,7 +71,7 @@ pub fn make_test_file(root: &mut fs::File, tests_set: &str, onnx_tag: &str) {
     let out_dir = std::env::var("OUT_DIR").unwrap();
     let out_dir = path::PathBuf::from(out_dir);
     let test_dir = out_dir.join("tests");
-    let tests_set_ver = format!("{}_{}", tests_set.replace("-", "_"), onnx_tag.replace(".", "_"));
+    let tests_set_ver = format!("{}_opset{}", tests_set.replace("-", "_"), opset);
 
     writeln!(root, "include!(concat!(env!(\"OUT_DIR\"), \"/tests/{}.rs\"));", tests_set_ver)
         .unwrap();
@@ -91,7 +94,8 @@ pub fn make_test_file(root: &mut fs::File, tests_set: &str, onnx_tag: &str) {
                 writeln!(rs, "#[ignore]").unwrap();
             }
             writeln!(rs, "fn {}() {{", t).unwrap();
-            writeln!(rs, "crate::onnx::run_one({:?}, {:?}, {:?})", node_tests, t, optim).unwrap();
+            writeln!(rs, "crate::onnx::run_one({:?}, {:?}, {}, {:?})", node_tests, t, opset, optim)
+                .unwrap();
             writeln!(rs, "}}").unwrap();
         }
         writeln!(rs, "}}").unwrap();
@@ -107,8 +111,8 @@ fn main() {
     fs::create_dir_all(&test_dir).unwrap();
     let mut root = fs::File::create(test_dir.join("root.rs")).unwrap();
     for set in "node real simple pytorch-operator pytorch-converted".split_whitespace() {
-        for ver in "1.4.1 1.5.0".split_whitespace() {
-            make_test_file(&mut root, set, ver);
+        for (ver, opset) in RELEASES {
+            make_test_file(&mut root, set, ver, *opset);
         }
     }
 }
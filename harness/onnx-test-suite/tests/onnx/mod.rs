@@ -34,7 +34,7 @@ pub fn load_dataset(path: &path::Path) -> (TVec<Tensor>, TVec<Tensor>) {
     (load_half_dataset("input", path), load_half_dataset("output", path))
 }
 
-pub fn run_one<P: AsRef<path::Path>>(root: P, test: &str, optim: bool) {
+pub fn run_one<P: AsRef<path::Path>>(root: P, test: &str, opset: i64, optim: bool) {
     setup_test_logger();
     let test_path = root.as_ref().join(test);
     let path = if test_path.join("data.json").exists() {
@@ -78,7 +78,10 @@ pub fn run_one<P: AsRef<path::Path>>(root: P, test: &str, optim: bool) {
     let model_file = path.join("model.onnx");
     info!("Loading {:?}", model_file);
     let onnx = onnx();
-    trace!("Proto Model:\n{:#?}", onnx.proto_model_for_path(&model_file));
+    let proto = onnx.proto_model_for_path(&model_file).unwrap();
+    trace!("Proto Model:\n{:#?}", proto);
+    let model_opset = proto.opset_import.iter().find(|i| i.domain == "").map(|i| i.version);
+    info!("Model operator set: {:?} (pass list for operator set {})", model_opset, opset);
     let mut model = onnx.model_for_path(&model_file).unwrap();
     info!("Analyse");
     trace!("Model:\n{:#?}", model);
@@ -102,4 +102,79 @@ mod tests {
         let label = &labels[label_id];
         assert_eq!(label, "military uniform");
     }
+
+    // compiles the generated code in a scratch crate, feeding the input on
+    // stdin and reading the output on stdout
+    fn run_aot(model: &NormalizedModel, input: &Tensor) -> Vec<f32> {
+        use std::io::Write;
+        use std::process::{Command, Stdio};
+        let root = path::PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../..");
+        let dir = root.join("target/aot/mobilenet_v2");
+        fs::create_dir_all(dir.join("src")).unwrap();
+        let manifest = format!(
+            "[package]\nname = \"aot-mobilenet-v2\"\nversion = \"0.0.0\"\nedition = \"2018\"\n\n\
+             [dependencies]\ntract-linalg = {{ path = {:?} }}\n\n[workspace]\n",
+            root.join("linalg")
+        );
+        fs::write(dir.join("Cargo.toml"), manifest).unwrap();
+        fs::copy(root.join("Cargo.lock"), dir.join("Cargo.lock")).unwrap();
+        let code = tract_core::aot::generate(model).unwrap();
+        fs::write(dir.join("src/model.rs"), code).unwrap();
+        let main = "mod model;
+use std::io::{Read, Write};
+fn main() {
+    let mut bytes = vec![];
+    std::io::stdin().read_to_end(&mut bytes).unwrap();
+    let input: Vec<f32> = bytes
+        .chunks(4)
+        .map(|b| f32::from_bits(u32::from_le_bytes([b[0], b[1], b[2], b[3]])))
+        .collect();
+    let mut output = vec![0.0; model::OUTPUT_SHAPES[0].iter().product()];
+    unsafe { model::run(&input, &mut output) };
+    for v in output {
+        std::io::stdout().write_all(&v.to_bits().to_le_bytes()).unwrap();
+    }
+}
+";
+        fs::write(dir.join("src/main.rs"), main).unwrap();
+        let cargo = ::std::env::var("CARGO").unwrap_or("cargo".to_string());
+        let build = Command::new(cargo)
+            .args(&["build", "--offline", "--release"])
+            .current_dir(&dir)
+            .env("CARGO_TARGET_DIR", root.join("target/aot/target"))
+            .output()
+            .unwrap();
+        assert!(build.status.success(), "{}", String::from_utf8_lossy(&build.stderr));
+        let mut child = Command::new(root.join("target/aot/target/release/aot-mobilenet-v2"))
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap();
+        for v in input.as_slice::<f32>().unwrap() {
+            child.stdin.as_mut().unwrap().write_all(&v.to_bits().to_le_bytes()).unwrap();
+        }
+        let output = child.wait_with_output().unwrap();
+        assert!(output.status.success());
+        output
+            .stdout
+            .chunks(4)
+            .map(|b| f32::from_bits(u32::from_le_bytes([b[0], b[1], b[2], b[3]])))
+            .collect()
+    }
+
+    #[test]
+    fn aot() {
+        let mut tfd = tract_tensorflow::tensorflow().model_for_path(mobilenet_v2()).unwrap();
+        tfd.set_input_fact(0, InferenceFact::dt_shape(f32::datum_type(), &[1, 224, 224, 3]))
+            .unwrap();
+        let tfd = tfd.into_normalized().unwrap();
+        let input = load_image(grace_hopper());
+        let expected = SimplePlan::new(&tfd).unwrap().run(tvec![input.clone()]).unwrap();
+        let found = run_aot(&tfd, &input);
+        let found = Tensor::from(ArrayD::from_shape_vec(expected[0].shape(), found).unwrap());
+        found.close_enough(&expected[0], true).unwrap();
+        let labels = load_labels();
+        let label_id = argmax(found.to_array_view::<f32>().unwrap());
+        assert_eq!(labels[label_id], "military uniform");
+    }
 }
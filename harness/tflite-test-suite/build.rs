@@ -0,0 +1,81 @@
+use std::{fs, path};
+
+pub fn dir() -> path::PathBuf {
+    let cache = ::std::env::var("CACHEDIR").ok().unwrap_or("../../.cached".to_string());
+    fs::create_dir_all(&cache).unwrap();
+    path::PathBuf::from(cache).join("tflite")
+}
+
+/// Generate the test cases with TensorFlow (see generate.py), once.
+pub fn ensure_cases() {
+    use fs2::FileExt;
+    fs::create_dir_all(dir()).unwrap();
+    let lockfile = dir().join(".lock");
+    let _lock = fs::File::create(lockfile).unwrap().lock_exclusive();
+    let wanted = dir().join("cases");
+    if !wanted.exists() {
+        let tmp = wanted.with_extension("tmp");
+        let _ = fs::remove_dir_all(&tmp);
+        fs::create_dir_all(&tmp).unwrap();
+        let run =
+            std::process::Command::new("python3").arg("generate.py").arg(&tmp).status().unwrap();
+        if !run.success() {
+            panic!("Failed to generate tflite test cases (requires python3 with tensorflow)")
+        }
+        fs::rename(tmp, wanted).unwrap();
+    }
+    println!("tflite test cases done");
+}
+
+pub fn make_test_file(root: &mut fs::File) {
+    use std::io::Write;
+    let cases = dir().join("cases");
+    let working_list_file = path::PathBuf::from(".").join("cases.txt");
+    println!("cargo:rerun-if-changed={}", working_list_file.to_str().unwrap());
+    println!("cargo:rerun-if-changed=generate.py");
+    let working_list: Vec<(String, bool)> = fs::read_to_string(&working_list_file)
+        .unwrap()
+        .split("\n")
+        .map(|s| s.to_string())
+        .filter(|s| s.trim().len() > 1 && s.trim().as_bytes()[0] != b'#')
+        .map(|s| {
+            let splits = s.split_whitespace().collect::<Vec<_>>();
+            (splits[0].to_string(), splits.len() == 1)
+        })
+        .collect();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    let test_dir = path::PathBuf::from(out_dir).join("tests");
+
+    writeln!(root, "include!(concat!(env!(\"OUT_DIR\"), \"/tests/cases.rs\"));").unwrap();
+
+    let mut rs = fs::File::create(test_dir.join("cases.rs")).unwrap();
+    let mut tests: Vec<String> = fs::read_dir(&cases)
+        .unwrap()
+        .map(|de| de.unwrap().file_name().to_str().unwrap().to_owned())
+        .collect();
+    tests.sort();
+    for (s, optim) in &[("plain", false), ("optim", true)] {
+        writeln!(rs, "mod {} {{", s).unwrap();
+        for t in &tests {
+            writeln!(rs, "#[test]").unwrap();
+            let pair = working_list.iter().find(|pair| &*pair.0 == &*t);
+            let run = pair.is_some() && (pair.unwrap().1 || !optim);
+            if !run {
+                writeln!(rs, "#[ignore]").unwrap();
+            }
+            writeln!(rs, "fn {}() {{", t).unwrap();
+            writeln!(rs, "crate::tflite::run_one({:?}, {:?}, {:?})", cases, t, optim).unwrap();
+            writeln!(rs, "}}").unwrap();
+        }
+        writeln!(rs, "}}").unwrap();
+    }
+}
+
+fn main() {
+    ensure_cases();
+    let out_dir = std::env::var("OUT_DIR").unwrap();
+    let test_dir = path::PathBuf::from(out_dir).join("tests");
+    fs::create_dir_all(&test_dir).unwrap();
+    let mut root = fs::File::create(test_dir.join("root.rs")).unwrap();
+    make_test_file(&mut root);
+}
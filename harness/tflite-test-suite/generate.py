@@ -0,0 +1,140 @@
+#!/usr/bin/env python3
+"""Generate the TFLite test cases.
+
+Each case is a small TensorFlow function converted to TFLite, in float and,
+for most of them, in full integer quantization (uint8 and int8 inputs and
+outputs). The TFLite interpreter runs the model on a random input, and
+everything goes to <output dir>/<case>/:
+
+    model.tflite
+    input_0.npy ... (inputs of the model)
+    output_0.npy ... (outputs computed by the TFLite interpreter)
+"""
+
+import os
+import sys
+
+import numpy as np
+import tensorflow as tf
+
+rng = np.random.RandomState(0)
+
+
+def conv(filters, kernel, **kwargs):
+    layer = tf.keras.layers.Conv2D(filters, kernel, **kwargs)
+    return lambda x: layer(x)
+
+
+def depthwise(kernel, **kwargs):
+    layer = tf.keras.layers.DepthwiseConv2D(kernel, **kwargs)
+    return lambda x: layer(x)
+
+
+def dense(units, **kwargs):
+    layer = tf.keras.layers.Dense(units, **kwargs)
+    return lambda x: layer(x)
+
+
+def lstm(units, **kwargs):
+    layer = tf.keras.layers.LSTM(units, return_sequences=True, **kwargs)
+    return lambda x: layer(x)
+
+
+W = tf.constant(rng.uniform(-1, 1, (1, 5, 5, 3)).astype(np.float32))
+
+# name, function, input shapes, quantizable
+CASES = [
+    ("abs", tf.abs, [[2, 3]], False),
+    ("add", lambda x: x + W, [[1, 5, 5, 3]], True),
+    ("add_relu", lambda x: tf.nn.relu(x + W), [[1, 5, 5, 3]], True),
+    ("avg_pool_valid", lambda x: tf.nn.avg_pool2d(x, 2, 2, "VALID"), [[1, 8, 8, 3]], True),
+    ("avg_pool_same", lambda x: tf.nn.avg_pool2d(x, 3, 2, "SAME"), [[1, 7, 7, 3]], True),
+    ("concat", lambda x: tf.concat([x, x * 2], axis=3), [[1, 5, 5, 3]], True),
+    ("conv_valid", conv(4, 3), [[1, 8, 8, 3]], True),
+    ("conv_same", conv(4, 3, padding="same"), [[1, 8, 8, 3]], True),
+    ("conv_strides", conv(4, 3, strides=2, padding="same"), [[1, 9, 9, 3]], True),
+    ("conv_dilations", conv(4, 3, dilation_rate=2), [[1, 9, 9, 3]], True),
+    ("conv_relu6", conv(4, 1, activation=tf.nn.relu6), [[1, 5, 5, 3]], True),
+    ("depthwise", depthwise(3), [[1, 8, 8, 3]], True),
+    ("depthwise_multiplier", depthwise(3, depth_multiplier=2), [[1, 8, 8, 3]], True),
+    ("depthwise_same_relu", depthwise(3, padding="same", activation="relu"), [[1, 8, 8, 3]], True),
+    ("exp", tf.exp, [[2, 3]], False),
+    ("fully_connected", dense(5), [[2, 7]], True),
+    ("fully_connected_relu", dense(5, activation="relu"), [[2, 7]], True),
+    ("gather", lambda x: tf.gather(x, [2, 0, 1], axis=1), [[2, 3, 4]], False),
+    ("leaky_relu", lambda x: tf.nn.leaky_relu(x, 0.1), [[2, 3]], False),
+    ("log_softmax", tf.nn.log_softmax, [[2, 5]], False),
+    ("logistic", tf.sigmoid, [[2, 5]], True),
+    ("lstm", lstm(4), [[1, 6, 3]], False),
+    ("max_pool_valid", lambda x: tf.nn.max_pool2d(x, 2, 2, "VALID"), [[1, 8, 8, 3]], True),
+    ("max_pool_same", lambda x: tf.nn.max_pool2d(x, 3, 2, "SAME"), [[1, 7, 7, 3]], True),
+    ("maximum", lambda x: tf.maximum(x, W), [[1, 5, 5, 3]], False),
+    ("mean", lambda x: tf.reduce_mean(x, axis=[1, 2]), [[1, 5, 5, 3]], True),
+    ("mul", lambda x: x * W, [[1, 5, 5, 3]], True),
+    ("pad", lambda x: tf.pad(x, [[0, 0], [1, 2], [2, 1], [0, 0]]), [[1, 5, 5, 3]], True),
+    ("reduce_max", lambda x: tf.reduce_max(x, axis=1, keepdims=True), [[2, 5, 3]], False),
+    ("relu", tf.nn.relu, [[2, 5]], False),
+    ("reshape", lambda x: tf.reshape(x, [1, 25, 3]), [[1, 5, 5, 3]], True),
+    ("rsqrt", lambda x: tf.math.rsqrt(tf.abs(x) + 1), [[2, 5]], False),
+    ("softmax", tf.nn.softmax, [[2, 5]], True),
+    ("split", lambda x: tf.split(x, 3, axis=1), [[2, 6]], False),
+    ("squeeze", lambda x: tf.squeeze(x, axis=[0]), [[1, 5, 3]], False),
+    ("strided_slice", lambda x: x[:, 1:4, ::2], [[2, 5, 6]], False),
+    ("sub", lambda x: x - W, [[1, 5, 5, 3]], True),
+    ("tanh", tf.tanh, [[2, 5]], True),
+    ("tile", lambda x: tf.tile(x, [2, 1, 3]), [[1, 2, 3]], False),
+    ("transpose", lambda x: tf.transpose(x, [0, 2, 1]), [[2, 3, 4]], True),
+]
+
+
+def convert(fn, shapes, dt):
+    specs = [tf.TensorSpec(shape, tf.float32) for shape in shapes]
+    concrete = tf.function(fn).get_concrete_function(*specs)
+    converter = tf.lite.TFLiteConverter.from_concrete_functions([concrete])
+    if dt is not None:
+        def representative():
+            for _ in range(20):
+                yield [rng.uniform(-1, 1, shape).astype(np.float32) for shape in shapes]
+        converter.optimizations = [tf.lite.Optimize.DEFAULT]
+        converter.representative_dataset = representative
+        converter.target_spec.supported_ops = [tf.lite.OpsSet.TFLITE_BUILTINS_INT8]
+        converter.inference_input_type = dt
+        converter.inference_output_type = dt
+    return converter.convert()
+
+
+def random_input(detail):
+    dt = detail["dtype"]
+    if dt == np.float32:
+        return rng.uniform(-1, 1, detail["shape"]).astype(np.float32)
+    info = np.iinfo(dt)
+    return rng.randint(info.min, info.max + 1, detail["shape"]).astype(dt)
+
+
+def generate(root, name, model):
+    directory = os.path.join(root, name)
+    os.makedirs(directory, exist_ok=True)
+    with open(os.path.join(directory, "model.tflite"), "wb") as f:
+        f.write(model)
+    interpreter = tf.lite.Interpreter(model_content=model)
+    interpreter.allocate_tensors()
+    for ix, detail in enumerate(interpreter.get_input_details()):
+        value = random_input(detail)
+        np.save(os.path.join(directory, "input_%d.npy" % ix), value)
+        interpreter.set_tensor(detail["index"], value)
+    interpreter.invoke()
+    for ix, detail in enumerate(interpreter.get_output_details()):
+        value = interpreter.get_tensor(detail["index"])
+        np.save(os.path.join(directory, "output_%d.npy" % ix), value)
+
+
+def main(root):
+    for (name, fn, shapes, quantizable) in CASES:
+        generate(root, name, convert(fn, shapes, None))
+        if quantizable:
+            generate(root, name + "_u8", convert(fn, shapes, tf.uint8))
+            generate(root, name + "_i8", convert(fn, shapes, tf.int8))
+
+
+if __name__ == "__main__":
+    main(sys.argv[1])
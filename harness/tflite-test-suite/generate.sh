@@ -0,0 +1,16 @@
+#!/bin/sh
+
+set -ex
+
+if [ -z "$CACHEDIR" ]
+then
+    CACHEDIR=`dirname $0`/../../.cached
+fi
+
+if [ ! -e $CACHEDIR/tflite/cases ]
+then
+    rm -rf $CACHEDIR/tflite/cases.tmp
+    mkdir -p $CACHEDIR/tflite/cases.tmp
+    python3 `dirname $0`/generate.py $CACHEDIR/tflite/cases.tmp
+    mv $CACHEDIR/tflite/cases.tmp $CACHEDIR/tflite/cases
+fi
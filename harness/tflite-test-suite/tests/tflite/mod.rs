@@ -0,0 +1,144 @@
+use std::{fs, path};
+
+use log::*;
+
+use tract_core::error_chain::bail;
+use tract_core::internal::*;
+use tract_tflite::*;
+
+#[allow(dead_code)]
+fn setup_test_logger() {
+    let _ = env_logger::Builder::from_env("TRACT_LOG").try_init();
+}
+
+/// Read a little endian, C ordered, .npy file.
+pub fn load_npy(path: &path::Path) -> Tensor {
+    let data = fs::read(path).unwrap();
+    assert_eq!(&data[..6], b"\x93NUMPY", "{:?} is not a npy file", path);
+    let (header_len, header_start) = if data[6] == 1 {
+        (u16::from_le_bytes([data[8], data[9]]) as usize, 10)
+    } else {
+        (u32::from_le_bytes([data[8], data[9], data[10], data[11]]) as usize, 12)
+    };
+    let header = std::str::from_utf8(&data[header_start..][..header_len]).unwrap();
+    let field = |name: &str| {
+        let start = header.find(&format!("'{}':", name)).unwrap() + name.len() + 3;
+        header[start..].trim_start()
+    };
+    assert!(field("fortran_order").starts_with("False"));
+    let descr = field("descr");
+    let descr = &descr[1..][..descr[1..].find('\'').unwrap()];
+    let dt = match descr {
+        "<f4" => f32::datum_type(),
+        "|u1" => u8::datum_type(),
+        "|i1" => i8::datum_type(),
+        "<i4" => i32::datum_type(),
+        "<i8" => i64::datum_type(),
+        "|b1" => bool::datum_type(),
+        _ => panic!("Unsupported npy type {}", descr),
+    };
+    let shape = field("shape");
+    let shape: Vec<usize> = shape[1..shape.find(')').unwrap()]
+        .split(',')
+        .map(|d| d.trim())
+        .filter(|d| d.len() > 0)
+        .map(|d| d.parse().unwrap())
+        .collect();
+    unsafe { Tensor::from_raw_dt(dt, &shape, &data[header_start + header_len..]).unwrap() }
+}
+
+pub fn load_half_dataset(prefix: &str, path: &path::Path) -> TVec<Tensor> {
+    let mut vec = tvec!();
+    let len = fs::read_dir(path)
+        .map_err(|e| format!("accessing {:?}, {:?}", path, e))
+        .unwrap()
+        .filter(|d| d.as_ref().unwrap().file_name().to_str().unwrap().starts_with(prefix))
+        .count();
+    for i in 0..len {
+        vec.push(load_npy(&path.join(format!("{}_{}.npy", prefix, i))))
+    }
+    debug!("{:?}: {:?}", path, vec);
+    vec
+}
+
+pub fn run_one<P: AsRef<path::Path>>(root: P, test: &str, optim: bool) {
+    setup_test_logger();
+    let path = root.as_ref().join(test);
+    let model_file = path.join("model.tflite");
+    info!("Loading {:?}", model_file);
+    let mut model = tflite().model_for_path(&model_file).unwrap();
+    info!("Analyse");
+    trace!("Model:\n{:#?}", model);
+    model.analyse(false).unwrap();
+    info!("Incorporate");
+    let model = model.incorporate().unwrap();
+    info!("Test model (optim: {:?}) {:#?}", optim, path);
+    if optim {
+        info!("Check full inference");
+        if model.missing_type_shape().unwrap().len() != 0 {
+            panic!("Incomplete inference {:?}", model.missing_type_shape());
+        }
+        info!("Into type");
+        let model = model.into_typed().unwrap();
+        let optimized = model.into_optimized().unwrap();
+        trace!("Run optimized model:\n{:#?}", optimized);
+        run_model(optimized, &path)
+    } else {
+        trace!("Run analysed model:\n{:#?}", model);
+        run_model(model, &path)
+    };
+}
+
+/// Quantized outputs may be one step away from TFLite ones, as tract
+/// computes some quantized operators in float.
+fn check(computed: &Tensor, expected: &Tensor) -> TractResult<()> {
+    if expected.datum_type() == u8::datum_type() || expected.datum_type() == i8::datum_type() {
+        if computed.shape() != expected.shape() {
+            bail!("Shape mismatch {:?} != {:?}", computed.shape(), expected.shape())
+        }
+        let a = computed.cast_to::<i32>()?;
+        let b = expected.cast_to::<i32>()?;
+        for (a, b) in a.as_slice::<i32>()?.iter().zip(b.as_slice::<i32>()?.iter()) {
+            if (a - b).abs() > 1 {
+                bail!("Mismatch {} != {}", a, b)
+            }
+        }
+        Ok(())
+    } else {
+        computed.close_enough(expected, true)
+    }
+}
+
+fn run_model<TI, O>(model: ModelImpl<TI, O>, path: &path::Path)
+where
+    TI: Fact + Clone + 'static,
+    O: std::fmt::Debug + std::fmt::Display + AsRef<dyn Op> + AsMut<dyn Op> + Clone + 'static,
+{
+    let plan = SimplePlan::new(&model).unwrap();
+    let inputs = load_half_dataset("input", path);
+    let expected = load_half_dataset("output", path);
+    trace!("Loaded inputs: {:?}", inputs);
+    trace!("Loaded output asserts: {:?}", expected);
+    let computed = plan.run(inputs).unwrap();
+    if computed.len() != expected.len() {
+        panic!(
+            "For {:?}, different number of results: got:{} expected:{}",
+            path,
+            computed.len(),
+            expected.len()
+        );
+    }
+    for (ix, (a, b)) in computed.iter().zip(expected.iter()).enumerate() {
+        use tract_core::error_chain::ChainedError;
+        if let Err(e) = check(a, b) {
+            panic!(
+                "For {:?}, different result for output #{}:\ngot:\n{:?}\nexpected:\n{:?}\n{}",
+                path,
+                ix,
+                a.cast_to::<f32>().unwrap().to_array_view::<f32>().unwrap(),
+                b.cast_to::<f32>().unwrap().to_array_view::<f32>().unwrap(),
+                e.display_chain()
+            )
+        }
+    }
+}
@@ -0,0 +1,5 @@
+#![allow(non_snake_case)]
+
+mod tflite;
+
+include!(concat!(env!("OUT_DIR"), "/tests/root.rs"));
@@ -13,6 +13,7 @@ pub mod parser;
 
 pub use model::Kaldi;
 pub use model::KaldiProtoModel;
+pub use parser::KaldiAttributeKind;
 
 pub fn kaldi() -> Kaldi {
     let mut kaldi = Kaldi::default();
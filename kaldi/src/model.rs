@@ -2,21 +2,119 @@ use tract_core::internal::*;
 
 use std::collections::BTreeMap;
 
+use tract_core::ndarray::Array2;
+
+use crate::parser::KaldiAttributeKind;
+
 #[derive(Clone, Debug)]
 pub struct KaldiProtoModel {
     pub config_lines: ConfigLines,
     pub components: HashMap<String, Component>,
     pub adjust_final_offset: isize,
+    /// Transition model of an acoustic model (.mdl) file.
+    pub transition_model: Option<TransitionModel>,
+    /// Pdf priors of an acoustic model (.mdl) file.
+    pub priors: Option<Arc<Tensor>>,
+    /// Subtract the log priors from the "output" node, making it a
+    /// pseudo-log-likelihood.
+    pub subtract_priors: bool,
+    /// Rate of the input nodes, by name. Inputs default to `InputRate::Frames`.
+    pub input_rates: HashMap<String, InputRate>,
+}
+
+/// How an input node relates to the frames of the network.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum InputRate {
+    /// One frame per time step, like the acoustic features.
+    Frames,
+    /// A single frame for the whole utterance, like an offline i-vector.
+    Constant,
+    /// One frame every `period` time steps, like an online i-vector. The
+    /// frame applies until the next one, and `ReplaceIndex(_, t, _)` picks
+    /// the one of the current time.
+    Subsampled(usize),
+}
+
+/// HMM topology of the phones.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct HmmTopology {
+    pub phones: Vec<i32>,
+    /// Entry index of each phone, or -1.
+    pub phone2idx: Vec<i32>,
+    pub entries: Vec<Vec<HmmState>>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct HmmState {
+    /// Pdf class, or -1 for a final state.
+    pub forward_pdf_class: i32,
+    pub self_loop_pdf_class: i32,
+    /// Destination state and probability.
+    pub transitions: Vec<(i32, f32)>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransitionTuple {
+    pub phone: i32,
+    pub hmm_state: i32,
+    pub forward_pdf: i32,
+    pub self_loop_pdf: i32,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct TransitionModel {
+    pub topology: HmmTopology,
+    /// Transition states, numbered from 1.
+    pub tuples: Vec<TransitionTuple>,
+    /// Log probability of each transition id (the first one is unused).
+    pub log_probs: Vec<f32>,
+}
+
+impl TransitionModel {
+    pub fn num_pdfs(&self) -> usize {
+        self.tuples.iter().map(|t| t.forward_pdf.max(t.self_loop_pdf) + 1).max().unwrap_or(0)
+            as usize
+    }
+
+    /// Pdf id of each transition id. Transition ids start at 1, so the first
+    /// item is -1.
+    pub fn transition_id_to_pdf(&self) -> TractResult<Vec<i32>> {
+        let mut pdfs = vec![-1];
+        for tuple in &self.tuples {
+            let entry = self
+                .topology
+                .phone2idx
+                .get(tuple.phone as usize)
+                .and_then(|&ix| self.topology.entries.get(ix as usize))
+                .ok_or_else(|| format!("No topology for phone {}", tuple.phone))?;
+            let state = entry
+                .get(tuple.hmm_state as usize)
+                .ok_or_else(|| format!("No state {} for phone {}", tuple.hmm_state, tuple.phone))?;
+            for &(dest, _) in &state.transitions {
+                pdfs.push(if dest == tuple.hmm_state {
+                    tuple.self_loop_pdf
+                } else {
+                    tuple.forward_pdf
+                });
+            }
+        }
+        Ok(pdfs)
+    }
 }
 
 #[derive(Clone, Debug)]
 pub struct ConfigLines {
-    pub input_name: String,
-    pub input_dim: usize,
+    pub input_nodes: Vec<InputNode>,
     pub nodes: Vec<(String, NodeLine)>,
     pub outputs: Vec<OutputLine>,
 }
 
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputNode {
+    pub name: String,
+    pub dim: usize,
+}
+
 #[derive(Clone, Debug)]
 pub enum NodeLine {
     Component(ComponentNode),
@@ -32,28 +130,70 @@ pub struct OutputLine {
 #[derive(Clone, Debug, PartialEq)]
 pub enum GeneralDescriptor {
     Append(Vec<GeneralDescriptor>),
+    Const(f32, usize),
+    Failover(Box<GeneralDescriptor>, Box<GeneralDescriptor>),
     IfDefined(Box<GeneralDescriptor>),
     Name(String),
     Offset(Box<GeneralDescriptor>, isize),
+    ReplaceIndex(Box<GeneralDescriptor>, char, isize),
+    Round(Box<GeneralDescriptor>, usize),
+    Scale(f32, Box<GeneralDescriptor>),
+    Sum(Box<GeneralDescriptor>, Box<GeneralDescriptor>),
+    Switch(Vec<GeneralDescriptor>),
+}
+
+/// Frames a descriptor (or a node) produces, relatively to the input frames.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Frames {
+    /// `Span(left, right)`: frame `i` is at time `i + left`, and the last
+    /// frame is `right` frames before the last input frame.
+    Span(isize, isize),
+    /// A single frame, valid at all times (Const, ReplaceIndex).
+    Invariant,
+    /// A recurrent input, following the frames it is combined with.
+    Recurrent,
+}
+
+impl Frames {
+    /// Frames of a combination of descriptors: the span where all of them
+    /// are defined.
+    fn combine(frames: impl IntoIterator<Item = Frames>) -> Frames {
+        frames.into_iter().fold(Frames::Invariant, |acc, f| match (acc, f) {
+            (Frames::Span(l1, r1), Frames::Span(l2, r2)) => Frames::Span(l1.max(l2), r1.max(r2)),
+            (Frames::Span(l, r), _) | (_, Frames::Span(l, r)) => Frames::Span(l, r),
+            (Frames::Recurrent, _) | (_, Frames::Recurrent) => Frames::Recurrent,
+            _ => Frames::Invariant,
+        })
+    }
 }
 
 impl GeneralDescriptor {
     pub fn inputs(&self) -> TVec<&str> {
+        use GeneralDescriptor::*;
         match self {
-            GeneralDescriptor::Append(ref gds) => gds.iter().fold(tvec!(), |mut acc, gd| {
-                gd.inputs().iter().for_each(|i| {
-                    if !acc.contains(i) {
-                        acc.push(i)
-                    }
-                });
-                acc
-            }),
-            GeneralDescriptor::IfDefined(ref gd) => gd.inputs(),
-            GeneralDescriptor::Name(ref s) => tvec!(&**s),
-            GeneralDescriptor::Offset(ref gd, _) => gd.inputs(),
+            Append(ref gds) | Switch(ref gds) => Self::union(gds.iter()),
+            Failover(ref a, ref b) | Sum(ref a, ref b) => Self::union(vec![&**a, &**b]),
+            Const(..) => tvec!(),
+            IfDefined(ref gd)
+            | Offset(ref gd, _)
+            | ReplaceIndex(ref gd, ..)
+            | Round(ref gd, _)
+            | Scale(_, ref gd) => gd.inputs(),
+            Name(ref s) => tvec!(&**s),
         }
     }
 
+    fn union<'a>(gds: impl IntoIterator<Item = &'a GeneralDescriptor>) -> TVec<&'a str> {
+        gds.into_iter().fold(tvec!(), |mut acc, gd| {
+            gd.inputs().iter().for_each(|i| {
+                if !acc.contains(i) {
+                    acc.push(i)
+                }
+            });
+            acc
+        })
+    }
+
     pub fn as_conv_shape_dilation(&self) -> Option<(usize, usize)> {
         if let GeneralDescriptor::Name(_) = self {
             return Some((1, 1));
@@ -75,15 +215,151 @@ impl GeneralDescriptor {
         return None;
     }
 
+    /// Recurrent input, made of a memory of another node output.
+    fn as_memory(&self) -> Option<(&str, isize)> {
+        if let GeneralDescriptor::IfDefined(ref o) = self {
+            if let GeneralDescriptor::Offset(ref n, o) = &**o {
+                if let GeneralDescriptor::Name(n) = &**n {
+                    return Some((n, *o));
+                }
+            }
+        }
+        None
+    }
+
+    /// Primary descriptor of a Failover, considered undefined outside of the
+    /// frames of its input, even under `IfDefined`.
+    fn failover_primary(&self, known: &HashMap<String, Frames>) -> &GeneralDescriptor {
+        if let GeneralDescriptor::IfDefined(ref inner) = self {
+            if let Ok(Frames::Span(..)) = inner.frames(known, None) {
+                return inner;
+            }
+        }
+        self
+    }
+
+    /// Replace `ReplaceIndex(name, t, _)` by `name` for the given inputs.
+    fn follow_time(&self, inputs: &[&str]) -> GeneralDescriptor {
+        use GeneralDescriptor::*;
+        let follow = |gd: &GeneralDescriptor| Box::new(gd.follow_time(inputs));
+        match self {
+            Append(gds) => Append(gds.iter().map(|gd| gd.follow_time(inputs)).collect()),
+            Switch(gds) => Switch(gds.iter().map(|gd| gd.follow_time(inputs)).collect()),
+            Failover(a, b) => Failover(follow(a), follow(b)),
+            Sum(a, b) => Sum(follow(a), follow(b)),
+            IfDefined(gd) => IfDefined(follow(gd)),
+            Offset(gd, o) => Offset(follow(gd), *o),
+            ReplaceIndex(gd, index, value) => match &**gd {
+                Name(n) if *index == 't' && inputs.contains(&&**n) => Name(n.clone()),
+                _ => ReplaceIndex(follow(gd), *index, *value),
+            },
+            Round(gd, m) => Round(follow(gd), *m),
+            Scale(scale, gd) => Scale(*scale, follow(gd)),
+            Const(..) | Name(_) => self.clone(),
+        }
+    }
+
+    /// Frames produced by the descriptor, given the frames of the nodes it
+    /// refers to. Unknown nodes are assumed recurrent.
+    ///
+    /// Positive offsets crop the beginning of the input (plus
+    /// `adjust_final_offset` for outputs), negative offsets only shift time.
+    pub fn frames(
+        &self,
+        known: &HashMap<String, Frames>,
+        adjust_final_offset: Option<isize>,
+    ) -> TractResult<Frames> {
+        use GeneralDescriptor::*;
+        let frames = |gd: &GeneralDescriptor| gd.frames(known, adjust_final_offset);
+        let it = match self {
+            Append(gds) | Switch(gds) => {
+                Frames::combine(gds.iter().map(frames).collect::<TractResult<TVec<_>>>()?)
+            }
+            Sum(a, b) => Frames::combine(tvec!(frames(a)?, frames(b)?)),
+            Const(..) => Frames::Invariant,
+            Failover(a, b) => match (frames(a.failover_primary(known))?, frames(b)?) {
+                (Frames::Invariant, _) => Frames::Invariant,
+                (Frames::Span(..), f @ Frames::Span(..)) => f,
+                _ => bail!("Failover needs input descriptors with known frames: {:?}", self),
+            },
+            IfDefined(gd) => {
+                if self.as_memory().is_some() {
+                    Frames::Recurrent
+                } else {
+                    frames(gd)?
+                }
+            }
+            Name(n) => known.get(n).cloned().unwrap_or(Frames::Recurrent),
+            Offset(gd, o) => match frames(gd)? {
+                Frames::Span(l, r) if *o > 0 => {
+                    Frames::Span(l + adjust_final_offset.unwrap_or(0), r + o)
+                }
+                Frames::Span(l, r) => Frames::Span(l - o, r + o),
+                f => f,
+            },
+            ReplaceIndex(gd, 'x', _) => frames(gd)?,
+            ReplaceIndex(..) => Frames::Invariant,
+            Round(gd, m) => match frames(gd)? {
+                Frames::Span(l, r) => Frames::Span(l + (-l).rem_euclid(*m as isize), r),
+                f => f,
+            },
+            Scale(_, gd) => frames(gd)?,
+        };
+        Ok(it)
+    }
+
+    /// Wire the descriptor, cropped to `frames` if it produces more.
+    fn wire_aligned(
+        &self,
+        frames: Frames,
+        inlet: InletId,
+        name: &str,
+        model: &mut InferenceModel,
+        deferred: &mut BTreeMap<InletId, String>,
+        known: &HashMap<String, Frames>,
+        adjust_final_offset: Option<isize>,
+    ) -> TractResult<()> {
+        let mine = self.frames(known, adjust_final_offset)?;
+        if let (Frames::Span(l, r), Frames::Span(target_l, target_r)) = (mine, frames) {
+            if (l, r) != (target_l, target_r) {
+                if target_l < l || target_r < r {
+                    bail!("Can not align {:?} to {:?}", mine, frames)
+                }
+                let name = format!("{}-Align", name);
+                let id = model.add_node(
+                    &*name,
+                    tract_core::ops::array::Crop::new(
+                        0,
+                        (target_l - l) as usize,
+                        (target_r - r) as usize,
+                    ),
+                    tvec!(InferenceFact::default()),
+                )?;
+                deferred.insert(inlet, name.to_string());
+                return self.wire(
+                    InletId::new(id, 0),
+                    &*name,
+                    model,
+                    deferred,
+                    known,
+                    adjust_final_offset,
+                );
+            }
+        }
+        self.wire(inlet, name, model, deferred, known, adjust_final_offset)
+    }
+
     fn wire<'a>(
         &'a self,
         inlet: InletId,
         name: &str,
         model: &mut InferenceModel,
         deferred: &mut BTreeMap<InletId, String>,
+        known: &HashMap<String, Frames>,
         adjust_final_offset: Option<isize>,
     ) -> TractResult<()> {
         use GeneralDescriptor::*;
+        let frames = self.frames(known, adjust_final_offset)?;
         match &self {
             &Name(n) => {
                 deferred.insert(inlet, n.to_string());
@@ -96,34 +372,180 @@ impl GeneralDescriptor {
                     tract_core::ops::array::Concat::new(1),
                     tvec!(InferenceFact::default()),
                 )?;
-                model.add_edge(OutletId::new(id, 0), inlet)?;
+                deferred.insert(inlet, name.to_string());
+                let reference = appendees.iter().position(|app| {
+                    app.frames(known, adjust_final_offset).ok() != Some(Frames::Invariant)
+                });
                 for (ix, appendee) in appendees.iter().enumerate() {
                     let name = format!("{}-{}", name, ix);
-                    appendee.wire(
+                    let inlet = InletId::new(id, ix);
+                    if let (Frames::Invariant, Some(reference)) =
+                        (appendee.frames(known, adjust_final_offset)?, reference)
+                    {
+                        // repeat the single frame along the other appendees frames
+                        let rows = if let Const(value, dim) = appendee {
+                            Some(Array2::from_elem((1, *dim), *value).into_arc_tensor())
+                        } else {
+                            None
+                        };
+                        let bname = format!("{}-Broadcast", name);
+                        let op = crate::ops::descriptor::Broadcast::new(rows.clone());
+                        let bid = model.add_node(&*bname, op, tvec!(InferenceFact::default()))?;
+                        deferred.insert(inlet, bname.to_string());
+                        if rows.is_none() {
+                            appendee.wire(
+                                InletId::new(bid, 0),
+                                &*name,
+                                model,
+                                deferred,
+                                known,
+                                adjust_final_offset,
+                            )?;
+                        }
+                        appendees[reference].wire_aligned(
+                            frames,
+                            InletId::new(bid, rows.is_none() as usize),
+                            &*bname,
+                            model,
+                            deferred,
+                            known,
+                            adjust_final_offset,
+                        )?;
+                    } else {
+                        appendee.wire_aligned(
+                            frames,
+                            inlet,
+                            &*name,
+                            model,
+                            deferred,
+                            known,
+                            adjust_final_offset,
+                        )?;
+                    }
+                }
+                return Ok(());
+            }
+            &Sum(a, b) => {
+                let name = format!("{}-Sum", name);
+                let id = model.add_node(
+                    &*name,
+                    tract_core::ops::math::add::bin(),
+                    tvec!(InferenceFact::default()),
+                )?;
+                deferred.insert(inlet, name.to_string());
+                for (ix, operand) in [a, b].iter().enumerate() {
+                    operand.wire_aligned(
+                        frames,
                         InletId::new(id, ix),
-                        &*name,
+                        &*format!("{}-{}", name, ix),
                         model,
                         deferred,
+                        known,
                         adjust_final_offset,
                     )?;
                 }
                 return Ok(());
             }
-            &IfDefined(ref o) => {
-                if let &Offset(ref n, ref o) = &**o {
-                    if let Name(n) = &**n {
-                        let name = format!("{}-Memory", name);
-                        model.add_node(
-                            &*name,
-                            crate::ops::memory::Memory::new(n.to_string(), *o),
-                            tvec!(InferenceFact::default()),
-                        )?;
-                        deferred.insert(inlet, name);
-                        return Ok(());
+            &Switch(operands) => {
+                let phase = if let Frames::Span(l, _) = frames {
+                    l.rem_euclid(operands.len() as isize) as usize
+                } else {
+                    bail!("Switch needs at least one input with known frames: {:?}", self)
+                };
+                let name = format!("{}-Switch", name);
+                let id = model.add_node(
+                    &*name,
+                    crate::ops::descriptor::Switch::new(phase),
+                    tvec!(InferenceFact::default()),
+                )?;
+                deferred.insert(inlet, name.to_string());
+                for (ix, operand) in operands.iter().enumerate() {
+                    operand.wire_aligned(
+                        frames,
+                        InletId::new(id, ix),
+                        &*format!("{}-{}", name, ix),
+                        model,
+                        deferred,
+                        known,
+                        adjust_final_offset,
+                    )?;
+                }
+                return Ok(());
+            }
+            &Scale(scale, inner) => {
+                let name = format!("{}-Scale", name);
+                let id = model.add_node(
+                    &*name,
+                    tract_core::ops::math::mul::bin(),
+                    tvec!(InferenceFact::default()),
+                )?;
+                deferred.insert(inlet, name.to_string());
+                let factor = format!("{}-Factor", name);
+                model.add_const(&*factor, tensor0(*scale))?;
+                deferred.insert(InletId::new(id, 1), factor);
+                return inner.wire(
+                    InletId::new(id, 0),
+                    &*name,
+                    model,
+                    deferred,
+                    known,
+                    adjust_final_offset,
+                );
+            }
+            &Const(value, dim) => {
+                let name = format!("{}-Const", name);
+                model.add_const(&*name, Array2::from_elem((1, *dim), *value).into_tensor())?;
+                deferred.insert(inlet, name);
+                return Ok(());
+            }
+            &Failover(primary, fallback) => {
+                let primary = primary.failover_primary(known);
+                let shift = match (primary.frames(known, adjust_final_offset)?, frames) {
+                    (Frames::Span(l, _), Frames::Span(target_l, _)) => target_l - l,
+                    _ => {
+                        return primary.wire(
+                            inlet,
+                            name,
+                            model,
+                            deferred,
+                            known,
+                            adjust_final_offset,
+                        )
                     }
+                };
+                let name = format!("{}-Failover", name);
+                let id = model.add_node(
+                    &*name,
+                    crate::ops::descriptor::Failover::new(shift),
+                    tvec!(InferenceFact::default()),
+                )?;
+                deferred.insert(inlet, name.to_string());
+                for (ix, operand) in [primary, &**fallback].iter().enumerate() {
+                    operand.wire(
+                        InletId::new(id, ix),
+                        &*format!("{}-{}", name, ix),
+                        model,
+                        deferred,
+                        known,
+                        adjust_final_offset,
+                    )?;
                 }
+                return Ok(());
             }
-            &Offset(ref n, o) if *o > 0 => {
+            &IfDefined(inner) => {
+                if let Some((n, o)) = self.as_memory() {
+                    let name = format!("{}-Memory", name);
+                    model.add_node(
+                        &*name,
+                        crate::ops::memory::Memory::new(n.to_string(), o),
+                        tvec!(InferenceFact::default()),
+                    )?;
+                    deferred.insert(inlet, name);
+                    return Ok(());
+                }
+                return inner.wire(inlet, name, model, deferred, known, adjust_final_offset);
+            }
+            &Offset(ref n, o) if *o > 0 && frames != Frames::Invariant => {
                 let name = format!("{}-Delay", name);
                 let crop = *o as isize + adjust_final_offset.unwrap_or(0);
                 if crop < 0 {
@@ -134,10 +556,63 @@ impl GeneralDescriptor {
                     tract_core::ops::array::Crop::new(0, crop as usize, 0),
                     tvec!(InferenceFact::default()),
                 )?;
-                model.add_edge(OutletId::new(id, 0), inlet)?;
-                n.wire(InletId::new(id, 0), &*name, model, deferred, adjust_final_offset)?;
+                deferred.insert(inlet, name.to_string());
+                n.wire(InletId::new(id, 0), &*name, model, deferred, known, adjust_final_offset)?;
                 return Ok(());
             }
+            &Offset(ref n, _) => {
+                return n.wire(inlet, name, model, deferred, known, adjust_final_offset);
+            }
+            &ReplaceIndex(ref n, 'x', 0) => {
+                return n.wire(inlet, name, model, deferred, known, adjust_final_offset);
+            }
+            &ReplaceIndex(ref n, 't', t) => match n.frames(known, adjust_final_offset)? {
+                Frames::Invariant => {
+                    return n.wire(inlet, name, model, deferred, known, adjust_final_offset)
+                }
+                Frames::Span(l, _) if *t >= l => {
+                    let name = format!("{}-ReplaceIndex", name);
+                    let frame = (*t - l) as usize;
+                    let id = model.add_node(
+                        &*name,
+                        tract_core::ops::array::Slice::new(0, frame, frame + 1),
+                        tvec!(InferenceFact::default()),
+                    )?;
+                    deferred.insert(inlet, name.to_string());
+                    return n.wire(
+                        InletId::new(id, 0),
+                        &*name,
+                        model,
+                        deferred,
+                        known,
+                        adjust_final_offset,
+                    );
+                }
+                _ => (),
+            },
+            &Round(ref n, modulus) => match n.frames(known, adjust_final_offset)? {
+                Frames::Span(..) if *modulus > 1 => {
+                    let name = format!("{}-Round", name);
+                    let id = model.add_node(
+                        &*name,
+                        crate::ops::descriptor::Round::new(*modulus),
+                        tvec!(InferenceFact::default()),
+                    )?;
+                    deferred.insert(inlet, name.to_string());
+                    // start on a frame at a multiple of the modulus
+                    return n.wire_aligned(
+                        frames,
+                        InletId::new(id, 0),
+                        &*name,
+                        model,
+                        deferred,
+                        known,
+                        adjust_final_offset,
+                    );
+                }
+                Frames::Recurrent => (),
+                _ => return n.wire(inlet, name, model, deferred, known, adjust_final_offset),
+            },
             _ => (),
         }
         bail!("Unhandled input descriptor: {:?}", self)
@@ -167,18 +642,42 @@ pub struct ParsingContext<'a> {
     pub proto_model: &'a KaldiProtoModel,
 }
 
+pub type KaldiOpBuilder =
+    Arc<dyn Fn(&ParsingContext, &str) -> TractResult<Box<dyn InferenceOp>> + Send + Sync>;
+
+/// Component builders by component class. The second map declares the
+/// attributes of components unknown to tract, needed to parse the binary
+/// format.
 #[derive(Clone, Default)]
 pub struct KaldiOpRegister(
-    pub HashMap<String, fn(&ParsingContext, node: &str) -> TractResult<Box<dyn InferenceOp>>>,
+    pub HashMap<String, KaldiOpBuilder>,
+    pub HashMap<String, HashMap<String, KaldiAttributeKind>>,
 );
 
 impl KaldiOpRegister {
     pub fn insert(
         &mut self,
-        s: &'static str,
-        builder: fn(&ParsingContext, node: &str) -> TractResult<Box<dyn InferenceOp>>,
+        s: &str,
+        builder: impl Fn(&ParsingContext, &str) -> TractResult<Box<dyn InferenceOp>>
+            + Send
+            + Sync
+            + 'static,
+    ) {
+        self.0.insert(s.into(), Arc::new(builder));
+    }
+
+    /// Register a builder for a custom component, with its attributes.
+    pub fn insert_component(
+        &mut self,
+        s: &str,
+        attributes: &[(&str, KaldiAttributeKind)],
+        builder: impl Fn(&ParsingContext, &str) -> TractResult<Box<dyn InferenceOp>>
+            + Send
+            + Sync
+            + 'static,
     ) {
-        self.0.insert(s.into(), builder);
+        self.1.insert(s.into(), attributes.iter().map(|(k, v)| (k.to_string(), *v)).collect());
+        self.insert(s, builder)
     }
 }
 
@@ -187,31 +686,72 @@ pub struct Kaldi {
     pub op_register: KaldiOpRegister,
 }
 
+impl Kaldi {
+    /// Register the components of a plugin.
+    pub fn with_plugin(mut self, plugin: &dyn OpPlugin<KaldiOpRegister>) -> Kaldi {
+        plugin.register_ops(&mut self.op_register);
+        self
+    }
+}
+
 impl Framework<KaldiProtoModel> for Kaldi {
     fn proto_model_for_read(&self, r: &mut dyn std::io::Read) -> TractResult<KaldiProtoModel> {
         use crate::parser;
         let mut v = vec![];
         r.read_to_end(&mut v)?;
-        parser::nnet3(&*v)
+        parser::nnet3_with_components(&*v, &self.op_register.1)
     }
 
     fn model_for_proto_model(&self, proto_model: &KaldiProtoModel) -> TractResult<InferenceModel> {
         let ctx = ParsingContext { proto_model };
+        let config_lines = &proto_model.config_lines;
         let mut model = InferenceModel::default();
-        model.add_source(
-            proto_model.config_lines.input_name.clone(),
-            InferenceFact::dt_shape(
-                f32::datum_type(),
-                shapefact!(S, (proto_model.config_lines.input_dim)),
-            ),
-        )?;
         let mut inputs_to_wire: BTreeMap<InletId, String> = Default::default();
-        for (name, node) in &proto_model.config_lines.nodes {
+        let mut known: HashMap<String, Frames> = HashMap::new();
+        // subsampled inputs are upsampled to the frame rate, under another name
+        let mut aliases: HashMap<String, String> = HashMap::new();
+        let rate = |name: &str| match proto_model.input_rates.get(name) {
+            Some(InputRate::Subsampled(1)) | None => InputRate::Frames,
+            Some(rate) => *rate,
+        };
+        let reference =
+            config_lines.input_nodes.iter().find(|i| rate(&i.name) == InputRate::Frames);
+        for input in &config_lines.input_nodes {
+            let (frames, input_frames) = match rate(&input.name) {
+                InputRate::Frames => (TDim::s(), Frames::Span(0, 0)),
+                InputRate::Constant => (1.to_dim(), Frames::Invariant),
+                InputRate::Subsampled(period) => {
+                    (TDim::s().div_ceil(period.to_dim()), Frames::Span(0, 0))
+                }
+            };
+            let source = model.add_source(
+                input.name.clone(),
+                InferenceFact::dt_shape(f32::datum_type(), vec![frames, input.dim.to_dim()]),
+            )?;
+            if let InputRate::Subsampled(period) = rate(&input.name) {
+                let reference = reference.ok_or_else(|| {
+                    format!("Subsampled input {} requires an input at frame rate", input.name)
+                })?;
+                let name = format!("{}-Upsample", input.name);
+                let id = model.add_node(
+                    &*name,
+                    crate::ops::descriptor::Upsample::new(period),
+                    tvec!(InferenceFact::default()),
+                )?;
+                model.add_edge(source, InletId::new(id, 0))?;
+                inputs_to_wire.insert(InletId::new(id, 1), reference.name.clone());
+                aliases.insert(input.name.clone(), name);
+            }
+            known.insert(input.name.clone(), input_frames);
+        }
+        let subsampled: Vec<&str> = aliases.keys().map(|k| &**k).collect();
+        for (name, node) in &config_lines.nodes {
             match node {
                 NodeLine::Component(line) => {
+                    let input = line.input.follow_time(&subsampled);
                     let component = &proto_model.components[&line.component];
                     if crate::ops::AFFINE.contains(&&*component.klass)
-                        && line.input.as_conv_shape_dilation().is_some()
+                        && input.as_conv_shape_dilation().is_some()
                     {
                         let op = crate::ops::affine::affine_component(&ctx, name)?;
                         let id = model.add_node(
@@ -219,8 +759,7 @@ impl Framework<KaldiProtoModel> for Kaldi {
                             op,
                             tvec!(InferenceFact::default()),
                         )?;
-                        inputs_to_wire
-                            .insert(InletId::new(id, 0), line.input.inputs()[0].to_owned());
+                        inputs_to_wire.insert(InletId::new(id, 0), input.inputs()[0].to_owned());
                     } else {
                         let op = match self.op_register.0.get(&*component.klass) {
                             Some(builder) => (builder)(&ctx, name)?,
@@ -236,16 +775,33 @@ impl Framework<KaldiProtoModel> for Kaldi {
                             op,
                             tvec!(InferenceFact::default()),
                         )?;
-                        line.input.wire(
+                        input.wire(
                             InletId::new(id, 0),
                             name,
                             &mut model,
                             &mut inputs_to_wire,
+                            &known,
                             None,
                         )?
                     }
+                    let mut frames = input.frames(&known, None)?;
+                    if let Frames::Span(l, r) = frames {
+                        // the convolutions consume the context of their time offsets
+                        let offsets = match &*component.klass {
+                            "TdnnComponent" => Some(crate::ops::affine::time_offsets(component)?),
+                            "TimeHeightConvolutionComponent" => {
+                                Some(crate::ops::conv::time_offsets(component)?)
+                            }
+                            _ => None,
+                        };
+                        if let Some(offsets) = offsets {
+                            frames = Frames::Span(l - offsets[0], r + offsets[offsets.len() - 1]);
+                        }
+                    }
+                    known.insert(name.to_string(), frames);
                 }
                 NodeLine::DimRange(line) => {
+                    let input = line.input.follow_time(&subsampled);
                     let op = tract_core::ops::array::Slice::new(
                         1,
                         line.offset as usize,
@@ -253,37 +809,302 @@ impl Framework<KaldiProtoModel> for Kaldi {
                     );
                     let id =
                         model.add_node(name.to_string(), op, tvec!(InferenceFact::default()))?;
-                    line.input.wire(
+                    input.wire(
                         InletId::new(id, 0),
                         name,
                         &mut model,
                         &mut inputs_to_wire,
+                        &known,
                         None,
-                    )?
+                    )?;
+                    let frames = input.frames(&known, None)?;
+                    known.insert(name.to_string(), frames);
                 }
             }
         }
         let mut outputs = vec![];
-        for o in &proto_model.config_lines.outputs {
-            let output = model.add_node(
-                &*o.output_alias,
-                tract_core::ops::identity::Identity::default(),
-                tvec!(InferenceFact::default()),
-            )?;
-            o.descriptor.wire(
+        for o in &config_lines.outputs {
+            let priors = proto_model.priors.as_ref().filter(|_| proto_model.subtract_priors);
+            let output = if let (Some(priors), "output") = (priors, &*o.output_alias) {
+                // pseudo-log-likelihood: log(posterior) - log(prior)
+                let neg_log_priors = priors.to_array_view::<f32>()?.mapv(|p| -p.ln());
+                let name = format!("{}-LogPriors", o.output_alias);
+                model.add_const(&*name, neg_log_priors.into_arc_tensor())?;
+                let output = model.add_node(
+                    &*o.output_alias,
+                    tract_core::ops::math::add::bin(),
+                    tvec!(InferenceFact::default()),
+                )?;
+                inputs_to_wire.insert(InletId::new(output, 1), name);
+                output
+            } else {
+                model.add_node(
+                    &*o.output_alias,
+                    tract_core::ops::identity::Identity::default(),
+                    tvec!(InferenceFact::default()),
+                )?
+            };
+            o.descriptor.follow_time(&subsampled).wire(
                 InletId::new(output, 0),
                 "output",
                 &mut model,
                 &mut inputs_to_wire,
+                &known,
                 Some(proto_model.adjust_final_offset),
             )?;
             outputs.push(OutletId::new(output, 0));
         }
         for (inlet, name) in inputs_to_wire {
-            let src = OutletId::new(model.node_by_name(&*name)?.id, 0);
+            let name = aliases.get(&name).unwrap_or(&name);
+            let src = OutletId::new(model.node_by_name(&**name)?.id, 0);
             model.add_edge(src, inlet)?;
         }
         model.set_output_outlets(&*outputs)?;
         Ok(model)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tract_core::ndarray::{arr2, s, Axis, Ix2};
+    use tract_core::pulse::StreamingSession;
+
+    fn run(descriptor: &str, frames: usize) -> TractResult<Arc<Tensor>> {
+        let nnet = format!(
+            "<Nnet3>\ninput-node name=input dim=2\noutput-node name=output input={}\n\n\
+             <NumComponents> 0\n</Nnet3>",
+            descriptor
+        );
+        let mut model = crate::kaldi().model_for_read(&mut nnet.as_bytes())?;
+        model.set_input_fact(0, InferenceFact::dt_shape(f32::datum_type(), &[frames, 2]))?;
+        let input = Array2::from_shape_fn((frames, 2), |(t, d)| (10 * t + d) as f32);
+        let plan = SimplePlan::new(model.into_optimized()?)?;
+        Ok(plan.run(tvec!(input.into_tensor()))?.remove(0))
+    }
+
+    /// Runs the pulsed model on a NaN padded input, returning the output delay
+    /// and the valid output frames.
+    fn run_pulsed(descriptor: &str, frames: usize, pulse: usize) -> TractResult<(usize, Tensor)> {
+        let nnet = format!(
+            "<Nnet3>\ninput-node name=input dim=2\noutput-node name=output input={}\n\n\
+             <NumComponents> 0\n</Nnet3>",
+            descriptor
+        );
+        let model = crate::kaldi().model_for_read(&mut nnet.as_bytes())?;
+        let pulsed = PulsedModel::new(&model.into_normalized()?, pulse)?;
+        let delay = pulsed.output_fact(0)?.delay;
+        let plan = SimplePlan::new(pulsed.into_typed()?)?;
+        let mut state = SimpleState::new(&plan)?;
+        state.session_state.known_stream_len = Some(frames);
+        let mut outputs = vec![];
+        for p in 0..(frames + delay + pulse - 1) / pulse {
+            let input = Array2::from_shape_fn((pulse, 2), |(t, d)| {
+                let t = p * pulse + t;
+                if t < frames {
+                    (10 * t + d) as f32
+                } else {
+                    std::f32::NAN
+                }
+            });
+            let output = state.run(tvec!(input.into_tensor()))?.remove(0);
+            outputs.push(output.to_array_view::<f32>()?.to_owned());
+        }
+        let views = outputs.iter().map(|o| o.view()).collect::<Vec<_>>();
+        let output = tract_core::ndarray::stack(Axis(0), &*views)?;
+        Ok((
+            delay,
+            output.slice_axis(Axis(0), (delay..delay + frames).into()).to_owned().into_tensor(),
+        ))
+    }
+
+    fn ivector_model(rate: InputRate) -> TractResult<InferenceModel> {
+        let nnet = "<Nnet3>\ninput-node name=input dim=2\ninput-node name=ivector dim=1\n\
+             output-node name=output input=Append(input, ReplaceIndex(ivector, t, 0))\n\n\
+             <NumComponents> 0\n</Nnet3>";
+        let kaldi = crate::kaldi();
+        let mut proto = kaldi.proto_model_for_read(&mut nnet.as_bytes())?;
+        proto.input_rates.insert("ivector".to_string(), rate);
+        kaldi.model_for_proto_model(&proto)
+    }
+
+    #[test]
+    fn constant_ivector() {
+        let model = ivector_model(InputRate::Constant).unwrap();
+        let input = arr2(&[[0.0f32, 1.0], [10.0, 11.0]]).into_tensor();
+        let ivector = arr2(&[[5.0f32]]).into_tensor();
+        let plan = SimplePlan::new(model.into_optimized().unwrap()).unwrap();
+        let output = plan.run(tvec!(input, ivector)).unwrap().remove(0);
+        assert_eq!(*output, arr2(&[[0.0f32, 1.0, 5.0], [10.0, 11.0, 5.0]]).into_tensor());
+    }
+
+    #[test]
+    fn subsampled_ivector() {
+        let model = ivector_model(InputRate::Subsampled(2)).unwrap();
+        let input = Array2::from_shape_fn((5, 2), |(t, d)| (10 * t + d) as f32).into_tensor();
+        let ivector = arr2(&[[5.0f32], [6.0], [7.0]]).into_tensor();
+        let plan = SimplePlan::new(model.into_optimized().unwrap()).unwrap();
+        let output = plan.run(tvec!(input, ivector)).unwrap().remove(0);
+        let ivectors = output
+            .to_array_view::<f32>()
+            .unwrap()
+            .index_axis(Axis(1), 2)
+            .iter()
+            .cloned()
+            .collect::<Vec<_>>();
+        assert_eq!(ivectors, vec![5.0, 5.0, 6.0, 6.0, 7.0]);
+    }
+
+    #[test]
+    fn pulse_subsampled_ivector() {
+        let model = ivector_model(InputRate::Subsampled(2)).unwrap();
+        let pulsed = PulsedModel::new(&model.into_normalized().unwrap(), 4).unwrap();
+        assert_eq!(pulsed.input_fact(1).unwrap().shape, tvec!(2, 1));
+        let plan = SimplePlan::new(pulsed.into_typed().unwrap()).unwrap();
+        let mut state = SimpleState::new(&plan).unwrap();
+        for pulse in 0..2 {
+            let input = Array2::from_shape_fn((4, 2), |(t, d)| (10 * (4 * pulse + t) + d) as f32);
+            let ivector = Array2::from_shape_fn((2, 1), |(t, _)| (2 * pulse + t) as f32);
+            let output =
+                state.run(tvec!(input.into_tensor(), ivector.into_tensor())).unwrap().remove(0);
+            let ivectors = output
+                .to_array_view::<f32>()
+                .unwrap()
+                .index_axis(Axis(1), 2)
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>();
+            let first = 2.0 * pulse as f32;
+            assert_eq!(ivectors, vec![first, first, first + 1.0, first + 1.0]);
+        }
+    }
+
+    #[test]
+    fn pulse_constant_ivector() {
+        let model = ivector_model(InputRate::Constant).unwrap();
+        let pulsed = PulsedModel::new(&model.into_normalized().unwrap(), 4).unwrap();
+        assert!(!pulsed.input_fact(1).unwrap().is_streaming());
+        let plan = SimplePlan::new(pulsed).unwrap();
+        let mut session = StreamingSession::new(&plan).unwrap();
+        let input = Array2::from_shape_fn((6, 2), |(t, d)| (10 * t + d) as f32);
+        let ivector = arr2(&[[5.0f32]]).into_tensor();
+        let mut outputs = vec![];
+        for chunk in &[input.slice(s![..4, ..]), input.slice(s![4.., ..])] {
+            let inputs = tvec!(chunk.to_owned().into_tensor(), ivector.clone());
+            outputs.push(session.feed_inputs(inputs).unwrap().remove(0));
+        }
+        outputs.push(session.flush().unwrap().remove(0));
+        let views = outputs.iter().map(|o| o.to_array_view::<f32>().unwrap()).collect::<Vec<_>>();
+        let output = tract_core::ndarray::stack(Axis(0), &*views).unwrap();
+        let expected = Array2::from_shape_fn((6, 3), |(t, d)| match d {
+            2 => 5.0f32,
+            _ => (10 * t + d) as f32,
+        });
+        assert_eq!(output.into_dimensionality::<Ix2>().unwrap(), expected);
+    }
+
+    #[test]
+    fn subtract_priors() {
+        let kaldi = crate::kaldi();
+        let mut proto = kaldi
+            .proto_model_for_path("test_cases/mdl_affine_log_softmax_6x4_T10/model.raw")
+            .unwrap();
+        let input = Array2::from_shape_fn((3, 6), |(t, d)| (t + d) as f32).into_arc_tensor();
+        let mut run = |subtract_priors| {
+            proto.subtract_priors = subtract_priors;
+            let model = kaldi.model_for_proto_model(&proto).unwrap();
+            let plan = SimplePlan::new(model.into_optimized().unwrap()).unwrap();
+            plan.run(tvec!(input.as_ref().clone())).unwrap().remove(0)
+        };
+        let posteriors = run(false).to_array_view::<f32>().unwrap().to_owned();
+        let likelihoods = run(true).to_array_view::<f32>().unwrap().to_owned();
+        let log_priors =
+            proto.priors.as_ref().unwrap().to_array_view::<f32>().unwrap().mapv(f32::ln);
+        assert!((likelihoods + log_priors - posteriors).iter().all(|d| d.abs() < 1e-5));
+    }
+
+    #[test]
+    fn load_empty_vectors() {
+        // these models hold empty stats vectors ("[ ]") in their components
+        let kaldi = crate::kaldi();
+        for case in &[
+            "log_softmax_D10_T11",
+            "mdl_affine_log_softmax_6x4_T10",
+            "sigmoid_D10_T11",
+            "tanh_D10_T11",
+            "tdnn_8x6_T25_o-1_0_2_nobias",
+            "tdnnf_D12_B4_T30",
+        ] {
+            let path = format!("test_cases/{}/model.raw", case);
+            let proto = kaldi.proto_model_for_path(&path).unwrap();
+            kaldi.model_for_proto_model(&proto).unwrap().into_optimized().unwrap();
+        }
+    }
+
+    #[test]
+    fn sum_aligns_offsets() {
+        let output = run("Sum(Offset(input, -1), input)", 3).unwrap();
+        assert_eq!(*output, arr2(&[[10.0f32, 12.0], [30.0, 32.0]]).into_tensor());
+    }
+
+    #[test]
+    fn append_aligns_offsets() {
+        let output = run("Append(Offset(input, -1), input)", 3).unwrap();
+        assert_eq!(
+            *output,
+            arr2(&[[0.0f32, 1.0, 10.0, 11.0], [10.0, 11.0, 20.0, 21.0]]).into_tensor()
+        );
+    }
+
+    #[test]
+    fn append_const_and_scale() {
+        let output = run("Append(Scale(2.0, input), Const(0.5, 1))", 2).unwrap();
+        assert_eq!(*output, arr2(&[[0.0f32, 2.0, 0.5], [20.0, 22.0, 0.5]]).into_tensor());
+    }
+
+    #[test]
+    fn append_replace_index() {
+        let output = run("Append(input, ReplaceIndex(input, t, 0))", 2).unwrap();
+        assert_eq!(*output, arr2(&[[0.0f32, 1.0, 0.0, 1.0], [10.0, 11.0, 0.0, 1.0]]).into_tensor());
+    }
+
+    #[test]
+    fn round() {
+        let output = run("Round(input, 2)", 3).unwrap();
+        assert_eq!(*output, arr2(&[[0.0f32, 1.0], [0.0, 1.0], [20.0, 21.0]]).into_tensor());
+    }
+
+    #[test]
+    fn switch() {
+        let output = run("Switch(input, Scale(-1.0, input))", 3).unwrap();
+        assert_eq!(*output, arr2(&[[0.0f32, 1.0], [-10.0, -11.0], [20.0, 21.0]]).into_tensor());
+    }
+
+    #[test]
+    fn failover_at_start() {
+        let output = run("Failover(IfDefined(Offset(input, -1)), Scale(-1.0, input))", 3).unwrap();
+        assert_eq!(*output, arr2(&[[0.0f32, -1.0], [0.0, 1.0], [10.0, 11.0]]).into_tensor());
+    }
+
+    #[test]
+    fn failover_at_end() {
+        let output = run("Failover(Offset(input, 1), Scale(-1.0, input))", 3).unwrap();
+        assert_eq!(*output, arr2(&[[10.0f32, 11.0], [20.0, 21.0], [-20.0, -21.0]]).into_tensor());
+    }
+
+    #[test]
+    fn pulse_failover_at_start() {
+        let descriptor = "Failover(IfDefined(Offset(input, -1)), Scale(-1.0, input))";
+        let (delay, output) = run_pulsed(descriptor, 5, 2).unwrap();
+        assert_eq!(delay, 0);
+        assert_eq!(output, *run(descriptor, 5).unwrap());
+    }
+
+    #[test]
+    fn pulse_failover_at_end() {
+        let descriptor = "Failover(Offset(input, 1), Scale(-1.0, input))";
+        let (delay, output) = run_pulsed(descriptor, 5, 2).unwrap();
+        assert_eq!(delay, 1);
+        assert_eq!(output, *run(descriptor, 5).unwrap());
+    }
+}
@@ -1,9 +1,16 @@
-use crate::model::KaldiOpRegister;
+use tract_core::internal::*;
+
+use crate::model::{Component, KaldiOpRegister, NodeLine, ParsingContext};
 
 pub(crate) mod affine;
+mod block;
+pub(crate) mod conv;
+pub(crate) mod descriptor;
 pub(crate) mod lstm_nonlin;
 pub(crate) mod memory;
 mod renorm;
+mod scale_and_offset;
+mod stats;
 
 pub const AFFINE: &'static [&'static str] =
     &["FixedAffineComponent", "NaturalGradientAffineComponent"];
@@ -20,4 +27,32 @@ pub fn register_all_ops(reg: &mut KaldiOpRegister) {
     reg.insert("RectifiedLinearComponent", |_, _| {
         Ok(Box::new(tract_core::ops::math::scalar_max((0.0).into())))
     });
+    reg.insert("SigmoidComponent", |_, _| Ok(Box::new(tract_core::ops::nn::sigmoid())));
+    reg.insert("TanhComponent", |_, _| Ok(Box::new(tract_core::ops::math::tanh())));
+    reg.insert("LogSoftmaxComponent", |_, _| {
+        Ok(Box::new(tract_core::ops::nn::LayerLogSoftmax::new(1)))
+    });
+    reg.insert("LinearComponent", affine::linear_component);
+    reg.insert("TdnnComponent", affine::tdnn_component);
+    reg.insert("TimeHeightConvolutionComponent", conv::time_height_convolution);
+    reg.insert("BatchNormComponent", scale_and_offset::batch_norm);
+    reg.insert("ScaleAndOffsetComponent", scale_and_offset::scale_and_offset);
+    reg.insert("DropoutComponent", scale_and_offset::dropout);
+    for identity in &["NoOpComponent", "GeneralDropoutComponent"] {
+        reg.insert(identity, |_, _| Ok(Box::new(tract_core::ops::identity::Identity::default())));
+    }
+    reg.insert("ElementwiseProductComponent", block::elementwise_product);
+    reg.insert("SumBlockComponent", block::sum_block);
+    reg.insert("StatisticsExtractionComponent", stats::statistics_extraction);
+    reg.insert("StatisticsPoolingComponent", stats::statistics_pooling);
+}
+
+/// Component of a component node.
+pub(crate) fn component<'a>(ctx: &'a ParsingContext, name: &str) -> TractResult<&'a Component> {
+    let node = &ctx.proto_model.config_lines.nodes.iter().find(|l| l.0 == name);
+    if let Some((_, NodeLine::Component(line))) = node {
+        Ok(&ctx.proto_model.components[&line.component])
+    } else {
+        bail!("Could not find component {}", name);
+    }
 }
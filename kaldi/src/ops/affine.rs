@@ -1,6 +1,7 @@
 use tract_core::internal::*;
 use tract_core::ndarray;
 
+use crate::model::Component;
 use crate::model::NodeLine;
 use crate::model::ParsingContext;
 
@@ -28,6 +29,76 @@ pub fn affine_component(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn
     }))
 }
 
+pub fn linear_component(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let component = super::component(ctx, name)?;
+    let params = component.attributes.get("Params").ok_or("missing attribute Params")?;
+    // O•I -> I•O -> T•I•O
+    let o_i = params.to_array_view::<f32>()?.into_dimensionality::<ndarray::Ix2>()?;
+    let t_i_o = o_i.t().insert_axis(ndarray::Axis(0)).to_owned();
+    let bias = ndarray::Array1::<f32>::zeros(o_i.shape()[0]);
+    Ok(Box::new(Affine {
+        kernel_len: 1,
+        dilation: 1,
+        linear_params: t_i_o.into_arc_tensor(),
+        bias_params: bias.into_arc_tensor(),
+    }))
+}
+
+/// Time offsets of a TdnnComponent, in increasing order.
+pub fn time_offsets(component: &Component) -> TractResult<Vec<isize>> {
+    let offsets = component.attributes.get("TimeOffsets").ok_or("missing attribute TimeOffsets")?;
+    let offsets: Vec<isize> =
+        offsets.cast_to::<i32>()?.as_slice::<i32>()?.iter().map(|&o| o as isize).collect();
+    if offsets.len() == 0 || offsets.windows(2).any(|pair| pair[0] >= pair[1]) {
+        bail!("Invalid TimeOffsets {:?}", offsets);
+    }
+    Ok(offsets)
+}
+
+/// A TdnnComponent is a convolution over time. Its offsets may be unevenly
+/// spaced: the kernel has a tap for each multiple of their gcd, the ones not
+/// in the offsets are zeroes.
+pub fn tdnn_component(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let component = super::component(ctx, name)?;
+    let offsets = time_offsets(component)?;
+    let kernel: &Tensor =
+        component.attributes.get("LinearParams").ok_or("missing attribute LinearParams")?;
+    let o_ti = kernel.to_array_view::<f32>()?.into_dimensionality::<ndarray::Ix2>()?;
+    let output_dim = o_ti.shape()[0];
+    let input_dim = o_ti.shape()[1] / offsets.len();
+    let span = (offsets[offsets.len() - 1] - offsets[0]) as usize;
+    let dilation = offsets.iter().fold(span, |g, &o| gcd(g, (o - offsets[0]) as usize)).max(1);
+    let mut t_i_o = ndarray::Array3::<f32>::zeros((span / dilation + 1, input_dim, output_dim));
+    for (ix, offset) in offsets.iter().enumerate() {
+        let tap = (offset - offsets[0]) as usize / dilation;
+        t_i_o.index_axis_mut(ndarray::Axis(0), tap).assign(
+            &o_ti.slice_axis(ndarray::Axis(1), (ix * input_dim..(ix + 1) * input_dim).into()).t(),
+        );
+    }
+    // bias is optional (use-bias=false)
+    let bias = match component.attributes.get("BiasParams").filter(|b| b.len() > 0) {
+        Some(bias) if bias.len() != output_dim => {
+            bail!("TdnnComponent {} has {} bias params, expected {}", name, bias.len(), output_dim)
+        }
+        Some(bias) => bias.clone(),
+        None => ndarray::Array1::<f32>::zeros(output_dim).into_arc_tensor(),
+    };
+    Ok(Box::new(Affine {
+        kernel_len: span / dilation + 1,
+        dilation,
+        linear_params: t_i_o.into_arc_tensor(),
+        bias_params: bias,
+    }))
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
 #[derive(Clone, Debug, new)]
 struct Affine {
     kernel_len: usize,
@@ -43,6 +114,7 @@ impl Affine {
         let conv = Conv::default()
             .nhwc()
             .hwio()
+            .bias_input(2)
             .dilations(tvec!(self.dilation))
             .kernel_shape(tvec!(self.kernel_len));
         trace!("{:?} -> {:?}", self, conv);
@@ -0,0 +1,137 @@
+use tract_core::internal::*;
+use tract_core::ndarray;
+
+use crate::model::ParsingContext;
+
+fn dims(ctx: &ParsingContext, name: &str) -> TractResult<(usize, usize)> {
+    let component = super::component(ctx, name)?;
+    let dim = |name: &str| -> TractResult<usize> {
+        let dim =
+            component.attributes.get(name).ok_or_else(|| format!("missing attribute {}", name))?;
+        Ok(dim.cast_to_scalar::<i32>()? as usize)
+    };
+    let (input_dim, output_dim) = (dim("InputDim")?, dim("OutputDim")?);
+    if output_dim == 0 || input_dim % output_dim != 0 {
+        bail!("InputDim ({}) must be a multiple of OutputDim ({})", input_dim, output_dim)
+    }
+    Ok((input_dim, output_dim))
+}
+
+pub fn sum_block(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let (_, output_dim) = dims(ctx, name)?;
+    let scale = super::component(ctx, name)?
+        .attributes
+        .get("Scale")
+        .map(|s| s.cast_to_scalar::<f32>())
+        .transpose()?
+        .unwrap_or(1.0);
+    Ok(Box::new(Block::new(output_dim, BlockReducer::Sum(scale))))
+}
+
+pub fn elementwise_product(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let (_, output_dim) = dims(ctx, name)?;
+    Ok(Box::new(Block::new(output_dim, BlockReducer::Product)))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BlockReducer {
+    /// Scaled sum of the blocks
+    Sum(f32),
+    /// Elementwise product of the blocks
+    Product,
+}
+
+/// Combines the consecutive blocks of `output_dim` of each input frame.
+#[derive(Clone, Debug, new)]
+pub struct Block {
+    output_dim: usize,
+    reducer: BlockReducer,
+}
+
+impl Op for Block {
+    fn name(&self) -> std::borrow::Cow<str> {
+        match self.reducer {
+            BlockReducer::Sum(_) => "kaldi.SumBlock".into(),
+            BlockReducer::Product => "kaldi.ElementwiseProduct".into(),
+        }
+    }
+
+    op_as_typed_op!();
+    op_as_pulsed_op!();
+}
+
+impl StatelessOp for Block {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let input = input.to_array_view::<f32>()?.into_dimensionality::<ndarray::Ix2>()?;
+        let blocks = input.shape()[1] / self.output_dim;
+        let output =
+            ndarray::Array2::from_shape_fn((input.shape()[0], self.output_dim), |(t, d)| {
+                let block = (0..blocks).map(|b| input[(t, b * self.output_dim + d)]);
+                match self.reducer {
+                    BlockReducer::Sum(scale) => scale * block.sum::<f32>(),
+                    BlockReducer::Product => block.product(),
+                }
+            });
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Block {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[0], &outputs[0].shape[0])?;
+        s.equals(&outputs[0].shape[1], self.output_dim.to_dim())?;
+        Ok(())
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Block {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(TypedFact::dt_shape(
+            f32::datum_type(),
+            [inputs[0].shape.dim(0), self.output_dim.to_dim()].as_ref()
+        )?))
+    }
+
+    fn axes_info(&self, _model: &TypedModel, _node: &TypedNode) -> TractResult<AxesInfo> {
+        Ok(vec![AxisInfo::simple(0)].into_iter().collect())
+    }
+
+    fn pulsify(
+        &self,
+        _source: &NormalizedModel,
+        node: &NormalizedNode,
+        target: &mut PulsedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        _pulse: usize,
+    ) -> TractResult<TVec<OutletId>> {
+        target.wire_node(&*node.name, self.clone(), &[mapping[&node.inputs[0]]])
+    }
+}
+
+impl PulsedOp for Block {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.shape[1] = self.output_dim;
+        Ok(tvec!(fact))
+    }
+
+    pulsed_op_as_op!();
+    pulsed_op_to_typed_op!();
+}
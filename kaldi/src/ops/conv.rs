@@ -0,0 +1,255 @@
+use tract_core::internal::*;
+use tract_core::ndarray;
+use tract_core::ndarray::Axis;
+
+use crate::model::Component;
+use crate::model::ParsingContext;
+
+fn int_attribute(component: &Component, name: &str) -> TractResult<usize> {
+    let value =
+        component.attributes.get(name).ok_or_else(|| format!("missing attribute {}", name))?;
+    Ok(value.cast_to_scalar::<i32>()? as usize)
+}
+
+/// (time, height) offsets of a TimeHeightConvolutionComponent.
+fn offsets(component: &Component) -> TractResult<Vec<(isize, isize)>> {
+    let offsets = component.attributes.get("Offsets").ok_or("missing attribute Offsets")?;
+    let offsets = offsets.cast_to::<i32>()?;
+    let offsets = offsets.to_array_view::<i32>()?.into_dimensionality::<ndarray::Ix2>()?;
+    if offsets.shape()[0] == 0 || offsets.shape()[1] != 2 {
+        bail!("Invalid Offsets {:?}", offsets)
+    }
+    Ok(offsets.outer_iter().map(|pair| (pair[0] as isize, pair[1] as isize)).collect())
+}
+
+/// Distinct time offsets of a TimeHeightConvolutionComponent, in increasing
+/// order. They are all considered required.
+pub fn time_offsets(component: &Component) -> TractResult<Vec<isize>> {
+    let mut times: Vec<isize> = offsets(component)?.into_iter().map(|(t, _)| t).collect();
+    times.sort();
+    times.dedup();
+    Ok(times)
+}
+
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Dilation and number of taps of a kernel covering the offsets from `start`.
+fn taps(offsets: impl Iterator<Item = isize> + Clone, start: isize) -> (usize, usize) {
+    let span = offsets.clone().map(|o| (o - start) as usize).max().unwrap_or(0);
+    let dilation = offsets.fold(span, |g, o| gcd(g, (o - start) as usize)).max(1);
+    (dilation, span / dilation + 1)
+}
+
+/// A TimeHeightConvolutionComponent is a 2D convolution over (time, height),
+/// the input and output frames being height-major (height * filters).
+/// Heights out of the input are zero-padded, time is not.
+pub fn time_height_convolution(
+    ctx: &ParsingContext,
+    name: &str,
+) -> TractResult<Box<dyn InferenceOp>> {
+    let component = super::component(ctx, name)?;
+    let filters_in = int_attribute(component, "NumFiltersIn")?;
+    let filters_out = int_attribute(component, "NumFiltersOut")?;
+    let height_in = int_attribute(component, "HeightIn")?;
+    let height_out = int_attribute(component, "HeightOut")?;
+    let height_subsample = int_attribute(component, "HeightSubsampleOut")?;
+    let offsets = offsets(component)?;
+    let linear: &Tensor =
+        component.attributes.get("LinearParams").ok_or("missing attribute LinearParams")?;
+    let o_ti = linear.to_array_view::<f32>()?.into_dimensionality::<ndarray::Ix2>()?;
+    if o_ti.shape() != &[filters_out, offsets.len() * filters_in] {
+        bail!(
+            "LinearParams shape is {:?}, expected {}x{}",
+            o_ti.shape(),
+            filters_out,
+            offsets.len() * filters_in
+        )
+    }
+
+    let time_start = offsets.iter().map(|o| o.0).min().unwrap();
+    let (time_dilation, time_taps) = taps(offsets.iter().map(|o| o.0), time_start);
+    // the kernel starts at or before the output height, so the padding stays positive
+    let height_start = offsets.iter().map(|o| o.1).min().unwrap().min(0);
+    let (height_dilation, height_taps) = taps(offsets.iter().map(|o| o.1), height_start);
+    let last_height =
+        ((height_out - 1) * height_subsample) as isize + offsets.iter().map(|o| o.1).max().unwrap();
+    let height_padding =
+        ((-height_start) as usize, (last_height - height_in as isize + 1).max(0) as usize);
+
+    let mut kernel =
+        ndarray::Array4::<f32>::zeros((time_taps, height_taps, filters_in, filters_out));
+    for (ix, &(time, height)) in offsets.iter().enumerate() {
+        let mut tap = kernel
+            .index_axis_mut(Axis(0), (time - time_start) as usize / time_dilation)
+            .index_axis_move(Axis(0), (height - height_start) as usize / height_dilation);
+        tap += &o_ti.slice_axis(Axis(1), (ix * filters_in..(ix + 1) * filters_in).into()).t();
+    }
+    let bias = component.attributes.get("BiasParams").ok_or("missing attribute BiasParams")?;
+    if bias.len() != filters_out {
+        bail!(
+            "TimeHeightConvolutionComponent {} has {} bias params, expected {}",
+            name,
+            bias.len(),
+            filters_out
+        )
+    }
+    Ok(Box::new(TimeHeightConvolution {
+        height_in,
+        filters_in,
+        height_out,
+        filters_out,
+        height_subsample,
+        height_padding,
+        dilations: (time_dilation, height_dilation),
+        kernel: kernel.into_arc_tensor(),
+        bias: bias.clone(),
+    }))
+}
+
+#[derive(Clone, Debug)]
+struct TimeHeightConvolution {
+    height_in: usize,
+    filters_in: usize,
+    height_out: usize,
+    filters_out: usize,
+    height_subsample: usize,
+    height_padding: (usize, usize),
+    dilations: (usize, usize),
+    kernel: Arc<Tensor>, // HWIO
+    bias: Arc<Tensor>,
+}
+
+impl TimeHeightConvolution {
+    fn as_conv(&self) -> tract_core::ops::cnn::Conv {
+        use tract_core::ops::cnn::*;
+        let conv = Conv::default()
+            .nhwc()
+            .hwio()
+            .bias_input(2)
+            .dilations(tvec!(self.dilations.0, self.dilations.1))
+            .strides(tvec!(1, self.height_subsample))
+            .padding(PaddingSpec::Explicit(
+                tvec!(0, self.height_padding.0),
+                tvec!(0, self.height_padding.1),
+            ))
+            .kernel_shape(self.kernel.shape()[..2].into());
+        trace!("{:?} -> {:?}", self, conv);
+        conv
+    }
+
+    /// Frames consumed by the convolution on the time axis.
+    fn time_context(&self) -> usize {
+        (self.kernel.shape()[0] - 1) * self.dilations.0
+    }
+}
+
+impl Op for TimeHeightConvolution {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "kaldi.TimeHeightConvolution".into()
+    }
+
+    not_a_typed_op!();
+}
+
+impl StatelessOp for TimeHeightConvolution {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let frames = input.shape()[0];
+        let input = input.into_tensor().into_array::<f32>()?.into_shape((
+            1,
+            frames,
+            self.height_in,
+            self.filters_in,
+        ))?;
+        let output = self
+            .as_conv()
+            .eval(tvec!(input.into_arc_tensor(), self.kernel.clone(), self.bias.clone()))?
+            .remove(0);
+        let output = output.to_array_view::<f32>()?;
+        let output = output.slice_axis(Axis(2), (0..self.height_out).into());
+        let frames = output.shape()[1];
+        let output = output.to_owned().into_shape((frames, self.height_out * self.filters_out))?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for TimeHeightConvolution {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[1], (self.height_in * self.filters_in).to_dim())?;
+        s.equals(&outputs[0].shape[1], (self.height_out * self.filters_out).to_dim())?;
+        s.given(&inputs[0].shape[0], move |s, frames| {
+            s.equals(&outputs[0].shape[0], frames - self.time_context().to_dim())
+        })?;
+        Ok(())
+    }
+
+    inference_op_as_op!();
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        use tract_core::ops::array::{AddDims, RmDims, Slice, TypedReshape};
+        let input = mapping[&node.inputs[0]];
+        let frames = target.outlet_fact(input)?.shape.dim(0);
+        let mut wire = target.wire_node(
+            format!("{}-SplitHeight", node.name),
+            TypedReshape::new(tvec!(
+                frames.clone(),
+                self.height_in.to_dim(),
+                self.filters_in.to_dim()
+            )),
+            &[input],
+        )?[0];
+        wire = target.wire_node(
+            format!("{}-AddBatchDim", node.name),
+            AddDims::new(vec![0]),
+            &[wire],
+        )?[0];
+        let kernel = target.add_const(format!("{}-Kernel", node.name), self.kernel.clone())?;
+        let bias = target.add_const(format!("{}-Bias", node.name), self.bias.clone())?;
+        wire = target.wire_node(
+            format!("{}-Conv", node.name),
+            self.as_conv(),
+            &[wire, kernel.into(), bias.into()],
+        )?[0];
+        wire =
+            target.wire_node(format!("{}-RmBatchDim", node.name), RmDims::new(vec![0]), &[wire])?
+                [0];
+        if target.outlet_fact(wire)?.shape.dim(1) != self.height_out.to_dim() {
+            wire = target.wire_node(
+                format!("{}-CropHeight", node.name),
+                Slice::new(1, 0, self.height_out),
+                &[wire],
+            )?[0];
+        }
+        target.wire_node(
+            &*node.name,
+            TypedReshape::new(tvec!(
+                frames - self.time_context().to_dim(),
+                (self.height_out * self.filters_out).to_dim()
+            )),
+            &[wire],
+        )
+    }
+}
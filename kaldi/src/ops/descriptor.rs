@@ -0,0 +1,623 @@
+//! Operators for the nnet3 descriptors with no direct core equivalent.
+//!
+//! Time is the first axis. `Round` and `Switch` depend on the time of each
+//! frame: in pulsed mode, the pulse must be a multiple of their modulus.
+
+use tract_core::internal::*;
+use tract_core::ndarray::*;
+use tract_core::pulse::delay::Delay;
+
+/// Repeats a single frame along the time axis of a reference input.
+///
+/// The frame is either `rows`, or the first input (then the reference is the
+/// second input). In pulsed mode, a frame input must not be streaming: it is
+/// fed whole with every pulse.
+#[derive(Clone, Debug, new)]
+pub struct Broadcast {
+    pub rows: Option<Arc<Tensor>>,
+}
+
+impl Op for Broadcast {
+    fn name(&self) -> Cow<str> {
+        "kaldi.Broadcast".into()
+    }
+
+    op_as_typed_op!();
+    op_as_pulsed_op!();
+}
+
+impl StatelessOp for Broadcast {
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (rows, reference) = match &self.rows {
+            Some(rows) => (rows, &inputs[0]),
+            None => (&inputs[0], &inputs[1]),
+        };
+        let rows = rows.to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+        let output =
+            rows.broadcast((reference.shape()[0], rows.shape()[1])).ok_or("Can not broadcast")?;
+        Ok(tvec!(output.to_owned().into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Broadcast {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, if self.rows.is_some() { 1 } else { 2 })?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].rank, 2)?;
+        let reference = &inputs[inputs.len() - 1];
+        s.equals(&outputs[0].shape[0], &reference.shape[0])?;
+        if let Some(rows) = &self.rows {
+            s.equals(&outputs[0].shape[1], rows.shape()[1].to_dim())?;
+        } else {
+            s.equals(&outputs[0].shape[1], &inputs[0].shape[1])?;
+        }
+        Ok(())
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Broadcast {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let frames = inputs[inputs.len() - 1].shape.dim(0);
+        let dim = match &self.rows {
+            Some(rows) => rows.shape()[1].to_dim(),
+            None => inputs[0].shape.dim(1),
+        };
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), [frames, dim].as_ref())?))
+    }
+
+    fn pulsify(
+        &self,
+        _source: &NormalizedModel,
+        node: &NormalizedNode,
+        target: &mut PulsedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        _pulse: usize,
+    ) -> TractResult<TVec<OutletId>> {
+        let inputs = node.inputs.iter().map(|i| mapping[i]).collect::<TVec<_>>();
+        if self.rows.is_none() && target.outlet_fact(inputs[0])?.is_streaming() {
+            bail!("Broadcast of a frame of a stream can not be pulsified")
+        }
+        target.wire_node(&*node.name, self.clone(), &*inputs)
+    }
+}
+
+impl PulsedOp for Broadcast {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[inputs.len() - 1].clone();
+        fact.datum_type = f32::datum_type();
+        fact.shape[1] = match &self.rows {
+            Some(rows) => rows.shape()[1],
+            None => inputs[0].shape[1],
+        };
+        Ok(tvec!(fact))
+    }
+
+    pulsed_op_as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+/// Repeats each frame of a subsampled input `period` times, along the time
+/// axis of a reference input (the second input).
+#[derive(Clone, Debug, new)]
+pub struct Upsample {
+    pub period: usize,
+}
+
+impl Op for Upsample {
+    fn name(&self) -> Cow<str> {
+        "kaldi.Upsample".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("period: {}", self.period)])
+    }
+
+    op_as_typed_op!();
+    op_as_pulsed_op!();
+}
+
+impl StatelessOp for Upsample {
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let rows = inputs[0].to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+        if rows.shape()[0] == 0 {
+            bail!("Upsample of an empty input")
+        }
+        let frames = inputs[1].shape()[0];
+        let last = rows.shape()[0] - 1;
+        let output = Array2::from_shape_fn((frames, rows.shape()[1]), |(t, d)| {
+            rows[((t / self.period).min(last), d)]
+        });
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Upsample {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&outputs[0].shape[0], &inputs[1].shape[0])?;
+        s.equals(&outputs[0].shape[1], &inputs[0].shape[1])?;
+        Ok(())
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Upsample {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let shape = [inputs[1].shape.dim(0), inputs[0].shape.dim(1)];
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), shape.as_ref())?))
+    }
+
+    fn pulsify(
+        &self,
+        _source: &NormalizedModel,
+        node: &NormalizedNode,
+        target: &mut PulsedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        _pulse: usize,
+    ) -> TractResult<TVec<OutletId>> {
+        let mut rows = mapping[&node.inputs[0]];
+        let mut reference = mapping[&node.inputs[1]];
+        let rows_fact = target.outlet_fact(rows)?.clone();
+        let reference_fact = target.outlet_fact(reference)?.clone();
+        if rows_fact.pulse() * self.period != reference_fact.pulse() {
+            bail!(
+                "Upsample({}) requires a pulse multiple of its period (got {} subsampled frames for {} frames)",
+                self.period,
+                rows_fact.pulse(),
+                reference_fact.pulse()
+            )
+        }
+        // delay both inputs so that each pulse of the subsampled input covers
+        // the pulse of the reference
+        let delay = reference_fact.delay.div_ceil(self.period).max(rows_fact.delay);
+        if delay > rows_fact.delay {
+            rows = target.wire_node(
+                format!("{}/Delay-0", node.name),
+                Delay::new(&rows_fact, delay - rows_fact.delay, 0),
+                &[rows],
+            )?[0];
+        }
+        if delay * self.period > reference_fact.delay {
+            reference = target.wire_node(
+                format!("{}/Delay-1", node.name),
+                Delay::new(&reference_fact, delay * self.period - reference_fact.delay, 0),
+                &[reference],
+            )?[0];
+        }
+        target.wire_node(&*node.name, self.clone(), &[rows, reference])
+    }
+}
+
+impl PulsedOp for Upsample {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[1].clone();
+        fact.shape[1] = inputs[0].shape[1];
+        Ok(tvec!(fact))
+    }
+
+    pulsed_op_as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+/// Round descriptor: each frame is replaced by the last frame at a time
+/// multiple of `modulus`. The first input frame must be at such a time.
+#[derive(Clone, Debug, new)]
+pub struct Round {
+    pub modulus: usize,
+}
+
+impl Op for Round {
+    fn name(&self) -> Cow<str> {
+        "kaldi.Round".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("modulus: {}", self.modulus)])
+    }
+
+    op_as_typed_op!();
+    op_as_pulsed_op!();
+}
+
+impl StatelessOp for Round {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let input = input.to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+        let output = Array2::from_shape_fn(input.dim(), |(t, d)| input[(t - t % self.modulus, d)]);
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Round {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Round {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    fn pulsify(
+        &self,
+        _source: &NormalizedModel,
+        node: &NormalizedNode,
+        target: &mut PulsedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        _pulse: usize,
+    ) -> TractResult<TVec<OutletId>> {
+        let mut input = mapping[&node.inputs[0]];
+        let fact = target.outlet_fact(input)?.clone();
+        if fact.pulse() % self.modulus != 0 {
+            bail!("Round({}) requires a pulse multiple of its modulus", self.modulus)
+        }
+        // delay the stream so that each pulse starts with a held frame
+        if fact.delay % self.modulus != 0 {
+            input = target.wire_node(
+                format!("{}/Delay", node.name),
+                Delay::new(&fact, self.modulus - fact.delay % self.modulus, 0),
+                &[input],
+            )?[0];
+        }
+        target.wire_node(&*node.name, self.clone(), &[input])
+    }
+}
+
+impl PulsedOp for Round {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    pulsed_op_as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+/// Switch descriptor: frame at time `t` comes from input `t % inputs`.
+/// `phase` is the time of the first frame, modulo the number of inputs.
+/// Single frame inputs apply at all times.
+#[derive(Clone, Debug, new)]
+pub struct Switch {
+    pub phase: usize,
+}
+
+impl Op for Switch {
+    fn name(&self) -> Cow<str> {
+        "kaldi.Switch".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("phase: {}", self.phase)])
+    }
+
+    op_as_typed_op!();
+    op_as_pulsed_op!();
+}
+
+impl StatelessOp for Switch {
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let inputs = inputs
+            .iter()
+            .map(|i| i.to_array_view::<f32>()?.into_dimensionality::<Ix2>().map_err(|e| e.into()))
+            .collect::<TractResult<TVec<_>>>()?;
+        let frames = inputs.iter().map(|i| i.shape()[0]).max().unwrap_or(0);
+        let output = Array2::from_shape_fn((frames, inputs[0].shape()[1]), |(t, d)| {
+            let input = &inputs[(t + self.phase) % inputs.len()];
+            input[(if input.shape()[0] == 1 { 0 } else { t }, d)]
+        });
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Switch {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].rank, 2)?;
+        for input in inputs {
+            s.equals(&input.datum_type, &outputs[0].datum_type)?;
+            s.equals(&input.shape[1], &outputs[0].shape[1])?;
+        }
+        s.given_all(inputs.iter().map(|i| &i.shape[0]), move |s, frames| {
+            if let Some(frames) = frames.into_iter().find(|f| *f != 1.to_dim()) {
+                s.equals(&outputs[0].shape[0], frames)?;
+            }
+            Ok(())
+        })?;
+        Ok(())
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Switch {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let reference = inputs.iter().find(|i| i.shape.dim(0) != 1.to_dim()).unwrap_or(&inputs[0]);
+        Ok(tvec!((*reference).clone()))
+    }
+
+    fn pulsify(
+        &self,
+        _source: &NormalizedModel,
+        node: &NormalizedNode,
+        target: &mut PulsedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        _pulse: usize,
+    ) -> TractResult<TVec<OutletId>> {
+        let n = node.inputs.len();
+        let facts = node
+            .inputs
+            .iter()
+            .map(|i| Ok(target.outlet_fact(mapping[i])?.clone()))
+            .collect::<TractResult<TVec<_>>>()?;
+        let delay = facts.iter().map(|f| f.delay).max().unwrap();
+        if facts[0].pulse() % n != 0 {
+            bail!("Switch of {} inputs requires a pulse multiple of {}", n, n)
+        }
+        let mut inputs = tvec!();
+        for (ix, fact) in facts.iter().enumerate() {
+            let mut input = mapping[&node.inputs[ix]];
+            if fact.delay < delay {
+                input = target.wire_node(
+                    format!("{}/Delay-{}", node.name, ix),
+                    Delay::new(&fact, delay - fact.delay, 0),
+                    &[input],
+                )?[0];
+            }
+            inputs.push(input);
+        }
+        let phase = (self.phase + n - delay % n) % n;
+        target.wire_node(&*node.name, Switch::new(phase), &*inputs)
+    }
+}
+
+impl PulsedOp for Switch {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    pulsed_op_as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+/// Failover descriptor: frame `i` comes from frame `i + shift` of the first
+/// input if it exists, and from frame `i` of the second input otherwise.
+#[derive(Clone, Debug, new)]
+pub struct Failover {
+    pub shift: isize,
+}
+
+impl Op for Failover {
+    fn name(&self) -> Cow<str> {
+        "kaldi.Failover".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("shift: {}", self.shift)])
+    }
+
+    op_as_typed_op!();
+    not_a_pulsed_op!();
+}
+
+impl StatelessOp for Failover {
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let primary = inputs[0].to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+        let fallback = inputs[1].to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+        let len = primary.shape()[0] as isize;
+        let output = Array2::from_shape_fn(fallback.dim(), |(t, d)| {
+            let source = t as isize + self.shift;
+            if source >= 0 && source < len {
+                primary[(source as usize, d)]
+            } else {
+                fallback[(t, d)]
+            }
+        });
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Failover {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[1].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&inputs[1].shape, &outputs[0].shape)?;
+        s.equals(&inputs[0].shape[1], &outputs[0].shape[1])?;
+        Ok(())
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Failover {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[1].clone()))
+    }
+
+    fn pulsify(
+        &self,
+        _source: &NormalizedModel,
+        node: &NormalizedNode,
+        target: &mut PulsedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        _pulse: usize,
+    ) -> TractResult<TVec<OutletId>> {
+        let facts = node
+            .inputs
+            .iter()
+            .map(|i| Ok(target.outlet_fact(mapping[i])?.clone()))
+            .collect::<TractResult<TVec<_>>>()?;
+        // align frame `i + shift` of the primary with frame `i` of the fallback
+        let delay = (facts[1].delay as isize).max(facts[0].delay as isize + self.shift) as usize;
+        let delays = [(delay as isize - self.shift) as usize, delay];
+        let mut inputs = tvec!();
+        for (ix, fact) in facts.iter().enumerate() {
+            let mut input = mapping[&node.inputs[ix]];
+            if fact.delay < delays[ix] {
+                input = target.wire_node(
+                    format!("{}/Delay-{}", node.name, ix),
+                    Delay::new(&fact, delays[ix] - fact.delay, 0),
+                    &[input],
+                )?[0];
+            }
+            inputs.push(input);
+        }
+        let begin = delay + (-self.shift).max(0) as usize;
+        let end = facts[0].dim.clone() + delay - self.shift;
+        let op = PulsedFailover::new(facts[1].pulse(), begin, end);
+        target.wire_node(&*node.name, op, &*inputs)
+    }
+}
+
+/// Pulsed Failover, on aligned inputs: the first input is used for the stream
+/// positions from `begin` to `end`, the second one elsewhere.
+#[derive(Clone, Debug, new)]
+pub struct PulsedFailover {
+    pub pulse: usize,
+    pub begin: usize,
+    pub end: TDim,
+}
+
+impl Op for PulsedFailover {
+    fn name(&self) -> Cow<str> {
+        "kaldi.PulsedFailover".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("begin: {} end: {:?}", self.begin, self.end)])
+    }
+
+    op_as_typed_op!();
+    op_as_pulsed_op!();
+}
+
+impl StatefullOp for PulsedFailover {
+    fn state(
+        &self,
+        _session: &mut SessionState,
+        _node_id: usize,
+    ) -> TractResult<Option<Box<dyn OpState>>> {
+        Ok(Some(Box::new(PulsedFailoverState::default())))
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+struct PulsedFailoverState {
+    current_pos: usize,
+}
+
+impl OpState for PulsedFailoverState {
+    fn eval(
+        &mut self,
+        session: &mut SessionState,
+        op: &dyn Op,
+        inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let op = op.downcast_ref::<PulsedFailover>().ok_or("Wrong Op type")?;
+        let pulse_begin = self.current_pos;
+        self.current_pos += op.pulse;
+        let end = session
+            .known_stream_len
+            .map(|s| op.end.eval(s as i32).unwrap().max(0) as usize)
+            .unwrap_or(std::usize::MAX);
+        if pulse_begin >= op.begin && pulse_begin + op.pulse <= end {
+            return Ok(tvec!(inputs[0].clone()));
+        }
+        let primary = inputs[0].to_array_view::<f32>()?;
+        let fallback = inputs[1].to_array_view::<f32>()?;
+        let output = ArrayD::from_shape_fn(fallback.shape(), |coords| {
+            let pos = pulse_begin + coords[0];
+            if pos >= op.begin && pos < end {
+                primary[&coords]
+            } else {
+                fallback[&coords]
+            }
+        });
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!(tensor0(self.current_pos as i64)))
+    }
+
+    fn load(&mut self, tensors: TVec<Tensor>) -> TractResult<()> {
+        let current_pos = tensors.first().ok_or("Failover state expects a position")?;
+        self.current_pos = *current_pos.to_scalar::<i64>()? as usize;
+        Ok(())
+    }
+}
+
+impl TypedOp for PulsedFailover {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[1].clone()))
+    }
+}
+
+impl PulsedOp for PulsedFailover {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        Ok(tvec!(inputs[1].clone()))
+    }
+
+    pulsed_op_as_op!();
+    pulsed_op_to_typed_op!();
+}
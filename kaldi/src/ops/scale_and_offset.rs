@@ -0,0 +1,114 @@
+use tract_core::internal::*;
+use tract_core::ndarray;
+
+use crate::model::ParsingContext;
+
+pub fn scale_and_offset(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let component = super::component(ctx, name)?;
+    let scales = component.attributes.get("Scales").ok_or("missing attribute Scales")?;
+    let offsets = component.attributes.get("Offsets").ok_or("missing attribute Offsets")?;
+    let dim = component.attributes.get("Dim").ok_or("missing attribute Dim")?;
+    let dim = dim.cast_to_scalar::<i32>()? as usize;
+    Ok(Box::new(ScaleAndOffset::new(
+        tile(scales.to_array_view::<f32>()?, dim)?,
+        tile(offsets.to_array_view::<f32>()?, dim)?,
+    )))
+}
+
+/// BatchNormComponent in test mode: normalizes with the stored statistics.
+pub fn batch_norm(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let component = super::component(ctx, name)?;
+    let attr = |name: &str| {
+        component.attributes.get(name).ok_or_else(|| format!("missing attribute {}", name))
+    };
+    let dim = attr("Dim")?.cast_to_scalar::<i32>()? as usize;
+    let epsilon = attr("Epsilon")?.cast_to_scalar::<f32>()?;
+    let target_rms = attr("TargetRms")?.cast_to_scalar::<f32>()?;
+    let mean = attr("StatsMean")?.to_array_view::<f32>()?;
+    let var = attr("StatsVar")?.to_array_view::<f32>()?;
+    let scales = var.mapv(|v| target_rms * (v.max(0.0) + epsilon).powf(-0.5));
+    let offsets = -&mean * &scales;
+    Ok(Box::new(ScaleAndOffset::new(tile(scales.view(), dim)?, tile(offsets.view(), dim)?)))
+}
+
+/// DropoutComponent in test mode scales its input by the kept proportion.
+pub fn dropout(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let component = super::component(ctx, name)?;
+    let proportion = component
+        .attributes
+        .get("DropoutProportion")
+        .ok_or("missing attribute DropoutProportion")?
+        .cast_to_scalar::<f32>()?;
+    Ok(Box::new(ScaleAndOffset::new(rctensor1(&[1.0 - proportion]), rctensor1(&[0.0f32]))))
+}
+
+/// Repeat per-block parameters along a dimension of `dim`.
+fn tile(v: ndarray::ArrayViewD<f32>, dim: usize) -> TractResult<Arc<Tensor>> {
+    if v.len() == 0 || dim % v.len() != 0 {
+        bail!("Can not tile a vector of {} on {} dimensions", v.len(), dim)
+    }
+    Ok(ndarray::Array1::from_shape_fn(dim, |i| v[i % v.len()]).into_arc_tensor())
+}
+
+/// `input * scales + offsets`, scales and offsets being broadcast along the
+/// time axis.
+#[derive(Clone, Debug, new)]
+pub struct ScaleAndOffset {
+    scales: Arc<Tensor>,
+    offsets: Arc<Tensor>,
+}
+
+impl Op for ScaleAndOffset {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "kaldi.ScaleAndOffset".into()
+    }
+
+    not_a_typed_op!();
+}
+
+impl StatelessOp for ScaleAndOffset {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let mut output = input.into_tensor().into_array::<f32>()?;
+        output *= &self.scales.to_array_view::<f32>()?;
+        output += &self.offsets.to_array_view::<f32>()?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for ScaleAndOffset {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    inference_op_as_op!();
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let scaled = target.wire_node(
+            format!("{}-Scale", node.name),
+            tract_core::ops::math::mul::unary(self.scales.clone()),
+            [mapping[&node.inputs[0]]].as_ref(),
+        )?;
+        target.wire_node(
+            &*node.name,
+            tract_core::ops::math::add::unary(self.offsets.clone()),
+            &*scaled,
+        )
+    }
+}
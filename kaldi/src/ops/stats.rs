@@ -0,0 +1,630 @@
+use tract_core::internal::*;
+use tract_core::ndarray;
+use tract_core::ndarray::Axis;
+
+use crate::model::ParsingContext;
+
+fn attribute<T: Datum + Copy>(ctx: &ParsingContext, name: &str, attribute: &str) -> TractResult<T> {
+    let component = super::component(ctx, name)?;
+    let value = component
+        .attributes
+        .get(attribute)
+        .ok_or_else(|| format!("missing attribute {}", attribute))?;
+    Ok(value.cast_to_scalar::<T>()?)
+}
+
+pub fn statistics_extraction(
+    ctx: &ParsingContext,
+    name: &str,
+) -> TractResult<Box<dyn InferenceOp>> {
+    let input_period = attribute::<i32>(ctx, name, "InputPeriod")? as usize;
+    let output_period = attribute::<i32>(ctx, name, "OutputPeriod")? as usize;
+    if input_period != 1 {
+        bail!("StatisticsExtractionComponent only supports InputPeriod=1 (got {})", input_period)
+    }
+    if output_period == 0 {
+        bail!("Invalid OutputPeriod {}", output_period)
+    }
+    Ok(Box::new(StatisticsExtraction {
+        input_dim: attribute::<i32>(ctx, name, "InputDim")? as usize,
+        output_period,
+        include_variance: attribute::<bool>(ctx, name, "IncludeVarinance")?,
+    }))
+}
+
+pub fn statistics_pooling(ctx: &ParsingContext, name: &str) -> TractResult<Box<dyn InferenceOp>> {
+    let input_period = attribute::<i32>(ctx, name, "InputPeriod")? as usize;
+    let left_context = attribute::<i32>(ctx, name, "LeftContext")? as usize;
+    let right_context = attribute::<i32>(ctx, name, "RightContext")? as usize;
+    if input_period == 0 || left_context % input_period != 0 || right_context % input_period != 0 {
+        bail!(
+            "StatisticsPoolingComponent contexts ({}, {}) must be multiples of InputPeriod ({})",
+            left_context,
+            right_context,
+            input_period
+        )
+    }
+    let input_dim = attribute::<i32>(ctx, name, "InputDim")? as usize;
+    let output_stddevs = attribute::<bool>(ctx, name, "OutputStddevs")?;
+    if input_dim < 2 || (output_stddevs && input_dim % 2 == 0) {
+        bail!("Invalid InputDim {} for StatisticsPoolingComponent", input_dim)
+    }
+    Ok(Box::new(StatisticsPooling {
+        input_dim,
+        input_period,
+        left_context,
+        right_context,
+        num_log_count_features: attribute::<i32>(ctx, name, "NumLogCountFeatures")? as usize,
+        output_stddevs,
+        variance_floor: attribute::<f32>(ctx, name, "VarianceFloor")?,
+    }))
+}
+
+/// Sum over a window of `kernel` frames spaced by `dilation`, the first one
+/// `before` frames in the past. Frames out of the signal count as zeroes.
+#[derive(Clone, Debug)]
+struct WindowSum {
+    before: usize,
+    after: usize,
+    kernel: usize,
+    dilation: usize,
+}
+
+impl WindowSum {
+    fn eval(&self, input: ndarray::ArrayView2<f32>) -> ndarray::Array2<f32> {
+        let len = input.shape()[0] as isize;
+        let mut output = ndarray::Array2::<f32>::zeros(input.raw_dim());
+        for t in 0..len {
+            for k in 0..self.kernel {
+                let source = t - self.before as isize + (k * self.dilation) as isize;
+                if source >= 0 && source < len {
+                    let mut row = output.index_axis_mut(Axis(0), t as usize);
+                    row += &input.index_axis(Axis(0), source as usize);
+                }
+            }
+        }
+        output
+    }
+
+    /// Zero padding followed by an average pooling on the time axis, scaled
+    /// back to a sum. Both translate to pulsing.
+    fn wire(
+        &self,
+        target: &mut TypedModel,
+        name: &str,
+        output_name: String,
+        input: OutletId,
+    ) -> TractResult<OutletId> {
+        use tract_core::ops::array::{AddDims, Pad, PadMode, RmDims};
+        use tract_core::ops::cnn::{AvgPool, PaddingSpec, PoolSpec};
+        use tract_core::ops::nn::DataFormat;
+        let mut wire =
+            target.wire_node(format!("{}-AddBatchDim", name), AddDims::new(vec![0]), &[input])?[0];
+        wire = target.wire_node(
+            format!("{}-Pad", name),
+            Pad::new(
+                vec![(0, 0), (self.before, self.after), (0, 0)],
+                PadMode::Constant(rctensor0(0.0f32)),
+            ),
+            &[wire],
+        )?[0];
+        let pool_spec = PoolSpec::new(
+            DataFormat::NHWC,
+            tvec!(self.kernel),
+            PaddingSpec::Valid,
+            Some(tvec!(self.dilation)),
+            None,
+            None,
+        );
+        wire = target.wire_node(
+            format!("{}-Window", name),
+            AvgPool::new(pool_spec, false),
+            &[wire],
+        )?[0];
+        wire = target.wire_node(format!("{}-RmBatchDim", name), RmDims::new(vec![0]), &[wire])?[0];
+        Ok(target.wire_node(
+            output_name,
+            tract_core::ops::math::mul::unary(rctensor0(self.kernel as f32)),
+            &[wire],
+        )?[0])
+    }
+}
+
+/// StatisticsExtractionComponent: for each frame, the count, sum and
+/// optionally sum of squares of the `output_period` frames starting there.
+#[derive(Clone, Debug)]
+pub struct StatisticsExtraction {
+    input_dim: usize,
+    output_period: usize,
+    include_variance: bool,
+}
+
+impl StatisticsExtraction {
+    fn output_dim(&self) -> usize {
+        1 + self.input_dim * if self.include_variance { 2 } else { 1 }
+    }
+
+    fn window(&self) -> WindowSum {
+        WindowSum {
+            before: 0,
+            after: self.output_period - 1,
+            kernel: self.output_period,
+            dilation: 1,
+        }
+    }
+}
+
+impl Op for StatisticsExtraction {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "kaldi.StatisticsExtraction".into()
+    }
+
+    not_a_typed_op!();
+}
+
+impl StatelessOp for StatisticsExtraction {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let moments = Moments { include_variance: self.include_variance }.eval(tvec!(input))?;
+        let moments = moments[0].to_array_view::<f32>()?.into_dimensionality()?;
+        Ok(tvec!(self.window().eval(moments).into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for StatisticsExtraction {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[0], &outputs[0].shape[0])?;
+        s.equals(&inputs[0].shape[1], self.input_dim.to_dim())?;
+        s.equals(&outputs[0].shape[1], self.output_dim().to_dim())?;
+        Ok(())
+    }
+
+    inference_op_as_op!();
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let moments = Moments { include_variance: self.include_variance };
+        if self.output_period == 1 {
+            return target.wire_node(&*node.name, moments, &[mapping[&node.inputs[0]]]);
+        }
+        let wire = target.wire_node(
+            format!("{}-Moments", node.name),
+            moments,
+            &[mapping[&node.inputs[0]]],
+        )?[0];
+        Ok(tvec!(self.window().wire(target, &*node.name, node.name.clone(), wire)?))
+    }
+}
+
+/// StatisticsPoolingComponent: for each frame, the log count, mean and
+/// optionally standard deviations of the extracted statistics over the
+/// context, at the times multiple of `input_period`. The first input frame is
+/// at time 0.
+#[derive(Clone, Debug)]
+pub struct StatisticsPooling {
+    input_dim: usize,
+    input_period: usize,
+    left_context: usize,
+    right_context: usize,
+    num_log_count_features: usize,
+    output_stddevs: bool,
+    variance_floor: f32,
+}
+
+impl StatisticsPooling {
+    fn normalize(&self) -> NormalizeMoments {
+        NormalizeMoments {
+            num_log_count_features: self.num_log_count_features,
+            output_stddevs: self.output_stddevs,
+            variance_floor: self.variance_floor,
+        }
+    }
+
+    /// Sum over the context, of the frames at a time multiple of the
+    /// period, once the others are masked out.
+    fn window(&self) -> WindowSum {
+        WindowSum {
+            before: self.left_context,
+            after: self.right_context,
+            kernel: self.left_context + self.right_context + 1,
+            dilation: 1,
+        }
+    }
+}
+
+impl Op for StatisticsPooling {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "kaldi.StatisticsPooling".into()
+    }
+
+    not_a_typed_op!();
+}
+
+impl StatelessOp for StatisticsPooling {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let input = KeepPeriod::new(self.input_period, 0).eval(tvec!(input))?.remove(0);
+        let sums = self.window().eval(input.to_array_view::<f32>()?.into_dimensionality()?);
+        self.normalize().eval(tvec!(sums.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for StatisticsPooling {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, f32::datum_type())?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].rank, 2)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&inputs[0].shape[0], &outputs[0].shape[0])?;
+        s.equals(&inputs[0].shape[1], self.input_dim.to_dim())?;
+        s.equals(
+            &outputs[0].shape[1],
+            (self.num_log_count_features + self.input_dim - 1).to_dim(),
+        )?;
+        Ok(())
+    }
+
+    inference_op_as_op!();
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let mut wire = mapping[&node.inputs[0]];
+        if self.input_period > 1 {
+            wire = target.wire_node(
+                format!("{}-KeepPeriod", node.name),
+                KeepPeriod::new(self.input_period, 0),
+                &[wire],
+            )?[0];
+        }
+        let sum = self.window().wire(target, &*node.name, format!("{}-Sum", node.name), wire)?;
+        target.wire_node(&*node.name, self.normalize(), &[sum])
+    }
+}
+
+/// Zeroes the frames that are not at a time multiple of `period`. `phase` is
+/// the time of the first frame, modulo the period.
+#[derive(Clone, Debug, new)]
+struct KeepPeriod {
+    period: usize,
+    phase: usize,
+}
+
+impl Op for KeepPeriod {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "kaldi.KeepPeriod".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("period: {} phase: {}", self.period, self.phase)])
+    }
+
+    op_as_typed_op!();
+    op_as_pulsed_op!();
+}
+
+impl StatelessOp for KeepPeriod {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let mut output = input.into_tensor().into_array::<f32>()?;
+        for (t, mut frame) in output.outer_iter_mut().enumerate() {
+            if (t + self.phase) % self.period != 0 {
+                frame.fill(0.0);
+            }
+        }
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for KeepPeriod {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    fn pulsify(
+        &self,
+        _source: &NormalizedModel,
+        node: &NormalizedNode,
+        target: &mut PulsedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        _pulse: usize,
+    ) -> TractResult<TVec<OutletId>> {
+        let input = mapping[&node.inputs[0]];
+        let fact = target.outlet_fact(input)?;
+        if fact.pulse() % self.period != 0 {
+            bail!("StatisticsPooling requires a pulse multiple of its input period")
+        }
+        let phase = (self.phase + self.period - fact.delay % self.period) % self.period;
+        target.wire_node(&*node.name, KeepPeriod::new(self.period, phase), &[input])
+    }
+}
+
+impl PulsedOp for KeepPeriod {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    pulsed_op_as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+/// Per-frame count, value and optionally squared value.
+#[derive(Clone, Debug)]
+struct Moments {
+    include_variance: bool,
+}
+
+/// Per-frame log counts, means and optionally standard deviations from
+/// the output of Moments.
+#[derive(Clone, Debug)]
+struct NormalizeMoments {
+    num_log_count_features: usize,
+    output_stddevs: bool,
+    variance_floor: f32,
+}
+
+impl Moments {
+    fn output_dim(&self, input_dim: usize) -> usize {
+        1 + input_dim * if self.include_variance { 2 } else { 1 }
+    }
+}
+
+impl NormalizeMoments {
+    fn output_dim(&self, input_dim: usize) -> usize {
+        self.num_log_count_features + input_dim - 1
+    }
+}
+
+impl Op for Moments {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "kaldi.Moments".into()
+    }
+
+    op_as_typed_op!();
+    op_as_pulsed_op!();
+}
+
+impl StatelessOp for Moments {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let input = input.to_array_view::<f32>()?.into_dimensionality::<ndarray::Ix2>()?;
+        let dim = input.shape()[1];
+        let mut output = ndarray::Array2::<f32>::ones((input.shape()[0], self.output_dim(dim)));
+        output.slice_axis_mut(Axis(1), (1..dim + 1).into()).assign(&input);
+        if self.include_variance {
+            output
+                .slice_axis_mut(Axis(1), (dim + 1..2 * dim + 1).into())
+                .assign(&input.mapv(|x| x * x));
+        }
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for Moments {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let dim = inputs[0].shape.dim(1).to_integer()? as usize;
+        Ok(tvec!(TypedFact::dt_shape(
+            f32::datum_type(),
+            [inputs[0].shape.dim(0), self.output_dim(dim).to_dim()].as_ref()
+        )?))
+    }
+
+    fn axes_info(&self, _model: &TypedModel, _node: &TypedNode) -> TractResult<AxesInfo> {
+        Ok(vec![AxisInfo::simple(0)].into_iter().collect())
+    }
+
+    fn pulsify(
+        &self,
+        _source: &NormalizedModel,
+        node: &NormalizedNode,
+        target: &mut PulsedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        _pulse: usize,
+    ) -> TractResult<TVec<OutletId>> {
+        target.wire_node(&*node.name, self.clone(), &[mapping[&node.inputs[0]]])
+    }
+}
+
+impl PulsedOp for Moments {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.shape[1] = self.output_dim(fact.shape[1]);
+        Ok(tvec!(fact))
+    }
+
+    pulsed_op_as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+impl Op for NormalizeMoments {
+    fn name(&self) -> std::borrow::Cow<str> {
+        "kaldi.NormalizeMoments".into()
+    }
+
+    op_as_typed_op!();
+    op_as_pulsed_op!();
+}
+
+impl StatelessOp for NormalizeMoments {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let input = input.to_array_view::<f32>()?.into_dimensionality::<ndarray::Ix2>()?;
+        let dim =
+            if self.output_stddevs { (input.shape()[1] - 1) / 2 } else { input.shape()[1] - 1 };
+        let logs = self.num_log_count_features;
+        let mut output =
+            ndarray::Array2::<f32>::zeros((input.shape()[0], self.output_dim(input.shape()[1])));
+        for (input, mut output) in input.outer_iter().zip(output.outer_iter_mut()) {
+            let count = input[0];
+            output.slice_axis_mut(Axis(0), (0..logs).into()).fill(count.ln());
+            for d in 0..dim {
+                let mean = input[1 + d] / count;
+                output[logs + d] = mean;
+                if self.output_stddevs {
+                    let variance = input[1 + dim + d] / count - mean * mean;
+                    output[logs + dim + d] = variance.max(self.variance_floor).sqrt();
+                }
+            }
+        }
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl TypedOp for NormalizeMoments {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let dim = inputs[0].shape.dim(1).to_integer()? as usize;
+        Ok(tvec!(TypedFact::dt_shape(
+            f32::datum_type(),
+            [inputs[0].shape.dim(0), self.output_dim(dim).to_dim()].as_ref()
+        )?))
+    }
+
+    fn axes_info(&self, _model: &TypedModel, _node: &TypedNode) -> TractResult<AxesInfo> {
+        Ok(vec![AxisInfo::simple(0)].into_iter().collect())
+    }
+
+    fn pulsify(
+        &self,
+        _source: &NormalizedModel,
+        node: &NormalizedNode,
+        target: &mut PulsedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+        _pulse: usize,
+    ) -> TractResult<TVec<OutletId>> {
+        target.wire_node(&*node.name, self.clone(), &[mapping[&node.inputs[0]]])
+    }
+}
+
+impl PulsedOp for NormalizeMoments {
+    fn pulsed_output_facts(&self, inputs: &[&PulsedFact]) -> TractResult<TVec<PulsedFact>> {
+        let mut fact = inputs[0].clone();
+        fact.shape[1] = self.output_dim(fact.shape[1]);
+        Ok(tvec!(fact))
+    }
+
+    pulsed_op_as_op!();
+    pulsed_op_to_typed_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tract_core::ndarray::{arr1, s, stack, Array2};
+
+    const FRAMES: usize = 9;
+
+    fn model() -> InferenceModel {
+        let nnet = "<Nnet3>\ninput-node name=input dim=2\n\
+            component-node name=extraction component=extraction input=input\n\
+            component-node name=pooling component=pooling input=extraction\n\
+            output-node name=output input=pooling\n\n<NumComponents> 2\n\
+            <ComponentName> extraction <StatisticsExtractionComponent> <InputDim> 2 \
+            <InputPeriod> 1 <OutputPeriod> 2 <IncludeVarinance> F \
+            </StatisticsExtractionComponent>\n\
+            <ComponentName> pooling <StatisticsPoolingComponent> <InputDim> 3 \
+            <InputPeriod> 2 <LeftContext> 2 <RightContext> 2 <NumLogCountFeatures> 1 \
+            <OutputStddevs> F <VarianceFloor> 1e-10 </StatisticsPoolingComponent>\n</Nnet3>";
+        crate::kaldi().model_for_read(&mut nnet.as_bytes()).unwrap()
+    }
+
+    fn input(frames: usize) -> Array2<f32> {
+        Array2::from_shape_fn((frames, 2), |(t, d)| {
+            if t < FRAMES {
+                (10 * t + d) as f32
+            } else {
+                std::f32::NAN
+            }
+        })
+    }
+
+    /// Pooling over the frames at even times in [t-2, t+2].
+    fn expected() -> Array2<f32> {
+        let input = input(FRAMES);
+        let mut output = Array2::<f32>::zeros((FRAMES, 3));
+        for t in 0..FRAMES {
+            let mut count = 0.0f32;
+            let mut sums = arr1(&[0.0f32, 0.0]);
+            for s in (t.saturating_sub(2)..(t + 3).min(FRAMES)).filter(|s| s % 2 == 0) {
+                for f in s..(s + 2).min(FRAMES) {
+                    count += 1.0;
+                    sums += &input.row(f);
+                }
+            }
+            output[(t, 0)] = count.ln();
+            output.slice_mut(s![t, 1..]).assign(&(sums / count));
+        }
+        output
+    }
+
+    fn close(found: &Tensor, expected: &Array2<f32>) -> bool {
+        let found = found.to_array_view::<f32>().unwrap();
+        found.shape() == expected.shape()
+            && found.iter().zip(expected.iter()).all(|(a, b)| (a - b).abs() < 1e-4)
+    }
+
+    #[test]
+    fn pooling_unrounded_times() {
+        let mut model = model();
+        model.set_input_fact(0, InferenceFact::dt_shape(f32::datum_type(), &[FRAMES, 2])).unwrap();
+        let output = SimplePlan::new(&model).unwrap().run(tvec!(input(FRAMES).into())).unwrap();
+        assert!(close(&output[0], &expected()));
+        let plan = SimplePlan::new(model.into_optimized().unwrap()).unwrap();
+        let output = plan.run(tvec!(input(FRAMES).into())).unwrap();
+        assert!(close(&output[0], &expected()));
+    }
+
+    #[test]
+    fn pulse_pooling_unrounded_times() {
+        let pulse = 4;
+        let pulsed = PulsedModel::new(&model().into_normalized().unwrap(), pulse).unwrap();
+        let delay = pulsed.output_fact(0).unwrap().delay;
+        let plan = SimplePlan::new(pulsed.into_typed().unwrap()).unwrap();
+        let mut state = SimpleState::new(&plan).unwrap();
+        state.session_state.known_stream_len = Some(FRAMES);
+        let pulses = (FRAMES + delay + pulse - 1) / pulse;
+        let input = input(pulses * pulse);
+        let mut outputs = vec![];
+        for p in 0..pulses {
+            let chunk = input.slice(s![p * pulse..(p + 1) * pulse, ..]).to_owned();
+            let output = state.run(tvec!(chunk.into_tensor())).unwrap().remove(0);
+            outputs.push(output.to_array_view::<f32>().unwrap().to_owned());
+        }
+        let views = outputs.iter().map(|o| o.view()).collect::<Vec<_>>();
+        let output = stack(Axis(0), &*views).unwrap();
+        let output = output.slice(s![delay..delay + FRAMES, ..]).to_owned();
+        assert!(close(&output.into_tensor(), &expected()));
+    }
+}
@@ -12,7 +12,7 @@ use nom::{
 
 use std::collections::HashMap;
 
-use crate::model::{Component, KaldiProtoModel};
+use crate::model::{Component, KaldiProtoModel, TransitionModel};
 
 use itertools::Itertools;
 
@@ -21,17 +21,37 @@ mod components;
 mod config_lines;
 mod descriptor;
 mod text;
+mod transition;
+
+pub use components::KaldiAttributeKind;
 
 pub fn nnet3(slice: &[u8]) -> TractResult<KaldiProtoModel> {
-    let (_, (config, components)) = parse_top_level(slice).map_err(|e| match e {
-        nom::Err::Error(err) => format!(
-            "Parsing kaldi enveloppe at: {:?}",
-            err.0.iter().map(|b| format!("{:02x}", b)).join(" ")
-        ),
-        e => format!("{:?}", e),
-    })?;
+    nnet3_with_components(slice, &HashMap::new())
+}
+
+/// Parse a model, with the attributes of the custom components it may use.
+pub fn nnet3_with_components(
+    slice: &[u8],
+    custom: &HashMap<String, HashMap<String, KaldiAttributeKind>>,
+) -> TractResult<KaldiProtoModel> {
+    let (_, (transition_model, config, components, priors)) = parse_top_level(slice, custom)
+        .map_err(|e| match e {
+            nom::Err::Error(err) => format!(
+                "Parsing kaldi enveloppe at: {:?}",
+                err.0.iter().map(|b| format!("{:02x}", b)).join(" ")
+            ),
+            e => format!("{:?}", e),
+        })?;
     let config_lines = config_lines::parse_config(config)?;
-    Ok(KaldiProtoModel { config_lines, components, adjust_final_offset: 0 })
+    Ok(KaldiProtoModel {
+        config_lines,
+        components,
+        adjust_final_offset: 0,
+        transition_model,
+        priors: priors.map(|p| p.into_arc_tensor()),
+        subtract_priors: false,
+        input_rates: HashMap::new(),
+    })
 }
 
 pub fn if_then_else<'a, T>(
@@ -42,20 +62,28 @@ pub fn if_then_else<'a, T>(
     map(pair(cond(condition, then), cond(!condition, otherwise)), |(a, b)| a.or(b).unwrap())
 }
 
-fn parse_top_level(i: &[u8]) -> IResult<&[u8], (&str, HashMap<String, Component>)> {
+/// A raw nnet3 network, or an acoustic model (.mdl): transition model,
+/// network, then priors.
+fn parse_top_level<'a>(
+    i: &'a [u8],
+    custom: &HashMap<String, HashMap<String, KaldiAttributeKind>>,
+) -> IResult<&'a [u8], (Option<TransitionModel>, &'a str, HashMap<String, Component>, Option<Tensor>)>
+{
     let (i, bin) = map(opt(tag([0, 0x42])), |o| Option::is_some(&o))(i)?;
+    let (i, transition_model) = opt(|i| transition::transition_model(i, bin))(i)?;
     let (i, _) = open(i, "Nnet3")?;
     let (i, config_lines) = map_res(take_until("<NumComponents>"), std::str::from_utf8)(i)?;
     let (i, num_components) = num_components(bin, i)?;
     let mut components = HashMap::new();
     let mut i = i;
     for _ in 0..num_components {
-        let (new_i, (name, op)) = pair(component_name, component(bin))(i)?;
+        let (new_i, (name, op)) = pair(component_name, component(bin, custom))(i)?;
         i = new_i;
         components.insert(name.to_owned(), op);
     }
     let (i, _) = close(i, "Nnet3")?;
-    Ok((i, (config_lines, components)))
+    let (i, priors) = map(opt(|i| transition::am_nnet_simple(i, bin)), Option::flatten)(i)?;
+    Ok((i, (transition_model, config_lines, components, priors)))
 }
 
 fn num_components(bin: bool, i: &[u8]) -> IResult<&[u8], usize> {
@@ -64,10 +92,21 @@ fn num_components(bin: bool, i: &[u8]) -> IResult<&[u8], usize> {
     Ok((i, n as usize))
 }
 
-fn component(bin: bool) -> impl Fn(&[u8]) -> IResult<&[u8], Component> {
+fn component<'c>(
+    bin: bool,
+    custom: &'c HashMap<String, HashMap<String, KaldiAttributeKind>>,
+) -> impl Fn(&[u8]) -> IResult<&[u8], Component> + 'c {
     move |i: &[u8]| {
         let (i, klass) = open_any(i)?;
-        let (i, attributes) = if bin { bin::attributes(i, klass)? } else { text::attributes(i)? };
+        let parse_attributes =
+            |i| if bin { bin::attributes(i, klass, custom) } else { text::attributes(i) };
+        let (mut i, mut attributes) = parse_attributes(i)?;
+        // nested sections (like <ConvolutionModel>) are flattened in the component attributes
+        while let Ok((j, _)) = close_nested(i, klass) {
+            let (j, nested) = parse_attributes(j)?;
+            attributes.extend(nested);
+            i = j;
+        }
         let (i, _) = close(i, klass)?;
         Ok((i, Component { klass: klass.to_string(), attributes }))
     }
@@ -85,6 +124,10 @@ pub fn close<'a>(i: &'a [u8], t: &str) -> IResult<&'a [u8], ()> {
     map(multispaced(tuple((tag("</"), tag(t.as_bytes()), tag(">")))), |_| ())(i)
 }
 
+fn close_nested<'a>(i: &'a [u8], klass: &str) -> IResult<&'a [u8], &'a str> {
+    verify(multispaced(delimited(tag("</"), name, tag(">"))), |name: &str| name != klass)(i)
+}
+
 pub fn open_any(i: &[u8]) -> IResult<&[u8], &str> {
     multispaced(delimited(tag("<"), name, tag(">")))(i)
 }
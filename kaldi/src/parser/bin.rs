@@ -3,14 +3,27 @@ use tract_core::internal::*;
 use nom::combinator::*;
 use nom::IResult;
 
-use super::components::COMPONENTS;
+use super::components::{KaldiAttributeKind, COMPONENTS};
 
-pub fn attributes<'a>(i: &'a [u8], klass: &str) -> IResult<&'a [u8], HashMap<String, Arc<Tensor>>> {
-    map(nom::multi::many0(|j| attribute(j, klass)), |v| v.into_iter().collect())(i)
+pub fn attributes<'a>(
+    i: &'a [u8],
+    klass: &str,
+    custom: &HashMap<String, HashMap<String, KaldiAttributeKind>>,
+) -> IResult<&'a [u8], HashMap<String, Arc<Tensor>>> {
+    map(nom::multi::many0(|j| attribute(j, klass, custom)), |v| v.into_iter().collect())(i)
 }
 
-fn attribute<'a>(i: &'a [u8], klass: &str) -> IResult<&'a [u8], (String, Arc<Tensor>)> {
+fn attribute<'a>(
+    i: &'a [u8],
+    klass: &str,
+    custom: &HashMap<String, HashMap<String, KaldiAttributeKind>>,
+) -> IResult<&'a [u8], (String, Arc<Tensor>)> {
     let (i, name) = super::open_any(i)?;
-    let (i, value) = COMPONENTS[klass][name].parse_bin(i)?;
+    let kind = COMPONENTS
+        .get(klass)
+        .and_then(|attributes| attributes.get(name))
+        .or_else(|| custom.get(klass).and_then(|attributes| attributes.get(name)))
+        .ok_or(nom::Err::Error((i, nom::error::ErrorKind::Tag)))?;
+    let (i, value) = kind.parse_bin(i)?;
     Ok((i, (name.to_string(), value.into_arc_tensor())))
 }
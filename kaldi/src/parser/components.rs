@@ -10,17 +10,25 @@ use nom::{
     bytes::complete::*,
     combinator::*,
     multi::many_m_n,
-    number::complete::{le_f32, le_f64},
+    number::complete::{le_f32, le_f64, le_i32},
     sequence::*,
     IResult,
 };
 
+/// Type of a component attribute in the binary format.
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum KaldiAttributeKind {
     Bool,
     Int,
+    IntPair,
+    IntVector,
+    IntPairVector,
     Float,
+    FloatPair,
     FloatVector,
     FloatMatrix,
+    /// A token without value, present when set.
+    Flag,
 }
 
 impl KaldiAttributeKind {
@@ -31,21 +39,42 @@ impl KaldiAttributeKind {
                 map(tag("T"), |_| Tensor::from(true)),
             ))(i),
             Int => map(super::integer(true), Tensor::from)(i),
+            IntPair => {
+                map(pair(super::integer(true), super::integer(true)), |(a, b)| tensor1(&[a, b]))(i)
+            }
+            IntVector => Self::parse_int_vector(i),
+            IntPairVector => Self::parse_int_pair_vector(i),
             Float => map(Self::parse_float_value, Tensor::from)(i),
+            FloatPair => map(pair(Self::parse_float_value, Self::parse_float_value), |(a, b)| {
+                tensor1(&[a, b])
+            })(i),
             FloatVector => preceded(multispaced(tag("FV")), Self::parse_float_vector)(i),
             FloatMatrix => preceded(multispaced(tag("FM")), Self::parse_float_matrix)(i),
+            Flag => Ok((i, Tensor::from(true))),
         }
     }
 
+    fn parse_int_vector<'a>(i: &'a [u8]) -> IResult<&'a [u8], Tensor> {
+        let (i, len) = preceded(tag([4]), le_i32)(i)?;
+        map(many_m_n(len as usize, len as usize, le_i32), |data| tensor1(&*data))(i)
+    }
+
+    fn parse_int_pair_vector<'a>(i: &'a [u8]) -> IResult<&'a [u8], Tensor> {
+        let (i, len) = preceded(tag([4]), le_i32)(i)?;
+        map_res(many_m_n(2 * len as usize, 2 * len as usize, le_i32), move |data| {
+            tract_core::ndarray::Array2::from_shape_vec((len as usize, 2), data).map(Tensor::from)
+        })(i)
+    }
+
     fn parse_float_value<'a>(i: &'a [u8]) -> IResult<&'a [u8], f32> {
         alt((preceded(tag([4]), le_f32), map(preceded(tag([8]), le_f64), |f| f as f32)))(i)
     }
 
     fn parse_float_vector<'a>(i: &'a [u8]) -> IResult<&'a [u8], Tensor> {
         let (i, len) = super::integer(true)(i)?;
-        // FIXME pending merge of https://github.com/Geal/nom/pull/995
+        // an empty vector "[ ]" is an empty tensor
         if len == 0 {
-            Ok((i, tensor1(&[0.0f32])))
+            Ok((i, tensor1::<f32>(&[])))
         } else {
             map(many_m_n(len as usize, len as usize, le_f32), |data| tensor1(&*data))(i)
         }
@@ -123,22 +152,144 @@ lazy_static::lazy_static! {
             "NumElementsProcessed" => Float,
             "NumZeroingBoundaries" => Float,
         },
-        "LogSoftmaxComponent" => hashmap!{
+        "LogSoftmaxComponent" => nonlinear_attributes(Int),
+        "RectifiedLinearComponent" => nonlinear_attributes(Float),
+        "SigmoidComponent" => nonlinear_attributes(Float),
+        "TanhComponent" => nonlinear_attributes(Float),
+        "LinearComponent" => updatable_attributes(hashmap!{
+            "Params" => FloatMatrix,
+            "OrthonormalConstraint" => Float,
+            "UseNaturalGradient" => Bool,
+            "RankInOut" => IntPair,
+            "Alpha" => Float,
+            "NumSamplesHistory" => Float,
+            "UpdatePeriod" => Int,
+        }),
+        "TdnnComponent" => updatable_attributes(hashmap!{
+            "TimeOffsets" => IntVector,
+            "LinearParams" => FloatMatrix,
+            "BiasParams" => FloatVector,
+            "OrthonormalConstraint" => Float,
+            "UseNaturalGradient" => Bool,
+            "NumSamplesHistory" => Float,
+            "AlphaInOut" => FloatPair,
+            "RankInOut" => IntPair,
+        }),
+        "ScaleAndOffsetComponent" => updatable_attributes(hashmap!{
+            "Dim" => Int,
+            "Scales" => FloatVector,
+            "Offsets" => FloatVector,
+            "UseNaturalGradient" => Bool,
+            "Rank" => Int,
+        }),
+        "BatchNormComponent" => hashmap!{
+            "Dim" => Int,
+            "BlockDim" => Int,
+            "Epsilon" => Float,
+            "TargetRms" => Float,
+            "TestMode" => Bool,
+            "Count" => Float,
+            "StatsMean" => FloatVector,
+            "StatsVar" => FloatVector,
+        },
+        "NoOpComponent" => hashmap!{
             "Dim" => Int,
-            "ValueAvg" => FloatVector,
-            "DerivAvg" => FloatVector,
-            "Count" => Int,
-            "NumDimsSelfRepaired" => Int,
-            "NumDimsProcessed" => Int,
+            "BackpropScale" => Float,
         },
-        "RectifiedLinearComponent" => hashmap!{
+        "DropoutComponent" => hashmap!{
             "Dim" => Int,
-            "ValueAvg" => FloatVector,
-            "DerivAvg" => FloatVector,
-            "Count" => Float,
-            "NumDimsSelfRepaired" => Float,
-            "NumDimsProcessed" => Float,
-            "SelfRepairScale" => Float,
-        }
+            "DropoutProportion" => Float,
+            "DropoutPerFrame" => Bool,
+            "TestMode" => Bool,
+        },
+        "GeneralDropoutComponent" => hashmap!{
+            "Dim" => Int,
+            "BlockDim" => Int,
+            "TimePeriod" => Int,
+            "DropoutProportion" => Float,
+            "SpecAugmentMaxProportion" => Float,
+            "SpecAugmentMaxRegions" => Int,
+            "Continuous" => Flag,
+            "TestMode" => Bool,
+        },
+        "ElementwiseProductComponent" => hashmap!{
+            "InputDim" => Int,
+            "OutputDim" => Int,
+        },
+        "SumBlockComponent" => hashmap!{
+            "InputDim" => Int,
+            "OutputDim" => Int,
+            "Scale" => Float,
+        },
+        "TimeHeightConvolutionComponent" => updatable_attributes(hashmap!{
+            // the nested <ConvolutionModel> section is flattened
+            "Model" => Flag,
+            "ConvolutionModel" => Flag,
+            "NumFiltersIn" => Int,
+            "NumFiltersOut" => Int,
+            "HeightIn" => Int,
+            "HeightOut" => Int,
+            "HeightSubsampleOut" => Int,
+            "Offsets" => IntPairVector,
+            "RequiredTimeOffsets" => IntVector,
+            "LinearParams" => FloatMatrix,
+            "BiasParams" => FloatVector,
+            "MaxMemoryMb" => Float,
+            "UseNaturalGradient" => Bool,
+            "NumMinibatchesHistory" => Float,
+            "AlphaInOut" => FloatPair,
+            "RankInOut" => IntPair,
+        }),
+        "StatisticsExtractionComponent" => hashmap!{
+            "InputDim" => Int,
+            "InputPeriod" => Int,
+            "OutputPeriod" => Int,
+            // sic, this is how kaldi spells it
+            "IncludeVarinance" => Bool,
+        },
+        "StatisticsPoolingComponent" => hashmap!{
+            "InputDim" => Int,
+            "InputPeriod" => Int,
+            "LeftContext" => Int,
+            "RightContext" => Int,
+            "NumLogCountFeatures" => Int,
+            "OutputStddevs" => Bool,
+            "VarianceFloor" => Float,
+        },
+    };
+}
+
+/// Attributes of the components derived from kaldi NonlinearComponent, with
+/// the kind of the statistics counts.
+fn nonlinear_attributes(count: KaldiAttributeKind) -> HashMap<&'static str, KaldiAttributeKind> {
+    hashmap! {
+        "Dim" => Int,
+        "BlockDim" => Int,
+        "ValueAvg" => FloatVector,
+        "DerivAvg" => FloatVector,
+        "Count" => count,
+        "OderivRms" => FloatVector,
+        "OderivCount" => Float,
+        "NumDimsSelfRepaired" => count,
+        "NumDimsProcessed" => count,
+        "SelfRepairLowerThreshold" => Float,
+        "SelfRepairUpperThreshold" => Float,
+        "SelfRepairScale" => Float,
+    }
+}
+
+/// Attributes of the components derived from kaldi UpdatableComponent,
+/// followed by their own attributes.
+fn updatable_attributes(
+    attributes: HashMap<&'static str, KaldiAttributeKind>,
+) -> HashMap<&'static str, KaldiAttributeKind> {
+    let mut common = hashmap! {
+        "LearningRateFactor" => Float,
+        "IsGradient" => Bool,
+        "MaxChange" => Float,
+        "L2Regularize" => Float,
+        "LearningRate" => Float,
     };
+    common.extend(attributes);
+    common
 }
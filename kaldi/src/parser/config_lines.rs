@@ -3,11 +3,11 @@ use tract_core::internal::*;
 use nom::IResult;
 use nom::{bytes::complete::*, character::complete::*, combinator::*, sequence::*};
 
-use crate::model::{ComponentNode, ConfigLines, DimRangeNode, NodeLine, OutputLine};
+use crate::model::{ComponentNode, ConfigLines, DimRangeNode, InputNode, NodeLine, OutputLine};
 use crate::parser::spaced;
 
 pub fn parse_config(s: &str) -> TractResult<ConfigLines> {
-    let mut input_node: Option<(String, usize)> = None;
+    let mut input_nodes = vec![];
     let mut nodes = vec![];
     let mut outputs = vec![];
     for line in s.lines() {
@@ -20,13 +20,11 @@ pub fn parse_config(s: &str) -> TractResult<ConfigLines> {
         }
         let line_kind = line.split(" ").next().unwrap();
         match line_kind {
-            "input-node" => {
-                input_node = Some(
-                    parse_input_node_line(line)
-                        .map_err(|e| format!("Error {:?} while parsing {}", e, line))?
-                        .1,
-                )
-            }
+            "input-node" => input_nodes.push(
+                parse_input_node_line(line)
+                    .map_err(|e| format!("Error {:?} while parsing {}", e, line))?
+                    .1,
+            ),
             "dim-range-node" => {
                 let (name, it) = parse_dim_range_node_line(line)
                     .map_err(|e| format!("Error {:?} while parsing {}", e, line))?
@@ -47,16 +45,21 @@ pub fn parse_config(s: &str) -> TractResult<ConfigLines> {
             _ => bail!("Unknown config line {}", line_kind),
         }
     }
-    let (input_name, input_dim) = input_node.unwrap();
-    Ok(ConfigLines { input_dim, input_name, nodes, outputs })
+    if input_nodes.is_empty() {
+        bail!("No input-node in config")
+    }
+    Ok(ConfigLines { input_nodes, nodes, outputs })
 }
 
-fn parse_input_node_line(i: &str) -> IResult<&str, (String, usize)> {
+fn parse_input_node_line(i: &str) -> IResult<&str, InputNode> {
     let (i, _) = tag("input-node")(i)?;
-    nom::branch::permutation((
-        spaced(map(preceded(tag("name="), identifier), |n: &str| n.to_string())),
-        spaced(preceded(tag("dim="), uinteger)),
-    ))(i)
+    map(
+        nom::branch::permutation((
+            spaced(map(preceded(tag("name="), identifier), |n: &str| n.to_string())),
+            spaced(preceded(tag("dim="), uinteger)),
+        )),
+        |(name, dim)| InputNode { name, dim },
+    )(i)
 }
 
 fn parse_component_node_line(i: &str) -> IResult<&str, (String, ComponentNode)> {
@@ -111,4 +114,20 @@ mod test {
     fn identifiet_with_dot() {
         assert_eq!(identifier("lstm.c").unwrap().1, "lstm.c")
     }
+
+    #[test]
+    fn several_input_nodes() {
+        let config = parse_config(
+            "input-node name=input dim=40\ninput-node name=ivector dim=100\n\
+             output-node name=output input=Append(input, ReplaceIndex(ivector, t, 0))",
+        )
+        .unwrap();
+        assert_eq!(
+            config.input_nodes,
+            vec!(
+                InputNode { name: "input".into(), dim: 40 },
+                InputNode { name: "ivector".into(), dim: 100 }
+            )
+        );
+    }
 }
@@ -1,46 +1,101 @@
 use nom::IResult;
 use nom::{
-    bytes::complete::*, character::complete::*, combinator::*, multi::separated_list, sequence::*,
+    branch::alt, bytes::complete::*, character::complete::*, combinator::*, multi::separated_list,
+    number::complete::float, sequence::*,
 };
 
 use crate::model::GeneralDescriptor;
 use crate::parser::spaced;
 
 pub fn parse_general(i: &str) -> IResult<&str, GeneralDescriptor> {
-    spaced(nom::branch::alt((
+    spaced(alt((
+        map(preceded(keyword("Append"), cut(args(list))), GeneralDescriptor::Append),
         map(
             preceded(
-                tag("Append"),
-                cut(delimited(
-                    spaced(tag("(")),
-                    separated_list(spaced(tag(",")), parse_general),
-                    spaced(tag(")")),
-                )),
+                keyword("Offset"),
+                cut(args(tuple((
+                    parse_general,
+                    preceded(comma, integer),
+                    opt(preceded(comma, integer)),
+                )))),
             ),
-            GeneralDescriptor::Append,
+            |(inner, offset, _x)| GeneralDescriptor::Offset(Box::new(inner), offset as isize),
         ),
+        map(preceded(keyword("IfDefined"), cut(args(parse_general))), |inner| {
+            GeneralDescriptor::IfDefined(Box::new(inner))
+        }),
         map(
             preceded(
-                tag("Offset"),
-                cut(delimited(
-                    spaced(tag("(")),
-                    separated_pair(parse_general, spaced(tag(",")), integer),
-                    spaced(tag(")")),
-                )),
+                keyword("Sum"),
+                cut(args(separated_pair(parse_general, comma, parse_general))),
             ),
-            |(inner, offset)| GeneralDescriptor::Offset(Box::new(inner), offset as isize),
+            |(a, b)| GeneralDescriptor::Sum(Box::new(a), Box::new(b)),
         ),
         map(
             preceded(
-                tag("IfDefined"),
-                cut(delimited(spaced(tag("(")), parse_general, spaced(tag(")")))),
+                keyword("Scale"),
+                cut(args(separated_pair(spaced(float), comma, parse_general))),
             ),
-            |inner| GeneralDescriptor::IfDefined(Box::new(inner)),
+            |(scale, inner)| GeneralDescriptor::Scale(scale, Box::new(inner)),
+        ),
+        map(
+            preceded(
+                keyword("Const"),
+                cut(args(separated_pair(spaced(float), comma, spaced(integer)))),
+            ),
+            |(value, dim)| GeneralDescriptor::Const(value, dim as usize),
+        ),
+        map(
+            preceded(
+                keyword("ReplaceIndex"),
+                cut(args(tuple((
+                    parse_general,
+                    preceded(comma, spaced(one_of("tx"))),
+                    preceded(comma, spaced(integer)),
+                )))),
+            ),
+            |(inner, index, value)| {
+                GeneralDescriptor::ReplaceIndex(Box::new(inner), index, value as isize)
+            },
+        ),
+        map(
+            preceded(
+                keyword("Round"),
+                cut(args(separated_pair(parse_general, comma, spaced(integer)))),
+            ),
+            |(inner, modulus)| GeneralDescriptor::Round(Box::new(inner), modulus as usize),
+        ),
+        map(preceded(keyword("Switch"), cut(args(list))), GeneralDescriptor::Switch),
+        map(
+            preceded(
+                keyword("Failover"),
+                cut(args(separated_pair(parse_general, comma, parse_general))),
+            ),
+            |(a, b)| GeneralDescriptor::Failover(Box::new(a), Box::new(b)),
         ),
         map(super::config_lines::identifier, |i| GeneralDescriptor::Name(i.to_string())),
     )))(i)
 }
 
+/// A descriptor name, followed by its arguments.
+fn keyword<'a>(name: &'static str) -> impl Fn(&'a str) -> IResult<&'a str, &'a str> {
+    terminated(tag(name), peek(preceded(space0, tag("("))))
+}
+
+fn args<'a, O>(
+    inner: impl Fn(&'a str) -> IResult<&'a str, O>,
+) -> impl Fn(&'a str) -> IResult<&'a str, O> {
+    delimited(spaced(tag("(")), inner, spaced(tag(")")))
+}
+
+fn list(i: &str) -> IResult<&str, Vec<GeneralDescriptor>> {
+    separated_list(comma, parse_general)(i)
+}
+
+fn comma(i: &str) -> IResult<&str, &str> {
+    spaced(tag(","))(i)
+}
+
 pub fn integer(i: &str) -> IResult<&str, i32> {
     map_res(recognize(pair(opt(tag("-")), digit1)), |s: &str| s.parse::<i32>())(i)
 }
@@ -78,4 +133,37 @@ mod test {
             Append(vec!(name("input"), IfDefined(Offset(name("lstm1.c").into(), -1).into())))
         )
     }
+
+    #[test]
+    fn test_tdnnf() {
+        assert_eq!(
+            parse_general("Sum(Scale(0.66, tdnnf2.noop), tdnnf3.dropout)").unwrap().1,
+            Sum(Scale(0.66, name("tdnnf2.noop").into()).into(), name("tdnnf3.dropout").into())
+        )
+    }
+
+    #[test]
+    fn test_ivector() {
+        assert_eq!(
+            parse_general("Append(input, ReplaceIndex(ivector, t, 0))").unwrap().1,
+            Append(vec!(name("input"), ReplaceIndex(name("ivector").into(), 't', 0)))
+        )
+    }
+
+    #[test]
+    fn test_others() {
+        assert_eq!(parse_general("Const(1.5, 3)").unwrap().1, Const(1.5, 3));
+        assert_eq!(
+            parse_general("Round(ivector, 10)").unwrap().1,
+            Round(name("ivector").into(), 10)
+        );
+        assert_eq!(
+            parse_general("Switch(a, Offset(b, 1))").unwrap().1,
+            Switch(vec!(name("a"), Offset(name("b").into(), 1)))
+        );
+        assert_eq!(
+            parse_general("Failover(Offset(a, -1), Const(0, 2))").unwrap().1,
+            Failover(Offset(name("a").into(), -1).into(), Const(0.0, 2).into())
+        );
+    }
 }
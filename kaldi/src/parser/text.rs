@@ -2,21 +2,38 @@ use tract_core::internal::*;
 
 use nom::IResult;
 use nom::{
-    bytes::complete::*, character::complete::*, combinator::*, multi::separated_list,
-    number::complete::float, sequence::*,
+    bytes::complete::*,
+    character::complete::*,
+    combinator::*,
+    multi::{separated_list, separated_nonempty_list},
+    number::complete::float,
+    sequence::*,
 };
 
 use super::{integer, multispaced, open_any, spaced};
 
 pub fn attributes(i: &[u8]) -> IResult<&[u8], HashMap<String, Arc<Tensor>>> {
-    let (i, attributes) = nom::multi::many0(map(pair(open_any, tensor), |(k, v)| {
-        (k.to_string(), v.into_arc_tensor())
+    let (i, attributes) = nom::multi::many0(map(pair(open_any, opt(tensor)), |(k, v)| {
+        (k.to_string(), v.unwrap_or_else(|| Tensor::from(true)).into_arc_tensor())
     }))(i)?;
     Ok((i, attributes.into_iter().collect()))
 }
 
 pub fn tensor(i: &[u8]) -> IResult<&[u8], Tensor> {
-    nom::branch::alt((scalar, vector, matrix))(i)
+    nom::branch::alt((scalars, vector, int_pairs, matrix))(i)
+}
+
+/// One scalar, or a list of float scalars (like `<AlphaInOut> 4 4`).
+pub fn scalars(i: &[u8]) -> IResult<&[u8], Tensor> {
+    map_res(separated_nonempty_list(space1, scalar), |mut v| -> TractResult<Tensor> {
+        if v.len() == 1 {
+            Ok(v.remove(0))
+        } else {
+            let v =
+                v.iter().map(|t| Ok(*t.to_scalar::<f32>()?)).collect::<TractResult<Vec<_>>>()?;
+            Ok(tensor1(&*v))
+        }
+    })(i)
 }
 
 pub fn scalar(i: &[u8]) -> IResult<&[u8], Tensor> {
@@ -34,6 +51,21 @@ pub fn vector(i: &[u8]) -> IResult<&[u8], Tensor> {
     })(i)
 }
 
+/// A vector of integer pairs (like `<Offsets> [ -1,0 0,0 1,0 ]`), as a N•2 tensor.
+pub fn int_pairs(i: &[u8]) -> IResult<&[u8], Tensor> {
+    map_res(
+        delimited(
+            spaced(tag("[")),
+            separated_list(space1, separated_pair(integer(false), tag(","), integer(false))),
+            spaced(tag("]")),
+        ),
+        |pairs| -> TractResult<Tensor> {
+            let data = pairs.iter().flat_map(|&(a, b)| vec![a, b]).collect();
+            Ok(tract_core::ndarray::Array2::from_shape_vec((pairs.len(), 2), data)?.into_tensor())
+        },
+    )(i)
+}
+
 pub fn matrix(i: &[u8]) -> IResult<&[u8], Tensor> {
     let (i, v) = delimited(
         multispaced(tag("[")),
@@ -72,12 +104,27 @@ output-node name=output input=fixed1
         nnet3(slice.as_bytes()).unwrap();
     }
 
+    #[test]
+    fn test_flag_and_pairs() {
+        let slice = r#"<Continuous> <AlphaInOut> 4 4 <TestMode> F "#;
+        let attributes = attributes(slice.as_bytes()).unwrap().1;
+        assert_eq!(*attributes["Continuous"], Tensor::from(true));
+        assert_eq!(*attributes["AlphaInOut"], tensor1(&[4.0f32, 4.0]));
+        assert_eq!(*attributes["TestMode"], Tensor::from(false));
+    }
+
     #[test]
     fn test_vector() {
         let slice = r#"[ 7.0 8.0 ]"#;
         assert_eq!(tensor(slice.as_bytes()).unwrap().1, tensor1(&[7.0f32, 8.0]));
     }
 
+    #[test]
+    fn test_int_pairs() {
+        let slice = r#"[ -1,0 0,-2 1,3 ]"#;
+        assert_eq!(tensor(slice.as_bytes()).unwrap().1, tensor2(&[[-1i32, 0], [0, -2], [1, 3]]));
+    }
+
     #[test]
     fn test_matrix() {
         let slice = r#"[
@@ -94,4 +141,15 @@ output-node name=output input=fixed1
         let slice = std::fs::read("test_cases/fixed_affine_40x10_T40_S3/model.raw.txt").unwrap();
         nnet3(&slice).unwrap();
     }
+
+    #[test]
+    fn nested_section() {
+        let slice =
+            std::fs::read("test_cases/time_height_conv_F3x4_H8x8_T15/model.raw.txt").unwrap();
+        let model = nnet3(&slice).unwrap();
+        let attributes = &model.components["conv"].attributes;
+        assert_eq!(attributes["HeightIn"].cast_to_scalar::<i32>().unwrap(), 8);
+        assert_eq!(attributes["Offsets"].shape(), &[9, 2]);
+        assert!(attributes.contains_key("LinearParams"));
+    }
 }
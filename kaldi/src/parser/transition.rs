@@ -0,0 +1,211 @@
+use tract_core::internal::*;
+
+use nom::{
+    branch::alt,
+    bytes::complete::*,
+    combinator::*,
+    multi::{count, many0},
+    number::complete::{le_f32, le_f64},
+    sequence::*,
+    IResult,
+};
+
+use crate::model::{HmmState, HmmTopology, TransitionModel, TransitionTuple};
+
+use super::components::KaldiAttributeKind;
+use super::{close, integer, multispaced, open};
+
+fn int<'a>(bin: bool) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], i32> {
+    multispaced(integer(bin))
+}
+
+fn float<'a>(bin: bool) -> impl Fn(&'a [u8]) -> IResult<&'a [u8], f32> {
+    multispaced(super::if_then_else(
+        bin,
+        alt((preceded(tag([4]), le_f32), map(preceded(tag([8]), le_f64), |f| f as f32))),
+        nom::number::complete::float,
+    ))
+}
+
+fn int_vector(i: &[u8], bin: bool) -> IResult<&[u8], Vec<i32>> {
+    if bin {
+        map_res(multispaced(|i| KaldiAttributeKind::IntVector.parse_bin(i)), |t| {
+            t.as_slice::<i32>().map(|s| s.to_vec())
+        })(i)
+    } else {
+        delimited(multispaced(tag("[")), many0(int(false)), multispaced(tag("]")))(i)
+    }
+}
+
+pub fn float_vector(i: &[u8], bin: bool) -> IResult<&[u8], Tensor> {
+    if bin {
+        multispaced(|i| KaldiAttributeKind::FloatVector.parse_bin(i))(i)
+    } else {
+        multispaced(super::text::vector)(i)
+    }
+}
+
+/// A TransitionModel, as found at the beginning of .mdl files.
+pub fn transition_model(i: &[u8], bin: bool) -> IResult<&[u8], TransitionModel> {
+    let (i, _) = open(i, "TransitionModel")?;
+    let (i, topology) = topology(i, bin)?;
+    let (i, tuples) = alt((|i| tuples(i, bin, "Triples", 3), |i| tuples(i, bin, "Tuples", 4)))(i)?;
+    let (i, _) = open(i, "LogProbs")?;
+    let (i, log_probs) =
+        map_res(|i| float_vector(i, bin), |t| t.as_slice::<f32>().map(|s| s.to_vec()))(i)?;
+    let (i, _) = close(i, "LogProbs")?;
+    let (i, _) = close(i, "TransitionModel")?;
+    Ok((i, TransitionModel { topology, tuples, log_probs }))
+}
+
+fn topology(i: &[u8], bin: bool) -> IResult<&[u8], HmmTopology> {
+    let (i, _) = open(i, "Topology")?;
+    let (i, topology) = if bin { bin_topology(i)? } else { text_topology(i)? };
+    let (i, _) = close(i, "Topology")?;
+    Ok((i, topology))
+}
+
+fn bin_topology(i: &[u8]) -> IResult<&[u8], HmmTopology> {
+    let (i, phones) = int_vector(i, true)?;
+    let (i, phone2idx) = int_vector(i, true)?;
+    // -1 announces the extended format with self-loop pdf classes
+    let (i, mut entries) = int(true)(i)?;
+    let is_hmm = entries != -1;
+    let i = if is_hmm {
+        i
+    } else {
+        let (i, e) = int(true)(i)?;
+        entries = e;
+        i
+    };
+    let state = |i| {
+        let (i, forward_pdf_class) = int(true)(i)?;
+        let (i, self_loop_pdf_class) = if is_hmm { (i, forward_pdf_class) } else { int(true)(i)? };
+        let (i, transitions) = int(true)(i)?;
+        let (i, transitions) = count(pair(int(true), float(true)), transitions as usize)(i)?;
+        Ok((i, HmmState { forward_pdf_class, self_loop_pdf_class, transitions }))
+    };
+    let entry = |i| {
+        let (i, states) = int(true)(i)?;
+        count(state, states as usize)(i)
+    };
+    let (i, entries) = count(entry, entries as usize)(i)?;
+    Ok((i, HmmTopology { phones, phone2idx, entries }))
+}
+
+fn text_topology(i: &[u8]) -> IResult<&[u8], HmmTopology> {
+    let state = |i| {
+        let (i, _) = open(i, "State")?;
+        let (i, _) = int(false)(i)?;
+        let (i, pdf_classes) = opt(alt((
+            map(preceded(|i| open(i, "PdfClass"), int(false)), |c| (c, c)),
+            pair(
+                preceded(|i| open(i, "ForwardPdfClass"), int(false)),
+                preceded(|i| open(i, "SelfLoopPdfClass"), int(false)),
+            ),
+        )))(i)?;
+        let (i, transitions) =
+            many0(preceded(|i| open(i, "Transition"), pair(int(false), float(false))))(i)?;
+        let (i, _) = close(i, "State")?;
+        let (forward_pdf_class, self_loop_pdf_class) = pdf_classes.unwrap_or((-1, -1));
+        Ok((i, HmmState { forward_pdf_class, self_loop_pdf_class, transitions }))
+    };
+    let entry = |i| {
+        let (i, _) = open(i, "TopologyEntry")?;
+        let (i, _) = open(i, "ForPhones")?;
+        let (i, phones) = many0(int(false))(i)?;
+        let (i, _) = close(i, "ForPhones")?;
+        let (i, states) = many0(state)(i)?;
+        let (i, _) = close(i, "TopologyEntry")?;
+        Ok((i, (phones, states)))
+    };
+    let (i, entries) = many0(entry)(i)?;
+    let mut topology = HmmTopology::default();
+    for (ix, (phones, states)) in entries.into_iter().enumerate() {
+        for phone in phones {
+            if topology.phone2idx.len() <= phone as usize {
+                topology.phone2idx.resize(phone as usize + 1, -1);
+            }
+            topology.phone2idx[phone as usize] = ix as i32;
+            topology.phones.push(phone);
+        }
+        topology.entries.push(states);
+    }
+    topology.phones.sort();
+    Ok((i, topology))
+}
+
+fn tuples<'a>(
+    i: &'a [u8],
+    bin: bool,
+    token: &str,
+    arity: usize,
+) -> IResult<&'a [u8], Vec<TransitionTuple>> {
+    let (i, _) = open(i, token)?;
+    let (i, len) = int(bin)(i)?;
+    let (i, tuples) = count(
+        map(count(int(bin), arity), |t| TransitionTuple {
+            phone: t[0],
+            hmm_state: t[1],
+            forward_pdf: t[2],
+            self_loop_pdf: t[arity - 1],
+        }),
+        len as usize,
+    )(i)?;
+    let (i, _) = close(i, token)?;
+    Ok((i, tuples))
+}
+
+/// What follows the network in an AmNnetSimple (.mdl): contexts and priors.
+pub fn am_nnet_simple(i: &[u8], bin: bool) -> IResult<&[u8], Option<Tensor>> {
+    let (i, _) = preceded(|i| open(i, "LeftContext"), int(bin))(i)?;
+    let (i, _) = preceded(|i| open(i, "RightContext"), int(bin))(i)?;
+    let (i, priors) = preceded(|i| open(i, "Priors"), |i| float_vector(i, bin))(i)?;
+    Ok((i, if priors.len() > 0 { Some(priors) } else { None }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn text_transition_model() {
+        let slice = r#"<TransitionModel>
+<Topology>
+<TopologyEntry>
+<ForPhones>
+1 2
+</ForPhones>
+<State> 0 <PdfClass> 0 <Transition> 0 0.5 <Transition> 1 0.5 </State>
+<State> 1 </State>
+</TopologyEntry>
+</Topology>
+<Triples> 2
+1 0 0
+2 0 1
+</Triples>
+<LogProbs>
+ [ 0 -0.69 -0.69 -0.69 -0.69 ]
+</LogProbs>
+</TransitionModel>
+"#;
+        let model = transition_model(slice.as_bytes(), false).unwrap().1;
+        assert_eq!(model.topology.phone2idx, vec![-1, 0, 0]);
+        assert_eq!(model.num_pdfs(), 2);
+        assert_eq!(model.transition_id_to_pdf().unwrap(), vec![-1, 0, 0, 1, 1]);
+        assert_eq!(model.log_probs.len(), 5);
+    }
+
+    #[test]
+    fn mdl_text_and_bin() {
+        let dir = "test_cases/mdl_affine_log_softmax_6x4_T10";
+        let text = crate::parser::nnet3(&std::fs::read(format!("{}/model.raw.txt", dir)).unwrap())
+            .unwrap();
+        let bin =
+            crate::parser::nnet3(&std::fs::read(format!("{}/model.raw", dir)).unwrap()).unwrap();
+        assert!(text.transition_model.is_some());
+        assert_eq!(text.transition_model, bin.transition_model);
+        assert_eq!(text.priors.as_ref().unwrap().shape(), &[4]);
+        assert_eq!(text.priors, bin.priors);
+    }
+}
@@ -0,0 +1,4 @@
+left_context=0
+right_context=0
+subsampling=1
+adjust_final_offset=0
@@ -0,0 +1,4 @@
+left_context=1
+right_context=1
+subsampling=1
+adjust_final_offset=0
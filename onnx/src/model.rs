@@ -5,7 +5,9 @@ use std::collections::HashMap;
 use tract_core::internal::*;
 
 use crate::pb;
+use crate::tensor::ExternalData;
 use prost::Message;
+use std::path::Path;
 
 pub fn optional_inputs(pb: &pb::NodeProto) -> impl Iterator<Item = Option<usize>> + '_ {
     let mut real_input = 0;
@@ -34,9 +36,12 @@ pub fn optional_outputs(pb: &pb::NodeProto) -> impl Iterator<Item = Option<usize
 #[derive(Clone)]
 pub struct ParsingContext<'a> {
     pub onnx_operator_set_version: i64,
+    /// Operator set versions imported by the model, by domain.
+    pub opset_imports: HashMap<String, i64>,
     pub framework: &'a Onnx,
     pub model: &'a pb::ModelProto,
     pub parent_graphs: Vec<&'a pb::GraphProto>,
+    pub external_data: &'a ExternalData,
 }
 
 #[derive(Clone, Debug)]
@@ -56,7 +61,7 @@ impl<'a> ParsingContext<'a> {
         let mut initializers: HashMap<&str, Tensor> = graph
             .initializer
             .iter()
-            .map(|init| Ok((&*init.name, init.try_into()?)))
+            .map(|init| Ok((&*init.name, self.external_data.tensor(init)?)))
             .collect::<TractResult<_>>()?;
         for (k, v) in initializers.iter() {
             trace!("Initializer: {} {:?}", k, v);
@@ -104,7 +109,10 @@ impl<'a> ParsingContext<'a> {
                 .map(|_| InferenceFact::default())
                 .collect();
             trace!("  outputs {:?}", pbnode.output);
-            let (op, closures) = match self.framework.op_register.0.get(&pbnode.op_type) {
+            let domain = normalize_domain(&pbnode.domain);
+            let version = self.opset_imports.get(domain).cloned().unwrap_or(std::i64::MAX);
+            let builder = self.framework.op_register.get(domain, &pbnode.op_type, version);
+            let (op, closures) = match builder {
                 Some(builder) => (builder)(&ctx, pbnode)?,
                 None => (
                     tract_core::ops::unimpl::UnimplementedOp::new(
@@ -164,27 +172,91 @@ impl<'a> ParsingContext<'a> {
     }
 }
 
+/// "ai.onnx" is an alias for the default (empty) domain.
+fn normalize_domain(domain: &str) -> &str {
+    if domain == "ai.onnx" {
+        ""
+    } else {
+        domain
+    }
+}
+
+pub type OnnxOpBuilder = Arc<
+    dyn Fn(&ParsingContext, &pb::NodeProto) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)>
+        + Send
+        + Sync,
+>;
+
+/// Operator builders, by (domain, operator), with the operator set version
+/// each builder was introduced in.
 #[derive(Clone, Default)]
-pub struct OnnxOpRegister(
-    pub  HashMap<
-        String,
-        fn(
-            &ParsingContext,
-            node: &pb::NodeProto,
-        ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)>,
-    >,
-);
+pub struct OnnxOpRegister(pub HashMap<(String, String), Vec<(i64, OnnxOpBuilder)>>);
 
 impl OnnxOpRegister {
-    pub fn insert(
-        &mut self,
-        s: &'static str,
-        builder: fn(
-            &ParsingContext,
-            node: &pb::NodeProto,
-        ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)>,
-    ) {
-        self.0.insert(s.into(), builder);
+    /// Register a builder for an operator of the default domain, for all
+    /// operator set versions.
+    pub fn insert<B>(&mut self, s: &'static str, builder: B)
+    where
+        B: Fn(&ParsingContext, &pb::NodeProto) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.insert_since(s, 1, builder)
+    }
+
+    /// Register a builder for an operator of the default domain, starting at
+    /// operator set version `since`.
+    pub fn insert_since<B>(&mut self, s: &'static str, since: i64, builder: B)
+    where
+        B: Fn(&ParsingContext, &pb::NodeProto) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.insert_domain("", s, since, builder)
+    }
+
+    /// Register a builder for an operator of `domain`, starting at operator
+    /// set version `since`.
+    pub fn insert_domain<B>(&mut self, domain: &str, s: &str, since: i64, builder: B)
+    where
+        B: Fn(&ParsingContext, &pb::NodeProto) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)>
+            + Send
+            + Sync
+            + 'static,
+    {
+        let domain = normalize_domain(domain);
+        let versions = self.0.entry((domain.to_string(), s.to_string())).or_insert(vec![]);
+        versions.retain(|v| v.0 != since);
+        versions.push((since, Arc::new(builder)));
+        versions.sort_by_key(|v| v.0);
+    }
+
+    /// Find the builder for an operator at a given operator set version: the
+    /// one with the highest version not greater than `version`.
+    pub fn get(&self, domain: &str, op: &str, version: i64) -> Option<&OnnxOpBuilder> {
+        let versions = self.0.get(&(normalize_domain(domain).to_string(), op.to_string()))?;
+        versions.iter().rev().find(|v| v.0 <= version).map(|v| &v.1)
+    }
+
+    /// Supported (domain, operator, version ranges). Ranges are inclusive,
+    /// the last one has no upper bound.
+    pub fn ops(&self) -> Vec<(&str, &str, Vec<(i64, Option<i64>)>)> {
+        let mut ops: Vec<_> = self
+            .0
+            .iter()
+            .map(|((domain, op), versions)| {
+                let ranges = versions
+                    .iter()
+                    .enumerate()
+                    .map(|(ix, v)| (v.0, versions.get(ix + 1).map(|next| next.0 - 1)))
+                    .collect();
+                (&**domain, &**op, ranges)
+            })
+            .collect();
+        ops.sort_by(|a, b| (a.0, a.1).cmp(&(b.0, b.1)));
+        ops
     }
 }
 
@@ -194,18 +266,51 @@ pub struct Onnx {
 }
 
 impl Onnx {
+    /// Register the operators of a plugin, typically custom operators in
+    /// their own domain.
+    pub fn with_plugin(mut self, plugin: &dyn OpPlugin<OnnxOpRegister>) -> Onnx {
+        plugin.register_ops(&mut self.op_register);
+        self
+    }
+
     pub fn parse(&self, proto: &pb::ModelProto) -> TractResult<ParseResult> {
-        let onnx_operator_set_version =
-            proto.opset_import.iter().find(|import| import.domain == "").unwrap().version;
+        self.parse_with_model_dir(proto, None)
+    }
+
+    /// Parse a model, resolving external data relatively to `model_dir`.
+    pub fn parse_with_model_dir(
+        &self,
+        proto: &pb::ModelProto,
+        model_dir: Option<&Path>,
+    ) -> TractResult<ParseResult> {
+        let opset_imports: HashMap<String, i64> = proto
+            .opset_import
+            .iter()
+            .map(|import| (normalize_domain(&import.domain).to_string(), import.version))
+            .collect();
+        let onnx_operator_set_version = *opset_imports
+            .get("")
+            .ok_or_else(|| format!("Model does not import the default operator set"))?;
         let graph = &proto.graph;
+        let external_data = ExternalData::new(model_dir);
         let ctx = ParsingContext {
             framework: self,
             model: proto,
             parent_graphs: vec![],
             onnx_operator_set_version,
+            opset_imports,
+            external_data: &external_data,
         };
         ctx.parse_graph(graph.as_ref().unwrap())
     }
+
+    fn model_for_parse_result(&self, result: ParseResult) -> TractResult<InferenceModel> {
+        let ParseResult { model, unresolved_inputs, .. } = result;
+        if unresolved_inputs.len() > 0 {
+            bail!("Could not resolve inputs at top-level: {:?}", unresolved_inputs)
+        }
+        Ok(model)
+    }
 }
 
 impl Framework<pb::ModelProto> for Onnx {
@@ -216,10 +321,68 @@ impl Framework<pb::ModelProto> for Onnx {
     }
 
     fn model_for_proto_model(&self, proto: &pb::ModelProto) -> TractResult<InferenceModel> {
-        let ParseResult { model, unresolved_inputs, .. } = self.parse(proto)?;
-        if unresolved_inputs.len() > 0 {
-            bail!("Could not resolve inputs at top-level: {:?}", unresolved_inputs)
-        }
-        Ok(model)
+        self.model_for_parse_result(self.parse(proto)?)
+    }
+
+    fn model_for_path(&self, p: impl AsRef<Path>) -> TractResult<InferenceModel> {
+        let proto = self.proto_model_for_path(p.as_ref())?;
+        self.model_for_parse_result(self.parse_with_model_dir(&proto, p.as_ref().parent())?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn v1(
+        _: &ParsingContext,
+        _: &pb::NodeProto,
+    ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+        bail!("v1")
+    }
+
+    fn v10(
+        _: &ParsingContext,
+        _: &pb::NodeProto,
+    ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+        bail!("v10")
+    }
+
+    fn resolved(reg: &OnnxOpRegister, domain: &str, op: &str, version: i64) -> Option<String> {
+        let onnx = Onnx::default();
+        let model = pb::ModelProto::default();
+        let external_data = ExternalData::new(None);
+        let ctx = ParsingContext {
+            onnx_operator_set_version: version,
+            opset_imports: HashMap::new(),
+            framework: &onnx,
+            model: &model,
+            parent_graphs: vec![],
+            external_data: &external_data,
+        };
+        let builder = reg.get(domain, op, version)?;
+        Some((builder)(&ctx, &pb::NodeProto::default()).unwrap_err().to_string())
+    }
+
+    #[test]
+    fn resolve_versions() {
+        let mut reg = OnnxOpRegister::default();
+        reg.insert("Op", v1);
+        reg.insert_since("Op", 10, v10);
+        reg.insert_domain("ai.onnx.ml", "MlOp", 2, v10);
+        assert_eq!(resolved(&reg, "", "Op", 1).as_deref(), Some("v1"));
+        assert_eq!(resolved(&reg, "", "Op", 9).as_deref(), Some("v1"));
+        assert_eq!(resolved(&reg, "ai.onnx", "Op", 10).as_deref(), Some("v10"));
+        assert_eq!(resolved(&reg, "", "Op", 12).as_deref(), Some("v10"));
+        assert!(reg.get("", "MlOp", 12).is_none());
+        assert!(reg.get("ai.onnx.ml", "MlOp", 1).is_none());
+        assert!(reg.get("ai.onnx.ml", "MlOp", 2).is_some());
+        assert_eq!(
+            reg.ops(),
+            vec![
+                ("", "Op", vec![(1, Some(9)), (10, None)]),
+                ("ai.onnx.ml", "MlOp", vec![(2, None)])
+            ]
+        );
     }
 }
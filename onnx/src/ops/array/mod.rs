@@ -6,6 +6,7 @@ use tract_core::ndarray;
 use tract_core::ops as tractops;
 
 use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::ops::ParamsFromInputs;
 use crate::pb::*;
 use num_traits::AsPrimitive;
 
@@ -20,16 +21,22 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("EyeLike", eye_like);
     reg.insert("Flatten", flatten);
     reg.insert("Gather", gather);
-    reg.insert("Pad", pad);
+    reg.insert("Pad", pad1);
+    reg.insert_since("Pad", 2, pad2);
+    reg.insert_since("Pad", 11, pad11);
     reg.insert("Reshape", |_, _| Ok((Box::new(tractops::array::Reshape::default()), vec![])));
     reg.insert("Shape", |_, _| Ok((Box::new(tractops::array::Shape::new(DatumType::I64)), vec![])));
     reg.insert("Size", |_, _| Ok((Box::new(tractops::array::Size::new(DatumType::I64)), vec![])));
     reg.insert("Transpose", transpose);
     reg.insert("Tile", |_, _| Ok((Box::new(tractops::array::Tile::default()), vec![])));
-    reg.insert("Slice", slice::slice);
+    reg.insert("Slice", slice::slice1);
+    reg.insert_since("Slice", 10, slice::slice10);
     reg.insert("Split", split);
+    reg.insert_since("Split", 13, split13);
     reg.insert("Squeeze", squeeze);
+    reg.insert_since("Squeeze", 13, squeeze13);
     reg.insert("Unsqueeze", unsqueeze);
+    reg.insert_since("Unsqueeze", 13, unsqueeze13);
 }
 
 pub fn concat(
@@ -99,11 +106,44 @@ pub fn gather(
     Ok((Box::new(tractops::array::Gather::new(axis)), vec![]))
 }
 
-pub fn pad(
+pub fn pad1(
     _ctx: &ParsingContext,
     node: &NodeProto,
 ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
     let value: f32 = node.get_attr_opt("value")?.unwrap_or(0.0);
+    let pads = node.get_attr_tvec("paddings")?;
+    Ok((pad_op(node, &pads, value)?, vec![]))
+}
+
+pub fn pad2(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let value: f32 = node.get_attr_opt("value")?.unwrap_or(0.0);
+    let pads = node.get_attr_tvec("pads")?;
+    Ok((pad_op(node, &pads, value)?, vec![]))
+}
+
+pub fn pad11(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let node_ = node.clone();
+    let op = ParamsFromInputs::for_node(node, 2, move |params| {
+        let pads = params[0].as_ref().ok_or("Pad requires pads")?;
+        let pads = pads.cast_to::<i64>()?;
+        let pads = pads.as_slice::<i64>()?;
+        if pads.iter().any(|&p| p < 0) {
+            bail!("Negative pads (cropping) are not supported: {:?}", pads)
+        }
+        let pads: TVec<usize> = pads.iter().map(|&p| p as usize).collect();
+        let value = params[1].as_ref().map(|v| v.cast_to_scalar::<f32>()).transpose()?;
+        pad_op(&node_, &pads, value.unwrap_or(0.0))
+    });
+    Ok((Box::new(op), vec![]))
+}
+
+fn pad_op(node: &NodeProto, pads: &[usize], value: f32) -> TractResult<Box<dyn InferenceOp>> {
     let mode = match node.get_attr_opt("mode")? {
         None | Some("constant") => None,
         Some(mode) => node.check_value(
@@ -116,10 +156,9 @@ pub fn pad(
         )?,
     }
     .unwrap_or_else(|| tractops::array::PadMode::Constant(Arc::new(value.into())));
-    let pads = node.get_attr_tvec("pads")?;
     let rank = pads.len() / 2;
     let pads = (0..rank).map(|ax| (pads[ax], pads[ax + rank])).collect();
-    Ok((Box::new(tractops::array::Pad::new(pads, mode)), vec![]))
+    Ok(Box::new(tractops::array::Pad::new(pads, mode)))
 }
 
 pub fn split(
@@ -131,6 +170,46 @@ pub fn split(
     Ok((Box::new(tractops::array::Split::new(axis, node.output.len(), split)), vec![]))
 }
 
+pub fn split13(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let axis = node.get_attr_opt("axis")?.unwrap_or(0);
+    let outputs = node.output.len();
+    let op = ParamsFromInputs::for_node(node, 1, move |params| {
+        let split = params[0].as_ref().map(|s| usize_vec(s)).transpose()?;
+        Ok(Box::new(tractops::array::Split::new(axis, outputs, split)))
+    });
+    Ok((Box::new(op), vec![]))
+}
+
+/// Read a sizes parameter input.
+fn usize_vec(t: &Tensor) -> TractResult<Vec<usize>> {
+    t.cast_to::<i64>()?
+        .as_slice::<i64>()?
+        .iter()
+        .map(|&a| if a >= 0 { Ok(a as usize) } else { bail!("Negative size: {}", a) })
+        .collect()
+}
+
+/// Read an axes parameter input, resolving negative axes against `rank`.
+fn axes_vec(t: &Tensor, rank: usize) -> TractResult<Vec<usize>> {
+    let mut axes = t
+        .cast_to::<i64>()?
+        .as_slice::<i64>()?
+        .iter()
+        .map(|&a| {
+            let axis = if a < 0 { a + rank as i64 } else { a };
+            if axis < 0 || axis >= rank as i64 {
+                bail!("Axis {} is out of range for rank {}", a, rank)
+            }
+            Ok(axis as usize)
+        })
+        .collect::<TractResult<Vec<usize>>>()?;
+    axes.sort();
+    Ok(axes)
+}
+
 pub fn squeeze(
     _ctx: &ParsingContext,
     node: &NodeProto,
@@ -139,6 +218,17 @@ pub fn squeeze(
     Ok((Box::new(tractops::array::Squeeze::new(axes)), vec![]))
 }
 
+pub fn squeeze13(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let op = ParamsFromInputs::for_node_with_rank(node, 1, |rank, params| {
+        let axes = params[0].as_ref().map(|a| axes_vec(a, rank)).transpose()?;
+        Ok(Box::new(tractops::array::Squeeze::new(axes)))
+    });
+    Ok((Box::new(op), vec![]))
+}
+
 pub fn transpose(
     _ctx: &ParsingContext,
     node: &NodeProto,
@@ -154,3 +244,72 @@ pub fn unsqueeze(
     let axes = node.get_attr_vec("axes")?;
     Ok((Box::new(tractops::array::AddDims::new(axes)), vec![]))
 }
+
+pub fn unsqueeze13(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let op = ParamsFromInputs::for_node_with_rank(node, 1, |rank, params| {
+        let axes = params[0].as_ref().ok_or("Unsqueeze requires axes")?;
+        // axes refer to the output
+        let axes = axes_vec(axes, rank + axes.len())?;
+        Ok(Box::new(tractops::array::AddDims::new(axes)))
+    });
+    Ok((Box::new(op), vec![]))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::model::Onnx;
+    use crate::tensor::ExternalData;
+
+    fn build(
+        builder: fn(
+            &ParsingContext,
+            &NodeProto,
+        ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)>,
+        op_type: &str,
+        inputs: TVec<Arc<Tensor>>,
+    ) -> TractResult<TVec<Arc<Tensor>>> {
+        let onnx = Onnx::default();
+        let model = ModelProto::default();
+        let external_data = ExternalData::new(None);
+        let ctx = ParsingContext {
+            onnx_operator_set_version: 13,
+            opset_imports: HashMap::new(),
+            framework: &onnx,
+            model: &model,
+            parent_graphs: vec![],
+            external_data: &external_data,
+        };
+        let node = NodeProto {
+            op_type: op_type.to_string(),
+            input: (0..inputs.len()).map(|i| format!("input_{}", i)).collect(),
+            output: vec!["output".to_string()],
+            ..NodeProto::default()
+        };
+        let (op, _) = builder(&ctx, &node)?;
+        op.as_op().as_stateless().unwrap().eval(inputs)
+    }
+
+    #[test]
+    fn squeeze13_negative_axes() {
+        let input = Tensor::from(ndarray::Array3::<f32>::zeros((2, 3, 1))).into_arc_tensor();
+        let output = build(squeeze13, "Squeeze", tvec!(input, rctensor1(&[-1i64]))).unwrap();
+        assert_eq!(output[0].shape(), &[2, 3]);
+    }
+
+    #[test]
+    fn unsqueeze13_negative_axes() {
+        let input = Tensor::from(ndarray::Array2::<f32>::zeros((2, 3))).into_arc_tensor();
+        let output = build(unsqueeze13, "Unsqueeze", tvec!(input, rctensor1(&[-1i64, 0]))).unwrap();
+        assert_eq!(output[0].shape(), &[1, 2, 3, 1]);
+    }
+
+    #[test]
+    fn pad11_negative_pads() {
+        let input = rctensor1(&[1.0f32, 2.0, 3.0]);
+        assert!(build(pad11, "Pad", tvec!(input, rctensor1(&[-1i64, 0]))).is_err());
+    }
+}
@@ -3,21 +3,7 @@ use crate::pb::*;
 use tract_core::internal::*;
 use tract_core::ndarray;
 
-pub fn slice(
-    ctx: &ParsingContext,
-    node: &NodeProto,
-) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
-    let v = ctx.onnx_operator_set_version;
-    if v >= 1 && v < 10 {
-        slice1(ctx, node)
-    } else if v == 10 {
-        slice10(ctx, node)
-    } else {
-        bail!("Only Slice-1 and Slice-10 are supported")
-    }
-}
-
-fn slice1(
+pub fn slice1(
     _ctx: &ParsingContext,
     node: &NodeProto,
 ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
@@ -146,7 +132,7 @@ impl InferenceRulesOp for Slice1 {
     inference_op_as_op!();
 }
 
-fn slice10(
+pub fn slice10(
     _ctx: &ParsingContext,
     node: &NodeProto,
 ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
@@ -1,6 +1,7 @@
 use tract_core::ops as tractops;
 
 use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::ops::ParamsFromInputs;
 use crate::pb::*;
 use tract_core::internal::*;
 use tract_core::ops::binary::Nary;
@@ -22,6 +23,7 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("Ceil", |_, _| Ok((Box::new(tractops::math::ceil()), vec![])));
     reg.insert("Floor", |_, _| Ok((Box::new(tractops::math::floor()), vec![])));
     reg.insert("Clip", clip);
+    reg.insert_since("Clip", 11, clip11);
 
     reg.insert("Cos", |_, _| Ok((Box::new(tractops::math::cos()), vec![])));
     reg.insert("Sin", |_, _| Ok((Box::new(tractops::math::sin()), vec![])));
@@ -37,7 +39,7 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("Asinh", |_, _| Ok((Box::new(tractops::math::asinh()), vec![])));
     reg.insert("Atanh", |_, _| Ok((Box::new(tractops::math::atanh()), vec![])));
 
-    reg.insert("Erf", |_, _| Ok((Box::new(erf()), vec![])));
+    reg.insert("Erf", |_, _| Ok((Box::new(tractops::math::erf()), vec![])));
     reg.insert("Exp", |_, _| Ok((Box::new(tractops::math::exp()), vec![])));
     reg.insert("Log", |_, _| Ok((Box::new(tractops::math::ln()), vec![])));
     reg.insert("Sqrt", |_, _| Ok((Box::new(tractops::math::sqrt()), vec![])));
@@ -62,22 +64,30 @@ pub fn clip(
 ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
     let min: Option<f32> = node.get_attr_opt("min")?;
     let max: Option<f32> = node.get_attr_opt("max")?;
-    let op: Box<dyn InferenceOp> = match (min, max) {
+    Ok((clip_op(min, max), vec![]))
+}
+
+pub fn clip11(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let op = ParamsFromInputs::for_node(node, 2, |params| {
+        let min = params[0].as_ref().map(|t| t.cast_to_scalar::<f32>()).transpose()?;
+        let max = params[1].as_ref().map(|t| t.cast_to_scalar::<f32>()).transpose()?;
+        Ok(clip_op(min, max))
+    });
+    Ok((Box::new(op), vec![]))
+}
+
+fn clip_op(min: Option<f32>, max: Option<f32>) -> Box<dyn InferenceOp> {
+    match (min, max) {
         (Some(min), Some(max)) => Box::new(tractops::math::scalar_min_max(max.into(), min.into())),
         (None, Some(max)) => Box::new(tractops::math::scalar_min(max.into())),
         (Some(min), None) => Box::new(tractops::math::scalar_max(min.into())),
         (None, None) => Box::new(tractops::identity::Identity::default()),
-    };
-    Ok((op, vec![]))
+    }
 }
 
-element_wise!(erf, Erf,
-    [f32] => |_, xs| {
-        xs.iter_mut().for_each(|x| *x = erf_f32(*x));
-        Ok(())
-    };
-    prefix: "onnx."
-);
 element_wise_oop!(is_nan, IsNan,
     [f32] => bool |_, xs, ys| {
         xs.iter().zip(ys.iter_mut()).for_each(|(x,y)| *y = x.is_nan());
@@ -86,28 +96,6 @@ element_wise_oop!(is_nan, IsNan,
     prefix: "onnx."
 );
 
-#[allow(non_upper_case_globals)]
-fn erf_f32(x: f32) -> f32 {
-    const a1: f32 = 0.0705230784;
-    const a2: f32 = 0.0422820123;
-    const a3: f32 = 0.0092705272;
-    const a4: f32 = 0.0001520143;
-    const a5: f32 = 0.0002765672;
-    const a6: f32 = 0.0000430638;
-
-    let signum = x.signum();
-    let x = x.abs();
-    let y = a6 * x;
-    let y = (a5 + y) * x;
-    let y = (a4 + y) * x;
-    let y = (a3 + y) * x;
-    let y = (a2 + y) * x;
-    let y = (a1 + y) * x;
-    let y = 1.0 - (y + 1.0).powi(16).recip();
-
-    y.copysign(signum)
-}
-
 pub fn gemm(
     _ctx: &ParsingContext,
     node: &NodeProto,
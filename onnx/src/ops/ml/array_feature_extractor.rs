@@ -0,0 +1,136 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::*;
+use tract_core::internal::*;
+use tract_core::ndarray::*;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert_domain("ai.onnx.ml", "ArrayFeatureExtractor", 1, array_feature_extractor);
+}
+
+fn array_feature_extractor(
+    _ctx: &ParsingContext,
+    _node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    Ok((Box::new(ArrayFeatureExtractor), vec![]))
+}
+
+/// Selects the features given by the flattened indices (second input) along
+/// the last axis. A rank 1 input is a single sample: the output is (1, k).
+#[derive(Clone, Debug)]
+pub struct ArrayFeatureExtractor;
+
+impl ArrayFeatureExtractor {
+    fn output_shape<D: DimLike>(input: &[D], indices: &[D]) -> TractResult<TVec<D>> {
+        if input.is_empty() {
+            bail!("ArrayFeatureExtractor expects an input of rank 1 or more")
+        }
+        let mut shape: TVec<D> =
+            if input.len() == 1 { tvec!(D::one()) } else { input[..input.len() - 1].into() };
+        shape.push(indices.iter().fold(D::one(), |acc, d| acc * d.clone()));
+        Ok(shape)
+    }
+
+    fn eval_t<T: Datum>(input: &Tensor, indices: &[usize]) -> TractResult<Tensor> {
+        let input = input.to_array_view::<T>()?;
+        let last = input.ndim() - 1;
+        let mut shape = input.shape().to_vec();
+        shape[last] = indices.len();
+        let selected = ArrayD::from_shape_fn(shape, |coords| {
+            let mut coords = coords.slice().to_vec();
+            coords[last] = indices[coords[last]];
+            input[&*coords].clone()
+        });
+        let shape = Self::output_shape(input.shape(), &[indices.len()])?;
+        Ok(selected.into_shape(&*shape)?.into_tensor())
+    }
+}
+
+impl Op for ArrayFeatureExtractor {
+    fn name(&self) -> Cow<str> {
+        "onnx-ml.ArrayFeatureExtractor".into()
+    }
+
+    op_as_typed_op!();
+}
+
+impl StatelessOp for ArrayFeatureExtractor {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, indices) = args_2!(inputs);
+        if input.rank() == 0 {
+            bail!("ArrayFeatureExtractor expects an input of rank 1 or more")
+        }
+        let features = input.shape()[input.rank() - 1];
+        let indices = indices
+            .cast_to::<i64>()?
+            .as_slice::<i64>()?
+            .iter()
+            .map(|&i| {
+                if i < 0 || i as usize >= features {
+                    bail!("Feature index {} out of range (0..{})", i, features)
+                }
+                Ok(i as usize)
+            })
+            .collect::<TractResult<Vec<_>>>()?;
+        let output = dispatch_datum!(Self::eval_t(input.datum_type())(&input, &*indices))?;
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for ArrayFeatureExtractor {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.given(&inputs[0].rank, move |s, rank| s.equals(&outputs[0].rank, rank.max(2)))?;
+        s.given_2(&inputs[0].shape, &inputs[1].shape, move |s, input, indices| {
+            s.equals(&outputs[0].shape, Self::output_shape(&*input, &*indices)?)
+        })
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for ArrayFeatureExtractor {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let shape = Self::output_shape(&*inputs[0].shape.to_tvec(), &*inputs[1].shape.to_tvec())?;
+        Ok(tvec!(TypedFact::dt_shape(inputs[0].datum_type, &*shape)?))
+    }
+
+    typed_op_as_op!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run(input: Tensor, indices: Tensor) -> Arc<Tensor> {
+        let inputs = tvec!(input.into_arc_tensor(), indices.into_arc_tensor());
+        ArrayFeatureExtractor.eval(inputs).unwrap().remove(0)
+    }
+
+    #[test]
+    fn reference() {
+        // onnx reference runtime, indices as emitted by skl2onnx
+        let x = tensor2(&[[0.0f32, 1.0, 2.0], [3.0, 4.0, 5.0]]);
+        let found = run(x, tensor2(&[[2i64, 0]]));
+        assert_eq!(found, rctensor2(&[[2.0f32, 0.0], [5.0, 3.0]]));
+    }
+
+    #[test]
+    fn single_sample() {
+        let found = run(tensor1(&[10i64, 11, 12]), tensor1(&[1i64]));
+        assert_eq!(found, rctensor2(&[[11i64]]));
+    }
+
+    #[test]
+    fn out_of_range() {
+        let inputs = tvec!(rctensor1(&[1.0f32]), rctensor1(&[1i64]));
+        assert!(ArrayFeatureExtractor.eval(inputs).is_err());
+    }
+}
@@ -4,7 +4,7 @@ use std::hash::Hash;
 use tract_core::internal::*;
 
 pub fn register_all_ops(reg: &mut OnnxOpRegister) {
-    reg.insert("CategoryMapper", category_mapper);
+    reg.insert_domain("ai.onnx.ml", "CategoryMapper", 1, category_mapper);
 }
 
 fn category_mapper(
@@ -31,7 +31,7 @@ fn category_mapper(
 }
 
 #[derive(Clone, new, Debug)]
-struct CategoryMapper<Src: Datum + Hash + Eq, Dst: Datum> {
+pub(crate) struct CategoryMapper<Src: Datum + Hash + Eq, Dst: Datum> {
     hash: HashMap<Src, Dst>,
     default: Dst,
 }
@@ -0,0 +1,167 @@
+use super::category_mapper::CategoryMapper;
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::*;
+use tract_core::internal::*;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert_domain("ai.onnx.ml", "LabelEncoder", 1, label_encoder);
+}
+
+fn label_encoder(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let default_int = node.get_attr_opt("default_int64")?.unwrap_or(-1i64);
+    let default_string = node.get_attr_opt("default_string")?.unwrap_or("_Unused").to_string();
+    // ai.onnx.ml v1: classes_strings, string to index or index to string
+    if let Some(classes) = node.get_attr_opt_vec::<String>("classes_strings")? {
+        return Ok((Box::new(LabelEncoderV1 { classes, default_int, default_string }), vec![]));
+    }
+    let default_float = node.get_attr_opt("default_float")?.unwrap_or(-0.0f32);
+    let keys_strings: Option<Vec<String>> = node.get_attr_opt_vec("keys_strings")?;
+    let keys_ints: Option<Vec<i64>> = node.get_attr_opt_vec("keys_int64s")?;
+    let values_strings: Option<Vec<String>> = node.get_attr_opt_vec("values_strings")?;
+    let values_ints: Option<Vec<i64>> = node.get_attr_opt_vec("values_int64s")?;
+    let values_floats: Option<Vec<f32>> = node.get_attr_opt_vec("values_floats")?;
+    macro_rules! mapper {
+        ($keys: expr) => {
+            match (values_strings, values_ints, values_floats) {
+                (Some(v), None, None) => Box::new(CategoryMapper::new(
+                    itertools::zip($keys, v).collect(),
+                    default_string,
+                )) as Box<dyn InferenceOp>,
+                (None, Some(v), None) => {
+                    Box::new(CategoryMapper::new(itertools::zip($keys, v).collect(), default_int))
+                }
+                (None, None, Some(v)) => {
+                    Box::new(CategoryMapper::new(itertools::zip($keys, v).collect(), default_float))
+                }
+                _ => node.bail(
+                    "expected exactly one of values_strings, values_int64s and values_floats",
+                )?,
+            }
+        };
+    }
+    let op = match (keys_strings, keys_ints) {
+        (Some(keys), None) => mapper!(keys),
+        (None, Some(keys)) => mapper!(keys),
+        _ => node.bail(
+            "expected exactly one of keys_strings and keys_int64s (keys_floats is not supported)",
+        )?,
+    };
+    Ok((op, vec![]))
+}
+
+/// ai.onnx.ml v1 LabelEncoder: maps strings to their index in `classes` or
+/// indexes to strings, depending on the input type.
+#[derive(Clone, Debug)]
+struct LabelEncoderV1 {
+    classes: Vec<String>,
+    default_int: i64,
+    default_string: String,
+}
+
+impl Op for LabelEncoderV1 {
+    fn name(&self) -> Cow<str> {
+        "onnx-ml.LabelEncoderV1".into()
+    }
+
+    op_as_typed_op!();
+}
+
+impl StatelessOp for LabelEncoderV1 {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let output = if input.datum_type() == String::datum_type() {
+            input
+                .to_array_view::<String>()?
+                .map(|s| {
+                    self.classes
+                        .iter()
+                        .position(|c| c == s)
+                        .map(|p| p as i64)
+                        .unwrap_or(self.default_int)
+                })
+                .into_arc_tensor()
+        } else {
+            input
+                .cast_to::<i64>()?
+                .to_array_view::<i64>()?
+                .map(|&i| {
+                    self.classes
+                        .get(i as usize)
+                        .filter(|_| i >= 0)
+                        .unwrap_or(&self.default_string)
+                        .clone()
+                })
+                .into_arc_tensor()
+        };
+        Ok(tvec!(output))
+    }
+}
+
+impl InferenceRulesOp for LabelEncoderV1 {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        s.given(&inputs[0].datum_type, move |s, dt| {
+            if dt == String::datum_type() {
+                s.equals(&outputs[0].datum_type, i64::datum_type())
+            } else {
+                s.equals(&outputs[0].datum_type, String::datum_type())
+            }
+        })
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for LabelEncoderV1 {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let dt = if inputs[0].datum_type == String::datum_type() {
+            i64::datum_type()
+        } else {
+            String::datum_type()
+        };
+        Ok(tvec!(TypedFact::dt_shape(dt, inputs[0].shape.clone())?))
+    }
+
+    typed_op_as_op!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn string_to_int() {
+        let op = CategoryMapper::new(
+            vec![("a".to_string(), 0i64), ("b".to_string(), 1)].into_iter().collect(),
+            -1i64,
+        );
+        let input = tensor1(&["b".to_string(), "c".to_string(), "a".to_string()]);
+        let output = op.eval(tvec!(input.into_arc_tensor())).unwrap();
+        assert_eq!(output[0], rctensor1(&[1i64, -1, 0]));
+    }
+
+    #[test]
+    fn v1_both_ways() {
+        let op = LabelEncoderV1 {
+            classes: vec!["a".to_string(), "b".to_string()],
+            default_int: -1,
+            default_string: "_Unused".to_string(),
+        };
+        let input = rctensor1(&["b".to_string(), "z".to_string()]);
+        assert_eq!(op.eval(tvec!(input)).unwrap()[0], rctensor1(&[1i64, -1]));
+        let input = rctensor1(&[0i64, 3, 1]);
+        let expected = rctensor1(&["a".to_string(), "_Unused".to_string(), "b".to_string()]);
+        assert_eq!(op.eval(tvec!(input)).unwrap()[0], expected);
+    }
+}
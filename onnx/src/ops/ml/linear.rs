@@ -0,0 +1,238 @@
+use super::*;
+use crate::model::ParsingContext;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert_domain("ai.onnx.ml", "LinearClassifier", 1, linear_classifier);
+    reg.insert_domain("ai.onnx.ml", "LinearRegressor", 1, linear_regressor);
+}
+
+fn linear_classifier(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let labels = ClassLabels::parse(node, "classlabels_ints")?;
+    let intercepts: Option<Vec<f32>> = node.get_attr_opt_vec("intercepts")?;
+    let rows = intercepts.as_ref().map(|i| i.len()).unwrap_or(labels.len());
+    let linear = Linear::parse(node, rows, intercepts)?;
+    node.expect(
+        linear.rows() == labels.len() || (linear.rows() == 1 && labels.len() == 2),
+        "one coefficient row per class (or one row for two classes)",
+    )?;
+    let post_transform = PostTransform::parse(node)?;
+    Ok((Box::new(LinearClassifier::new(linear, labels, post_transform)), vec![]))
+}
+
+fn linear_regressor(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let targets = node.get_attr_opt("targets")?.unwrap_or(1usize);
+    let intercepts: Option<Vec<f32>> = node.get_attr_opt_vec("intercepts")?;
+    let linear = Linear::parse(node, targets, intercepts)?;
+    let post_transform = PostTransform::parse(node)?;
+    Ok((Box::new(LinearRegressor::new(linear, post_transform)), vec![]))
+}
+
+/// Affine transform shared by LinearClassifier and LinearRegressor: scores
+/// are X . coefficients^T + intercepts.
+#[derive(Clone, Debug)]
+pub struct Linear {
+    coefficients: Array2<f32>,
+    intercepts: Option<Array1<f32>>,
+}
+
+impl Linear {
+    fn parse(node: &NodeProto, rows: usize, intercepts: Option<Vec<f32>>) -> TractResult<Linear> {
+        let coefficients: Vec<f32> = node.get_attr_vec("coefficients")?;
+        node.expect_attr("coefficients", rows > 0 && coefficients.len() % rows == 0, || {
+            format!("a multiple of {} values", rows)
+        })?;
+        let features = coefficients.len() / rows;
+        let coefficients = Array2::from_shape_vec((rows, features), coefficients)?;
+        Ok(Linear { coefficients, intercepts: intercepts.map(Array1::from) })
+    }
+
+    fn rows(&self) -> usize {
+        self.coefficients.shape()[0]
+    }
+
+    fn eval(&self, input: &Tensor) -> TractResult<Array2<f32>> {
+        let x = features(input)?;
+        if x.shape()[1] != self.coefficients.shape()[1] {
+            bail!("Expected {} features, got {}", self.coefficients.shape()[1], x.shape()[1]);
+        }
+        let mut scores = x.dot(&self.coefficients.t());
+        if let Some(intercepts) = &self.intercepts {
+            scores += intercepts;
+        }
+        Ok(scores)
+    }
+}
+
+#[derive(Clone, Debug, new)]
+pub struct LinearClassifier {
+    linear: Linear,
+    labels: ClassLabels,
+    post_transform: PostTransform,
+}
+
+impl LinearClassifier {
+    /// A single row of coefficients for two labels is a binary classifier
+    /// scoring the second class. It still outputs two scores.
+    fn is_binary(&self) -> bool {
+        self.linear.rows() == 1 && self.labels.len() == 2
+    }
+
+    fn n_scores(&self) -> usize {
+        if self.is_binary() {
+            2
+        } else {
+            self.linear.rows()
+        }
+    }
+}
+
+impl Op for LinearClassifier {
+    fn name(&self) -> Cow<str> {
+        "onnx-ml.LinearClassifier".into()
+    }
+
+    op_as_typed_op!();
+}
+
+impl StatelessOp for LinearClassifier {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let mut scores = self.linear.eval(&input)?;
+        let classes: Vec<usize>;
+        if self.is_binary() {
+            classes = scores.iter().map(|&s| (s > 0.0) as usize).collect();
+            scores = stack(Axis(1), &[(-&scores).view(), scores.view()])?;
+        } else {
+            classes = scores.outer_iter().map(argmax).collect();
+        }
+        for row in scores.outer_iter_mut() {
+            self.post_transform.apply(row);
+        }
+        Ok(tvec!(self.labels.select(&classes).into_arc_tensor(), scores.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for LinearClassifier {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        classifier_rules(s, inputs, outputs, self.labels.datum_type(), self.n_scores())
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for LinearClassifier {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        classifier_output_facts(inputs[0], self.labels.datum_type(), self.n_scores())
+    }
+
+    typed_op_as_op!();
+}
+
+#[derive(Clone, Debug, new)]
+pub struct LinearRegressor {
+    linear: Linear,
+    post_transform: PostTransform,
+}
+
+impl Op for LinearRegressor {
+    fn name(&self) -> Cow<str> {
+        "onnx-ml.LinearRegressor".into()
+    }
+
+    op_as_typed_op!();
+}
+
+impl StatelessOp for LinearRegressor {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let mut scores = self.linear.eval(&input)?;
+        for row in scores.outer_iter_mut() {
+            self.post_transform.apply(row);
+        }
+        Ok(tvec!(scores.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for LinearRegressor {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        regressor_rules(s, inputs, outputs, self.linear.rows())
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for LinearRegressor {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        regressor_output_facts(inputs[0], self.linear.rows())
+    }
+
+    typed_op_as_op!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn linear(coefficients: Array2<f32>, intercepts: &[f32]) -> Linear {
+        Linear { coefficients, intercepts: Some(arr1(intercepts)) }
+    }
+
+    #[test]
+    fn multiclass() {
+        let op = LinearClassifier::new(
+            linear(arr2(&[[1.0, 0.0], [0.0, 1.0], [-1.0, -1.0]]), &[0.0, 0.5, 0.0]),
+            ClassLabels::Strings(vec!["a".into(), "b".into(), "c".into()]),
+            PostTransform::None,
+        );
+        let input = rctensor2(&[[2.0f32, 1.0], [0.0, 1.0], [-1.0, -1.0]]);
+        let result = op.eval(tvec!(input)).unwrap();
+        assert_eq!(result[0], rctensor1(&["a".to_string(), "b".to_string(), "c".to_string()]));
+        assert_eq!(
+            result[1],
+            rctensor2(&[[2.0f32, 1.5, -3.0], [0.0, 1.5, -1.0], [-1.0, -0.5, 2.0]])
+        );
+    }
+
+    #[test]
+    fn binary_logistic() {
+        // sklearn LogisticRegression(coef_=[[1, -1]], intercept_=[0.5]).predict_proba
+        let op = LinearClassifier::new(
+            linear(arr2(&[[1.0, -1.0]]), &[0.5]),
+            ClassLabels::Ints(vec![3, 7]),
+            PostTransform::Logistic,
+        );
+        let input = rctensor2(&[[1.0f32, 2.0], [1.0, 0.0]]);
+        let result = op.eval(tvec!(input)).unwrap();
+        assert_eq!(result[0], rctensor1(&[3i64, 7]));
+        let expected = rctensor2(&[[0.62245935f32, 0.37754068], [0.18242553, 0.81757448]]);
+        expected.close_enough(&result[1], true).unwrap();
+    }
+
+    #[test]
+    fn regressor() {
+        let op = LinearRegressor::new(
+            linear(arr2(&[[1.0, 2.0], [0.0, -1.0]]), &[1.0, 0.0]),
+            PostTransform::None,
+        );
+        let result = op.eval(tvec!(rctensor1(&[1.0f32, 1.0]))).unwrap();
+        assert_eq!(result[0], rctensor2(&[[4.0f32, -1.0]]));
+    }
+}
@@ -0,0 +1,279 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::*;
+use tract_core::internal::*;
+use tract_core::ndarray::*;
+
+mod array_feature_extractor;
+mod category_mapper;
+mod label_encoder;
+mod linear;
+mod preprocess;
+mod svm;
+mod tree_ensemble;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    array_feature_extractor::register_all_ops(reg);
+    category_mapper::register_all_ops(reg);
+    label_encoder::register_all_ops(reg);
+    linear::register_all_ops(reg);
+    preprocess::register_all_ops(reg);
+    svm::register_all_ops(reg);
+    tree_ensemble::register_all_ops(reg);
+    reg.insert_domain("ai.onnx.ml", "ZipMap", 1, zip_map);
+}
+
+// tract has no sequence or map types
+fn zip_map(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    node.bail(
+        "ZipMap outputs a sequence of maps, which is not supported: use the probability \
+         tensor it consumes (skl2onnx option zipmap=False)",
+    )
+}
+
+/// Class labels of a classifier, as found in `classlabels_strings` or in the
+/// integer variant of the attribute (its name varies across operators).
+#[derive(Clone, Debug)]
+pub enum ClassLabels {
+    Ints(Vec<i64>),
+    Strings(Vec<String>),
+}
+
+impl ClassLabels {
+    pub fn parse(node: &NodeProto, ints_attr: &str) -> TractResult<ClassLabels> {
+        let ints: Option<Vec<i64>> = node.get_attr_opt_vec(ints_attr)?;
+        let strings: Option<Vec<String>> = node.get_attr_opt_vec("classlabels_strings")?;
+        match (ints, strings) {
+            (Some(ints), None) => Ok(ClassLabels::Ints(ints)),
+            (None, Some(strings)) => Ok(ClassLabels::Strings(strings)),
+            _ => node.bail(&format!(
+                "expected exactly one of {} and classlabels_strings attributes",
+                ints_attr
+            )),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            ClassLabels::Ints(v) => v.len(),
+            ClassLabels::Strings(v) => v.len(),
+        }
+    }
+
+    pub fn datum_type(&self) -> DatumType {
+        match self {
+            ClassLabels::Ints(_) => i64::datum_type(),
+            ClassLabels::Strings(_) => String::datum_type(),
+        }
+    }
+
+    /// Build the label output tensor from the winning class indexes.
+    pub fn select(&self, classes: &[usize]) -> Tensor {
+        match self {
+            ClassLabels::Ints(v) => tensor1(&*classes.iter().map(|&c| v[c]).collect::<Vec<_>>()),
+            ClassLabels::Strings(v) => {
+                tensor1(&*classes.iter().map(|&c| v[c].clone()).collect::<Vec<_>>())
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum PostTransform {
+    None,
+    Softmax,
+    Logistic,
+    SoftmaxZero,
+    Probit,
+}
+
+impl PostTransform {
+    pub fn parse(node: &NodeProto) -> TractResult<PostTransform> {
+        match node.get_attr_opt("post_transform")?.unwrap_or("NONE") {
+            "NONE" => Ok(PostTransform::None),
+            "SOFTMAX" => Ok(PostTransform::Softmax),
+            "LOGISTIC" => Ok(PostTransform::Logistic),
+            "SOFTMAX_ZERO" => Ok(PostTransform::SoftmaxZero),
+            "PROBIT" => Ok(PostTransform::Probit),
+            other => node.bail_attr("post_transform", &format!("unknown value {}", other)),
+        }
+    }
+
+    /// Apply the transform to one row of scores.
+    pub fn apply(&self, mut scores: ArrayViewMut1<f32>) {
+        match self {
+            PostTransform::None => (),
+            PostTransform::Logistic => scores.mapv_inplace(logistic),
+            PostTransform::Probit => scores.mapv_inplace(probit),
+            PostTransform::Softmax => {
+                let max = scores.fold(std::f32::MIN, |a, &b| a.max(b));
+                scores.mapv_inplace(|x| (x - max).exp());
+                let sum = scores.sum();
+                scores.mapv_inplace(|x| x / sum);
+            }
+            PostTransform::SoftmaxZero => {
+                let max = scores.fold(std::f32::MIN, |a, &b| a.max(b));
+                scores.mapv_inplace(|x| if x == 0.0 { 0.0 } else { (x - max).exp() });
+                let sum = scores.sum();
+                if sum > 0.0 {
+                    scores.mapv_inplace(|x| x / sum);
+                }
+            }
+        }
+    }
+}
+
+pub fn logistic(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+pub fn probit(x: f32) -> f32 {
+    std::f32::consts::SQRT_2 * erf_inv(2.0 * x - 1.0)
+}
+
+/// Single precision inverse error function (Giles' approximation).
+fn erf_inv(x: f32) -> f32 {
+    let w = -((1.0 - x) * (1.0 + x)).ln();
+    if w < 5.0 {
+        let w = w - 2.5;
+        let p = [
+            2.810_226_4e-8,
+            3.432_739_4e-7,
+            -3.523_387_7e-6,
+            -4.391_506_5e-6,
+            0.000_218_580_87,
+            -0.001_253_725,
+            -0.004_177_681_6,
+            0.246_640_73,
+            1.501_409_4,
+        ];
+        p.iter().fold(0.0, |acc, c| acc * w + c) * x
+    } else {
+        let w = w.sqrt() - 3.0;
+        let p = [
+            -0.000_200_214_26,
+            0.000_100_950_56,
+            0.001_349_343_2,
+            -0.003_673_428_4,
+            0.005_739_507_7,
+            -0.007_622_461,
+            0.009_438_870_5,
+            1.001_674_1,
+            2.832_976_8,
+        ];
+        p.iter().fold(0.0, |acc, c| acc * w + c) * x
+    }
+}
+
+/// Index of the first maximum score.
+pub fn argmax(row: ArrayView1<f32>) -> usize {
+    row.iter().enumerate().fold(0, |best, (ix, &s)| if s > row[best] { ix } else { best })
+}
+
+/// View the input features as a (N, C) f32 matrix. Rank 1 inputs are a single
+/// sample.
+pub fn features(input: &Tensor) -> TractResult<Array2<f32>> {
+    let input = input.cast_to::<f32>()?;
+    let input = input.to_array_view::<f32>()?;
+    let (n, c) = match input.ndim() {
+        1 => (1, input.len()),
+        2 => (input.shape()[0], input.shape()[1]),
+        r => bail!("Expected input of rank 1 or 2, got {}", r),
+    };
+    Ok(input.into_shape((n, c))?.to_owned())
+}
+
+/// Number of samples (N) for an input features shape.
+pub fn batch_dim<D: DimLike>(shape: &[D]) -> D {
+    if shape.len() == 2 {
+        shape[0].clone()
+    } else {
+        D::one()
+    }
+}
+
+/// Inference rules for classifiers: X is (N, C) or (C), labels are (N) and
+/// scores (N, n_scores).
+pub fn classifier_rules<'r, 'p: 'r, 's: 'r>(
+    s: &mut Solver<'r>,
+    inputs: &'p [TensorProxy],
+    outputs: &'p [TensorProxy],
+    labels_type: DatumType,
+    n_scores: usize,
+) -> InferenceResult {
+    check_input_arity(&inputs, 1)?;
+    check_output_arity(&outputs, 2)?;
+    s.equals(&outputs[0].datum_type, labels_type)?;
+    s.equals(&outputs[1].datum_type, f32::datum_type())?;
+    s.equals(&outputs[0].rank, 1)?;
+    s.equals(&outputs[1].rank, 2)?;
+    s.equals(&outputs[1].shape[1], n_scores.to_dim())?;
+    s.given(&inputs[0].shape, move |s, shape| {
+        let n = batch_dim(&*shape);
+        s.equals(&outputs[0].shape[0], n.clone())?;
+        s.equals(&outputs[1].shape[0], n)
+    })
+}
+
+pub fn classifier_output_facts(
+    input: &TypedFact,
+    labels_type: DatumType,
+    n_scores: usize,
+) -> TractResult<TVec<TypedFact>> {
+    let n = batch_dim(&*input.shape.to_tvec());
+    Ok(tvec!(
+        TypedFact::dt_shape(labels_type, [n.clone()].as_ref())?,
+        TypedFact::dt_shape(f32::datum_type(), [n, n_scores.to_dim()].as_ref())?
+    ))
+}
+
+/// Inference rules for regressors and (N, C) -> (N, n_targets) transforms.
+pub fn regressor_rules<'r, 'p: 'r, 's: 'r>(
+    s: &mut Solver<'r>,
+    inputs: &'p [TensorProxy],
+    outputs: &'p [TensorProxy],
+    n_targets: usize,
+) -> InferenceResult {
+    check_input_arity(&inputs, 1)?;
+    check_output_arity(&outputs, 1)?;
+    s.equals(&outputs[0].datum_type, f32::datum_type())?;
+    s.equals(&outputs[0].rank, 2)?;
+    s.equals(&outputs[0].shape[1], n_targets.to_dim())?;
+    s.given(&inputs[0].shape, move |s, shape| s.equals(&outputs[0].shape[0], batch_dim(&*shape)))
+}
+
+pub fn regressor_output_facts(input: &TypedFact, n_targets: usize) -> TractResult<TVec<TypedFact>> {
+    let n = batch_dim(&*input.shape.to_tvec());
+    Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), [n, n_targets.to_dim()].as_ref())?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn softmax() {
+        let mut scores = arr1(&[1.0f32, 2.0, 3.0]);
+        PostTransform::Softmax.apply(scores.view_mut());
+        let expected = arr1(&[0.09003057f32, 0.24472847, 0.66524096]);
+        scores.into_tensor().close_enough(&expected.into_tensor(), true).unwrap();
+    }
+
+    #[test]
+    fn softmax_zero() {
+        let mut scores = arr1(&[0.0f32, 1.0, 1.0]);
+        PostTransform::SoftmaxZero.apply(scores.view_mut());
+        assert_eq!(scores, arr1(&[0.0f32, 0.5, 0.5]));
+    }
+
+    #[test]
+    fn probit_reference() {
+        // scipy.stats.norm.ppf
+        for (p, z) in &[(0.5f32, 0.0f32), (0.975, 1.959964), (0.1, -1.2815516), (0.999, 3.0902324)]
+        {
+            assert!((probit(*p) - z).abs() < 1e-4, "probit({}) = {} != {}", p, probit(*p), z);
+        }
+    }
+}
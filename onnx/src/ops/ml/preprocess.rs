@@ -0,0 +1,373 @@
+use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::pb::*;
+use tract_core::internal::*;
+use tract_core::ndarray::*;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert_domain("ai.onnx.ml", "Binarizer", 1, binarizer);
+    reg.insert_domain("ai.onnx.ml", "Imputer", 1, imputer);
+    reg.insert_domain("ai.onnx.ml", "Normalizer", 1, normalizer);
+    reg.insert_domain("ai.onnx.ml", "Scaler", 1, scaler);
+}
+
+fn binarizer(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let threshold = node.get_attr_opt("threshold")?.unwrap_or(0.0f32);
+    Ok((Box::new(Binarizer::new(threshold)), vec![]))
+}
+
+fn imputer(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let floats: Option<Vec<f32>> = node.get_attr_opt_vec("imputed_value_floats")?;
+    let ints: Option<Vec<i64>> = node.get_attr_opt_vec("imputed_value_int64s")?;
+    let op = match (floats, ints) {
+        (Some(values), None) => Imputer::F32 {
+            values,
+            replaced: node.get_attr_opt("replaced_value_float")?.unwrap_or(0.0),
+        },
+        (None, Some(values)) => Imputer::I64 {
+            values,
+            replaced: node.get_attr_opt("replaced_value_int64")?.unwrap_or(0),
+        },
+        _ => node.bail("expected exactly one of imputed_value_floats and imputed_value_int64s")?,
+    };
+    Ok((Box::new(op), vec![]))
+}
+
+fn normalizer(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let norm = match node.get_attr_opt("norm")?.unwrap_or("MAX") {
+        "MAX" => Norm::Max,
+        "L1" => Norm::L1,
+        "L2" => Norm::L2,
+        other => node.bail_attr("norm", &format!("unknown value {}", other))?,
+    };
+    Ok((Box::new(Normalizer::new(norm)), vec![]))
+}
+
+fn scaler(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let offset = node.get_attr_opt_vec("offset")?.unwrap_or(vec![0.0f32]);
+    let scale = node.get_attr_opt_vec("scale")?.unwrap_or(vec![1.0f32]);
+    Ok((Box::new(Scaler::new(offset, scale)), vec![]))
+}
+
+/// Per-feature parameter: either one value for all features, or one per
+/// feature (last axis).
+fn per_feature<T: Copy>(values: &[T], feature: usize) -> T {
+    if values.len() == 1 {
+        values[0]
+    } else {
+        values[feature]
+    }
+}
+
+fn check_per_feature<T>(values: &[T], input: &Tensor, name: &str) -> TractResult<()> {
+    let features = input.shape().last().cloned().unwrap_or(1);
+    if values.len() != 1 && values.len() != features {
+        bail!("Expected 1 or {} values for {}, got {}", features, name, values.len());
+    }
+    Ok(())
+}
+
+/// Rules shared by element-wise preprocessing ops. `dt` is the output type,
+/// None meaning same as input.
+fn same_shape_rules<'r, 'p: 'r, 's: 'r>(
+    s: &mut Solver<'r>,
+    inputs: &'p [TensorProxy],
+    outputs: &'p [TensorProxy],
+    dt: Option<DatumType>,
+) -> InferenceResult {
+    check_input_arity(&inputs, 1)?;
+    check_output_arity(&outputs, 1)?;
+    s.equals(&inputs[0].shape, &outputs[0].shape)?;
+    if let Some(dt) = dt {
+        s.equals(&outputs[0].datum_type, dt)
+    } else {
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)
+    }
+}
+
+#[derive(Clone, Debug, new)]
+pub struct Binarizer {
+    threshold: f32,
+}
+
+impl Op for Binarizer {
+    fn name(&self) -> Cow<str> {
+        "onnx-ml.Binarizer".into()
+    }
+
+    op_as_typed_op!();
+}
+
+impl StatelessOp for Binarizer {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let dt = input.datum_type();
+        let output = input
+            .cast_to::<f32>()?
+            .to_array_view::<f32>()?
+            .mapv(|x| if x > self.threshold { 1.0f32 } else { 0.0 })
+            .into_tensor();
+        Ok(tvec!(output.cast_to_dt(dt)?.into_owned().into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Binarizer {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        same_shape_rules(s, inputs, outputs, None)
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Binarizer {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    typed_op_as_op!();
+}
+
+#[derive(Clone, Debug)]
+pub enum Imputer {
+    F32 { values: Vec<f32>, replaced: f32 },
+    I64 { values: Vec<i64>, replaced: i64 },
+}
+
+impl Op for Imputer {
+    fn name(&self) -> Cow<str> {
+        "onnx-ml.Imputer".into()
+    }
+
+    op_as_typed_op!();
+}
+
+impl StatelessOp for Imputer {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let output = match self {
+            Imputer::F32 { values, replaced } => {
+                check_per_feature(values, &input, "imputed_value_floats")?;
+                let mut array = input.into_tensor().into_array::<f32>()?;
+                let rank = array.ndim();
+                for (coords, x) in array.indexed_iter_mut() {
+                    if *x == *replaced || (replaced.is_nan() && x.is_nan()) {
+                        *x = per_feature(values, if rank > 0 { coords[rank - 1] } else { 0 });
+                    }
+                }
+                array.into_arc_tensor()
+            }
+            Imputer::I64 { values, replaced } => {
+                check_per_feature(values, &input, "imputed_value_int64s")?;
+                let mut array = input.into_tensor().into_array::<i64>()?;
+                let rank = array.ndim();
+                for (coords, x) in array.indexed_iter_mut() {
+                    if *x == *replaced {
+                        *x = per_feature(values, if rank > 0 { coords[rank - 1] } else { 0 });
+                    }
+                }
+                array.into_arc_tensor()
+            }
+        };
+        Ok(tvec!(output))
+    }
+}
+
+impl InferenceRulesOp for Imputer {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        let dt = match self {
+            Imputer::F32 { .. } => f32::datum_type(),
+            Imputer::I64 { .. } => i64::datum_type(),
+        };
+        s.equals(&inputs[0].datum_type, dt)?;
+        same_shape_rules(s, inputs, outputs, Some(dt))
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Imputer {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+
+    typed_op_as_op!();
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Norm {
+    Max,
+    L1,
+    L2,
+}
+
+#[derive(Clone, Debug, new)]
+pub struct Normalizer {
+    norm: Norm,
+}
+
+impl Op for Normalizer {
+    fn name(&self) -> Cow<str> {
+        "onnx-ml.Normalizer".into()
+    }
+
+    op_as_typed_op!();
+}
+
+impl StatelessOp for Normalizer {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let mut array = input.cast_to::<f32>()?.into_owned().into_array::<f32>()?;
+        let axis = Axis(array.ndim().saturating_sub(1));
+        for mut row in array.lanes_mut(axis) {
+            let norm = match self.norm {
+                Norm::Max => row.fold(std::f32::MIN, |a, &b| a.max(b)),
+                Norm::L1 => row.fold(0.0, |a, &b| a + b.abs()),
+                Norm::L2 => row.fold(0.0, |a, &b| a + b * b).sqrt(),
+            };
+            if norm != 0.0 {
+                row.mapv_inplace(|x| x / norm);
+            }
+        }
+        Ok(tvec!(array.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Normalizer {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        same_shape_rules(s, inputs, outputs, Some(f32::datum_type()))
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Normalizer {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), inputs[0].shape.clone())?))
+    }
+
+    typed_op_as_op!();
+}
+
+#[derive(Clone, Debug, new)]
+pub struct Scaler {
+    offset: Vec<f32>,
+    scale: Vec<f32>,
+}
+
+impl Op for Scaler {
+    fn name(&self) -> Cow<str> {
+        "onnx-ml.Scaler".into()
+    }
+
+    op_as_typed_op!();
+}
+
+impl StatelessOp for Scaler {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        check_per_feature(&self.offset, &input, "offset")?;
+        check_per_feature(&self.scale, &input, "scale")?;
+        let mut array = input.cast_to::<f32>()?.into_owned().into_array::<f32>()?;
+        let rank = array.ndim();
+        for (coords, x) in array.indexed_iter_mut() {
+            let feature = if rank > 0 { coords[rank - 1] } else { 0 };
+            *x = (*x - per_feature(&self.offset, feature)) * per_feature(&self.scale, feature);
+        }
+        Ok(tvec!(array.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Scaler {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        same_shape_rules(s, inputs, outputs, Some(f32::datum_type()))
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Scaler {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), inputs[0].shape.clone())?))
+    }
+
+    typed_op_as_op!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn run(op: impl StatelessOp, input: Tensor) -> Arc<Tensor> {
+        op.eval(tvec!(input.into_arc_tensor())).unwrap().remove(0)
+    }
+
+    #[test]
+    fn scaler() {
+        // sklearn StandardScaler, mean [1, 2], std [2, 4]
+        let op = Scaler::new(vec![1.0, 2.0], vec![0.5, 0.25]);
+        let found = run(op, tensor2(&[[1.0f32, 2.0], [3.0, 10.0]]));
+        assert_eq!(found, rctensor2(&[[0.0f32, 0.0], [1.0, 2.0]]));
+    }
+
+    #[test]
+    fn normalizer() {
+        let input = || tensor2(&[[3.0f32, -4.0], [0.0, 0.0]]);
+        let found = run(Normalizer::new(Norm::L2), input());
+        assert_eq!(found, rctensor2(&[[0.6f32, -0.8], [0.0, 0.0]]));
+        let found = run(Normalizer::new(Norm::L1), input());
+        assert_eq!(found, rctensor2(&[[3.0f32 / 7.0, -4.0 / 7.0], [0.0, 0.0]]));
+        let found = run(Normalizer::new(Norm::Max), input());
+        assert_eq!(found, rctensor2(&[[1.0f32, -4.0 / 3.0], [0.0, 0.0]]));
+    }
+
+    #[test]
+    fn binarizer() {
+        let found = run(Binarizer::new(0.5), tensor1(&[0.2f32, 0.5, 0.7]));
+        assert_eq!(found, rctensor1(&[0.0f32, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn imputer() {
+        let op = Imputer::F32 { values: vec![10.0, 20.0], replaced: std::f32::NAN };
+        let found = run(op, tensor2(&[[std::f32::NAN, 1.0], [2.0, std::f32::NAN]]));
+        assert_eq!(found, rctensor2(&[[10.0f32, 1.0], [2.0, 20.0]]));
+        let op = Imputer::I64 { values: vec![-1], replaced: 0 };
+        let found = run(op, tensor1(&[0i64, 3, 0]));
+        assert_eq!(found, rctensor1(&[-1i64, 3, -1]));
+    }
+}
@@ -0,0 +1,499 @@
+use super::*;
+use crate::model::ParsingContext;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert_domain("ai.onnx.ml", "SVMClassifier", 1, svm_classifier);
+    reg.insert_domain("ai.onnx.ml", "SVMRegressor", 1, svm_regressor);
+}
+
+fn svm_classifier(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let labels = ClassLabels::parse(node, "classlabels_ints")?;
+    let kernel = Kernel::parse(node)?;
+    let coefficients: Vec<f32> = node.get_attr_vec("coefficients")?;
+    let rho: Vec<f32> = node.get_attr_vec("rho")?;
+    let vectors_per_class: Vec<usize> =
+        node.get_attr_opt_vec("vectors_per_class")?.unwrap_or(vec![]);
+    let n_classes = labels.len();
+    let mode = if vectors_per_class.is_empty() {
+        node.expect_attr("rho", rho.len() == 1, "a single value in linear mode")?;
+        node.expect_attr("coefficients", coefficients.len() % n_classes == 0, || {
+            format!("a multiple of {} values", n_classes)
+        })?;
+        let features = coefficients.len() / n_classes;
+        SvmMode::Linear(Array2::from_shape_vec((n_classes, features), coefficients)?)
+    } else {
+        node.expect_attr("vectors_per_class", vectors_per_class.len() == n_classes, || {
+            format!("{} values (one per class)", n_classes)
+        })?;
+        let n_vectors: usize = vectors_per_class.iter().sum();
+        let support_vectors: Vec<f32> = node.get_attr_vec("support_vectors")?;
+        node.expect_attr("support_vectors", support_vectors.len() % n_vectors == 0, || {
+            format!("a multiple of {} values", n_vectors)
+        })?;
+        node.expect_attr(
+            "coefficients",
+            coefficients.len() == n_vectors * (n_classes - 1),
+            || format!("{} values", n_vectors * (n_classes - 1)),
+        )?;
+        node.expect_attr(
+            "rho",
+            rho.len() == n_classes * (n_classes - 1) / 2,
+            "one value per pair of classes",
+        )?;
+        let features = support_vectors.len() / n_vectors;
+        SvmMode::Svc {
+            support_vectors: Array2::from_shape_vec((n_vectors, features), support_vectors)?,
+            coefficients: Array2::from_shape_vec((n_classes - 1, n_vectors), coefficients)?,
+            vectors_per_class,
+        }
+    };
+    let prob_a: Vec<f32> = node.get_attr_opt_vec("prob_a")?.unwrap_or(vec![]);
+    let prob_b: Vec<f32> = node.get_attr_opt_vec("prob_b")?.unwrap_or(vec![]);
+    let probabilities = if prob_a.is_empty() {
+        None
+    } else {
+        node.expect(
+            prob_a.len() == prob_b.len() && prob_a.len() == rho.len() && mode.is_svc(),
+            "prob_a and prob_b to have one value per pair of classes",
+        )?;
+        Some((prob_a, prob_b))
+    };
+    let post_transform = PostTransform::parse(node)?;
+    Ok((
+        Box::new(SvmClassifier { kernel, mode, rho, probabilities, labels, post_transform }),
+        vec![],
+    ))
+}
+
+fn svm_regressor(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let kernel = Kernel::parse(node)?;
+    let coefficients: Vec<f32> = node.get_attr_vec("coefficients")?;
+    let rho: Vec<f32> = node.get_attr_vec("rho")?;
+    node.expect_attr("rho", rho.len() == 1, "a single value")?;
+    let n_supports = node.get_attr_opt("n_supports")?.unwrap_or(0usize);
+    let support_vectors = if n_supports == 0 {
+        // linear regression: the coefficients are a single "support vector"
+        Array2::from_shape_vec((1, coefficients.len()), coefficients.clone())?
+    } else {
+        node.expect_attr(
+            "coefficients",
+            coefficients.len() == n_supports,
+            "one value per support",
+        )?;
+        let support_vectors: Vec<f32> = node.get_attr_vec("support_vectors")?;
+        node.expect_attr("support_vectors", support_vectors.len() % n_supports == 0, || {
+            format!("a multiple of {} values", n_supports)
+        })?;
+        let features = support_vectors.len() / n_supports;
+        Array2::from_shape_vec((n_supports, features), support_vectors)?
+    };
+    let coefficients = if n_supports == 0 { arr1(&[1.0]) } else { Array1::from(coefficients) };
+    let kernel = if n_supports == 0 { Kernel::Linear } else { kernel };
+    let one_class = node.get_attr_opt("one_class")?.unwrap_or(0i64) != 0;
+    let post_transform = PostTransform::parse(node)?;
+    Ok((
+        Box::new(SvmRegressor {
+            kernel,
+            support_vectors,
+            coefficients,
+            rho: rho[0],
+            one_class,
+            post_transform,
+        }),
+        vec![],
+    ))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kernel {
+    Linear,
+    Poly { gamma: f32, coef0: f32, degree: f32 },
+    Rbf { gamma: f32 },
+    Sigmoid { gamma: f32, coef0: f32 },
+}
+
+impl Kernel {
+    fn parse(node: &NodeProto) -> TractResult<Kernel> {
+        let params: Vec<f32> = node.get_attr_opt_vec("kernel_params")?.unwrap_or(vec![]);
+        let param = |ix: usize| params.get(ix).cloned().unwrap_or(0.0);
+        let (gamma, coef0, degree) = (param(0), param(1), param(2));
+        match node.get_attr_opt("kernel_type")?.unwrap_or("LINEAR") {
+            "LINEAR" => Ok(Kernel::Linear),
+            "POLY" => Ok(Kernel::Poly { gamma, coef0, degree }),
+            "RBF" => Ok(Kernel::Rbf { gamma }),
+            "SIGMOID" => Ok(Kernel::Sigmoid { gamma, coef0 }),
+            other => node.bail_attr("kernel_type", &format!("unknown value {}", other)),
+        }
+    }
+
+    fn eval(&self, x: ArrayView1<f32>, y: ArrayView1<f32>) -> f32 {
+        match *self {
+            Kernel::Linear => x.dot(&y),
+            Kernel::Poly { gamma, coef0, degree } => (gamma * x.dot(&y) + coef0).powf(degree),
+            Kernel::Rbf { gamma } => {
+                let d2: f32 = x.iter().zip(y.iter()).map(|(a, b)| (a - b) * (a - b)).sum();
+                (-gamma * d2).exp()
+            }
+            Kernel::Sigmoid { gamma, coef0 } => (gamma * x.dot(&y) + coef0).tanh(),
+        }
+    }
+}
+
+fn check_features(x: &Array2<f32>, expected: usize) -> TractResult<()> {
+    if x.shape()[1] != expected {
+        bail!("Expected {} features, got {}", expected, x.shape()[1]);
+    }
+    Ok(())
+}
+
+#[derive(Clone, Debug)]
+pub enum SvmMode {
+    /// One row of coefficients per class, scored independently.
+    Linear(Array2<f32>),
+    /// libsvm one-vs-one classification. Support vectors are grouped by class,
+    /// coefficients have libsvm's (classes - 1, vectors) layout.
+    Svc { support_vectors: Array2<f32>, coefficients: Array2<f32>, vectors_per_class: Vec<usize> },
+}
+
+impl SvmMode {
+    fn is_svc(&self) -> bool {
+        match self {
+            SvmMode::Svc { .. } => true,
+            SvmMode::Linear(_) => false,
+        }
+    }
+
+    fn features(&self) -> usize {
+        match self {
+            SvmMode::Svc { support_vectors, .. } => support_vectors.shape()[1],
+            SvmMode::Linear(coefficients) => coefficients.shape()[1],
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct SvmClassifier {
+    kernel: Kernel,
+    mode: SvmMode,
+    rho: Vec<f32>,
+    probabilities: Option<(Vec<f32>, Vec<f32>)>,
+    labels: ClassLabels,
+    post_transform: PostTransform,
+}
+
+impl SvmClassifier {
+    fn n_scores(&self) -> usize {
+        let n = self.labels.len();
+        if self.mode.is_svc() && self.probabilities.is_none() {
+            n * (n - 1) / 2
+        } else {
+            n
+        }
+    }
+
+    /// Pairwise decision values and the class winning the vote.
+    fn one_vs_one(
+        &self,
+        x: ArrayView1<f32>,
+        support_vectors: &Array2<f32>,
+        coefficients: &Array2<f32>,
+        vectors_per_class: &[usize],
+    ) -> (Vec<f32>, usize) {
+        let kernels: Vec<f32> =
+            support_vectors.outer_iter().map(|sv| self.kernel.eval(x, sv)).collect();
+        let starts: Vec<usize> = vectors_per_class
+            .iter()
+            .scan(0, |acc, &n| {
+                *acc += n;
+                Some(*acc - n)
+            })
+            .collect();
+        let n = vectors_per_class.len();
+        let mut votes = vec![0usize; n];
+        let mut decisions = Vec::with_capacity(n * (n - 1) / 2);
+        for i in 0..n {
+            for j in i + 1..n {
+                let range_i = starts[i]..starts[i] + vectors_per_class[i];
+                let range_j = starts[j]..starts[j] + vectors_per_class[j];
+                let mut sum = self.rho[decisions.len()];
+                sum += range_i.map(|k| coefficients[(j - 1, k)] * kernels[k]).sum::<f32>();
+                sum += range_j.map(|k| coefficients[(i, k)] * kernels[k]).sum::<f32>();
+                votes[if sum > 0.0 { i } else { j }] += 1;
+                decisions.push(sum);
+            }
+        }
+        let winner =
+            votes.iter().enumerate().fold(0, |w, (ix, &v)| if v > votes[w] { ix } else { w });
+        (decisions, winner)
+    }
+}
+
+/// Platt scaling of a decision value (libsvm's sigmoid_predict).
+fn sigmoid_predict(decision: f32, a: f32, b: f32) -> f32 {
+    let f = decision * a + b;
+    if f >= 0.0 {
+        (-f).exp() / (1.0 + (-f).exp())
+    } else {
+        1.0 / (1.0 + f.exp())
+    }
+}
+
+/// Class probabilities from pairwise probabilities `r` (libsvm's
+/// multiclass_probability, method 2 of Wu, Lin and Weng).
+fn multiclass_probability(r: &Array2<f32>) -> Array1<f32> {
+    let k = r.shape()[0];
+    let mut q = Array2::<f32>::zeros((k, k));
+    for t in 0..k {
+        for j in 0..k {
+            if j != t {
+                q[(t, t)] += r[(j, t)] * r[(j, t)];
+                q[(t, j)] = -r[(j, t)] * r[(t, j)];
+            }
+        }
+    }
+    let mut p = Array1::<f32>::from_elem(k, 1.0 / k as f32);
+    let eps = 0.005 / k as f32;
+    for _ in 0..100.max(k) {
+        let mut qp = q.dot(&p);
+        let mut pqp = p.dot(&qp);
+        if qp.iter().all(|&v| (v - pqp).abs() < eps) {
+            break;
+        }
+        for t in 0..k {
+            let diff = (-qp[t] + pqp) / q[(t, t)];
+            p[t] += diff;
+            pqp = (pqp + diff * (diff * q[(t, t)] + 2.0 * qp[t])) / (1.0 + diff) / (1.0 + diff);
+            for j in 0..k {
+                qp[j] = (qp[j] + diff * q[(t, j)]) / (1.0 + diff);
+                p[j] /= 1.0 + diff;
+            }
+        }
+    }
+    p
+}
+
+impl Op for SvmClassifier {
+    fn name(&self) -> Cow<str> {
+        "onnx-ml.SVMClassifier".into()
+    }
+
+    op_as_typed_op!();
+}
+
+impl StatelessOp for SvmClassifier {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let x = features(&input)?;
+        check_features(&x, self.mode.features())?;
+        let mut scores = Array2::<f32>::zeros((x.shape()[0], self.n_scores()));
+        let mut classes = Vec::with_capacity(x.shape()[0]);
+        for (x, mut scores) in x.outer_iter().zip(scores.outer_iter_mut()) {
+            match &self.mode {
+                SvmMode::Linear(coefficients) => {
+                    for (s, c) in scores.iter_mut().zip(coefficients.outer_iter()) {
+                        *s = self.kernel.eval(x, c) + self.rho[0];
+                    }
+                    classes.push(argmax(scores.view()));
+                }
+                SvmMode::Svc { support_vectors, coefficients, vectors_per_class } => {
+                    let (decisions, winner) =
+                        self.one_vs_one(x, support_vectors, coefficients, vectors_per_class);
+                    if let Some((a, b)) = &self.probabilities {
+                        let k = self.labels.len();
+                        let mut r = Array2::<f32>::zeros((k, k));
+                        let mut p = 0;
+                        for i in 0..k {
+                            for j in i + 1..k {
+                                let prob = sigmoid_predict(decisions[p], a[p], b[p])
+                                    .max(1e-7)
+                                    .min(1.0 - 1e-7);
+                                r[(i, j)] = prob;
+                                r[(j, i)] = 1.0 - prob;
+                                p += 1;
+                            }
+                        }
+                        scores.assign(&multiclass_probability(&r));
+                        classes.push(argmax(scores.view()));
+                    } else {
+                        scores.assign(&Array1::from(decisions));
+                        classes.push(winner);
+                    }
+                }
+            }
+            self.post_transform.apply(scores);
+        }
+        Ok(tvec!(self.labels.select(&classes).into_arc_tensor(), scores.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for SvmClassifier {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        classifier_rules(s, inputs, outputs, self.labels.datum_type(), self.n_scores())
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for SvmClassifier {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        classifier_output_facts(inputs[0], self.labels.datum_type(), self.n_scores())
+    }
+
+    typed_op_as_op!();
+}
+
+#[derive(Clone, Debug)]
+pub struct SvmRegressor {
+    kernel: Kernel,
+    support_vectors: Array2<f32>,
+    coefficients: Array1<f32>,
+    rho: f32,
+    one_class: bool,
+    post_transform: PostTransform,
+}
+
+impl Op for SvmRegressor {
+    fn name(&self) -> Cow<str> {
+        "onnx-ml.SVMRegressor".into()
+    }
+
+    op_as_typed_op!();
+}
+
+impl StatelessOp for SvmRegressor {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let x = features(&input)?;
+        check_features(&x, self.support_vectors.shape()[1])?;
+        let mut scores = Array2::<f32>::zeros((x.shape()[0], 1));
+        for (x, mut score) in x.outer_iter().zip(scores.outer_iter_mut()) {
+            let mut s = self.rho;
+            for (sv, c) in self.support_vectors.outer_iter().zip(self.coefficients.iter()) {
+                s += c * self.kernel.eval(x, sv);
+            }
+            if self.one_class {
+                s = if s > 0.0 { 1.0 } else { -1.0 };
+            }
+            score[0] = s;
+            self.post_transform.apply(score);
+        }
+        Ok(tvec!(scores.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for SvmRegressor {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        regressor_rules(s, inputs, outputs, 1)
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for SvmRegressor {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        regressor_output_facts(inputs[0], 1)
+    }
+
+    typed_op_as_op!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn three_classes(probabilities: Option<(Vec<f32>, Vec<f32>)>) -> SvmClassifier {
+        // one support vector per class, at (0,0), (2,0) and (0,2)
+        SvmClassifier {
+            kernel: Kernel::Linear,
+            mode: SvmMode::Svc {
+                support_vectors: arr2(&[[0.0, 0.0], [2.0, 0.0], [0.0, 2.0]]),
+                coefficients: arr2(&[[1.0, -1.0, -1.0], [1.0, 1.0, -1.0]]),
+                vectors_per_class: vec![1, 1, 1],
+            },
+            rho: vec![0.0, 0.0, 0.0],
+            probabilities,
+            labels: ClassLabels::Ints(vec![10, 20, 30]),
+            post_transform: PostTransform::None,
+        }
+    }
+
+    #[test]
+    fn one_vs_one_votes() {
+        // pairs (0,1), (0,2), (1,2): k = (0, 2x, 2y)
+        // d01 = -2x, d02 = -2y, d12 = 2x - 2y
+        let op = three_classes(None);
+        let input = rctensor2(&[[1.0f32, 0.5], [0.5, 1.0], [-1.0, -1.0]]);
+        let result = op.eval(tvec!(input)).unwrap();
+        assert_eq!(result[0], rctensor1(&[20i64, 30, 10]));
+        assert_eq!(
+            result[1],
+            rctensor2(&[[-2.0f32, -1.0, 1.0], [-1.0, -2.0, -1.0], [2.0, 2.0, 0.0]])
+        );
+    }
+
+    #[test]
+    fn probabilities() {
+        let op = three_classes(Some((vec![-1.0; 3], vec![0.0; 3])));
+        let result = op.eval(tvec!(rctensor2(&[[0.0f32, 0.0], [3.0, 0.0]]))).unwrap();
+        assert_eq!(result[0], rctensor1(&[10i64, 20]));
+        let scores = result[1].to_array_view::<f32>().unwrap();
+        // all decisions equal: uniform
+        for p in scores.index_axis(Axis(0), 0) {
+            assert!((p - 1.0 / 3.0).abs() < 1e-3);
+        }
+        // rows sum to one
+        for row in scores.outer_iter() {
+            assert!((row.sum() - 1.0).abs() < 1e-4);
+        }
+        assert!(scores[[1, 1]] > 0.9);
+    }
+
+    #[test]
+    fn linear_mode() {
+        let op = SvmClassifier {
+            kernel: Kernel::Linear,
+            mode: SvmMode::Linear(arr2(&[[1.0, 0.0], [0.0, 1.0]])),
+            rho: vec![0.5],
+            probabilities: None,
+            labels: ClassLabels::Strings(vec!["x".into(), "y".into()]),
+            post_transform: PostTransform::None,
+        };
+        let result = op.eval(tvec!(rctensor2(&[[1.0f32, 2.0]]))).unwrap();
+        assert_eq!(result[0], rctensor1(&["y".to_string()]));
+        assert_eq!(result[1], rctensor2(&[[1.5f32, 2.5]]));
+    }
+
+    #[test]
+    fn regressor_rbf() {
+        let op = SvmRegressor {
+            kernel: Kernel::Rbf { gamma: 0.5 },
+            support_vectors: arr2(&[[0.0, 0.0], [1.0, 1.0]]),
+            coefficients: arr1(&[1.0, -2.0]),
+            rho: 0.25,
+            one_class: false,
+            post_transform: PostTransform::None,
+        };
+        let result = op.eval(tvec!(rctensor2(&[[0.0f32, 0.0], [1.0, 1.0]]))).unwrap();
+        // 0.25 + exp(0) - 2 exp(-1), 0.25 + exp(-1) - 2 exp(0)
+        let expected = rctensor2(&[[0.514241f32], [-1.382121]]);
+        expected.close_enough(&result[0], true).unwrap();
+    }
+}
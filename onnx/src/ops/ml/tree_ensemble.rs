@@ -0,0 +1,498 @@
+use super::*;
+use crate::model::ParsingContext;
+
+pub fn register_all_ops(reg: &mut OnnxOpRegister) {
+    reg.insert_domain("ai.onnx.ml", "TreeEnsembleClassifier", 1, tree_ensemble_classifier);
+    reg.insert_domain("ai.onnx.ml", "TreeEnsembleRegressor", 1, tree_ensemble_regressor);
+}
+
+fn tree_ensemble_classifier(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let labels = ClassLabels::parse(node, "classlabels_int64s")?;
+    let ensemble = TreeEnsemble::parse(node, "class", labels.len(), Aggregate::Sum)?;
+    let base_values = node.get_attr_opt_vec("base_values")?.unwrap_or(vec![]);
+    let post_transform = PostTransform::parse(node)?;
+    Ok((
+        Box::new(TreeEnsembleClassifier::new(ensemble, labels, base_values, post_transform)),
+        vec![],
+    ))
+}
+
+fn tree_ensemble_regressor(
+    _ctx: &ParsingContext,
+    node: &NodeProto,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let n_targets = node.get_attr("n_targets")?;
+    let aggregate = match node.get_attr_opt("aggregate_function")?.unwrap_or("SUM") {
+        "SUM" => Aggregate::Sum,
+        "AVERAGE" => Aggregate::Average,
+        "MIN" => Aggregate::Min,
+        "MAX" => Aggregate::Max,
+        other => node.bail_attr("aggregate_function", &format!("unknown value {}", other))?,
+    };
+    let ensemble = TreeEnsemble::parse(node, "target", n_targets, aggregate)?;
+    let base_values = node.get_attr_opt_vec("base_values")?.unwrap_or(vec![]);
+    let post_transform = PostTransform::parse(node)?;
+    Ok((Box::new(TreeEnsembleRegressor::new(ensemble, base_values, post_transform)), vec![]))
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Aggregate {
+    Sum,
+    Average,
+    Min,
+    Max,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum NodeMode {
+    BranchLeq,
+    BranchLt,
+    BranchGte,
+    BranchGt,
+    BranchEq,
+    BranchNeq,
+    Leaf,
+}
+
+#[derive(Clone, Debug)]
+pub struct TreeNode {
+    mode: NodeMode,
+    feature: usize,
+    value: f32,
+    true_node: usize,
+    false_node: usize,
+    missing_tracks_true: bool,
+    /// (output column, weight) contributions of a leaf
+    weights: Vec<(usize, f32)>,
+}
+
+/// A forest in flat form: node children and tree roots are indexes in
+/// `nodes`.
+#[derive(Clone, Debug)]
+pub struct TreeEnsemble {
+    nodes: Vec<TreeNode>,
+    roots: Vec<usize>,
+    n_outputs: usize,
+    aggregate: Aggregate,
+}
+
+impl TreeEnsemble {
+    /// Parse the nodes_* attributes, and the leaf weights from the
+    /// `{prefix}_ids`, `{prefix}_nodeids`, `{prefix}_treeids` and
+    /// `{prefix}_weights` attributes (prefix is "class" or "target").
+    fn parse(
+        node: &NodeProto,
+        prefix: &str,
+        n_outputs: usize,
+        aggregate: Aggregate,
+    ) -> TractResult<TreeEnsemble> {
+        let tree_ids: Vec<i64> = node.get_attr_vec("nodes_treeids")?;
+        let node_ids: Vec<i64> = node.get_attr_vec("nodes_nodeids")?;
+        let features: Vec<usize> = node.get_attr_vec("nodes_featureids")?;
+        let values: Vec<f32> = node.get_attr_vec("nodes_values")?;
+        let modes: Vec<String> = node.get_attr_vec("nodes_modes")?;
+        let true_ids: Vec<i64> = node.get_attr_vec("nodes_truenodeids")?;
+        let false_ids: Vec<i64> = node.get_attr_vec("nodes_falsenodeids")?;
+        let missing: Vec<i64> =
+            node.get_attr_opt_vec("nodes_missing_value_tracks_true")?.unwrap_or(vec![]);
+        let len = tree_ids.len();
+        for (name, l) in &[
+            ("nodes_nodeids", node_ids.len()),
+            ("nodes_featureids", features.len()),
+            ("nodes_values", values.len()),
+            ("nodes_modes", modes.len()),
+            ("nodes_truenodeids", true_ids.len()),
+            ("nodes_falsenodeids", false_ids.len()),
+        ] {
+            node.expect_attr(name, *l == len, "same length as nodes_treeids")?;
+        }
+        let index: HashMap<(i64, i64), usize> =
+            tree_ids.iter().cloned().zip(node_ids.iter().cloned()).zip(0..).collect();
+        let lookup = |tree: i64, id: i64| {
+            node.expect_ok_or_else(index.get(&(tree, id)).cloned(), || {
+                format!("node {} in tree {}", id, tree)
+            })
+        };
+        let mut nodes = Vec::with_capacity(len);
+        let mut roots: Vec<(i64, usize)> = vec![];
+        for ix in 0..len {
+            let mode = match &*modes[ix] {
+                "BRANCH_LEQ" => NodeMode::BranchLeq,
+                "BRANCH_LT" => NodeMode::BranchLt,
+                "BRANCH_GTE" => NodeMode::BranchGte,
+                "BRANCH_GT" => NodeMode::BranchGt,
+                "BRANCH_EQ" => NodeMode::BranchEq,
+                "BRANCH_NEQ" => NodeMode::BranchNeq,
+                "LEAF" => NodeMode::Leaf,
+                other => node.bail_attr("nodes_modes", &format!("unknown mode {}", other))?,
+            };
+            let (true_node, false_node) = if mode == NodeMode::Leaf {
+                (0, 0)
+            } else {
+                (lookup(tree_ids[ix], true_ids[ix])?, lookup(tree_ids[ix], false_ids[ix])?)
+            };
+            nodes.push(TreeNode {
+                mode,
+                feature: features[ix],
+                value: values[ix],
+                true_node,
+                false_node,
+                missing_tracks_true: missing.get(ix).map(|&m| m != 0).unwrap_or(false),
+                weights: vec![],
+            });
+            // the root is the smallest node id of each tree
+            match roots.iter_mut().find(|r| r.0 == tree_ids[ix]) {
+                Some(r) if node_ids[ix] < node_ids[r.1] => r.1 = ix,
+                Some(_) => (),
+                None => roots.push((tree_ids[ix], ix)),
+            }
+        }
+        let w_ids: Vec<usize> = node.get_attr_vec(&format!("{}_ids", prefix))?;
+        let w_node_ids: Vec<i64> = node.get_attr_vec(&format!("{}_nodeids", prefix))?;
+        let w_tree_ids: Vec<i64> = node.get_attr_vec(&format!("{}_treeids", prefix))?;
+        let w_weights: Vec<f32> = node.get_attr_vec(&format!("{}_weights", prefix))?;
+        for ix in 0..w_ids.len() {
+            node.expect(w_ids[ix] < n_outputs, || {
+                format!("{}_ids to be less than {}, got {}", prefix, n_outputs, w_ids[ix])
+            })?;
+            let leaf = lookup(w_tree_ids[ix], w_node_ids[ix])?;
+            nodes[leaf].weights.push((w_ids[ix], w_weights[ix]));
+        }
+        Ok(TreeEnsemble {
+            nodes,
+            roots: roots.into_iter().map(|r| r.1).collect(),
+            n_outputs,
+            aggregate,
+        })
+    }
+
+    fn leaf(&self, root: usize, x: ArrayView1<f32>) -> TractResult<&TreeNode> {
+        let mut node = &self.nodes[root];
+        while node.mode != NodeMode::Leaf {
+            let v = *x.get(node.feature).ok_or_else(|| {
+                format!("Tree node refers to feature {}, input has {}", node.feature, x.len())
+            })?;
+            let go_true = if v.is_nan() {
+                node.missing_tracks_true
+            } else {
+                match node.mode {
+                    NodeMode::BranchLeq => v <= node.value,
+                    NodeMode::BranchLt => v < node.value,
+                    NodeMode::BranchGte => v >= node.value,
+                    NodeMode::BranchGt => v > node.value,
+                    NodeMode::BranchEq => v == node.value,
+                    NodeMode::BranchNeq => v != node.value,
+                    NodeMode::Leaf => unreachable!(),
+                }
+            };
+            node = &self.nodes[if go_true { node.true_node } else { node.false_node }];
+        }
+        Ok(node)
+    }
+
+    /// Aggregated leaf weights for each sample, plus the base values.
+    fn eval(&self, input: &Tensor, base_values: &[f32]) -> TractResult<Array2<f32>> {
+        let x = features(input)?;
+        let mut scores = Array2::<f32>::zeros((x.shape()[0], self.n_outputs));
+        for (x, mut scores) in x.outer_iter().zip(scores.outer_iter_mut()) {
+            let mut seen = vec![false; self.n_outputs];
+            for &root in &self.roots {
+                for &(output, w) in &self.leaf(root, x)?.weights {
+                    let s = &mut scores[output];
+                    *s = match self.aggregate {
+                        Aggregate::Sum | Aggregate::Average => *s + w,
+                        Aggregate::Min if seen[output] => s.min(w),
+                        Aggregate::Max if seen[output] => s.max(w),
+                        Aggregate::Min | Aggregate::Max => w,
+                    };
+                    seen[output] = true;
+                }
+            }
+            if self.aggregate == Aggregate::Average {
+                let trees = self.roots.len() as f32;
+                scores.mapv_inplace(|s| s / trees);
+            }
+            for (s, b) in scores.iter_mut().zip(base_values) {
+                *s += b;
+            }
+        }
+        Ok(scores)
+    }
+}
+
+#[derive(Clone, Debug, new)]
+pub struct TreeEnsembleClassifier {
+    ensemble: TreeEnsemble,
+    labels: ClassLabels,
+    base_values: Vec<f32>,
+    post_transform: PostTransform,
+}
+
+impl TreeEnsembleClassifier {
+    /// Two classes, but leaves only weight one of them: the weight is the
+    /// score of the second class (like onnxruntime's binary case).
+    fn binary_weights(&self) -> Option<usize> {
+        if self.labels.len() != 2 {
+            return None;
+        }
+        let mut columns = self.ensemble.nodes.iter().flat_map(|n| n.weights.iter().map(|w| w.0));
+        let first = columns.next()?;
+        if columns.all(|c| c == first) {
+            Some(first)
+        } else {
+            None
+        }
+    }
+}
+
+impl Op for TreeEnsembleClassifier {
+    fn name(&self) -> Cow<str> {
+        "onnx-ml.TreeEnsembleClassifier".into()
+    }
+
+    op_as_typed_op!();
+}
+
+impl StatelessOp for TreeEnsembleClassifier {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let mut scores = self.ensemble.eval(&input, &self.base_values)?;
+        let classes: Vec<usize>;
+        if let Some(column) = self.binary_weights() {
+            let score = scores.index_axis(Axis(1), column).to_owned();
+            let all_positive =
+                self.ensemble.nodes.iter().flat_map(|n| n.weights.iter()).all(|w| w.1 >= 0.0);
+            if all_positive {
+                // weights are probabilities of the positive class
+                classes = score.iter().map(|&s| (s > 0.5) as usize).collect();
+                scores = Array2::from_shape_fn((score.len(), 2), |(n, c)| {
+                    if c == 1 {
+                        score[n]
+                    } else {
+                        1.0 - score[n]
+                    }
+                });
+            } else {
+                // weights are margins
+                classes = score.iter().map(|&s| (s > 0.0) as usize).collect();
+                scores = Array2::from_shape_fn((score.len(), 2), |(n, c)| {
+                    if c == 1 {
+                        score[n]
+                    } else {
+                        -score[n]
+                    }
+                });
+            }
+        } else {
+            classes = scores.outer_iter().map(argmax).collect();
+        }
+        for row in scores.outer_iter_mut() {
+            self.post_transform.apply(row);
+        }
+        Ok(tvec!(self.labels.select(&classes).into_arc_tensor(), scores.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for TreeEnsembleClassifier {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        classifier_rules(s, inputs, outputs, self.labels.datum_type(), self.labels.len())
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for TreeEnsembleClassifier {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        classifier_output_facts(inputs[0], self.labels.datum_type(), self.labels.len())
+    }
+
+    typed_op_as_op!();
+}
+
+#[derive(Clone, Debug, new)]
+pub struct TreeEnsembleRegressor {
+    ensemble: TreeEnsemble,
+    base_values: Vec<f32>,
+    post_transform: PostTransform,
+}
+
+impl Op for TreeEnsembleRegressor {
+    fn name(&self) -> Cow<str> {
+        "onnx-ml.TreeEnsembleRegressor".into()
+    }
+
+    op_as_typed_op!();
+}
+
+impl StatelessOp for TreeEnsembleRegressor {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let mut scores = self.ensemble.eval(&input, &self.base_values)?;
+        for row in scores.outer_iter_mut() {
+            self.post_transform.apply(row);
+        }
+        Ok(tvec!(scores.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for TreeEnsembleRegressor {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        regressor_rules(s, inputs, outputs, self.ensemble.n_outputs)
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for TreeEnsembleRegressor {
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        regressor_output_facts(inputs[0], self.ensemble.n_outputs)
+    }
+
+    typed_op_as_op!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pb::attribute_proto::AttributeType;
+
+    fn ints(name: &str, v: &[i64]) -> AttributeProto {
+        AttributeProto {
+            name: name.to_string(),
+            r#type: AttributeType::Ints as i32,
+            ints: v.to_vec(),
+            ..AttributeProto::default()
+        }
+    }
+
+    fn floats(name: &str, v: &[f32]) -> AttributeProto {
+        AttributeProto {
+            name: name.to_string(),
+            r#type: AttributeType::Floats as i32,
+            floats: v.to_vec(),
+            ..AttributeProto::default()
+        }
+    }
+
+    fn strings(name: &str, v: &[&str]) -> AttributeProto {
+        AttributeProto {
+            name: name.to_string(),
+            r#type: AttributeType::Strings as i32,
+            strings: v.iter().map(|s| s.as_bytes().to_vec()).collect(),
+            ..AttributeProto::default()
+        }
+    }
+
+    /// Two stumps on feature 0 and 1 (tree 1 lists its nodes out of order):
+    /// tree 0: x0 <= 0.5 ? leaf 1 : leaf 2
+    /// tree 1: x1 <= 2.0 ? leaf 1 : leaf 2
+    fn forest(prefix: &str, leaves: &[(i64, i64, i64, f32)]) -> NodeProto {
+        let mut attribute = vec![
+            ints("nodes_treeids", &[0, 0, 0, 1, 1, 1]),
+            ints("nodes_nodeids", &[0, 1, 2, 2, 0, 1]),
+            ints("nodes_featureids", &[0, 0, 0, 0, 1, 0]),
+            floats("nodes_values", &[0.5, 0.0, 0.0, 0.0, 2.0, 0.0]),
+            strings("nodes_modes", &["BRANCH_LEQ", "LEAF", "LEAF", "LEAF", "BRANCH_LEQ", "LEAF"]),
+            ints("nodes_truenodeids", &[1, 0, 0, 0, 1, 0]),
+            ints("nodes_falsenodeids", &[2, 0, 0, 0, 2, 0]),
+            ints("nodes_missing_value_tracks_true", &[1, 0, 0, 0, 0, 0]),
+        ];
+        attribute.push(ints(
+            &format!("{}_treeids", prefix),
+            &*leaves.iter().map(|l| l.0).collect::<Vec<_>>(),
+        ));
+        attribute.push(ints(
+            &format!("{}_nodeids", prefix),
+            &*leaves.iter().map(|l| l.1).collect::<Vec<_>>(),
+        ));
+        attribute.push(ints(
+            &format!("{}_ids", prefix),
+            &*leaves.iter().map(|l| l.2).collect::<Vec<_>>(),
+        ));
+        attribute.push(floats(
+            &format!("{}_weights", prefix),
+            &*leaves.iter().map(|l| l.3).collect::<Vec<_>>(),
+        ));
+        NodeProto { attribute, ..NodeProto::default() }
+    }
+
+    #[test]
+    fn regressor() {
+        let node =
+            forest("target", &[(0, 1, 0, 1.0), (0, 2, 0, 2.0), (1, 1, 0, 10.0), (1, 2, 0, 20.0)]);
+        let ensemble = TreeEnsemble::parse(&node, "target", 1, Aggregate::Sum).unwrap();
+        let op = TreeEnsembleRegressor::new(ensemble, vec![0.5], PostTransform::None);
+        let input = rctensor2(&[[0.0f32, 0.0], [1.0, 3.0], [std::f32::NAN, 3.0]]);
+        let result = op.eval(tvec!(input.clone())).unwrap();
+        assert_eq!(result[0], rctensor2(&[[11.5f32], [22.5], [21.5]]));
+
+        let ensemble = TreeEnsemble::parse(&node, "target", 1, Aggregate::Average).unwrap();
+        let op = TreeEnsembleRegressor::new(ensemble, vec![], PostTransform::None);
+        let result = op.eval(tvec!(input.clone())).unwrap();
+        assert_eq!(result[0], rctensor2(&[[5.5f32], [11.0], [10.5]]));
+
+        let ensemble = TreeEnsemble::parse(&node, "target", 1, Aggregate::Max).unwrap();
+        let op = TreeEnsembleRegressor::new(ensemble, vec![], PostTransform::None);
+        let result = op.eval(tvec!(input)).unwrap();
+        assert_eq!(result[0], rctensor2(&[[10.0f32], [20.0], [20.0]]));
+    }
+
+    #[test]
+    fn multiclass() {
+        let node =
+            forest("class", &[(0, 1, 0, 1.0), (0, 2, 1, 1.0), (1, 1, 2, 0.5), (1, 2, 1, 0.5)]);
+        let ensemble = TreeEnsemble::parse(&node, "class", 3, Aggregate::Sum).unwrap();
+        let labels = ClassLabels::Strings(vec!["a".into(), "b".into(), "c".into()]);
+        let op = TreeEnsembleClassifier::new(ensemble, labels, vec![], PostTransform::None);
+        let input = rctensor2(&[[0.0f32, 0.0], [1.0, 3.0], [0.0, 3.0]]);
+        let result = op.eval(tvec!(input)).unwrap();
+        let expected = rctensor1(&["a".to_string(), "b".to_string(), "a".to_string()]);
+        assert_eq!(result[0], expected);
+        assert_eq!(result[1], rctensor2(&[[1.0f32, 0.0, 0.5], [0.0, 1.5, 0.0], [1.0, 0.5, 0.0]]));
+    }
+
+    #[test]
+    fn binary_margins() {
+        // gradient-boosting style: single column of margins, logistic
+        let node =
+            forest("class", &[(0, 1, 0, -1.0), (0, 2, 0, 1.0), (1, 1, 0, -0.5), (1, 2, 0, 0.5)]);
+        let ensemble = TreeEnsemble::parse(&node, "class", 2, Aggregate::Sum).unwrap();
+        let labels = ClassLabels::Ints(vec![0, 1]);
+        let op = TreeEnsembleClassifier::new(ensemble, labels, vec![0.2], PostTransform::Logistic);
+        let input = rctensor2(&[[0.0f32, 0.0], [1.0, 3.0]]);
+        let result = op.eval(tvec!(input)).unwrap();
+        assert_eq!(result[0], rctensor1(&[0i64, 1]));
+        // logistic(-1.3), logistic(1.7)
+        let expected = rctensor2(&[[0.785835f32, 0.214165], [0.154465, 0.845535]]);
+        expected.close_enough(&result[1], true).unwrap();
+    }
+
+    #[test]
+    fn binary_probabilities() {
+        // random-forest style: positive class probabilities averaged in weights
+        let node =
+            forest("class", &[(0, 1, 1, 0.1), (0, 2, 1, 0.4), (1, 1, 1, 0.1), (1, 2, 1, 0.3)]);
+        let ensemble = TreeEnsemble::parse(&node, "class", 2, Aggregate::Sum).unwrap();
+        let labels = ClassLabels::Strings(vec!["no".into(), "yes".into()]);
+        let op = TreeEnsembleClassifier::new(ensemble, labels, vec![], PostTransform::None);
+        let input = rctensor2(&[[0.0f32, 0.0], [1.0, 3.0]]);
+        let result = op.eval(tvec!(input)).unwrap();
+        assert_eq!(result[0], rctensor1(&["no".to_string(), "yes".to_string()]));
+        rctensor2(&[[0.8f32, 0.2], [0.3, 0.7]]).close_enough(&result[1], true).unwrap();
+    }
+}
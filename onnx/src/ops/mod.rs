@@ -3,13 +3,16 @@ use crate::pb::*;
 use tract_core::internal::*;
 
 mod array;
-mod category_mapper;
 mod logic;
 mod math;
+mod ml;
 mod nn;
+mod params_from_inputs;
 mod quant;
 pub mod rec;
 
+pub use params_from_inputs::ParamsFromInputs;
+
 pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("Cast", cast);
     reg.insert("Constant", konst);
@@ -17,7 +20,7 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
         Ok((Box::new(::tract_core::ops::identity::Identity::default()), vec![]))
     });
     array::register_all_ops(reg);
-    category_mapper::register_all_ops(reg);
+    ml::register_all_ops(reg);
     logic::register_all_ops(reg);
     math::register_all_ops(reg);
     nn::register_all_ops(reg);
@@ -4,6 +4,7 @@ use tract_core::ops::cnn::PaddingSpec;
 use tract_core::ops::nn::DataFormat;
 
 use crate::model::{OnnxOpRegister, ParsingContext};
+use crate::ops::ParamsFromInputs;
 use crate::pb::NodeProto;
 use crate::pb_helpers::OptionExt;
 
@@ -19,6 +20,28 @@ fn reduce(node: &NodeProto, reducer: Reducer) -> TractResult<(Box<dyn InferenceO
     Ok((Box::new(tractops::nn::Reduce::new(axes, keep_dims, reducer)), vec![]))
 }
 
+/// Reduce operators taking their axes as an optional input (ReduceSum-13,
+/// other reducers from opset 18).
+fn reduce_axes_input(
+    node: &NodeProto,
+    reducer: Reducer,
+) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
+    let keep_dims = node.get_attr_opt("keepdims")?.unwrap_or(1i64) == 1;
+    let noop_with_empty_axes = node.get_attr_opt("noop_with_empty_axes")?.unwrap_or(0i64) == 1;
+    let op = ParamsFromInputs::for_node(node, 1, move |params| {
+        let axes = match &params[0] {
+            Some(axes) => Some(axes.cast_to::<i64>()?.as_slice::<i64>()?.to_vec()),
+            None => None,
+        }
+        .filter(|axes| axes.len() > 0);
+        if axes.is_none() && noop_with_empty_axes {
+            return Ok(Box::new(tractops::identity::Identity::default()));
+        }
+        Ok(Box::new(tractops::nn::Reduce::new(axes, keep_dims, reducer)))
+    });
+    Ok((Box::new(op), vec![]))
+}
+
 pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("ArgMax", arg_max_min);
     reg.insert("ArgMin", arg_max_min);
@@ -45,15 +68,25 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("QLinearConv", conv_qlinear);
     reg.insert("PRelu", |_, _| Ok((Box::new(prelu::bin()), vec![])));
     reg.insert("ReduceL1", |_, node| reduce(node, Reducer::L1));
+    reg.insert_since("ReduceL1", 18, |_, node| reduce_axes_input(node, Reducer::L1));
     reg.insert("ReduceL2", |_, node| reduce(node, Reducer::L2));
+    reg.insert_since("ReduceL2", 18, |_, node| reduce_axes_input(node, Reducer::L2));
     reg.insert("ReduceLogSum", |_, node| reduce(node, Reducer::LogSum));
+    reg.insert_since("ReduceLogSum", 18, |_, node| reduce_axes_input(node, Reducer::LogSum));
     reg.insert("ReduceLogSumExp", |_, node| reduce(node, Reducer::LogSumExp));
+    reg.insert_since("ReduceLogSumExp", 18, |_, node| reduce_axes_input(node, Reducer::LogSumExp));
     reg.insert("ReduceMax", |_, node| reduce(node, Reducer::Max));
+    reg.insert_since("ReduceMax", 18, |_, node| reduce_axes_input(node, Reducer::Max));
     reg.insert("ReduceMean", |_, node| reduce(node, Reducer::Mean));
+    reg.insert_since("ReduceMean", 18, |_, node| reduce_axes_input(node, Reducer::Mean));
     reg.insert("ReduceMin", |_, node| reduce(node, Reducer::Min));
+    reg.insert_since("ReduceMin", 18, |_, node| reduce_axes_input(node, Reducer::Min));
     reg.insert("ReduceProd", |_, node| reduce(node, Reducer::Prod));
+    reg.insert_since("ReduceProd", 18, |_, node| reduce_axes_input(node, Reducer::Prod));
     reg.insert("ReduceSum", |_, node| reduce(node, Reducer::Sum));
+    reg.insert_since("ReduceSum", 13, |_, node| reduce_axes_input(node, Reducer::Sum));
     reg.insert("ReduceSumSquare", |_, node| reduce(node, Reducer::SumSquare));
+    reg.insert_since("ReduceSumSquare", 18, |_, node| reduce_axes_input(node, Reducer::SumSquare));
     reg.insert("Relu", |_, _| Ok((Box::new(tractops::math::scalar_max((0.0).into())), vec![])));
     reg.insert("ScaledTanh", scaled_tanh);
     reg.insert("Shrink", shrink);
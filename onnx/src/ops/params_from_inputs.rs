@@ -0,0 +1,180 @@
+use crate::pb::NodeProto;
+use std::fmt;
+use tract_core::internal::*;
+
+/// Builds the op from the parameters values and the rank of the data input,
+/// if known.
+pub type ParamsBuilder = Arc<
+    dyn Fn(Option<usize>, &[Option<Arc<Tensor>>]) -> TractResult<Box<dyn InferenceOp>>
+        + Send
+        + Sync,
+>;
+
+/// Adapter for operators whose parameters moved from attributes to inputs in
+/// later operator sets (Pad-11, Clip-11, Split-13, Squeeze-13...).
+///
+/// The first input is the data, the others are parameters. Once the
+/// parameters are known constants, the node is replaced by the op `builder`
+/// makes from their values, typically the attribute-based version.
+#[derive(Clone, new)]
+pub struct ParamsFromInputs {
+    onnx_op: String,
+    /// input index of each optional parameter
+    params: Vec<Option<usize>>,
+    outputs: usize,
+    builder: ParamsBuilder,
+}
+
+impl ParamsFromInputs {
+    /// Adapter for `node`, which has the data as first input and up to
+    /// `params` parameters inputs.
+    pub fn for_node(
+        node: &NodeProto,
+        params: usize,
+        builder: impl Fn(&[Option<Arc<Tensor>>]) -> TractResult<Box<dyn InferenceOp>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> ParamsFromInputs {
+        Self::new_with_builder(node, params, move |_, params| builder(params))
+    }
+
+    /// Same as `for_node`, for builders depending on the data input rank,
+    /// like the ones resolving negative axes.
+    pub fn for_node_with_rank(
+        node: &NodeProto,
+        params: usize,
+        builder: impl Fn(usize, &[Option<Arc<Tensor>>]) -> TractResult<Box<dyn InferenceOp>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> ParamsFromInputs {
+        let op_type = node.op_type.clone();
+        let builder = move |rank: Option<usize>, params: &[Option<Arc<Tensor>>]| {
+            let rank = rank.ok_or_else(|| format!("{} requires the rank of its input", op_type))?;
+            builder(rank, params)
+        };
+        Self::new_with_builder(node, params, builder)
+    }
+
+    fn new_with_builder(
+        node: &NodeProto,
+        params: usize,
+        builder: impl Fn(Option<usize>, &[Option<Arc<Tensor>>]) -> TractResult<Box<dyn InferenceOp>>
+            + Send
+            + Sync
+            + 'static,
+    ) -> ParamsFromInputs {
+        let params = crate::model::optional_inputs(node).skip(1).take(params).collect();
+        let outputs = node.output.iter().filter(|s| !s.is_empty()).count();
+        ParamsFromInputs::new(node.op_type.clone(), params, outputs, Arc::new(builder))
+    }
+
+    fn build(
+        &self,
+        rank: Option<usize>,
+        inputs: &[Arc<Tensor>],
+    ) -> TractResult<Box<dyn InferenceOp>> {
+        let params: TVec<Option<Arc<Tensor>>> =
+            self.params.iter().map(|p| p.map(|ix| inputs[ix].clone())).collect();
+        (self.builder)(rank, &params)
+    }
+}
+
+impl fmt::Debug for ParamsFromInputs {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "ParamsFromInputs({}, {:?})", self.onnx_op, self.params)
+    }
+}
+
+impl Op for ParamsFromInputs {
+    fn name(&self) -> Cow<str> {
+        format!("onnx.{}", self.onnx_op).into()
+    }
+
+    fn incorporate(
+        &self,
+        model: &InferenceModel,
+        node: &InferenceNode,
+    ) -> TractResult<Option<InferenceModelPatch>> {
+        let rank = model.outlet_fact(node.inputs[0])?.shape.rank().concretize().map(|r| r as usize);
+        let mut values = tvec!(Tensor::default().into_arc_tensor());
+        for &input in &node.inputs[1..] {
+            match model.outlet_fact(input)?.value.concretize() {
+                Some(v) => values.push(v),
+                None => return Ok(None),
+            }
+        }
+        let op = self.build(rank, &values)?;
+        Ok(Some(InferenceModelPatch::replace_single_op(model, node, &node.inputs[..1], op)?))
+    }
+
+    not_a_typed_op!();
+}
+
+impl StatelessOp for ParamsFromInputs {
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let op = self.build(Some(inputs[0].rank()), &inputs)?;
+        let op = op.as_op().as_stateless().ok_or("Expected a stateless op")?;
+        op.eval(tvec!(inputs[0].clone()))
+    }
+}
+
+impl InferenceRulesOp for ParamsFromInputs {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1 + self.params.iter().filter(|p| p.is_some()).count())?;
+        check_output_arity(&outputs, self.outputs)?;
+        for output in outputs {
+            s.equals(&inputs[0].datum_type, &output.datum_type)?;
+        }
+        Ok(())
+    }
+
+    inference_op_as_op!();
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pb::*;
+
+    fn node(op_type: &str, inputs: &[&str]) -> NodeProto {
+        NodeProto {
+            op_type: op_type.to_string(),
+            input: inputs.iter().map(|s| s.to_string()).collect(),
+            output: vec!["output".to_string()],
+            ..NodeProto::default()
+        }
+    }
+
+    #[test]
+    fn eval_and_incorporate() {
+        let op = ParamsFromInputs::for_node(&node("Clip", &["x", "", "max"]), 2, |params| {
+            assert!(params[0].is_none());
+            let max = params[1].as_ref().unwrap().cast_to_scalar::<f32>()?;
+            Ok(Box::new(tract_core::ops::math::scalar_min(max.into())))
+        });
+        let input = rctensor1(&[-1.0f32, 2.0, 5.0]);
+        let result = op.eval(tvec!(input.clone(), rctensor0(3.0f32))).unwrap();
+        assert_eq!(result[0], rctensor1(&[-1.0f32, 2.0, 3.0]));
+
+        let mut model = InferenceModel::default();
+        let x = model.add_source("x", InferenceFact::dt_shape(f32::datum_type(), &[3])).unwrap();
+        let max = model.add_const("max", tensor0(3.0f32)).unwrap();
+        let clip = model.add_node("clip", op, tvec!(InferenceFact::default())).unwrap();
+        model.add_edge(x, InletId::new(clip, 0)).unwrap();
+        model.add_edge(max, InletId::new(clip, 1)).unwrap();
+        model.set_output_outlets(&[OutletId::new(clip, 0)]).unwrap();
+        model.analyse(false).unwrap();
+        let model = model.incorporate().unwrap();
+        assert!(!model.node_by_name("clip").unwrap().op_is::<ParamsFromInputs>());
+        let model = model.into_typed().unwrap();
+        let result = SimplePlan::new(&model).unwrap().run(tvec!(input.into_tensor())).unwrap();
+        assert_eq!(result[0], rctensor1(&[-1.0f32, 2.0, 3.0]));
+    }
+}
@@ -1,4 +1,6 @@
 use crate::model::OnnxOpRegister;
+use crate::pb::*;
+use tract_core::internal::*;
 
 pub mod gru;
 pub mod lstm;
@@ -11,3 +13,163 @@ pub fn register_all_ops(reg: &mut OnnxOpRegister) {
     reg.insert("RNN", rnn::rnn);
     reg.insert("Scan", scan::scan);
 }
+
+/// Way a recurrent layer walks the sequence.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Direction {
+    Forward,
+    Reverse,
+    Bidirectional,
+}
+
+impl Default for Direction {
+    fn default() -> Direction {
+        Direction::Forward
+    }
+}
+
+impl Direction {
+    pub fn parse(pb: &NodeProto) -> TractResult<Direction> {
+        match pb.get_attr_opt("direction")? {
+            None | Some("forward") => Ok(Direction::Forward),
+            Some(dir) => pb.check_value(
+                "direction",
+                match dir {
+                    "reverse" => Ok(Direction::Reverse),
+                    "bidirectional" => Ok(Direction::Bidirectional),
+                    _ => Err(dir),
+                },
+            ),
+        }
+    }
+
+    /// Whether the `dir`-th direction of the layer starts from the end of
+    /// the sequence.
+    pub fn is_backward(&self, dir: usize) -> bool {
+        *self == Direction::Reverse || dir == 1
+    }
+
+    /// Only forward layers are translated to a Scan. The others need the
+    /// whole sequence before emitting their first frame: they run as is, and
+    /// can not be pulsified.
+    pub fn is_forward(&self, num_directions: &TDim) -> bool {
+        *self == Direction::Forward && *num_directions == 1.to_dim()
+    }
+
+    /// Pulsification error for the layers that are not forward.
+    pub fn not_streamable(&self, node: &NormalizedNode) -> TractResult<TVec<OutletId>> {
+        bail!(
+            "{} runs {:?}: only forward recurrences can be streamed, \
+             reverse and bidirectional layers need the whole sequence",
+            node,
+            self
+        )
+    }
+}
+
+/// Facts of the Y, Y_h and Y_c outputs (by slot, if wired) of a recurrent
+/// layer run as is.
+pub fn output_facts(
+    inputs: &[&TypedFact],
+    slots: &[Option<usize>],
+) -> TractResult<TVec<TypedFact>> {
+    let dt = inputs[0].datum_type;
+    let (seq_length, batch_size) = (inputs[0].shape.dim(0), inputs[0].shape.dim(1));
+    let (num_directions, hidden_size) = (inputs[1].shape.dim(0), inputs[2].shape.dim(2));
+    let mut facts = vec![];
+    for (ix, slot) in slots.iter().enumerate() {
+        if let Some(slot) = slot {
+            let mut shape = tvec!(num_directions.clone(), batch_size.clone(), hidden_size.clone());
+            if ix == 0 {
+                shape.insert(0, seq_length.clone());
+            }
+            facts.push((*slot, TypedFact::dt_shape(dt, &*shape)?));
+        }
+    }
+    facts.sort_by_key(|f| f.0);
+    Ok(facts.into_iter().map(|f| f.1).collect())
+}
+
+/// A reverse or bidirectional layer, run as is on the whole sequence, with
+/// its constant inputs (weights, biases, initial states) folded in.
+#[derive(Debug, Clone)]
+pub struct WholeSequence<O> {
+    pub op: O,
+    pub direction: Direction,
+    pub konsts: Vec<Option<Arc<Tensor>>>,
+}
+
+impl<O: TypedOp + StatelessOp + Clone> WholeSequence<O> {
+    /// Folds the constant inputs of `op`, at `node`.
+    pub fn declutter(
+        op: &O,
+        direction: Direction,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        let konsts = node
+            .inputs
+            .iter()
+            .map(|i| Ok(model.outlet_fact(*i)?.konst.clone()))
+            .collect::<TractResult<Vec<_>>>()?;
+        if konsts.iter().all(|k| k.is_none()) {
+            return Ok(None);
+        }
+        let inputs: TVec<OutletId> =
+            node.inputs.iter().zip(konsts.iter()).filter(|p| p.1.is_none()).map(|p| *p.0).collect();
+        let op = WholeSequence { op: op.clone(), direction, konsts };
+        Ok(Some(TypedModelPatch::replace_single_op(model, node, &inputs, op)?))
+    }
+
+    /// Interleaves the folded constants with the actual inputs.
+    fn all_inputs<T>(&self, inputs: Vec<T>, konst: impl Fn(&Arc<Tensor>) -> T) -> TVec<T> {
+        let mut inputs = inputs.into_iter();
+        self.konsts
+            .iter()
+            .filter_map(|k| match k {
+                Some(k) => Some(konst(k)),
+                None => inputs.next(),
+            })
+            .collect()
+    }
+}
+
+impl<O: TypedOp + StatelessOp + Clone> Op for WholeSequence<O> {
+    fn name(&self) -> Cow<str> {
+        self.op.name()
+    }
+
+    fn validation(&self) -> Validation {
+        self.op.validation()
+    }
+
+    op_as_typed_op!();
+}
+
+impl<O: TypedOp + StatelessOp + Clone> StatelessOp for WholeSequence<O> {
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        self.op.eval(self.all_inputs(inputs.into_vec(), |k| k.clone()))
+    }
+}
+
+impl<O: TypedOp + StatelessOp + Clone> TypedOp for WholeSequence<O> {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let inputs = inputs.iter().map(|f| (*f).clone()).collect();
+        let inputs = self.all_inputs(inputs, |k| TypedFact::from(k.clone()));
+        let inputs: TVec<&TypedFact> = inputs.iter().collect();
+        self.op.output_facts(&*inputs)
+    }
+
+    fn pulsify(
+        &self,
+        _source: &NormalizedModel,
+        node: &NormalizedNode,
+        _target: &mut PulsedModel,
+        _mapping: &HashMap<OutletId, OutletId>,
+        _pulse: usize,
+    ) -> TractResult<TVec<OutletId>> {
+        self.direction.not_streamable(node)
+    }
+}
@@ -1,4 +1,5 @@
 use crate::model::ParsingContext;
+use crate::ops::rec::Direction;
 use crate::pb::*;
 use tract_core::internal::*;
 use tract_core::ndarray;
@@ -10,6 +11,7 @@ pub fn gru(
     pb: &NodeProto,
 ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
     let mut gru = GRU::default();
+    gru.direction = Direction::parse(pb)?;
 
     let mut options = crate::model::optional_inputs(pb).skip(3);
     gru.optional_bias_input = options.next().unwrap();
@@ -25,6 +27,7 @@ pub fn gru(
 
 #[derive(Debug, Clone, new)]
 pub struct GRU {
+    pub direction: Direction,
     pub optional_bias_input: Option<usize>,
     pub optional_sequence_lens_input: Option<usize>,
     pub optional_initial_h_input: Option<usize>,
@@ -38,6 +41,7 @@ pub struct GRU {
 impl Default for GRU {
     fn default() -> GRU {
         GRU {
+            direction: Direction::default(),
             optional_bias_input: None,
             optional_sequence_lens_input: None,
             optional_initial_h_input: None,
@@ -59,7 +63,7 @@ impl Op for GRU {
         Validation::Rounding
     }
 
-    not_a_typed_op!();
+    op_as_typed_op!();
 }
 
 impl InferenceRulesOp for GRU {
@@ -137,14 +141,18 @@ impl InferenceRulesOp for GRU {
     ) -> TractResult<TVec<OutletId>> {
         use tract_core::ops::{array, math, scan};
 
+        let num_directions = target.outlet_fact(mapping[&node.inputs[1]])?.shape.dim(0);
+        if !self.direction.is_forward(&num_directions) {
+            let inputs: TVec<OutletId> = node.inputs.iter().map(|i| mapping[i]).collect();
+            return target.wire_node(&*node.name, self.clone(), &*inputs);
+        }
+
         let x_fact = target.outlet_fact(mapping[&node.inputs[0]])?.clone();
         let r_fact = target.outlet_fact(mapping[&node.inputs[2]])?;
 
         let b_size = x_fact.shape.dim(1).to_integer().unwrap() as usize;
         let h_size = r_fact.shape.dim(2).to_integer().unwrap() as usize;
 
-        // FIXME: bidi
-
         let mut body = TypedModel::default();
         let mut outer_inputs = vec![];
         let mut input_mapping = vec![];
@@ -326,7 +334,7 @@ impl InferenceRulesOp for GRU {
 
         let mut result = tvec!();
         if let Some(slot) = self.optional_y_output {
-            target_wire!(y = array::AddDims::new(vec!(0)), scan_outputs[slot]);
+            target_wire!(y = array::AddDims::new(vec!(1)), scan_outputs[slot]);
             result.push(y);
         }
         if let Some(slot) = self.optional_y_h_output {
@@ -337,6 +345,33 @@ impl InferenceRulesOp for GRU {
     }
 }
 
+impl TypedOp for GRU {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        super::output_facts(inputs, &[self.optional_y_output, self.optional_y_h_output])
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        super::WholeSequence::declutter(self, self.direction, model, node)
+    }
+
+    fn pulsify(
+        &self,
+        _source: &NormalizedModel,
+        node: &NormalizedNode,
+        _target: &mut PulsedModel,
+        _mapping: &HashMap<OutletId, OutletId>,
+        _pulse: usize,
+    ) -> TractResult<TVec<OutletId>> {
+        self.direction.not_streamable(node)
+    }
+}
+
 impl StatelessOp for GRU {
     fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
         let x: ArrayView3<f32> = inputs[0].to_array_view::<f32>()?.into_dimensionality()?; // [seq_length, batch_size, input_size]
@@ -377,7 +412,7 @@ impl StatelessOp for GRU {
             };
 
             for ix in 0..seq_length {
-                let ix = if dir == 0 { ix } else { seq_length - 1 - ix };
+                let ix = if self.direction.is_backward(dir) { seq_length - 1 - ix } else { ix };
                 let x = x.index_axis_move(Axis(0), ix);
 
                 // Xt*W_zrh^T + Wb_zrh
@@ -1,4 +1,5 @@
 use crate::model::ParsingContext;
+use crate::ops::rec::Direction;
 use crate::pb::*;
 use tract_core::internal::*;
 use tract_core::ndarray;
@@ -10,6 +11,7 @@ pub fn lstm(
     pb: &NodeProto,
 ) -> TractResult<(Box<dyn InferenceOp>, Vec<String>)> {
     let mut lstm = LSTM::default();
+    lstm.direction = Direction::parse(pb)?;
 
     let mut options = crate::model::optional_inputs(pb).skip(3);
     lstm.optional_bias_input = options.next().unwrap();
@@ -28,6 +30,7 @@ pub fn lstm(
 
 #[derive(Debug, Clone, new)]
 pub struct LSTM {
+    pub direction: Direction,
     pub optional_bias_input: Option<usize>,
     pub optional_sequence_lens_input: Option<usize>,
     pub optional_initial_h_input: Option<usize>,
@@ -44,6 +47,7 @@ pub struct LSTM {
 impl Default for LSTM {
     fn default() -> LSTM {
         LSTM {
+            direction: Direction::default(),
             optional_bias_input: None,
             optional_sequence_lens_input: None,
             optional_initial_h_input: None,
@@ -68,7 +72,7 @@ impl Op for LSTM {
         Validation::Rounding
     }
 
-    not_a_typed_op!();
+    op_as_typed_op!();
 }
 
 impl InferenceRulesOp for LSTM {
@@ -171,14 +175,18 @@ impl InferenceRulesOp for LSTM {
     ) -> TractResult<TVec<OutletId>> {
         use tract_core::ops::{array, math, scan};
 
+        let num_directions = target.outlet_fact(mapping[&node.inputs[1]])?.shape.dim(0);
+        if !self.direction.is_forward(&num_directions) {
+            let inputs: TVec<OutletId> = node.inputs.iter().map(|i| mapping[i]).collect();
+            return target.wire_node(&*node.name, self.clone(), &*inputs);
+        }
+
         let x_fact = target.outlet_fact(mapping[&node.inputs[0]])?.clone();
         let r_fact = target.outlet_fact(mapping[&node.inputs[2]])?;
 
         let b_size = x_fact.shape.dim(1).to_integer().unwrap() as usize;
         let h_size = r_fact.shape.dim(2).to_integer().unwrap() as usize;
 
-        // FIXME: bidi
-
         let mut body = TypedModel::default();
         let mut outer_inputs = vec![];
         let mut input_mapping = vec![];
@@ -447,7 +455,7 @@ impl InferenceRulesOp for LSTM {
 
         let mut result = tvec!();
         if let Some(slot) = self.optional_y_output {
-            target_wire!(y = array::AddDims::new(vec!(0)), scan_outputs[slot]);
+            target_wire!(y = array::AddDims::new(vec!(1)), scan_outputs[slot]);
             result.push(y);
         }
         if let Some(slot) = self.optional_y_h_output {
@@ -461,6 +469,36 @@ impl InferenceRulesOp for LSTM {
     }
 }
 
+impl TypedOp for LSTM {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        super::output_facts(
+            inputs,
+            &[self.optional_y_output, self.optional_y_h_output, self.optional_y_c_output],
+        )
+    }
+
+    fn declutter(
+        &self,
+        model: &TypedModel,
+        node: &TypedNode,
+    ) -> TractResult<Option<TypedModelPatch>> {
+        super::WholeSequence::declutter(self, self.direction, model, node)
+    }
+
+    fn pulsify(
+        &self,
+        _source: &NormalizedModel,
+        node: &NormalizedNode,
+        _target: &mut PulsedModel,
+        _mapping: &HashMap<OutletId, OutletId>,
+        _pulse: usize,
+    ) -> TractResult<TVec<OutletId>> {
+        self.direction.not_streamable(node)
+    }
+}
+
 impl StatelessOp for LSTM {
     fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
         let x: ArrayView3<f32> = inputs[0].to_array_view::<f32>()?.into_dimensionality()?; // [seq_length, batch_size, input_size]
@@ -522,7 +560,7 @@ impl StatelessOp for LSTM {
             let peephole = peephole.map(|p| p.index_axis_move(Axis(0), dir));
 
             for ix in 0..seq_length {
-                let ix = if dir == 0 { ix } else { seq_length - 1 - ix };
+                let ix = if self.direction.is_backward(dir) { seq_length - 1 - ix } else { ix };
                 let x = x.index_axis_move(Axis(0), ix);
                 // x -> batch_size x input_size
                 // Wt -> k=input_size x n=4*hidden_size
@@ -1,7 +1,10 @@
-use crate::pb::tensor_proto::DataType;
+use crate::pb::tensor_proto::{DataLocation, DataType};
 use crate::pb::*;
 use prost::Message;
+use std::collections::HashMap;
 use std::convert::{TryFrom, TryInto};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 use tract_core::internal::*;
 use tract_core::*;
 
@@ -61,6 +64,9 @@ impl TryFrom<type_proto::Tensor> for InferenceFact {
 impl<'a> TryFrom<&'a TensorProto> for Tensor {
     type Error = TractError;
     fn try_from(t: &TensorProto) -> TractResult<Tensor> {
+        if t.data_location == DataLocation::External as i32 {
+            bail!("Tensor {} uses external data, it can only be loaded with the model path", t.name)
+        }
         let dt = DataType::from_i32(t.data_type).unwrap().try_into()?;
         let shape: Vec<usize> = t.dims.iter().map(|&i| i as usize).collect();
         if t.raw_data.len() > 0 {
@@ -143,6 +149,83 @@ impl TryFrom<TensorProto> for Tensor {
     }
 }
 
+/// Loads tensors whose data is stored in external files (data_location is
+/// EXTERNAL), relative to the directory of the model.
+///
+/// Each file is memory mapped once, and tensors borrow their content from
+/// the mapping instead of copying it.
+#[derive(Default)]
+pub struct ExternalData {
+    model_dir: Option<PathBuf>,
+    files: Mutex<HashMap<PathBuf, SharedBytes>>,
+}
+
+impl ExternalData {
+    pub fn new(model_dir: Option<&Path>) -> ExternalData {
+        ExternalData { model_dir: model_dir.map(|d| d.to_owned()), files: Mutex::default() }
+    }
+
+    /// Convert a TensorProto to a Tensor, resolving external data if needed.
+    pub fn tensor(&self, t: &TensorProto) -> TractResult<Tensor> {
+        if t.data_location != DataLocation::External as i32 {
+            return t.try_into();
+        }
+        let mut location = None;
+        let mut offset = 0;
+        let mut length = None;
+        for entry in &t.external_data {
+            match &*entry.key {
+                "location" => location = Some(&*entry.value),
+                "offset" => offset = entry.value.parse()?,
+                "length" => length = Some(entry.value.parse::<usize>()?),
+                _ => (),
+            }
+        }
+        let location =
+            location.ok_or_else(|| format!("Tensor {}: external data without location", t.name))?;
+        let dir = self.model_dir.as_ref().ok_or_else(|| {
+            format!("Tensor {} uses external data, but the model path is unknown", t.name)
+        })?;
+        let buffer = self.map(&dir.join(location))?;
+        let dt: DatumType = DataType::from_i32(t.data_type).unwrap().try_into()?;
+        let shape: Vec<usize> = t.dims.iter().map(|&i| i as usize).collect();
+        let bytes = shape.iter().product::<usize>() * dt.size_of();
+        if length.unwrap_or(bytes) != bytes {
+            bail!(
+                "Tensor {}: expected {} bytes of external data, got {}",
+                t.name,
+                bytes,
+                length.unwrap()
+            )
+        }
+        unsafe {
+            match dt {
+                DatumType::Bool => {
+                    Ok(Tensor::from_shared_dt(DatumType::U8, &*shape, buffer, offset)?
+                        .into_array::<u8>()?
+                        .mapv(|x| x != 0)
+                        .into())
+                }
+                DatumType::String => bail!("Tensor {}: string tensors can not be external", t.name),
+                _ => Tensor::from_shared_dt(dt, &*shape, buffer, offset),
+            }
+        }
+    }
+
+    fn map(&self, path: &Path) -> TractResult<SharedBytes> {
+        let mut files = self.files.lock().map_err(|_| "poisoned lock")?;
+        if let Some(buffer) = files.get(path) {
+            return Ok(buffer.clone());
+        }
+        let file = std::fs::File::open(path)
+            .map_err(|e| format!("Could not open external data {:?}: {}", path, e))?;
+        let mmap = unsafe { memmap::Mmap::map(&file)? };
+        let buffer: SharedBytes = Arc::new(mmap);
+        files.insert(path.to_owned(), buffer.clone());
+        Ok(buffer)
+    }
+}
+
 pub fn proto_from_reader<R: ::std::io::Read>(mut r: R) -> TractResult<TensorProto> {
     let mut v = vec![];
     r.read_to_end(&mut v)?;
@@ -152,3 +235,50 @@ pub fn proto_from_reader<R: ::std::io::Read>(mut r: R) -> TractResult<TensorProt
 pub fn from_reader<R: ::std::io::Read>(r: R) -> TractResult<Tensor> {
     proto_from_reader(r)?.try_into()
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::io::Write;
+
+    fn external(name: &str, location: &str, offset: usize, dims: &[i64]) -> TensorProto {
+        let entry = |key: &str, value: String| StringStringEntryProto { key: key.into(), value };
+        TensorProto {
+            name: name.into(),
+            dims: dims.to_vec(),
+            data_type: DataType::Float as i32,
+            data_location: DataLocation::External as i32,
+            external_data: vec![
+                entry("location", location.into()),
+                entry("offset", offset.to_string()),
+                entry("length", (dims.iter().product::<i64>() * 4).to_string()),
+            ],
+            ..TensorProto::default()
+        }
+    }
+
+    #[test]
+    fn external_data() {
+        let dir = std::env::temp_dir().join(format!("tract-onnx-external-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut file = std::fs::File::create(dir.join("weights.bin")).unwrap();
+        for v in 0..8 {
+            file.write_all(&(v as f32).to_le_bytes()).unwrap();
+        }
+        drop(file);
+        let data = ExternalData::new(Some(&dir));
+        let a = data.tensor(&external("a", "weights.bin", 0, &[2, 2])).unwrap();
+        assert!(a.is_shared());
+        assert_eq!(a, tensor2(&[[0f32, 1.0], [2.0, 3.0]]));
+        let mut b = data.tensor(&external("b", "weights.bin", 16, &[4])).unwrap();
+        assert_eq!(b, tensor1(&[4f32, 5.0, 6.0, 7.0]));
+        // mutation copies out of the mapping
+        b.as_slice_mut::<f32>().unwrap()[0] = 42.0;
+        assert!(!b.is_shared());
+        let c = data.tensor(&external("c", "weights.bin", 16, &[1])).unwrap();
+        assert_eq!(c, tensor1(&[4f32]));
+        assert!(data.tensor(&external("d", "weights.bin", 24, &[4])).is_err());
+        assert!(ExternalData::default().tensor(&external("a", "weights.bin", 0, &[2])).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,172 @@
+//! Reader for TensorFlow checkpoints in the tensor bundle format: a table
+//! (`<prefix>.index`) of entries pointing in data shards
+//! (`<prefix>.data-00000-of-00001`).
+
+use std::convert::TryFrom;
+use std::io::{Read, Seek, SeekFrom};
+use std::{fs, path};
+
+use prost::Message;
+use tract_core::internal::*;
+
+use crate::tfpb::tensorflow::{BundleEntryProto, BundleHeaderProto, DataType};
+
+const TABLE_MAGIC: u64 = 0xdb4775248b80fb57;
+const FOOTER_LEN: usize = 48;
+const BLOCK_TRAILER_LEN: usize = 5;
+
+pub struct Checkpoint {
+    prefix: path::PathBuf,
+    header: BundleHeaderProto,
+    entries: HashMap<String, BundleEntryProto>,
+}
+
+impl Checkpoint {
+    /// Open the checkpoint at `prefix` (like "variables/variables").
+    pub fn open(prefix: impl AsRef<path::Path>) -> TractResult<Checkpoint> {
+        let prefix = prefix.as_ref().to_path_buf();
+        let mut index = path::PathBuf::from(format!("{}.index", prefix.display()));
+        if !index.exists() {
+            index = prefix.with_extension("index");
+        }
+        let table = fs::read(&index)
+            .map_err(|e| format!("Could not read checkpoint index {:?}: {}", index, e))?;
+        let mut header = None;
+        let mut entries = HashMap::new();
+        for (key, value) in read_table(&table)? {
+            if key.is_empty() {
+                header = Some(BundleHeaderProto::decode(value).map_err(|e| format!("{:?}", e))?);
+            } else {
+                let key = String::from_utf8(key).map_err(|_| "Invalid checkpoint key")?;
+                let entry = BundleEntryProto::decode(value).map_err(|e| format!("{:?}", e))?;
+                entries.insert(key, entry);
+            }
+        }
+        let header = header.ok_or("Checkpoint index has no header")?;
+        if header.endianness != 0 {
+            bail!("Only little endian checkpoints are supported")
+        }
+        Ok(Checkpoint { prefix, header, entries })
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(|k| &**k)
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    /// Read the tensor saved under `key`.
+    pub fn tensor(&self, key: &str) -> TractResult<Tensor> {
+        let entry = self.entries.get(key).ok_or_else(|| format!("No {} in checkpoint", key))?;
+        if entry.slices.len() > 0 {
+            bail!("Partitioned variable {} is not supported", key)
+        }
+        let dt = DataType::from_i32(entry.dtype).ok_or("Invalid DataType")?;
+        let dt = DatumType::try_from(dt)?;
+        if !dt.is_copy() {
+            bail!("Variable {} is of type {:?}, only numeric types are supported", key, dt)
+        }
+        let shape: TVec<usize> = match entry.shape {
+            Some(ref shape) => TVec::try_from(shape)?,
+            None => tvec!(),
+        };
+        if entry.size as usize != shape.iter().product::<usize>() * dt.size_of() {
+            bail!("Inconsistent size {} for variable {} of shape {:?}", entry.size, key, shape)
+        }
+        let shard = format!(
+            "{}.data-{:05}-of-{:05}",
+            self.prefix.display(),
+            entry.shard_id,
+            self.header.num_shards
+        );
+        let mut file = fs::File::open(&shard)
+            .map_err(|e| format!("Could not open checkpoint shard {}: {}", shard, e))?;
+        file.seek(SeekFrom::Start(entry.offset as u64))?;
+        let mut content = vec![0u8; entry.size as usize];
+        file.read_exact(&mut content)?;
+        unsafe { Tensor::from_raw_dt(dt, &shape, &content) }
+    }
+}
+
+fn varint(bytes: &[u8], pos: &mut usize) -> TractResult<u64> {
+    let mut value = 0u64;
+    for shift in (0..64).step_by(7) {
+        let byte = *bytes.get(*pos).ok_or("Truncated varint in checkpoint index")?;
+        *pos += 1;
+        value |= ((byte & 0x7f) as u64) << shift;
+        if byte & 0x80 == 0 {
+            return Ok(value);
+        }
+    }
+    bail!("Invalid varint in checkpoint index")
+}
+
+fn u32_at(bytes: &[u8], pos: usize) -> TractResult<u32> {
+    let slice = bytes.get(pos..pos + 4).ok_or("Truncated checkpoint index")?;
+    Ok(u32::from_le_bytes([slice[0], slice[1], slice[2], slice[3]]))
+}
+
+/// The block of `table` pointed by the handle (offset, size) read in
+/// `handle` at `pos`.
+fn block<'t>(table: &'t [u8], handle: &[u8], pos: &mut usize) -> TractResult<&'t [u8]> {
+    let offset = varint(handle, pos)? as usize;
+    let size = varint(handle, pos)? as usize;
+    if offset + size + BLOCK_TRAILER_LEN > table.len() {
+        bail!("Truncated checkpoint index")
+    }
+    if table[offset + size] != 0 {
+        bail!("Compressed checkpoint indexes are not supported")
+    }
+    Ok(&table[offset..offset + size])
+}
+
+/// Key/value entries of a block. Keys share a prefix with their predecessor.
+fn block_entries(block: &[u8]) -> TractResult<Vec<(Vec<u8>, &[u8])>> {
+    if block.len() < 4 {
+        bail!("Truncated block in checkpoint index")
+    }
+    let restarts = u32_at(block, block.len() - 4)? as usize;
+    let end =
+        block.len().checked_sub(4 * (restarts + 1)).ok_or("Invalid block in checkpoint index")?;
+    let mut entries = vec![];
+    let mut key: Vec<u8> = vec![];
+    let mut pos = 0;
+    while pos < end {
+        let shared = varint(block, &mut pos)? as usize;
+        let non_shared = varint(block, &mut pos)? as usize;
+        let value_len = varint(block, &mut pos)? as usize;
+        if shared > key.len() || pos + non_shared + value_len > end {
+            bail!("Invalid entry in checkpoint index")
+        }
+        key.truncate(shared);
+        key.extend_from_slice(&block[pos..pos + non_shared]);
+        pos += non_shared;
+        entries.push((key.clone(), &block[pos..pos + value_len]));
+        pos += value_len;
+    }
+    Ok(entries)
+}
+
+/// All key/value entries of an (uncompressed) table, in order.
+fn read_table(table: &[u8]) -> TractResult<Vec<(Vec<u8>, &[u8])>> {
+    if table.len() < FOOTER_LEN {
+        bail!("Checkpoint index is too short")
+    }
+    let footer = table.len() - FOOTER_LEN;
+    let magic =
+        u32_at(table, table.len() - 8)? as u64 | (u32_at(table, table.len() - 4)? as u64) << 32;
+    if magic != TABLE_MAGIC {
+        bail!("Checkpoint index is not a table (wrong magic)")
+    }
+    let footer = &table[footer..];
+    let mut pos = 0;
+    let _metaindex = block(table, footer, &mut pos)?;
+    let index = block(table, footer, &mut pos)?;
+    let mut entries = vec![];
+    for (_, handle) in block_entries(index)? {
+        entries.extend(block_entries(block(table, handle, &mut 0)?)?);
+    }
+    Ok(entries)
+}
@@ -50,12 +50,16 @@ extern crate tensorflow;
 #[cfg(feature = "conform")]
 pub mod conform;
 
+pub mod checkpoint;
 pub mod model;
 pub mod ops;
+pub mod pbtxt;
+pub mod saved_model;
 pub mod tensor;
 pub mod tfpb;
 
 pub use model::Tensorflow;
+pub use saved_model::FrozenSignature;
 
 pub fn tensorflow() -> Tensorflow {
     let mut ops = crate::model::TfOpRegister::default();
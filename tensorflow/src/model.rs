@@ -1,4 +1,5 @@
 use prost::Message;
+use crate::saved_model::FrozenSignature;
 use crate::tfpb::tensorflow::{GraphDef, NodeDef, SavedModel};
 use std::{fs, path};
 use tract_core::internal::*;
@@ -8,18 +9,22 @@ pub struct ParsingContext {
     pub node_output_arities: HashMap<String, usize>,
 }
 
+pub type TfOpBuilder =
+    Arc<dyn Fn(&ParsingContext, &NodeDef) -> TractResult<Box<dyn InferenceOp>> + Send + Sync>;
+
 #[derive(Clone, Default)]
-pub struct TfOpRegister(
-    pub HashMap<String, fn(&ParsingContext, node: &NodeDef) -> TractResult<Box<dyn InferenceOp>>>,
-);
+pub struct TfOpRegister(pub HashMap<String, TfOpBuilder>);
 
 impl TfOpRegister {
     pub fn insert(
         &mut self,
-        s: &'static str,
-        builder: fn(&ParsingContext, node: &NodeDef) -> TractResult<Box<dyn InferenceOp>>,
+        s: &str,
+        builder: impl Fn(&ParsingContext, &NodeDef) -> TractResult<Box<dyn InferenceOp>>
+            + Send
+            + Sync
+            + 'static,
     ) {
-        self.0.insert(s.into(), builder);
+        self.0.insert(s.into(), Arc::new(builder));
     }
 }
 
@@ -28,12 +33,18 @@ pub struct Tensorflow {
 }
 
 impl Tensorflow {
+    /// Register the operators of a plugin.
+    pub fn with_plugin(mut self, plugin: &dyn OpPlugin<TfOpRegister>) -> Tensorflow {
+        plugin.register_ops(&mut self.op_register);
+        self
+    }
+
     // From the node_def.proto documentation:
     // Each input is "node:src_output" with "node" being a string name and
     // "src_output" indicating which output tensor to use from "node". If
     // "src_output" is 0 the ":0" suffix can be omitted. Regular inputs may
     // optionally be followed by control inputs that have the format "^node".
-    fn parse_input(i: &str) -> TractResult<(&str, usize)> {
+    pub(crate) fn parse_input(i: &str) -> TractResult<(&str, usize)> {
         let pair = if i.starts_with("^") {
             (&i[1..], 0)
         } else {
@@ -75,13 +86,77 @@ impl Tensorflow {
         let mut saved = self.open_saved_model(r)?;
         Ok(saved.meta_graphs.remove(0).graph_def.unwrap())
     }
+
+    /// Read a SavedModel directory, freezing the `signature` of the MetaGraph
+    /// tagged with `tags` with the variables from the checkpoint.
+    pub fn read_saved_model_dir(
+        &self,
+        dir: impl AsRef<path::Path>,
+        tags: &[&str],
+        signature: &str,
+    ) -> TractResult<FrozenSignature> {
+        let dir = dir.as_ref();
+        let saved = self.open_saved_model(&mut fs::File::open(dir.join("saved_model.pb"))?)?;
+        crate::saved_model::freeze(saved, dir, tags, signature)
+    }
+
+    /// Read a GraphDef in protobuf text format.
+    pub fn read_text_model(&self, r: &mut dyn std::io::Read) -> TractResult<GraphDef> {
+        let mut text = String::new();
+        r.read_to_string(&mut text)?;
+        crate::pbtxt::graph_def(&text)
+    }
+
+    /// Translate a frozen signature, its inputs and outputs becoming the
+    /// model ones.
+    pub fn model_for_frozen_signature(
+        &self,
+        frozen: &FrozenSignature,
+    ) -> TractResult<InferenceModel> {
+        let mut model = self.model_for_proto_model(&frozen.graph)?;
+        let outlets = |model: &InferenceModel, names: &[String]| -> TractResult<Vec<OutletId>> {
+            names
+                .iter()
+                .map(|name| {
+                    let (node, slot) = Self::parse_input(name)?;
+                    Ok(OutletId::new(model.node_by_name(node)?.id, slot))
+                })
+                .collect()
+        };
+        let inputs = outlets(&model, &frozen.inputs)?;
+        let outputs = outlets(&model, &frozen.outputs)?;
+        model.set_input_outlets(&inputs)?;
+        model.set_output_outlets(&outputs)?;
+        Ok(model)
+    }
 }
 
 impl Framework<GraphDef> for Tensorflow {
-    /// This method will try to read as frozen model, then as a saved model.
+    /// This method will read SavedModel directories (with the "serve" tag
+    /// and "serving_default" signature) and text GraphDefs (.pbtxt). Other
+    /// files are tried as frozen model, then as a saved model.
     fn proto_model_for_path(&self, r: impl AsRef<path::Path>) -> TractResult<GraphDef> {
-        self.read_frozen_model(&mut fs::File::open(r.as_ref())?)
-            .or_else(|_| self.read_saved_model(&mut fs::File::open(r.as_ref())?))
+        let r = r.as_ref();
+        if r.is_dir() {
+            return Ok(self.read_saved_model_dir(r, &["serve"], "serving_default")?.graph);
+        }
+        if r.extension().map(|e| e == "pbtxt").unwrap_or(false) {
+            return self.read_text_model(&mut fs::File::open(r)?);
+        }
+        self.read_frozen_model(&mut fs::File::open(r)?)
+            .or_else(|_| self.read_saved_model(&mut fs::File::open(r)?))
+    }
+
+    /// SavedModel directories are translated with their signature inputs
+    /// and outputs.
+    fn model_for_path(&self, p: impl AsRef<path::Path>) -> TractResult<InferenceModel> {
+        let p = p.as_ref();
+        if p.is_dir() {
+            let frozen = self.read_saved_model_dir(p, &["serve"], "serving_default")?;
+            self.model_for_frozen_signature(&frozen)
+        } else {
+            self.model_for_proto_model(&self.proto_model_for_path(p)?)
+        }
     }
 
     /// This method expects a frozen model, use open_saved_model for TF2 saved
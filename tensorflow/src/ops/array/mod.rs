@@ -9,7 +9,10 @@ mod gather_v2;
 mod pack;
 mod pad;
 mod range;
+mod rank;
+mod reverse;
 mod slice;
+mod split;
 mod squeeze;
 mod strided_slice;
 mod transpose;
@@ -20,14 +23,22 @@ pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("Fill", fill::fill);
     reg.insert("GatherNd", gather::gather_nd);
     reg.insert("GatherV2", gather_v2::gather_v2);
+    reg.insert("MirrorPad", pad::mirror_pad);
     reg.insert("Pack", pack::pack);
     reg.insert("Pad", pad::pad);
+    reg.insert("OnesLike", |_, _| Ok(Box::new(::tract_core::ops::array::ConstantLike::new(1.0))));
     reg.insert("Range", range::range);
+    reg.insert("Rank", |_, _| Ok(Box::new(rank::Rank)));
     reg.insert("Reshape", |_, _| Ok(Box::new(::tract_core::ops::array::Reshape::new())));
+    reg.insert("ReverseV2", |_, _| Ok(Box::new(reverse::ReverseV2)));
     reg.insert("Shape", |_, _| Ok(Box::new(::tract_core::ops::array::Shape::new(DatumType::I32))));
     reg.insert("Slice", |_, _| Ok(Box::new(slice::Slice)));
+    reg.insert("Split", split::split);
+    reg.insert("SplitV", split::split_v);
     reg.insert("Squeeze", squeeze::squeeze);
     reg.insert("StridedSlice", strided_slice::build);
     reg.insert("Tile", |_, _| Ok(Box::new(::tract_core::ops::array::Tile)));
     reg.insert("Transpose", transpose::transpose);
+    reg.insert("Unpack", split::unpack);
+    reg.insert("ZerosLike", |_, _| Ok(Box::new(::tract_core::ops::array::ConstantLike::new(0.0))));
 }
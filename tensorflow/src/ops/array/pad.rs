@@ -1,5 +1,5 @@
 use num_traits::Zero;
-use tract_core::ndarray::{Array, ArrayD, ArrayView2, ArrayViewD};
+use tract_core::ndarray::{Array, ArrayD, ArrayView2, ArrayViewD, Ix2};
 
 use crate::model::ParsingContext;
 use crate::tfpb::tensorflow::NodeDef;
@@ -105,6 +105,93 @@ impl<T: Copy + Datum + Zero> InferenceRulesOp for Pad<T> {
     inference_op_as_op!();
 }
 
+#[derive(Debug, Clone, new)]
+pub struct MirrorPad {
+    mode: tract_core::ops::array::PadMode,
+}
+
+pub fn mirror_pad(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    use tract_core::ops::array::PadMode;
+    let mode = match &*pb.get_attr_str("mode")? {
+        "REFLECT" => PadMode::Reflect,
+        "SYMMETRIC" => PadMode::Symmetric,
+        mode => bail!("Unsupported MirrorPad mode {}", mode),
+    };
+    Ok(Box::new(MirrorPad::new(mode)))
+}
+
+impl MirrorPad {
+    fn as_pad(&self, paddings: &Tensor) -> TractResult<tract_core::ops::array::Pad> {
+        let paddings = paddings.cast_to::<i64>()?;
+        let paddings = paddings.to_array_view::<i64>()?.into_dimensionality::<Ix2>()?;
+        if paddings.iter().any(|&p| p < 0) {
+            bail!("MirrorPad paddings must not be negative, got {:?}", paddings)
+        }
+        let pads = paddings.outer_iter().map(|p| (p[0] as usize, p[1] as usize)).collect();
+        Ok(tract_core::ops::array::Pad::new(pads, self.mode.clone()))
+    }
+}
+
+impl Op for MirrorPad {
+    fn name(&self) -> Cow<str> {
+        "tf.MirrorPad".into()
+    }
+
+    not_a_typed_op!();
+}
+
+impl StatelessOp for MirrorPad {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, paddings) = args_2!(inputs);
+        self.as_pad(&paddings)?.eval(tvec!(input))
+    }
+}
+
+impl InferenceRulesOp for MirrorPad {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        let input = &inputs[0];
+        let padding = &inputs[1];
+        let output = &outputs[0];
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&output.datum_type, &input.datum_type)?;
+        s.equals(&input.rank, &output.rank)?;
+        s.equals(&padding.rank, 2)?;
+        s.equals(&padding.shape[0], input.rank.bex().to_dim())?;
+        s.equals(&padding.shape[1], 2.to_dim())?;
+        s.given(&padding.value, move |s, paddings| {
+            let paddings = paddings.cast_to::<i64>()?;
+            let paddings = paddings.to_array_view::<i64>()?.into_dimensionality::<Ix2>()?;
+            for (d, p) in paddings.outer_iter().enumerate() {
+                s.equals(&output.shape[d], input.shape[d].bex() + (p[0] + p[1]).to_dim())?;
+            }
+            Ok(())
+        })
+    }
+
+    inference_op_as_op!();
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        if let Some(ref paddings) = target.outlet_fact(mapping[&node.inputs[1]])?.konst {
+            let op = self.as_pad(paddings)?;
+            target.wire_node(&*node.name, op, [mapping[&node.inputs[0]]].as_ref())
+        } else {
+            bail!("Need paddings to be const")
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,4 +209,26 @@ mod tests {
 
         assert_eq!(Pad::<i32>::new().eval(inputs).unwrap(), expected);
     }
+
+    #[test]
+    fn mirror_pad_symmetric() {
+        use tract_core::ops::array::PadMode;
+        let inputs = tvec![rctensor2(&[[1, 2, 3], [4, 5, 6]]), rctensor2(&[[1, 1], [2, 2]])];
+        let expected: TVec<_> = tvec!(rctensor2(&[
+            [2, 1, 1, 2, 3, 3, 2],
+            [2, 1, 1, 2, 3, 3, 2],
+            [5, 4, 4, 5, 6, 6, 5],
+            [5, 4, 4, 5, 6, 6, 5],
+        ]));
+        assert_eq!(MirrorPad::new(PadMode::Symmetric).eval(inputs).unwrap(), expected);
+    }
+
+    #[test]
+    fn mirror_pad_out_of_range() {
+        use tract_core::ops::array::PadMode;
+        let op = MirrorPad::new(PadMode::Reflect);
+        let input = rctensor2(&[[1, 2, 3], [4, 5, 6]]);
+        assert!(op.eval(tvec![input.clone(), rctensor2(&[[0, 0], [-1, 0]])]).is_err());
+        assert!(op.eval(tvec![input, rctensor2(&[[2, 0], [0, 0]])]).is_err());
+    }
 }
@@ -0,0 +1,47 @@
+use tract_core::internal::*;
+
+#[derive(Debug, Clone)]
+pub struct Rank;
+
+impl Op for Rank {
+    fn name(&self) -> Cow<str> {
+        "tf.Rank".into()
+    }
+
+    not_a_typed_op!();
+}
+
+impl StatelessOp for Rank {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        Ok(tvec!(rctensor0(input.rank() as i32)))
+    }
+}
+
+impl InferenceRulesOp for Rank {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, DatumType::I32)?;
+        s.equals(&outputs[0].rank, 0)?;
+        s.given(&inputs[0].rank, move |s, rank| s.equals(&outputs[0].value, rctensor0(rank)))
+    }
+
+    inference_op_as_op!();
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let rank = target.outlet_fact(mapping[&node.inputs[0]])?.shape.rank();
+        Ok(tvec!(target.add_const(&*node.name, rctensor0(rank as i32))?))
+    }
+}
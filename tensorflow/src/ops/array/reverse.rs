@@ -0,0 +1,114 @@
+use tract_core::internal::*;
+use tract_core::ndarray::*;
+
+/// ReverseV2, with the axes as second input.
+#[derive(Debug, Clone)]
+pub struct ReverseV2;
+
+fn axes(axes: &Tensor, rank: usize) -> TractResult<Vec<usize>> {
+    let axes = axes.cast_to::<i64>()?;
+    Ok(axes
+        .as_slice::<i64>()?
+        .iter()
+        .map(|&ax| if ax < 0 { ax + rank as i64 } else { ax } as usize)
+        .collect())
+}
+
+impl Op for ReverseV2 {
+    fn name(&self) -> Cow<str> {
+        "tf.ReverseV2".into()
+    }
+
+    not_a_typed_op!();
+}
+
+impl StatelessOp for ReverseV2 {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, ax) = args_2!(inputs);
+        Reverse::new(axes(&ax, input.rank())?).eval(tvec!(input))
+    }
+}
+
+impl InferenceRulesOp for ReverseV2 {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        s.equals(&inputs[1].rank, 1)?;
+        Ok(())
+    }
+
+    inference_op_as_op!();
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        if let Some(ref ax) = target.outlet_fact(mapping[&node.inputs[1]])?.konst {
+            let rank = target.outlet_fact(mapping[&node.inputs[0]])?.shape.rank();
+            let op = Reverse::new(axes(ax, rank)?);
+            target.wire_node(&*node.name, op, &[mapping[&node.inputs[0]]])
+        } else {
+            bail!("Need axes to be const")
+        }
+    }
+}
+
+#[derive(Debug, Clone, new)]
+pub struct Reverse {
+    axes: Vec<usize>,
+}
+
+impl Reverse {
+    fn eval_t<T: Datum>(&self, input: &Tensor) -> TractResult<Arc<Tensor>> {
+        let mut view = input.to_array_view::<T>()?;
+        for &ax in &self.axes {
+            view.invert_axis(Axis(ax));
+        }
+        Ok(view.to_owned().into_arc_tensor())
+    }
+}
+
+impl Op for Reverse {
+    fn name(&self) -> Cow<str> {
+        "tf.Reverse".into()
+    }
+
+    op_as_typed_op!();
+}
+
+impl StatelessOp for Reverse {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        Ok(tvec!(dispatch_datum!(Self::eval_t(input.datum_type())(self, &input))?))
+    }
+}
+
+impl TypedOp for Reverse {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reverse_last_axis() {
+        let output =
+            ReverseV2.eval(tvec!(rctensor2(&[[1, 2, 3], [4, 5, 6]]), rctensor1(&[-1]))).unwrap();
+        assert_eq!(output[0], rctensor2(&[[3, 2, 1], [6, 5, 4]]));
+    }
+}
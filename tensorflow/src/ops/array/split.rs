@@ -0,0 +1,308 @@
+use tract_core::internal::*;
+use tract_core::ops::array::{RmDims, Split};
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+fn axis(axis: &Tensor, rank: usize) -> TractResult<usize> {
+    let axis = *axis.cast_to::<i64>()?.to_scalar::<i64>()?;
+    Ok(if axis < 0 { axis + rank as i64 } else { axis } as usize)
+}
+
+pub fn split(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let num_split = pb.get_attr_int("num_split")?;
+    Ok(Box::new(SplitEqual::new(num_split)))
+}
+
+/// Split, with the axis as first input.
+#[derive(Debug, Clone, new)]
+pub struct SplitEqual {
+    num_split: usize,
+}
+
+impl Op for SplitEqual {
+    fn name(&self) -> Cow<str> {
+        "tf.Split".into()
+    }
+
+    not_a_typed_op!();
+}
+
+impl StatelessOp for SplitEqual {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (ax, input) = args_2!(inputs);
+        let ax = axis(&ax, input.rank())?;
+        Split::new(ax, self.num_split, None).eval(tvec!(input))
+    }
+}
+
+impl InferenceRulesOp for SplitEqual {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, self.num_split)?;
+        s.equals(&inputs[0].rank, 0)?;
+        for output in outputs {
+            s.equals(&inputs[1].datum_type, &output.datum_type)?;
+            s.equals(&inputs[1].rank, &output.rank)?;
+        }
+        s.given_2(&inputs[0].value, &inputs[1].shape, move |s, ax, shape| {
+            let ax = axis(&ax, shape.len())?;
+            for output in outputs {
+                let mut shape = shape.clone();
+                shape[ax] = shape[ax].clone() / self.num_split as i32;
+                s.equals(&output.shape, shape)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.num_split)
+    }
+
+    inference_op_as_op!();
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        if let Some(ref ax) = target.outlet_fact(mapping[&node.inputs[0]])?.konst {
+            let rank = target.outlet_fact(mapping[&node.inputs[1]])?.shape.rank();
+            let op = Split::new(axis(ax, rank)?, self.num_split, None);
+            target.wire_node(&*node.name, op, &[mapping[&node.inputs[1]]])
+        } else {
+            bail!("Need axis to be const")
+        }
+    }
+}
+
+pub fn split_v(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let num_split = pb.get_attr_int("num_split")?;
+    Ok(Box::new(SplitV::new(num_split)))
+}
+
+/// SplitV, with the sizes and axis as inputs. One of the sizes may be -1.
+#[derive(Debug, Clone, new)]
+pub struct SplitV {
+    num_split: usize,
+}
+
+impl SplitV {
+    fn core_op(&self, sizes: &Tensor, ax: &Tensor, shape: &[usize]) -> TractResult<Split> {
+        let ax = axis(ax, shape.len())?;
+        let sizes = sizes.cast_to::<i64>()?;
+        let sizes = sizes.as_slice::<i64>()?;
+        let known: i64 = sizes.iter().filter(|&&s| s >= 0).sum();
+        let sizes = sizes
+            .iter()
+            .map(|&s| if s < 0 { shape[ax] - known as usize } else { s as usize })
+            .collect();
+        Ok(Split::new(ax, self.num_split, Some(sizes)))
+    }
+}
+
+impl Op for SplitV {
+    fn name(&self) -> Cow<str> {
+        "tf.SplitV".into()
+    }
+
+    not_a_typed_op!();
+}
+
+impl StatelessOp for SplitV {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, sizes, ax) = args_3!(inputs);
+        self.core_op(&sizes, &ax, input.shape())?.eval(tvec!(input))
+    }
+}
+
+impl InferenceRulesOp for SplitV {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 3)?;
+        check_output_arity(&outputs, self.num_split)?;
+        s.equals(&inputs[1].rank, 1)?;
+        s.equals(&inputs[1].shape[0], self.num_split.to_dim())?;
+        s.equals(&inputs[2].rank, 0)?;
+        for output in outputs {
+            s.equals(&inputs[0].datum_type, &output.datum_type)?;
+            s.equals(&inputs[0].rank, &output.rank)?;
+        }
+        s.given_3(
+            &inputs[0].shape,
+            &inputs[1].value,
+            &inputs[2].value,
+            move |s, shape, sizes, ax| {
+                let ax = axis(&ax, shape.len())?;
+                let sizes = sizes.cast_to::<i64>()?;
+                let sizes = sizes.as_slice::<i64>()?;
+                let known: i64 = sizes.iter().filter(|&&s| s >= 0).sum();
+                for (output, &size) in outputs.iter().zip(sizes.iter()) {
+                    let mut shape = shape.clone();
+                    shape[ax] =
+                        if size < 0 { shape[ax].clone() - known as i32 } else { size.to_dim() };
+                    s.equals(&output.shape, shape)?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.num_split)
+    }
+
+    inference_op_as_op!();
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let input = target.outlet_fact(mapping[&node.inputs[0]])?;
+        let sizes = target.outlet_fact(mapping[&node.inputs[1]])?;
+        let ax = target.outlet_fact(mapping[&node.inputs[2]])?;
+        if let (Some(shape), Some(sizes), Some(ax)) =
+            (input.shape.as_finite(), &sizes.konst, &ax.konst)
+        {
+            let op = self.core_op(sizes, ax, &shape)?;
+            target.wire_node(&*node.name, op, &[mapping[&node.inputs[0]]])
+        } else {
+            bail!("Need sizes, axis and input shape to be known")
+        }
+    }
+}
+
+pub fn unpack(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let num = pb.get_attr_int("num")?;
+    let axis = pb.get_attr_opt_int("axis")?.unwrap_or(0);
+    Ok(Box::new(Unpack::new(num, axis)))
+}
+
+#[derive(Debug, Clone, new)]
+pub struct Unpack {
+    num: usize,
+    axis: i64,
+}
+
+impl Unpack {
+    fn axis(&self, rank: usize) -> usize {
+        if self.axis < 0 {
+            (self.axis + rank as i64) as usize
+        } else {
+            self.axis as usize
+        }
+    }
+}
+
+impl Op for Unpack {
+    fn name(&self) -> Cow<str> {
+        "tf.Unpack".into()
+    }
+
+    not_a_typed_op!();
+}
+
+impl StatelessOp for Unpack {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let axis = self.axis(input.rank());
+        Split::new(axis, self.num, None)
+            .eval(tvec!(input))?
+            .into_iter()
+            .map(|t| Ok(RmDims::new(vec![axis]).eval(tvec!(t))?.remove(0)))
+            .collect()
+    }
+}
+
+impl InferenceRulesOp for Unpack {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, self.num)?;
+        for output in outputs {
+            s.equals(&inputs[0].datum_type, &output.datum_type)?;
+            s.equals(inputs[0].rank.bex() - 1, &output.rank)?;
+        }
+        s.given(&inputs[0].shape, move |s, shape| {
+            let axis = self.axis(shape.len());
+            let mut shape = shape.clone();
+            shape.remove(axis);
+            for output in outputs {
+                s.equals(&output.shape, shape.clone())?;
+            }
+            Ok(())
+        })
+    }
+
+    fn nboutputs(&self) -> TractResult<usize> {
+        Ok(self.num)
+    }
+
+    inference_op_as_op!();
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let input = mapping[&node.inputs[0]];
+        let axis = self.axis(target.outlet_fact(input)?.shape.rank());
+        let splits = target.wire_node(
+            format!("{}-split", node.name),
+            Split::new(axis, self.num, None),
+            &[input],
+        )?;
+        splits
+            .iter()
+            .enumerate()
+            .map(|(ix, &split)| {
+                Ok(target.wire_node(
+                    format!("{}-{}", node.name, ix),
+                    RmDims::new(vec![axis]),
+                    &[split],
+                )?[0])
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_v_infers_size() {
+        let op = SplitV::new(2);
+        let output =
+            op.eval(tvec!(rctensor1(&[1, 2, 3, 4, 5]), rctensor1(&[-1, 2]), rctensor0(0))).unwrap();
+        assert_eq!(output, tvec!(rctensor1(&[1, 2, 3]), rctensor1(&[4, 5])));
+    }
+
+    #[test]
+    fn unpack() {
+        let op = Unpack::new(2, 1);
+        let output = op.eval(tvec!(rctensor2(&[[1, 2], [3, 4]]))).unwrap();
+        assert_eq!(output, tvec!(rctensor1(&[1, 3]), rctensor1(&[2, 4])));
+    }
+}
@@ -0,0 +1,297 @@
+use tract_core::internal::*;
+use tract_core::ndarray::*;
+use tract_core::ops::array::{CoordTransformer, Interpolator, NearestMode, Resize};
+
+use crate::model::ParsingContext;
+use crate::model::TfOpRegister;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn register_all_ops(reg: &mut TfOpRegister) {
+    reg.insert("CropAndResize", crop_and_resize);
+    reg.insert("ResizeBilinear", resize_bilinear);
+    reg.insert("ResizeNearestNeighbor", resize_nearest_neighbor);
+}
+
+pub fn resize_bilinear(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let coord_transformer = if pb.get_attr_opt_bool("align_corners")?.unwrap_or(false) {
+        CoordTransformer::AlignCorners
+    } else if pb.get_attr_opt_bool("half_pixel_centers")?.unwrap_or(false) {
+        CoordTransformer::HalfPixel
+    } else {
+        CoordTransformer::Asymmetric
+    };
+    Ok(Box::new(ResizeImage::new(coord_transformer, Interpolator::Linear)))
+}
+
+pub fn resize_nearest_neighbor(
+    _ctx: &ParsingContext,
+    pb: &NodeDef,
+) -> TractResult<Box<dyn InferenceOp>> {
+    let (coord_transformer, mode) = if pb.get_attr_opt_bool("align_corners")?.unwrap_or(false) {
+        (CoordTransformer::AlignCorners, NearestMode::RoundPreferCeil)
+    } else if pb.get_attr_opt_bool("half_pixel_centers")?.unwrap_or(false) {
+        (CoordTransformer::TfHalfPixelForNN, NearestMode::Floor)
+    } else {
+        (CoordTransformer::Asymmetric, NearestMode::Floor)
+    };
+    Ok(Box::new(ResizeImage::new(coord_transformer, Interpolator::Nearest(mode))))
+}
+
+/// ResizeBilinear and ResizeNearestNeighbor, on NHWC images.
+#[derive(Debug, Clone, new)]
+pub struct ResizeImage {
+    coord_transformer: CoordTransformer,
+    interpolator: Interpolator,
+}
+
+impl ResizeImage {
+    fn as_resize(&self, size: &Tensor) -> TractResult<Resize> {
+        let size = size.cast_to::<i32>()?;
+        let sizes = size.as_slice::<i32>()?.iter().map(|&s| s as usize).collect();
+        Ok(Resize::new(vec![1, 2], sizes, self.coord_transformer, self.interpolator))
+    }
+}
+
+impl Op for ResizeImage {
+    fn name(&self) -> Cow<str> {
+        match self.interpolator {
+            Interpolator::Linear => "tf.ResizeBilinear".into(),
+            Interpolator::Nearest(_) => "tf.ResizeNearestNeighbor".into(),
+        }
+    }
+
+    not_a_typed_op!();
+}
+
+impl StatelessOp for ResizeImage {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (images, size) = args_2!(inputs);
+        self.as_resize(&size)?.eval(tvec!(images))
+    }
+}
+
+impl InferenceRulesOp for ResizeImage {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        if self.interpolator == Interpolator::Linear {
+            s.equals(&outputs[0].datum_type, DatumType::F32)?;
+        } else {
+            s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        }
+        s.equals(&inputs[0].rank, 4)?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.equals(&inputs[1].rank, 1)?;
+        s.equals(&inputs[1].shape[0], 2.to_dim())?;
+        s.equals(&inputs[0].shape[0], &outputs[0].shape[0])?;
+        s.equals(&inputs[0].shape[3], &outputs[0].shape[3])?;
+        s.given(&inputs[1].value, move |s, size| {
+            let size = size.cast_to::<i32>()?;
+            let size = size.as_slice::<i32>()?;
+            s.equals(&outputs[0].shape[1], size[0].to_dim())?;
+            s.equals(&outputs[0].shape[2], size[1].to_dim())
+        })
+    }
+
+    inference_op_as_op!();
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        if let Some(ref size) = target.outlet_fact(mapping[&node.inputs[1]])?.konst {
+            let op = self.as_resize(size)?;
+            target.wire_node(&*node.name, op, [mapping[&node.inputs[0]]].as_ref())
+        } else {
+            bail!("Need size to be const")
+        }
+    }
+}
+
+pub fn crop_and_resize(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let bilinear = match &*pb.get_attr_opt_str("method")?.unwrap_or("bilinear".to_string()) {
+        "bilinear" => true,
+        "nearest" => false,
+        method => bail!("Unsupported CropAndResize method {}", method),
+    };
+    let extrapolation_value = pb.get_attr_opt_float("extrapolation_value")?.unwrap_or(0.0);
+    Ok(Box::new(CropAndResize::new(bilinear, extrapolation_value)))
+}
+
+/// Extract boxes from NHWC images, and resize them to a common size.
+#[derive(Debug, Clone, new)]
+pub struct CropAndResize {
+    bilinear: bool,
+    extrapolation_value: f32,
+}
+
+impl CropAndResize {
+    /// Input coordinate of the `x`-th of `crop` samples between `a` and `b`,
+    /// in an axis of length `len`.
+    fn coord(a: f32, b: f32, x: usize, crop: usize, len: usize) -> Option<f32> {
+        let len = (len - 1) as f32;
+        let coord = if crop > 1 {
+            a * len + x as f32 * (b - a) * len / (crop - 1) as f32
+        } else {
+            0.5 * (a + b) * len
+        };
+        if coord < 0.0 || coord > len {
+            None
+        } else {
+            Some(coord)
+        }
+    }
+
+    fn sample(&self, image: ArrayView3<f32>, y: f32, x: f32, output: ArrayViewMut1<f32>) {
+        if self.bilinear {
+            let (top, bottom) = (y.floor() as usize, y.ceil() as usize);
+            let (left, right) = (x.floor() as usize, x.ceil() as usize);
+            let (dy, dx) = (y - top as f32, x - left as f32);
+            Zip::from(output)
+                .and(image.slice(s![top, left, ..]))
+                .and(image.slice(s![top, right, ..]))
+                .and(image.slice(s![bottom, left, ..]))
+                .and(image.slice(s![bottom, right, ..]))
+                .apply(|o, &tl, &tr, &bl, &br| {
+                    let t = tl + (tr - tl) * dx;
+                    let b = bl + (br - bl) * dx;
+                    *o = t + (b - t) * dy
+                });
+        } else {
+            let (y, x) = (y.round() as usize, x.round() as usize);
+            let mut output = output;
+            output.assign(&image.slice(s![y, x, ..]));
+        }
+    }
+}
+
+impl Op for CropAndResize {
+    fn name(&self) -> Cow<str> {
+        "tf.CropAndResize".into()
+    }
+
+    op_as_typed_op!();
+    not_a_pulsed_op!();
+}
+
+impl StatelessOp for CropAndResize {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (images, boxes, box_ind, crop_size) = args_4!(inputs);
+        let images = images.cast_to::<f32>()?;
+        let images = images.to_array_view::<f32>()?.into_dimensionality::<Ix4>()?;
+        let boxes = boxes.cast_to::<f32>()?;
+        let boxes = boxes.to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
+        let box_ind = box_ind.cast_to::<i32>()?;
+        let box_ind = box_ind.as_slice::<i32>()?;
+        let crop_size = crop_size.cast_to::<i32>()?;
+        let (crop_h, crop_w) =
+            (crop_size.as_slice::<i32>()?[0] as usize, crop_size.as_slice::<i32>()?[1] as usize);
+        let (_, height, width, channels) = images.dim();
+        let mut output = Array4::<f32>::from_elem(
+            (boxes.shape()[0], crop_h, crop_w, channels),
+            self.extrapolation_value,
+        );
+        for (b, bx) in boxes.outer_iter().enumerate() {
+            if box_ind[b] < 0 || box_ind[b] as usize >= images.shape()[0] {
+                bail!("Box index {} out of range", box_ind[b])
+            }
+            let image = images.index_axis(Axis(0), box_ind[b] as usize);
+            for y in 0..crop_h {
+                let in_y = if let Some(y) = Self::coord(bx[0], bx[2], y, crop_h, height) {
+                    y
+                } else {
+                    continue;
+                };
+                for x in 0..crop_w {
+                    if let Some(in_x) = Self::coord(bx[1], bx[3], x, crop_w, width) {
+                        self.sample(image, in_y, in_x, output.slice_mut(s![b, y, x, ..]));
+                    }
+                }
+            }
+        }
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for CropAndResize {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 4)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, DatumType::F32)?;
+        s.equals(&inputs[0].rank, 4)?;
+        s.equals(&inputs[1].rank, 2)?;
+        s.equals(&inputs[1].shape[1], 4.to_dim())?;
+        s.equals(&inputs[2].rank, 1)?;
+        s.equals(&inputs[1].shape[0], &inputs[2].shape[0])?;
+        s.equals(&inputs[3].rank, 1)?;
+        s.equals(&inputs[3].shape[0], 2.to_dim())?;
+        s.equals(&outputs[0].rank, 4)?;
+        s.equals(&outputs[0].shape[0], &inputs[1].shape[0])?;
+        s.equals(&outputs[0].shape[3], &inputs[0].shape[3])?;
+        s.given(&inputs[3].value, move |s, size| {
+            let size = size.cast_to::<i32>()?;
+            let size = size.as_slice::<i32>()?;
+            s.equals(&outputs[0].shape[1], size[0].to_dim())?;
+            s.equals(&outputs[0].shape[2], size[1].to_dim())
+        })
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for CropAndResize {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let size = if let Some(ref size) = inputs[3].konst {
+            size.cast_to::<i32>()?.into_owned()
+        } else {
+            bail!("Need crop size to be const")
+        };
+        let size = size.as_slice::<i32>()?;
+        let shape =
+            [inputs[1].shape.dim(0), size[0].to_dim(), size[1].to_dim(), inputs[0].shape.dim(3)];
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), &shape[..])?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn resize_bilinear_align_corners() {
+        let op = ResizeImage::new(CoordTransformer::AlignCorners, Interpolator::Linear);
+        let image = rctensor4(&[[[[0.0f32], [3.0]]]]);
+        let output = op.eval(tvec!(image, rctensor1(&[1, 4]))).unwrap().remove(0);
+        output.close_enough(&tensor4(&[[[[0.0f32], [1.0], [2.0], [3.0]]]]), true).unwrap();
+    }
+
+    #[test]
+    fn crop_and_resize() {
+        let op = CropAndResize::new(true, -1.0);
+        let image = rctensor4(&[[[[0.0f32], [1.0]], [[2.0], [3.0]]]]);
+        let boxes = rctensor2(&[[0.0f32, 0.0, 1.0, 1.0], [0.0, 0.0, 0.0, 2.0]]);
+        let output =
+            op.eval(tvec!(image, boxes, rctensor1(&[0, 0]), rctensor1(&[3, 3]))).unwrap().remove(0);
+        let expected = tensor4(&[
+            [[[0.0f32], [0.5], [1.0]], [[1.0], [1.5], [2.0]], [[2.0], [2.5], [3.0]]],
+            [[[0.0f32], [1.0], [-1.0]], [[0.0], [1.0], [-1.0]], [[0.0], [1.0], [-1.0]]],
+        ]);
+        output.close_enough(&expected, true).unwrap();
+    }
+}
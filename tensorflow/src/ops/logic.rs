@@ -11,9 +11,14 @@ pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("Less", |_, _| Ok(Box::new(tractops::logic::lesser::bin())));
     reg.insert("LessEqual", |_, _| Ok(Box::new(tractops::logic::lesser_equal::bin())));
     reg.insert("LogicalAnd", |_, _| Ok(Box::new(tractops::logic::and::bin())));
+    reg.insert("LogicalNot", |_, _| Ok(Box::new(tractops::logic::not())));
     reg.insert("LogicalOr", |_, _| Ok(Box::new(tractops::logic::or::bin())));
     reg.insert("Merge", merge);
+    reg.insert("NotEqual", |_, _| Ok(Box::new(tractops::logic::not_equals::bin())));
+    reg.insert("Select", |_, _| Ok(Box::new(Select)));
+    reg.insert("SelectV2", |_, _| Ok(Box::new(tractops::logic::Iff)));
     reg.insert("Switch", switch);
+    reg.insert("Where", |_, _| Ok(Box::new(Where)));
 }
 
 fn switch(ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
@@ -139,3 +144,145 @@ impl TypedOp for Merge {
         ))
     }
 }
+
+/// Select, where the condition may also be a vector picking along the first
+/// axis.
+#[derive(Debug, Clone)]
+pub struct Select;
+
+impl Op for Select {
+    fn name(&self) -> Cow<str> {
+        "tf.Select".into()
+    }
+
+    not_a_typed_op!();
+}
+
+impl StatelessOp for Select {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (cond, t, f) = args_3!(inputs);
+        let cond = if cond.rank() == 1 && t.rank() > 1 {
+            let mut shape = tvec!(1; t.rank());
+            shape[0] = cond.len();
+            unsafe { cond.into_tensor().into_shape(&shape)?.into_arc_tensor() }
+        } else {
+            cond
+        };
+        tractops::logic::Iff.eval(tvec!(cond, t, f))
+    }
+}
+
+impl InferenceRulesOp for Select {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 3)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, DatumType::Bool)?;
+        s.equals(&inputs[1].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[2].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[1].shape, &outputs[0].shape)?;
+        s.equals(&inputs[2].shape, &outputs[0].shape)?;
+        s.equals(&inputs[0].shape[0], &outputs[0].shape[0])?;
+        Ok(())
+    }
+
+    inference_op_as_op!();
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let mut inputs: TVec<OutletId> = node.inputs.iter().map(|i| mapping[i]).collect();
+        let cond_rank = target.outlet_fact(inputs[0])?.shape.rank();
+        let rank = target.outlet_fact(inputs[1])?.shape.rank();
+        if cond_rank < rank {
+            let op = tractops::array::AddDims::new((cond_rank..rank).collect());
+            inputs[0] = target.wire_node(format!("{}-cond", node.name), op, &inputs[0..1])?[0];
+        }
+        target.wire_node(&*node.name, tractops::logic::Iff, &inputs)
+    }
+}
+
+/// Coordinates of the true (or non zero) elements of the input.
+#[derive(Debug, Clone)]
+pub struct Where;
+
+impl Where {
+    fn eval_t<T: Datum + Default + PartialEq>(input: &Tensor) -> TractResult<Arc<Tensor>> {
+        use tract_core::ndarray::*;
+        let input = input.to_array_view::<T>()?;
+        let zero = T::default();
+        let coords: Vec<i64> = input
+            .indexed_iter()
+            .filter(|(_, x)| **x != zero)
+            .flat_map(|(coords, _)| coords.slice().iter().map(|&c| c as i64).collect::<Vec<_>>())
+            .collect();
+        let rank = input.ndim();
+        let len = if rank > 0 {
+            coords.len() / rank
+        } else {
+            input.iter().filter(|x| **x != zero).count()
+        };
+        Ok(Array2::from_shape_vec((len, rank), coords)?.into_arc_tensor())
+    }
+}
+
+impl Op for Where {
+    fn name(&self) -> Cow<str> {
+        "tf.Where".into()
+    }
+
+    not_a_typed_op!();
+}
+
+impl StatelessOp for Where {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        Ok(tvec!(dispatch_datum!(Self::eval_t(input.datum_type())(&input))?))
+    }
+}
+
+impl InferenceRulesOp for Where {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, DatumType::I64)?;
+        s.equals(&outputs[0].rank, 2)?;
+        s.equals(&outputs[0].shape[1], inputs[0].rank.bex().to_dim())?;
+        Ok(())
+    }
+
+    inference_op_as_op!();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn select_rows() {
+        let cond = rctensor1(&[true, false]);
+        let output = Select
+            .eval(tvec!(cond, rctensor2(&[[1, 2], [3, 4]]), rctensor2(&[[5, 6], [7, 8]])))
+            .unwrap();
+        assert_eq!(output[0], rctensor2(&[[1, 2], [7, 8]]));
+    }
+
+    #[test]
+    fn where_coordinates() {
+        let output = Where.eval(tvec!(rctensor2(&[[true, false], [false, true]]))).unwrap();
+        assert_eq!(output[0], rctensor2(&[[0i64, 0], [1, 1]]));
+    }
+}
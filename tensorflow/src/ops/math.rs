@@ -5,6 +5,7 @@ use crate::model::ParsingContext;
 use crate::model::TfOpRegister;
 use crate::tfpb::tensorflow::NodeDef;
 
+mod cumsum;
 mod reduce;
 
 pub fn register_all_ops(reg: &mut TfOpRegister) {
@@ -12,15 +13,26 @@ pub fn register_all_ops(reg: &mut TfOpRegister) {
     reg.insert("Add", |_, _| Ok(Box::new(tractops::math::add::bin())));
     reg.insert("AddN", add_n);
     reg.insert("AddV2", |_, _| Ok(Box::new(tractops::math::add::bin())));
+    reg.insert("BatchMatMul", batch_mat_mul);
+    reg.insert("BatchMatMulV2", batch_mat_mul);
     reg.insert("BiasAdd", |_, _| Ok(Box::new(tractops::math::add::bin())));
     reg.insert("Ceil", |_, _| Ok(Box::new(tractops::math::ceil())));
+    reg.insert("Cumsum", cumsum::cumsum);
     reg.insert("Div", |_, _| Ok(Box::new(tractops::math::div::bin())));
+    reg.insert("Erf", |_, _| Ok(Box::new(tractops::math::erf())));
+    reg.insert("Exp", |_, _| Ok(Box::new(tractops::math::exp())));
+    reg.insert("Floor", |_, _| Ok(Box::new(tractops::math::floor())));
+    reg.insert("FloorDiv", |_, _| Ok(Box::new(floor_div::bin())));
     reg.insert("FloorMod", |_, _| Ok(Box::new(tractops::math::rem::bin())));
     reg.insert("MatMul", mat_mul);
     reg.insert("Max", reduce::max);
     reg.insert("Mean", reduce::mean);
     reg.insert("Min", reduce::min);
     reg.insert("Prod", reduce::prod);
+    reg.insert("Round", |_, _| Ok(Box::new(tractops::math::round_half_to_even())));
+    reg.insert("Sqrt", |_, _| Ok(Box::new(tractops::math::sqrt())));
+    reg.insert("Square", |_, _| Ok(Box::new(tractops::math::square())));
+    reg.insert("SquaredDifference", |_, _| Ok(Box::new(squared_difference::bin())));
     reg.insert("Sum", reduce::sum);
     reg.insert("Maximum", |_, _| Ok(Box::new(tractops::math::max::bin())));
     reg.insert("Minimum", |_, _| Ok(Box::new(tractops::math::min::bin())));
@@ -46,3 +58,17 @@ pub fn mat_mul(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn Infer
         tract_core::ops::math::MatMul::default().with_a_trans(trans_a).with_b_trans(trans_b),
     ))
 }
+
+pub fn batch_mat_mul(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let adj_x = pb.get_attr_opt_bool("adj_x")?.unwrap_or(false);
+    let adj_y = pb.get_attr_opt_bool("adj_y")?.unwrap_or(false);
+    Ok(Box::new(tract_core::ops::math::MatMul::default().with_a_trans(adj_x).with_b_trans(adj_y)))
+}
+
+bin_to_super_type!(floor_div, FloorDiv,
+     [f32, f64] => |c, a, b| *c = (a / b).floor(),
+     [i8, i16, i32, i64] => |c, &a, &b| *c = if (a % b != 0) && ((a < 0) != (b < 0)) { a / b - 1 } else { a / b },
+     [u8, u16] => |c, a, b| *c = a / b);
+
+bin_to_super_type!(squared_difference, SquaredDifference,
+     [f32, f64, i32, i64] => |c, a, b| *c = (*a - *b) * (*a - *b));
@@ -0,0 +1,151 @@
+use tract_core::internal::*;
+use tract_core::ndarray::*;
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn cumsum(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let exclusive = pb.get_attr_opt_bool("exclusive")?.unwrap_or(false);
+    let reverse = pb.get_attr_opt_bool("reverse")?.unwrap_or(false);
+    Ok(Box::new(Cumsum::new(exclusive, reverse)))
+}
+
+#[derive(Debug, Clone, new)]
+pub struct Cumsum {
+    exclusive: bool,
+    reverse: bool,
+}
+
+impl Cumsum {
+    fn typed(&self, input: &Tensor, axis: &Tensor) -> TractResult<TypedCumsum> {
+        let axis = *axis.cast_to::<i64>()?.to_scalar::<i64>()?;
+        let axis = if axis < 0 { axis + input.rank() as i64 } else { axis } as usize;
+        Ok(TypedCumsum::new(axis, self.exclusive, self.reverse))
+    }
+}
+
+impl Op for Cumsum {
+    fn name(&self) -> Cow<str> {
+        "tf.Cumsum".into()
+    }
+
+    not_a_typed_op!();
+}
+
+impl StatelessOp for Cumsum {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, axis) = args_2!(inputs);
+        self.typed(&input, &axis)?.eval(tvec!(input))
+    }
+}
+
+impl InferenceRulesOp for Cumsum {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&inputs[0].datum_type, &outputs[0].datum_type)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        s.equals(&inputs[1].rank, 0)?;
+        Ok(())
+    }
+
+    inference_op_as_op!();
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        if let Some(ref axis) = target.outlet_fact(mapping[&node.inputs[1]])?.konst {
+            let rank = target.outlet_fact(mapping[&node.inputs[0]])?.shape.rank();
+            let axis = *axis.cast_to::<i64>()?.to_scalar::<i64>()?;
+            let axis = if axis < 0 { axis + rank as i64 } else { axis } as usize;
+            let op = TypedCumsum::new(axis, self.exclusive, self.reverse);
+            target.wire_node(&*node.name, op, [mapping[&node.inputs[0]]].as_ref())
+        } else {
+            bail!("Need axis to be const")
+        }
+    }
+}
+
+#[derive(Debug, Clone, new)]
+pub struct TypedCumsum {
+    axis: usize,
+    exclusive: bool,
+    reverse: bool,
+}
+
+impl TypedCumsum {
+    fn eval_t<T>(&self, input: &Tensor) -> TractResult<Arc<Tensor>>
+    where
+        T: Datum + Copy + std::ops::Add<Output = T> + num_traits::Zero,
+    {
+        let mut output = input.to_array_view::<T>()?.to_owned();
+        for mut lane in output.lanes_mut(Axis(self.axis)) {
+            let len = lane.len();
+            let mut sum = T::zero();
+            for i in 0..len {
+                let i = if self.reverse { len - 1 - i } else { i };
+                let x = lane[i];
+                if self.exclusive {
+                    lane[i] = sum;
+                    sum = sum + x;
+                } else {
+                    sum = sum + x;
+                    lane[i] = sum;
+                }
+            }
+        }
+        Ok(output.into_arc_tensor())
+    }
+}
+
+impl Op for TypedCumsum {
+    fn name(&self) -> Cow<str> {
+        "tf.TypedCumsum".into()
+    }
+
+    op_as_typed_op!();
+}
+
+impl StatelessOp for TypedCumsum {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        Ok(tvec!(dispatch_numbers!(Self::eval_t(input.datum_type())(self, &input))?))
+    }
+}
+
+impl TypedOp for TypedCumsum {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(inputs[0].clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cumsum(exclusive: bool, reverse: bool) -> Arc<Tensor> {
+        let op = Cumsum::new(exclusive, reverse);
+        op.eval(tvec!(rctensor2(&[[1, 2, 3], [4, 5, 6]]), rctensor0(-1))).unwrap().remove(0)
+    }
+
+    #[test]
+    fn cumsum_inclusive() {
+        assert_eq!(cumsum(false, false), rctensor2(&[[1, 3, 6], [4, 9, 15]]));
+    }
+
+    #[test]
+    fn cumsum_exclusive_reverse() {
+        assert_eq!(cumsum(true, true), rctensor2(&[[5, 3, 0], [11, 6, 0]]));
+    }
+}
@@ -6,6 +6,7 @@ use crate::tfpb::tensorflow::NodeDef;
 
 pub mod array;
 pub mod control_flow;
+pub mod image;
 pub mod logic;
 pub mod math;
 pub mod nn;
@@ -17,6 +18,7 @@ pub mod vars;
 pub fn register_all_ops(reg: &mut TfOpRegister) {
     array::register_all_ops(reg);
     control_flow::register_all_ops(reg);
+    image::register_all_ops(reg);
     logic::register_all_ops(reg);
     math::register_all_ops(reg);
     nn::register_all_ops(reg);
@@ -0,0 +1,99 @@
+use tract_core::internal::*;
+use tract_core::ops::cast::Cast;
+use tract_core::ops::nn::ArgMaxMin;
+
+use crate::model::ParsingContext;
+use crate::tfpb::tensorflow::NodeDef;
+
+pub fn arg_max(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let output_type = pb.get_attr_opt_datum_type("output_type")?.unwrap_or(DatumType::I64);
+    Ok(Box::new(ArgMaxMinV2::new(true, output_type)))
+}
+
+pub fn arg_min(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let output_type = pb.get_attr_opt_datum_type("output_type")?.unwrap_or(DatumType::I64);
+    Ok(Box::new(ArgMaxMinV2::new(false, output_type)))
+}
+
+/// ArgMax and ArgMin, with the axis as second input.
+#[derive(Debug, Clone, new)]
+pub struct ArgMaxMinV2 {
+    max: bool,
+    output_type: DatumType,
+}
+
+impl ArgMaxMinV2 {
+    fn core_op(&self, axis: &Tensor, rank: usize) -> TractResult<ArgMaxMin> {
+        let axis = *axis.cast_to::<i64>()?.to_scalar::<i64>()?;
+        let axis = if axis < 0 { axis + rank as i64 } else { axis } as usize;
+        Ok(ArgMaxMin::new(self.max, axis, false))
+    }
+}
+
+impl Op for ArgMaxMinV2 {
+    fn name(&self) -> Cow<str> {
+        if self.max {
+            "tf.ArgMax".into()
+        } else {
+            "tf.ArgMin".into()
+        }
+    }
+
+    not_a_typed_op!();
+}
+
+impl StatelessOp for ArgMaxMinV2 {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let (input, axis) = args_2!(inputs);
+        let op = self.core_op(&axis, input.rank())?;
+        let output = op.eval(tvec!(input))?.remove(0);
+        Ok(tvec!(output.cast_to_dt(self.output_type)?.into_owned().into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for ArgMaxMinV2 {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 2)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, self.output_type)?;
+        s.equals(&inputs[1].rank, 0)?;
+        s.equals(&outputs[0].rank, inputs[0].rank.bex() - 1)?;
+        s.given_2(&inputs[0].rank, &inputs[1].value, move |s, rank, axis| {
+            let axis = *axis.cast_to::<i64>()?.to_scalar::<i64>()?;
+            let axis = if axis < 0 { axis + rank as i64 } else { axis } as usize;
+            for i in 0..rank as usize - 1 {
+                let input_axis = if i < axis { i } else { i + 1 };
+                s.equals(&outputs[0].shape[i], &inputs[0].shape[input_axis])?;
+            }
+            Ok(())
+        })
+    }
+
+    inference_op_as_op!();
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        if let Some(ref axis) = target.outlet_fact(mapping[&node.inputs[1]])?.konst {
+            let rank = target.outlet_fact(mapping[&node.inputs[0]])?.shape.rank();
+            let op = self.core_op(axis, rank)?;
+            let mut wire = mapping[&node.inputs[0]];
+            if self.output_type == DatumType::I64 {
+                return target.wire_node(&*node.name, op, &[wire]);
+            }
+            wire = target.wire_node(format!("{}-arg", node.name), op, &[wire])?[0];
+            target.wire_node(&*node.name, Cast::new(self.output_type), &[wire])
+        } else {
+            bail!("Need axis to be const")
+        }
+    }
+}
@@ -14,6 +14,24 @@ pub fn conv2d(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn Infere
     Ok(Box::new(op))
 }
 
+pub fn conv3d(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let strides: TVec<usize> = pb.get_attr_list_int("strides")?.into();
+    if strides.len() != 5 || strides[0] != 1 || strides[4] != 1 {
+        bail!("strides must be of the form [1, d, h, w, 1], found {:?}", strides)
+    }
+    let mut op = Conv::default().hwio().padding(super::padding(pb)?).strides(strides[1..4].into());
+    if let Some(dilations) = pb.get_attr_opt_list_int::<usize>("dilations")? {
+        if dilations.len() != 5 || dilations[0] != 1 || dilations[4] != 1 {
+            bail!("dilations must be of the form [1, d, h, w, 1], found {:?}", dilations)
+        }
+        op = op.dilations(dilations[1..4].into());
+    }
+    if pb.get_attr_opt_raw_str("data_format")?.unwrap_or(b"NDHWC") == b"NDHWC" {
+        op = op.nhwc()
+    }
+    Ok(Box::new(op))
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(non_snake_case)]
@@ -2,9 +2,11 @@ use tract_core::internal::*;
 use tract_core::ops::cnn::PaddingSpec;
 use tract_core::ops::nn::{DataFormat, LayerSoftmax};
 
+use crate::model::ParsingContext;
 use crate::model::TfOpRegister;
 use crate::tfpb::tensorflow::NodeDef;
 
+pub mod arg_max_min;
 pub mod conv2d;
 pub mod dw_conv2d;
 pub mod fused_batch_norm;
@@ -12,10 +14,16 @@ pub mod pools;
 pub mod s2b;
 
 pub fn register_all_ops(reg: &mut TfOpRegister) {
+    reg.insert("ArgMax", arg_max_min::arg_max);
+    reg.insert("ArgMin", arg_max_min::arg_min);
     reg.insert("AvgPool", pools::avgpool);
     reg.insert("Conv2D", conv2d::conv2d);
+    reg.insert("Conv3D", conv2d::conv3d);
     reg.insert("DepthwiseConv2dNative", dw_conv2d::depthwise_conv2d);
     reg.insert("FusedBatchNorm", fused_batch_norm::fused_batch_norm);
+    reg.insert("FusedBatchNormV3", fused_batch_norm::fused_batch_norm);
+    reg.insert("LeakyRelu", leaky_relu);
+    reg.insert("LRN", lrn);
     reg.insert("MaxPool", pools::maxpool);
     reg.insert("Relu", |_, _| Ok(Box::new(tract_core::ops::math::scalar_max((0.0).into()))));
     reg.insert("Relu6", |_, _| {
@@ -23,10 +31,27 @@ pub fn register_all_ops(reg: &mut TfOpRegister) {
     });
     reg.insert("Sigmoid", |_, _| Ok(Box::new(tract_core::ops::nn::sigmoid())));
     reg.insert("Softmax", |_, _| Ok(Box::new(LayerSoftmax::new(1))));
+    reg.insert("Softplus", |_, _| Ok(Box::new(tract_core::ops::nn::softplus())));
     reg.insert("SpaceToBatchND", s2b::space_to_batch_nd);
     reg.insert("BatchToSpaceND", s2b::batch_to_space_nd);
 }
 
+pub fn leaky_relu(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let alpha = pb.get_attr_opt_float("alpha")?.unwrap_or(0.2);
+    Ok(Box::new(tract_core::ops::nn::leaky_relu(alpha)))
+}
+
+/// TensorFlow normalizes over the last axis, with an alpha that is not
+/// divided by the window size.
+pub fn lrn(_ctx: &ParsingContext, pb: &NodeDef) -> TractResult<Box<dyn InferenceOp>> {
+    let depth_radius: usize = pb.get_attr_opt_int("depth_radius")?.unwrap_or(5);
+    let bias = pb.get_attr_opt_float("bias")?.unwrap_or(1.0);
+    let alpha: f32 = pb.get_attr_opt_float("alpha")?.unwrap_or(1.0);
+    let beta = pb.get_attr_opt_float("beta")?.unwrap_or(0.5);
+    let size = 2 * depth_radius + 1;
+    Ok(Box::new(tract_core::ops::nn::Lrn::new(alpha * size as f32, beta, bias, size).with_axis(3)))
+}
+
 pub fn strides(pb: &NodeDef) -> TractResult<Vec<usize>> {
     let strides: Vec<usize> = pb.get_attr_list_int("strides")?;
     if strides.len() != 4 || strides[0] != 1 && strides[3] != 1 {
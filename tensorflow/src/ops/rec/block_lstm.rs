@@ -34,20 +34,20 @@ impl StatelessOp for BlockLSTM {
         let len = *inputs[0].cast_to::<i32>()?.to_scalar::<i32>()? as usize;
 
         let x = inputs[1].to_array_view::<f32>()?.into_dimensionality::<Ix3>()?;
-        let cell_size = x.shape()[2];
         let cs_prev = inputs[2].to_array_view::<f32>()?;
+        let cell_size = cs_prev.shape()[1];
         let h_prev = inputs[3].to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
         let w = inputs[4].to_array_view::<f32>()?.into_dimensionality::<Ix2>()?;
         let bias = inputs[8].to_array_view::<f32>()?;
 
-        let outputs_shape = x.shape();
-        let mut i = unsafe { ArrayD::<f32>::uninitialized(&*outputs_shape) };
-        let mut cs = unsafe { ArrayD::<f32>::uninitialized(&*outputs_shape) };
-        let mut f = unsafe { ArrayD::<f32>::uninitialized(&*outputs_shape) };
-        let mut o = unsafe { ArrayD::<f32>::uninitialized(&*outputs_shape) };
-        let mut ci = unsafe { ArrayD::<f32>::uninitialized(&*outputs_shape) };
-        let mut co = unsafe { ArrayD::<f32>::uninitialized(&*outputs_shape) };
-        let mut h = unsafe { ArrayD::<f32>::uninitialized(&*outputs_shape) };
+        let outputs_shape = [x.shape()[0], x.shape()[1], cell_size];
+        let mut i = unsafe { ArrayD::<f32>::uninitialized(&outputs_shape[..]) };
+        let mut cs = unsafe { ArrayD::<f32>::uninitialized(&outputs_shape[..]) };
+        let mut f = unsafe { ArrayD::<f32>::uninitialized(&outputs_shape[..]) };
+        let mut o = unsafe { ArrayD::<f32>::uninitialized(&outputs_shape[..]) };
+        let mut ci = unsafe { ArrayD::<f32>::uninitialized(&outputs_shape[..]) };
+        let mut co = unsafe { ArrayD::<f32>::uninitialized(&outputs_shape[..]) };
+        let mut h = unsafe { ArrayD::<f32>::uninitialized(&outputs_shape[..]) };
         let mut h_prev = h_prev.to_owned();
         let mut cs_prev = cs_prev.to_owned();
 
@@ -146,7 +146,7 @@ impl InferenceRulesOp for BlockLSTM {
         // other inputs and outputs are consistent float-like
         s.equals_all((1..=7).map(move |i| (&inputs[i].datum_type).bex()).collect())?;
 
-        s.equals(&inputs[1].rank, 3)?; // x:  [ time, batch, cell_size ]
+        s.equals(&inputs[1].rank, 3)?; // x:  [ time, batch, input_size ]
         s.equals(&inputs[2].rank, 2)?; // cs_prev: [batch, cell_size]
         s.equals(&inputs[3].rank, 2)?; // h_prev: [batch, cell_size]
         s.equals(&inputs[4].rank, 2)?; // w: []
@@ -154,12 +154,15 @@ impl InferenceRulesOp for BlockLSTM {
         s.equals(&inputs[6].rank, 1)?; // peephole forget
         s.equals(&inputs[7].rank, 1)?; // peephole output
         s.equals(&inputs[8].rank, 1)?; // bias: [ 4*cell_size ]
-        s.equals(&inputs[8].shape[0], 4 * inputs[1].shape[2].bex())?; // bias: [ 4*cell_size ]
+        s.equals(&inputs[8].shape[0], 4 * inputs[2].shape[1].bex())?; // bias: [ 4*cell_size ]
 
         // i, cs, f, o, ci, co, h
         for i in 0..7 {
             s.equals(&inputs[1].datum_type, &outputs[i].datum_type)?;
-            s.equals(&outputs[i].shape, &inputs[1].shape)?;
+            s.equals(&outputs[i].rank, 3)?;
+            s.equals(&outputs[i].shape[0], &inputs[1].shape[0])?;
+            s.equals(&outputs[i].shape[1], &inputs[1].shape[1])?;
+            s.equals(&outputs[i].shape[2], &inputs[2].shape[1])?;
         }
 
         Ok(())
@@ -170,13 +173,158 @@ impl InferenceRulesOp for BlockLSTM {
     }
 
     inference_op_as_op!();
-    to_typed!();
+
+    fn to_typed(
+        &self,
+        _source: &InferenceModel,
+        node: &InferenceNode,
+        target: &mut TypedModel,
+        mapping: &HashMap<OutletId, OutletId>,
+    ) -> TractResult<TVec<OutletId>> {
+        let inputs: TVec<OutletId> = node.inputs.iter().map(|i| mapping[i]).collect();
+        let len = target.outlet_fact(inputs[0])?.konst.clone();
+        let time = target.outlet_fact(inputs[1])?.shape.dim(0).to_integer().ok();
+        // the scan ignores seq_len_max, so it must cover the whole sequence
+        let covers_sequence = match (len, time) {
+            (None, _) => false,
+            (Some(len), Some(time)) => *len.cast_to::<i64>()?.to_scalar::<i64>()? >= time as i64,
+            (Some(_), None) => true,
+        };
+        if covers_sequence {
+            self.wire_scan(&*node.name, target, &inputs)
+        } else {
+            target.wire_node(&*node.name, self.clone(), &inputs)
+        }
+    }
+}
+
+impl BlockLSTM {
+    /// Translates to a Scan iterating over time, carrying cs and h as states.
+    #[allow(non_snake_case)]
+    fn wire_scan(
+        &self,
+        name: &str,
+        target: &mut TypedModel,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        use tract_core::ops::{array, math, nn, scan};
+
+        if self.use_peephole {
+            bail!("{}: peepholes are not supported", name)
+        }
+        if self.cell_clip > 0.0 {
+            bail!("{}: cell_clip {} is not supported (only -1, no clipping)", name, self.cell_clip)
+        }
+
+        let x_fact = target.outlet_fact(inputs[1])?.clone();
+        let w_fact = target.outlet_fact(inputs[4])?.clone();
+        let b_size = x_fact.shape.dim(1).to_integer()? as usize;
+        let input_size = x_fact.shape.dim(2).to_integer()? as usize;
+        let cell_size = w_fact.shape.dim(1).to_integer()? as usize / 4;
+
+        let mut body = TypedModel::default();
+        let mut outer_inputs = tvec!(inputs[0], inputs[1]);
+
+        macro_rules! wire {
+            ($name: ident = $op: expr, $($param: expr),*) => {
+                let $name = body.wire_node(
+                    format!("{}-{}", name, stringify!($name)),
+                    $op, [$($param),*].as_ref())?[0];
+            }
+        }
+
+        // x: [time, batch, input_size], scanned by chunks of 1
+        let mut x_source_fact = x_fact.clone();
+        x_source_fact.shape.set_dim(0, 1.to_dim())?;
+        let x_source = body.add_source("x_source", x_source_fact)?;
+        wire!(x = array::RmDims::new(vec![0]), x_source);
+
+        // cs_prev and h_prev: [batch, cell_size] outside, [1, batch, cell_size] as states
+        let state_fact = TypedFact::dt_shape(x_fact.datum_type, [1, b_size, cell_size].as_ref())?;
+        let mut states = tvec!();
+        for (ix, state) in ["cs", "h"].iter().enumerate() {
+            outer_inputs.push(
+                target.wire_node(
+                    format!("{}-{}_prev", name, state),
+                    array::AddDims::new(vec![0]),
+                    &[inputs[2 + ix]],
+                )?[0],
+            );
+            states.push(body.add_source(format!("{}_source", state), state_fact.clone())?);
+        }
+        wire!(cs_prev = array::RmDims::new(vec![0]), states[0]);
+        wire!(h_prev = array::RmDims::new(vec![0]), states[1]);
+
+        // w: [input_size + cell_size, 4 * cell_size], b: [4 * cell_size]
+        outer_inputs.push(inputs[4]);
+        let w = body.add_source("w", w_fact)?;
+        outer_inputs.push(inputs[8]);
+        let b = body.add_source("b", target.outlet_fact(inputs[8])?.clone())?;
+
+        wire!(Wx = array::Slice::new(0, 0, input_size), w);
+        wire!(Wh = array::Slice::new(0, input_size, input_size + cell_size), w);
+        wire!(x_Wx = math::MatMul::default(), x, Wx);
+        wire!(h_Wh = math::MatMul::default(), h_prev, Wh);
+        wire!(i_ci_f_o_nobias = math::add::bin(), x_Wx, h_Wh);
+        wire!(i_ci_f_o = math::add::bin(), i_ci_f_o_nobias, b);
+
+        wire!(i_lin = array::Slice::new(1, 0, cell_size), i_ci_f_o);
+        wire!(ci_lin = array::Slice::new(1, cell_size, 2 * cell_size), i_ci_f_o);
+        wire!(f_lin = array::Slice::new(1, 2 * cell_size, 3 * cell_size), i_ci_f_o);
+        wire!(o_lin = array::Slice::new(1, 3 * cell_size, 4 * cell_size), i_ci_f_o);
+
+        let forget_bias =
+            body.add_const(format!("{}-forget_bias", name), tensor0(self.forget_bias))?;
+        wire!(f_biased = math::add::bin(), f_lin, forget_bias);
+        wire!(i = nn::sigmoid(), i_lin);
+        wire!(ci = math::tanh(), ci_lin);
+        wire!(f = nn::sigmoid(), f_biased);
+        wire!(o = nn::sigmoid(), o_lin);
+
+        wire!(f_cs_prev = math::mul::bin(), f, cs_prev);
+        wire!(ci_i = math::mul::bin(), ci, i);
+        wire!(cs = math::add::bin(), f_cs_prev, ci_i);
+        wire!(co = math::tanh(), cs);
+        wire!(h = math::mul::bin(), co, o);
+
+        // states first, in the same order as their sources
+        let mut outputs = tvec!();
+        let mut output_mapping = vec![];
+        for (wire, slot) in
+            [(cs, 1), (h, 6), (i, 0), (f, 2), (o, 3), (ci, 4), (co, 5)].iter().cloned()
+        {
+            let name = format!("{}-{}_chunk", body.node(wire.node).name, slot);
+            outputs.push(body.wire_node(name, array::AddDims::new(vec![0]), &[wire])?[0]);
+            output_mapping.push(scan::OutputMapping {
+                state: output_mapping.len() < 2,
+                axis: 0,
+                chunk: 1.to_dim(),
+                full_dim_hint: None,
+                last_value_slot: None,
+                full_slot: Some(slot),
+            });
+        }
+        body.set_output_outlets(&outputs)?;
+
+        let input_mapping = vec![
+            scan::InputMapping::Scan { slot: 1, axis: 0, chunk: 1.to_dim() },
+            scan::InputMapping::State { initializer: scan::StateInitializer::FromInput(2) },
+            scan::InputMapping::State { initializer: scan::StateInitializer::FromInput(3) },
+            scan::InputMapping::Full { slot: 4 },
+            scan::InputMapping::Full { slot: 5 },
+        ];
+        let op = scan::Typed::new(body, input_mapping, output_mapping, Some(0))?;
+        target.wire_node(name, op, &outer_inputs)
+    }
 }
 
 impl TypedOp for BlockLSTM {
     typed_op_as_op!();
 
     fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
-        Ok(std::iter::repeat(inputs[1].clone()).take(7).collect())
+        let mut shape = inputs[1].shape.clone();
+        shape.set_dim(2, inputs[2].shape.dim(1))?;
+        let fact = TypedFact::dt_shape(inputs[1].datum_type, shape)?;
+        Ok(std::iter::repeat(fact).take(7).collect())
     }
 }
@@ -50,6 +50,15 @@ impl OpState for VariableV2State {
             .ok_or_else(|| format!("Could not find state for variable {}", op.id))?;
         Ok(tvec!(tensor.clone().into()))
     }
+
+    // variable values live in the session state
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!())
+    }
+
+    fn load(&mut self, _tensors: TVec<Tensor>) -> TractResult<()> {
+        Ok(())
+    }
 }
 
 #[derive(Clone, Debug, new)]
@@ -161,6 +170,14 @@ impl OpState for AssignState {
         dispatch_datum!(assign(new.datum_type())(session, var_id, &new))?;
         Ok(tvec!(new))
     }
+
+    fn save(&self) -> TractResult<TVec<Tensor>> {
+        Ok(tvec!())
+    }
+
+    fn load(&mut self, _tensors: TVec<Tensor>) -> TractResult<()> {
+        Ok(())
+    }
 }
 
 impl StatefullOp for Assign {
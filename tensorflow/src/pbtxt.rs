@@ -0,0 +1,493 @@
+//! Parser for GraphDef in protobuf text format (.pbtxt).
+
+use std::str::FromStr;
+
+use tract_core::internal::*;
+
+use crate::tfpb::tensorflow::attr_value::{ListValue, Value as AttrValueValue};
+use crate::tfpb::tensorflow::tensor_shape_proto::Dim;
+use crate::tfpb::tensorflow::{
+    AttrValue, GraphDef, NameAttrList, NodeDef, TensorProto, TensorShapeProto, VersionDef,
+};
+
+/// DataType names, by value. `_REF` variants are offset by 100.
+const DATA_TYPES: &[&str] = &[
+    "DT_INVALID",
+    "DT_FLOAT",
+    "DT_DOUBLE",
+    "DT_INT32",
+    "DT_UINT8",
+    "DT_INT16",
+    "DT_INT8",
+    "DT_STRING",
+    "DT_COMPLEX64",
+    "DT_INT64",
+    "DT_BOOL",
+    "DT_QINT8",
+    "DT_QUINT8",
+    "DT_QINT32",
+    "DT_BFLOAT16",
+    "DT_QINT16",
+    "DT_QUINT16",
+    "DT_UINT16",
+    "DT_COMPLEX128",
+    "DT_HALF",
+    "DT_RESOURCE",
+    "DT_VARIANT",
+    "DT_UINT32",
+    "DT_UINT64",
+];
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Word(String),
+    Str(Vec<u8>),
+    Punct(char),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Value {
+    Word(String),
+    Bytes(Vec<u8>),
+    Message(Message),
+}
+
+type Message = Vec<(String, Value)>;
+
+fn is_word_char(c: u8) -> bool {
+    c.is_ascii_alphanumeric() || b"_-+.".contains(&c)
+}
+
+fn tokenize(text: &str) -> TractResult<Vec<Token>> {
+    let bytes = text.as_bytes();
+    let mut tokens = vec![];
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let c = bytes[pos];
+        if c.is_ascii_whitespace() {
+            pos += 1;
+        } else if c == b'#' {
+            while pos < bytes.len() && bytes[pos] != b'\n' {
+                pos += 1;
+            }
+        } else if c == b'"' || c == b'\'' {
+            tokens.push(Token::Str(string(bytes, &mut pos)?));
+        } else if b"{}<>[]:,;".contains(&c) {
+            tokens.push(Token::Punct(c as char));
+            pos += 1;
+        } else if is_word_char(c) {
+            let start = pos;
+            while pos < bytes.len() && is_word_char(bytes[pos]) {
+                pos += 1;
+            }
+            tokens.push(Token::Word(text[start..pos].to_string()));
+        } else {
+            bail!("Unexpected character {:?} in pbtxt", c as char)
+        }
+    }
+    Ok(tokens)
+}
+
+/// A quoted string starting at `pos`, with C-like escapes.
+fn string(bytes: &[u8], pos: &mut usize) -> TractResult<Vec<u8>> {
+    let quote = bytes[*pos];
+    *pos += 1;
+    let mut s = vec![];
+    let digits = |pos: &mut usize, radix: u32, max: usize| -> TractResult<u8> {
+        let start = *pos;
+        while *pos < bytes.len() && *pos - start < max && (bytes[*pos] as char).is_digit(radix) {
+            *pos += 1;
+        }
+        let digits = std::str::from_utf8(&bytes[start..*pos]).unwrap();
+        Ok(u32::from_str_radix(digits, radix).map_err(|_| "Invalid escape in pbtxt")? as u8)
+    };
+    loop {
+        let c = *bytes.get(*pos).ok_or("Unterminated string in pbtxt")?;
+        *pos += 1;
+        if c == quote {
+            return Ok(s);
+        } else if c != b'\\' {
+            s.push(c);
+            continue;
+        }
+        let e = *bytes.get(*pos).ok_or("Unterminated string in pbtxt")?;
+        *pos += 1;
+        match e {
+            b'n' => s.push(b'\n'),
+            b'r' => s.push(b'\r'),
+            b't' => s.push(b'\t'),
+            b'a' => s.push(7),
+            b'b' => s.push(8),
+            b'f' => s.push(12),
+            b'v' => s.push(11),
+            b'\\' | b'\'' | b'"' | b'?' => s.push(e),
+            b'x' | b'X' => s.push(digits(pos, 16, 2)?),
+            b'0'..=b'7' => {
+                *pos -= 1;
+                s.push(digits(pos, 8, 3)?)
+            }
+            _ => bail!("Invalid escape \\{} in pbtxt", e as char),
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn eat(&mut self, c: char) -> bool {
+        if self.tokens.get(self.pos) == Some(&Token::Punct(c)) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn next(&mut self) -> TractResult<Token> {
+        let token = self.tokens.get(self.pos).cloned().ok_or("Unexpected end of pbtxt")?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    /// Fields up to the `close` delimiter, or to the end of input.
+    fn message(&mut self, close: Option<char>) -> TractResult<Message> {
+        let mut fields = vec![];
+        loop {
+            match close {
+                Some(close) if self.eat(close) => return Ok(fields),
+                None if self.pos == self.tokens.len() => return Ok(fields),
+                _ => (),
+            }
+            let name = match self.next()? {
+                Token::Word(name) => name,
+                t => bail!("Expected a field name in pbtxt, got {:?}", t),
+            };
+            self.eat(':');
+            if self.eat('[') {
+                if !self.eat(']') {
+                    loop {
+                        fields.push((name.clone(), self.value()?));
+                        if self.eat(']') {
+                            break;
+                        } else if !self.eat(',') {
+                            bail!("Expected ',' or ']' in list {}", name)
+                        }
+                    }
+                }
+            } else {
+                fields.push((name, self.value()?));
+            }
+            let _ = self.eat(',') || self.eat(';');
+        }
+    }
+
+    fn value(&mut self) -> TractResult<Value> {
+        match self.next()? {
+            Token::Punct('{') => Ok(Value::Message(self.message(Some('}'))?)),
+            Token::Punct('<') => Ok(Value::Message(self.message(Some('>'))?)),
+            Token::Word(w) => Ok(Value::Word(w)),
+            Token::Str(mut s) => {
+                // adjacent strings are concatenated
+                while let Some(Token::Str(more)) = self.tokens.get(self.pos) {
+                    s.extend_from_slice(more);
+                    self.pos += 1;
+                }
+                Ok(Value::Bytes(s))
+            }
+            t => bail!("Unexpected {:?} in pbtxt", t),
+        }
+    }
+}
+
+impl Value {
+    fn word(&self) -> TractResult<&str> {
+        match self {
+            Value::Word(w) => Ok(w),
+            _ => bail!("Expected a scalar, got {:?}", self),
+        }
+    }
+
+    fn number<T: FromStr>(&self) -> TractResult<T> {
+        let w = self.word()?;
+        Ok(w.parse().map_err(|_| format!("Invalid number {}", w))?)
+    }
+
+    fn float<T: FromStr>(&self) -> TractResult<T> {
+        let w = self.word()?;
+        let w = if (w.ends_with('f') || w.ends_with('F')) && !w.to_lowercase().ends_with("inf") {
+            &w[..w.len() - 1]
+        } else {
+            w
+        };
+        Ok(w.parse().map_err(|_| format!("Invalid float {}", w))?)
+    }
+
+    fn bool(&self) -> TractResult<bool> {
+        match self.word()? {
+            "true" | "True" | "t" | "1" => Ok(true),
+            "false" | "False" | "f" | "0" => Ok(false),
+            w => bail!("Invalid boolean {}", w),
+        }
+    }
+
+    fn bytes(&self) -> TractResult<Vec<u8>> {
+        match self {
+            Value::Bytes(b) => Ok(b.clone()),
+            _ => bail!("Expected a string, got {:?}", self),
+        }
+    }
+
+    fn string(&self) -> TractResult<String> {
+        Ok(String::from_utf8(self.bytes()?).map_err(|_| "Invalid UTF-8 string in pbtxt")?)
+    }
+
+    fn message(&self) -> TractResult<&Message> {
+        match self {
+            Value::Message(m) => Ok(m),
+            _ => bail!("Expected a message, got {:?}", self),
+        }
+    }
+
+    fn data_type(&self) -> TractResult<i32> {
+        let w = self.word()?;
+        if let Ok(value) = w.parse() {
+            return Ok(value);
+        }
+        let (name, offset) = if w.ends_with("_REF") { (&w[..w.len() - 4], 100) } else { (w, 0) };
+        let value = DATA_TYPES
+            .iter()
+            .position(|dt| *dt == name)
+            .ok_or_else(|| format!("Unknown DataType {}", w))?;
+        Ok(value as i32 + offset)
+    }
+}
+
+/// Parse a GraphDef from its text format.
+pub fn graph_def(text: &str) -> TractResult<GraphDef> {
+    let mut parser = Parser { tokens: tokenize(text)?, pos: 0 };
+    let message = parser.message(None)?;
+    let mut graph = crate::tfpb::graph();
+    for (name, value) in &message {
+        match &**name {
+            "node" => graph.node.push(node_def(value.message()?)?),
+            "versions" => graph.versions = Some(version_def(value.message()?)?),
+            "version" => graph.version = value.number()?,
+            "library" => warn!("Ignoring function library of pbtxt GraphDef"),
+            _ => bail!("Unexpected field {} in GraphDef", name),
+        }
+    }
+    Ok(graph)
+}
+
+fn version_def(message: &Message) -> TractResult<VersionDef> {
+    let mut version = VersionDef { producer: 0, min_consumer: 0, bad_consumers: vec![] };
+    for (name, value) in message {
+        match &**name {
+            "producer" => version.producer = value.number()?,
+            "min_consumer" => version.min_consumer = value.number()?,
+            "bad_consumers" => version.bad_consumers.push(value.number()?),
+            _ => bail!("Unexpected field {} in VersionDef", name),
+        }
+    }
+    Ok(version)
+}
+
+fn node_def(message: &Message) -> TractResult<NodeDef> {
+    let mut node = crate::tfpb::node();
+    for (name, value) in message {
+        match &**name {
+            "name" => node.name = value.string()?,
+            "op" => node.op = value.string()?,
+            "input" => node.input.push(value.string()?),
+            "device" => node.device = value.string()?,
+            "attr" => {
+                let (k, v) = attr_entry(value.message()?)?;
+                node.attr.insert(k, v);
+            }
+            "experimental_debug_info" => (),
+            _ => bail!("Unexpected field {} in NodeDef", name),
+        }
+    }
+    Ok(node)
+}
+
+/// An entry of a map<string, AttrValue>.
+fn attr_entry(message: &Message) -> TractResult<(String, AttrValue)> {
+    let mut key = String::new();
+    let mut attr = AttrValue { value: None };
+    for (name, value) in message {
+        match &**name {
+            "key" => key = value.string()?,
+            "value" => attr = attr_value(value.message()?)?,
+            _ => bail!("Unexpected field {} in attr", name),
+        }
+    }
+    Ok((key, attr))
+}
+
+fn attr_value(message: &Message) -> TractResult<AttrValue> {
+    let mut attr = AttrValue { value: None };
+    for (name, value) in message {
+        attr.value = Some(match &**name {
+            "s" => AttrValueValue::S(value.bytes()?),
+            "i" => AttrValueValue::I(value.number()?),
+            "f" => AttrValueValue::F(value.float()?),
+            "b" => AttrValueValue::B(value.bool()?),
+            "type" => AttrValueValue::Type(value.data_type()?),
+            "shape" => AttrValueValue::Shape(tensor_shape(value.message()?)?),
+            "tensor" => AttrValueValue::Tensor(tensor_proto(value.message()?)?),
+            "list" => AttrValueValue::List(list_value(value.message()?)?),
+            "func" => AttrValueValue::Func(name_attr_list(value.message()?)?),
+            "placeholder" => AttrValueValue::Placeholder(value.string()?),
+            _ => bail!("Unexpected field {} in AttrValue", name),
+        });
+    }
+    Ok(attr)
+}
+
+fn list_value(message: &Message) -> TractResult<ListValue> {
+    let mut list = ListValue {
+        s: vec![],
+        i: vec![],
+        f: vec![],
+        b: vec![],
+        r#type: vec![],
+        shape: vec![],
+        tensor: vec![],
+        func: vec![],
+    };
+    for (name, value) in message {
+        match &**name {
+            "s" => list.s.push(value.bytes()?),
+            "i" => list.i.push(value.number()?),
+            "f" => list.f.push(value.float()?),
+            "b" => list.b.push(value.bool()?),
+            "type" => list.r#type.push(value.data_type()?),
+            "shape" => list.shape.push(tensor_shape(value.message()?)?),
+            "tensor" => list.tensor.push(tensor_proto(value.message()?)?),
+            "func" => list.func.push(name_attr_list(value.message()?)?),
+            _ => bail!("Unexpected field {} in ListValue", name),
+        }
+    }
+    Ok(list)
+}
+
+fn name_attr_list(message: &Message) -> TractResult<NameAttrList> {
+    let mut list = NameAttrList { name: String::new(), attr: HashMap::new() };
+    for (name, value) in message {
+        match &**name {
+            "name" => list.name = value.string()?,
+            "attr" => {
+                let (k, v) = attr_entry(value.message()?)?;
+                list.attr.insert(k, v);
+            }
+            _ => bail!("Unexpected field {} in NameAttrList", name),
+        }
+    }
+    Ok(list)
+}
+
+fn tensor_shape(message: &Message) -> TractResult<TensorShapeProto> {
+    let mut shape = TensorShapeProto { dim: vec![], unknown_rank: false };
+    for (name, value) in message {
+        match &**name {
+            "dim" => {
+                let mut dim = Dim { size: 0, name: String::new() };
+                for (name, value) in value.message()? {
+                    match &**name {
+                        "size" => dim.size = value.number()?,
+                        "name" => dim.name = value.string()?,
+                        _ => bail!("Unexpected field {} in Dim", name),
+                    }
+                }
+                shape.dim.push(dim);
+            }
+            "unknown_rank" => shape.unknown_rank = value.bool()?,
+            _ => bail!("Unexpected field {} in TensorShapeProto", name),
+        }
+    }
+    Ok(shape)
+}
+
+fn tensor_proto(message: &Message) -> TractResult<TensorProto> {
+    let mut tensor = TensorProto::default();
+    for (name, value) in message {
+        match &**name {
+            "dtype" => tensor.dtype = value.data_type()?,
+            "tensor_shape" => tensor.tensor_shape = Some(tensor_shape(value.message()?)?),
+            "version_number" => tensor.version_number = value.number()?,
+            "tensor_content" => tensor.tensor_content = value.bytes()?,
+            "half_val" => tensor.half_val.push(value.number()?),
+            "float_val" => tensor.float_val.push(value.float()?),
+            "double_val" => tensor.double_val.push(value.float()?),
+            "int_val" => tensor.int_val.push(value.number()?),
+            "string_val" => tensor.string_val.push(value.bytes()?),
+            "int64_val" => tensor.int64_val.push(value.number()?),
+            "bool_val" => tensor.bool_val.push(value.bool()?),
+            "uint32_val" => tensor.uint32_val.push(value.number()?),
+            "uint64_val" => tensor.uint64_val.push(value.number()?),
+            _ => bail!("Unexpected field {} in TensorProto", name),
+        }
+    }
+    Ok(tensor)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strings() {
+        let tokens = tokenize(r#"s: "a\"b\n\101\x42" 'c'"#).unwrap();
+        assert_eq!(tokens[2], Token::Str(b"a\"b\nAB".to_vec()));
+        let mut parser = Parser { tokens, pos: 0 };
+        let message = parser.message(None).unwrap();
+        assert_eq!(message, vec![("s".to_string(), Value::Bytes(b"a\"b\nABc".to_vec()))]);
+    }
+
+    #[test]
+    fn node_with_attrs() {
+        let graph = graph_def(
+            r#"
+            # a comment
+            node {
+              name: "c"
+              op: "Const"
+              attr { key: "dtype" value { type: DT_INT32 } }
+              attr {
+                key: "value"
+                value < tensor {
+                  dtype: DT_INT32
+                  tensor_shape { dim { size: 2 } }
+                  int_val: [1, 2]
+                } >
+              }
+              attr { key: "list" value { list { i: 1 i: 2 f: 0.5f type: DT_FLOAT_REF } } }
+            }
+            versions { producer: 27 }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(graph.versions.as_ref().unwrap().producer, 27);
+        let node = &graph.node[0];
+        assert_eq!(node.name, "c");
+        assert_eq!(node.get_attr_datum_type("dtype").unwrap(), DatumType::I32);
+        assert_eq!(node.get_attr_tensor("value").unwrap(), tensor1(&[1i32, 2]));
+        match node.attr["list"].value {
+            Some(AttrValueValue::List(ref list)) => {
+                assert_eq!(list.i, vec![1, 2]);
+                assert_eq!(list.f, vec![0.5]);
+                assert_eq!(list.r#type, vec![101]);
+            }
+            _ => panic!(),
+        }
+    }
+
+    #[test]
+    fn unknown_field() {
+        assert!(graph_def("node { nmae: \"a\" }").is_err());
+    }
+}
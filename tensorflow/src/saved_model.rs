@@ -0,0 +1,196 @@
+//! SavedModel directories: the graph of a signature is frozen, its variables
+//! being replaced by constants restored from the checkpoint.
+
+use std::collections::HashSet;
+use std::convert::TryFrom;
+use std::path;
+
+use tract_core::internal::*;
+
+use crate::checkpoint::Checkpoint;
+use crate::model::Tensorflow;
+use crate::tfpb::tensorflow::tensor_info::Encoding;
+use crate::tfpb::tensorflow::{
+    DataType, GraphDef, MetaGraphDef, NodeDef, SavedModel, TensorInfo, TensorProto,
+};
+
+/// Ops holding variables, turned into Const nodes.
+const VARIABLES: &[&str] = &["VariableV2", "Variable", "VarHandleOp"];
+
+/// Calls of TF2 functions, from the signatures of `tf.saved_model.save`.
+const FUNCTION_CALLS: &[&str] = &["StatefulPartitionedCall", "PartitionedCall"];
+
+/// A frozen GraphDef, with the tensor names ("node:slot") of the signature
+/// inputs and outputs, sorted by signature key.
+#[derive(Clone, Debug)]
+pub struct FrozenSignature {
+    pub graph: GraphDef,
+    pub inputs: Vec<String>,
+    pub outputs: Vec<String>,
+}
+
+/// Freeze the `signature` of the MetaGraph tagged with all the `tags` in a
+/// SavedModel stored in `dir`.
+pub fn freeze(
+    mut saved: SavedModel,
+    dir: &path::Path,
+    tags: &[&str],
+    signature: &str,
+) -> TractResult<FrozenSignature> {
+    let position = saved
+        .meta_graphs
+        .iter()
+        .position(|m| {
+            let found = m.meta_info_def.as_ref().map(|i| &*i.tags).unwrap_or(&[]);
+            tags.iter().all(|t| found.iter().any(|f| f == t))
+        })
+        .ok_or_else(|| format!("No MetaGraph tagged with {:?} in SavedModel", tags))?;
+    let meta: MetaGraphDef = saved.meta_graphs.remove(position);
+    let sig = meta.signature_def.get(signature).ok_or_else(|| {
+        let mut names: Vec<&str> = meta.signature_def.keys().map(|k| &**k).collect();
+        names.sort();
+        format!("No signature {} in SavedModel (found: {})", signature, names.join(", "))
+    })?;
+    let inputs = tensor_names(&sig.inputs)?;
+    let outputs = tensor_names(&sig.outputs)?;
+    let mut graph = meta.graph_def.ok_or("MetaGraph has no GraphDef")?;
+    let variables = dir.join("variables").join("variables");
+    let checkpoint = if path::PathBuf::from(format!("{}.index", variables.display())).exists() {
+        Some(Checkpoint::open(&variables)?)
+    } else {
+        None
+    };
+    let keys = checkpoint_keys(&graph)?;
+    // unused signature inputs are kept, as they are inputs of the model
+    let roots = outputs
+        .iter()
+        .chain(inputs.iter())
+        .map(|o| Ok(Tensorflow::parse_input(o)?.0.to_string()))
+        .collect::<TractResult<Vec<_>>>()?;
+    prune(&mut graph, &roots)?;
+    let calls: Vec<&str> =
+        graph.node.iter().filter(|n| FUNCTION_CALLS.contains(&&*n.op)).map(|n| &*n.name).collect();
+    if !calls.is_empty() {
+        bail!(
+            "Signature {} calls TF2 functions ({}), which are not supported: freeze it into a GraphDef first",
+            signature,
+            calls.join(", ")
+        )
+    }
+    if let Some(checkpoint) = checkpoint {
+        freeze_variables(&mut graph, &checkpoint, &keys)?;
+    } else {
+        let missing: Vec<&str> =
+            graph.node.iter().filter(|n| VARIABLES.contains(&&*n.op)).map(|n| &*n.name).collect();
+        if !missing.is_empty() {
+            bail!(
+                "No checkpoint index ({}.index) for the variables of signature {}: {}",
+                variables.display(),
+                signature,
+                missing.join(", ")
+            )
+        }
+    }
+    Ok(FrozenSignature { graph, inputs, outputs })
+}
+
+fn tensor_names(infos: &HashMap<String, TensorInfo>) -> TractResult<Vec<String>> {
+    let mut keys: Vec<&String> = infos.keys().collect();
+    keys.sort();
+    keys.into_iter()
+        .map(|k| match infos[k].encoding {
+            Some(Encoding::Name(ref name)) => Ok(name.clone()),
+            _ => bail!("Signature tensor {} is not dense", k),
+        })
+        .collect()
+}
+
+/// Checkpoint keys of the variables, found from the restore ops of the
+/// saver: each variable is assigned an output of RestoreV2, whose
+/// tensor_names give the keys.
+fn checkpoint_keys(graph: &GraphDef) -> TractResult<HashMap<String, String>> {
+    let nodes: HashMap<&str, &NodeDef> = graph.node.iter().map(|n| (&*n.name, n)).collect();
+    let mut keys = HashMap::new();
+    for node in &graph.node {
+        if (node.op != "Assign" && node.op != "AssignVariableOp") || node.input.len() < 2 {
+            continue;
+        }
+        let variable = Tensorflow::parse_input(&node.input[0])?.0;
+        let mut value = Tensorflow::parse_input(&node.input[1])?;
+        while let Some(identity) = nodes.get(value.0).filter(|n| n.op == "Identity") {
+            value = Tensorflow::parse_input(&identity.input[0])?;
+        }
+        let restore = match nodes.get(value.0) {
+            Some(restore) if restore.op == "RestoreV2" && restore.input.len() > 1 => restore,
+            _ => continue,
+        };
+        let names = Tensorflow::parse_input(&restore.input[1])?.0;
+        if let Some(names) = nodes.get(names).filter(|n| n.op == "Const") {
+            let names = names.get_attr_tensor("value")?;
+            if let Some(key) = names.as_slice::<Blob>()?.get(value.1) {
+                let key = String::from_utf8(key.to_vec()).map_err(|_| "Invalid checkpoint key")?;
+                keys.insert(variable.to_string(), key);
+            }
+        }
+    }
+    Ok(keys)
+}
+
+/// Replace variables by constants, and reads of resource variables by
+/// identities.
+fn freeze_variables(
+    graph: &mut GraphDef,
+    checkpoint: &Checkpoint,
+    keys: &HashMap<String, String>,
+) -> TractResult<()> {
+    let mut frozen = HashSet::new();
+    for node in &mut graph.node {
+        if !VARIABLES.contains(&&*node.op) {
+            continue;
+        }
+        let key = keys.get(&node.name).unwrap_or(&node.name);
+        if !checkpoint.contains(key) {
+            bail!("Variable {} ({}) not found in checkpoint", node.name, key)
+        }
+        let value = TensorProto::try_from(&checkpoint.tensor(key)?)?;
+        node.op = "Const".to_string();
+        node.input.clear();
+        node.attr.clear();
+        node.attr.insert("dtype".to_string(), DataType::from_i32(value.dtype).unwrap().into());
+        node.attr.insert("value".to_string(), value.into());
+        frozen.insert(node.name.clone());
+    }
+    for node in &mut graph.node {
+        if node.op == "ReadVariableOp"
+            && frozen.contains(Tensorflow::parse_input(&node.input[0])?.0)
+        {
+            node.op = "Identity".to_string();
+            if let Some(dt) = node.attr.remove("dtype") {
+                node.attr.insert("T".to_string(), dt);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Keep only the nodes computing the `roots`.
+fn prune(graph: &mut GraphDef, roots: &[String]) -> TractResult<()> {
+    let nodes: HashMap<&str, &NodeDef> = graph.node.iter().map(|n| (&*n.name, n)).collect();
+    let mut kept: HashSet<String> = HashSet::new();
+    let mut todo: Vec<&str> = roots.iter().map(|r| &**r).collect();
+    while let Some(name) = todo.pop() {
+        if kept.contains(name) {
+            continue;
+        }
+        let node = nodes.get(name).ok_or_else(|| format!("No node {} in graph", name))?;
+        kept.insert(name.to_string());
+        for input in node.input.iter().filter(|i| !i.starts_with("^")) {
+            todo.push(Tensorflow::parse_input(input)?.0);
+        }
+    }
+    graph.node.retain(|n| kept.contains(&n.name));
+    for node in &mut graph.node {
+        node.input.retain(|i| !i.starts_with("^") || kept.contains(&i[1..]));
+    }
+    Ok(())
+}
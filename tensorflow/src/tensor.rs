@@ -81,15 +81,19 @@ impl<'a> TryFrom<&'a TensorProto> for Tensor {
         let content = &t.tensor_content;
         let dtype = DataType::from_i32(t.dtype).unwrap();
         let mat: Tensor = if content.len() != 0 {
-            unsafe {
-                match dtype {
-                    DataType::DtFloat => Self::from_raw::<f32>(&dims, content)?,
-                    DataType::DtDouble => Self::from_raw::<f64>(&dims, content)?,
-                    DataType::DtInt32 => Self::from_raw::<i32>(&dims, content)?,
-                    DataType::DtInt64 => Self::from_raw::<i64>(&dims, content)?,
-                    _ => unimplemented!("missing type (for get_tensor_content) {:?}", dtype),
-                }
+            let dt = DatumType::try_from(dtype)?;
+            if !dt.is_copy() {
+                bail!("missing type (for get_tensor_content) {:?}", dtype)
+            }
+            if content.len() != dims.iter().product::<usize>() * dt.size_of() {
+                bail!(
+                    "tensor_content of {} bytes for a {:?} tensor of shape {:?}",
+                    content.len(),
+                    dt,
+                    dims
+                )
             }
+            unsafe { Self::from_raw_dt(dt, &dims, content)? }
         } else {
             match dtype {
                 DataType::DtInt32 => tensor_from_repeated_field(&*dims, t.int_val.to_vec())?,
@@ -155,8 +159,22 @@ impl<'a> TryFrom<&'a Tensor> for TensorProto {
             DatumType::I64 => {
                 tensor.int64_val = from.to_array_view::<i64>()?.iter().cloned().collect();
             }
+            dt if dt.is_copy() => {
+                tensor.tensor_content = dispatch_copy!(self::raw_content(dt)(from))?;
+            }
             _ => unimplemented!("missing type {:?}", from.datum_type()),
         }
         Ok(tensor)
     }
 }
+
+fn raw_content<T: Datum + Copy>(tensor: &Tensor) -> TractResult<Vec<u8>> {
+    let data = tensor.as_slice::<T>()?;
+    let bytes = unsafe {
+        std::slice::from_raw_parts(
+            data.as_ptr() as *const u8,
+            data.len() * T::datum_type().size_of(),
+        )
+    };
+    Ok(bytes.to_vec())
+}
@@ -143,6 +143,9 @@ impl NodeDef {
     pub fn get_attr_opt_shape(&self, name: &str) -> TractResult<Option<TVec<isize>>> {
         if let Some(a) = self.attr.get(name) {
             if let Value::Shape(ref shape) = a.value.as_ref().unwrap() {
+                if shape.unknown_rank {
+                    return Ok(None);
+                }
                 return Ok(Some(shape.try_into()?));
             }
         };
@@ -237,6 +240,12 @@ impl<'a> From<&'a str> for AttrValue {
     }
 }
 
+impl From<bool> for AttrValue {
+    fn from(t: bool) -> AttrValue {
+        AttrValue { value: Some(Value::B(t)) }
+    }
+}
+
 impl From<i32> for AttrValue {
     fn from(t: i32) -> AttrValue {
         AttrValue::from(t as i64)
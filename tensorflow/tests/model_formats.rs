@@ -0,0 +1,209 @@
+extern crate prost;
+extern crate tract_core;
+extern crate tract_tensorflow;
+
+use std::convert::TryFrom;
+use std::fs;
+
+use prost::Message;
+use tract_core::prelude::*;
+use tract_tensorflow::checkpoint::Checkpoint;
+use tract_tensorflow::tfpb;
+use tract_tensorflow::tfpb::tensorflow::meta_graph_def::MetaInfoDef;
+use tract_tensorflow::tfpb::tensorflow::tensor_info::Encoding;
+use tract_tensorflow::tfpb::tensorflow::*;
+
+#[test]
+fn text_graph_def() {
+    let tf = tract_tensorflow::tensorflow();
+    let text = tf.proto_model_for_path("tests/models/plus3.pbtxt").unwrap();
+    let binary = tf.proto_model_for_path("tests/models/plus3.pb").unwrap();
+    assert_eq!(text.node, binary.node);
+    let model = tf.model_for_path("tests/models/plus3.pbtxt").unwrap();
+    let outputs = SimplePlan::new(&model).unwrap().run(tvec!(tensor1(&[1.0f32, 2.5]))).unwrap();
+    assert_eq!(outputs[0], rctensor1(&[4.0f32, 5.5]));
+}
+
+fn varint(buf: &mut Vec<u8>, mut v: u64) {
+    while v >= 0x80 {
+        buf.push(v as u8 | 0x80);
+        v >>= 7;
+    }
+    buf.push(v as u8);
+}
+
+/// Append an uncompressed table block to `table`, returning its handle.
+fn block(table: &mut Vec<u8>, entries: &[(&[u8], Vec<u8>)]) -> Vec<u8> {
+    let offset = table.len();
+    for (key, value) in entries {
+        varint(table, 0);
+        varint(table, key.len() as u64);
+        varint(table, value.len() as u64);
+        table.extend_from_slice(key);
+        table.extend_from_slice(value);
+    }
+    table.extend_from_slice(&[0, 0, 0, 0, 1, 0, 0, 0]); // restarts: [0]
+    let mut handle = vec![];
+    varint(&mut handle, offset as u64);
+    varint(&mut handle, (table.len() - offset) as u64);
+    table.extend_from_slice(&[0; 5]); // no compression, crc not checked
+    handle
+}
+
+fn encode(message: &impl Message) -> Vec<u8> {
+    let mut buf = vec![];
+    message.encode(&mut buf).unwrap();
+    buf
+}
+
+fn write_checkpoint(prefix: &str, variables: &[(&str, Tensor)]) {
+    let mut data = vec![];
+    let mut entries = vec![(
+        &b""[..],
+        encode(&BundleHeaderProto { num_shards: 1, endianness: 0, version: None }),
+    )];
+    for (key, tensor) in variables {
+        let proto = TensorProto::try_from(tensor).unwrap();
+        let content: Vec<u8> = tensor
+            .as_slice::<f32>()
+            .unwrap()
+            .iter()
+            .flat_map(|f| f.to_bits().to_le_bytes().to_vec())
+            .collect();
+        let entry = BundleEntryProto {
+            dtype: proto.dtype,
+            shape: proto.tensor_shape,
+            shard_id: 0,
+            offset: data.len() as i64,
+            size: content.len() as i64,
+            crc32c: 0,
+            slices: vec![],
+        };
+        data.extend(content);
+        entries.push((key.as_bytes(), encode(&entry)));
+    }
+    let mut table = vec![];
+    let data_handle = block(&mut table, &entries);
+    let metaindex = block(&mut table, &[]);
+    let index = block(&mut table, &[(entries.last().unwrap().0, data_handle)]);
+    let mut footer = [metaindex, index].concat();
+    footer.resize(40, 0);
+    footer.extend_from_slice(&0xdb4775248b80fb57u64.to_le_bytes());
+    table.extend(footer);
+    fs::write(format!("{}.index", prefix), table).unwrap();
+    fs::write(format!("{}.data-00000-of-00001", prefix), data).unwrap();
+}
+
+fn tensor_info(name: &str) -> TensorInfo {
+    TensorInfo { dtype: 1, tensor_shape: None, encoding: Some(Encoding::Name(name.to_string())) }
+}
+
+#[test]
+fn saved_model_with_variables() {
+    let dir = std::env::temp_dir().join(format!("tract-saved-model-{}", std::process::id()));
+    fs::create_dir_all(dir.join("variables")).unwrap();
+    write_checkpoint(
+        dir.join("variables/variables").to_str().unwrap(),
+        &[("bias", tensor1(&[0.5f32, 0.5])), ("w", tensor1(&[3.0f32, 4.0]))],
+    );
+    let mut names = TensorProto::default();
+    names.dtype = DataType::DtString as i32;
+    let dim = tensor_shape_proto::Dim { size: 2, name: String::new() };
+    names.tensor_shape = Some(TensorShapeProto { dim: vec![dim], unknown_rank: false });
+    names.string_val = vec![b"w".to_vec(), b"bias".to_vec()];
+    let float = DataType::DtFloat;
+    let graph = tfpb::graph()
+        .node(tfpb::node().name("x").op("Placeholder").attr("dtype", float))
+        .node(tfpb::node().name("w").op("VariableV2").attr("dtype", float))
+        .node(tfpb::node().name("w/read").op("Identity").input("w").attr("T", float))
+        .node(tfpb::node().name("y").op("Mul").input("x").input("w/read").attr("T", float))
+        .node(tfpb::node().name("b").op("VarHandleOp").attr("dtype", float))
+        .node(tfpb::node().name("b/read").op("ReadVariableOp").input("b").attr("dtype", float))
+        .node(tfpb::node().name("z").op("Add").input("y").input("b/read").attr("T", float))
+        .node(
+            tfpb::node()
+                .name("save/names")
+                .op("Const")
+                .attr("dtype", DataType::DtString)
+                .attr("value", names),
+        )
+        .node(
+            tfpb::node()
+                .name("save/RestoreV2")
+                .op("RestoreV2")
+                .input("save/Const")
+                .input("save/names"),
+        )
+        .node(tfpb::node().name("save/Assign").op("Assign").input("w").input("save/RestoreV2"))
+        .node(tfpb::node().name("save/Identity").op("Identity").input("save/RestoreV2:1"))
+        .node(
+            tfpb::node()
+                .name("save/AssignVariableOp")
+                .op("AssignVariableOp")
+                .input("b")
+                .input("save/Identity"),
+        );
+    let mut signature = SignatureDef::default();
+    signature.inputs.insert("input".to_string(), tensor_info("x:0"));
+    signature.outputs.insert("output".to_string(), tensor_info("z"));
+    let mut meta = MetaGraphDef::default();
+    meta.meta_info_def =
+        Some(MetaInfoDef { tags: vec!["serve".to_string()], ..Default::default() });
+    meta.graph_def = Some(graph);
+    meta.signature_def.insert("serving_default".to_string(), signature);
+    let saved = SavedModel { saved_model_schema_version: 1, meta_graphs: vec![meta] };
+    fs::write(dir.join("saved_model.pb"), encode(&saved)).unwrap();
+
+    let tf = tract_tensorflow::tensorflow();
+    assert!(tf.read_saved_model_dir(&dir, &["train"], "serving_default").is_err());
+    assert!(tf.read_saved_model_dir(&dir, &["serve"], "predict").is_err());
+    let frozen = tf.read_saved_model_dir(&dir, &["serve"], "serving_default").unwrap();
+    assert_eq!(frozen.inputs, vec!["x:0"]);
+    assert_eq!(frozen.outputs, vec!["z"]);
+    assert!(frozen.graph.node.iter().all(|n| !n.name.starts_with("save/")));
+    let model = tf.model_for_frozen_signature(&frozen).unwrap();
+    let outputs = SimplePlan::new(&model).unwrap().run(tvec!(tensor1(&[1.0f32, 2.0]))).unwrap();
+    assert_eq!(outputs[0], rctensor1(&[3.5f32, 8.5]));
+    let model = tf.model_for_path(&dir).unwrap();
+    assert_eq!(model.output_outlets().unwrap().len(), 1);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn saved_model_fixture() {
+    let tf = tract_tensorflow::tensorflow();
+    let dir = "tests/models/regression_tf1";
+    let frozen = tf.read_saved_model_dir(dir, &["serve"], "tensorflow/serving/regress").unwrap();
+    assert_eq!(frozen.inputs, vec!["x:0", "y:0"]);
+    assert_eq!(frozen.outputs, vec!["y_hat:0"]);
+    let checkpoint = Checkpoint::open(format!("{}/variables/variables", dir)).unwrap();
+    let w = *checkpoint.tensor("w").unwrap().as_slice::<f32>().unwrap().first().unwrap();
+    let b = *checkpoint.tensor("b").unwrap().as_slice::<f32>().unwrap().first().unwrap();
+    let model = tf.model_for_frozen_signature(&frozen).unwrap();
+    let outputs = SimplePlan::new(&model)
+        .unwrap()
+        .run(tvec!(tensor1(&[2.0f32]), tensor1(&[0.0f32])))
+        .unwrap();
+    assert_eq!(outputs[0], rctensor1(&[w * 2.0 + b]));
+}
+
+#[test]
+fn saved_model_without_checkpoint() {
+    let dir = std::env::temp_dir().join(format!("tract-saved-model-nockpt-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::copy("tests/models/regression_tf1/saved_model.pb", dir.join("saved_model.pb")).unwrap();
+    let tf = tract_tensorflow::tensorflow();
+    let err = tf.read_saved_model_dir(&dir, &["serve"], "tensorflow/serving/regress").unwrap_err();
+    fs::remove_dir_all(&dir).unwrap();
+    let message = format!("{:?}", err);
+    assert!(message.contains("No checkpoint index"), "{}", message);
+    assert!(message.contains(": w, b"), "{}", message);
+}
+
+#[test]
+fn saved_model_tf2_signature() {
+    let tf = tract_tensorflow::tensorflow();
+    let err = tf.read_saved_model_dir("tests/models/regression_tf2", &["serve"], "w").unwrap_err();
+    let message = format!("{:?}", err);
+    assert!(message.contains("TF2 functions (StatefulPartitionedCall_2)"), "{}", message);
+}
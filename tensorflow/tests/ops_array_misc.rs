@@ -0,0 +1,122 @@
+#![cfg(feature = "conform")]
+#![allow(non_snake_case)]
+extern crate env_logger;
+#[macro_use]
+extern crate log;
+extern crate proptest;
+extern crate tract_core;
+extern crate tract_tensorflow;
+
+mod utils;
+
+use crate::utils::*;
+use proptest::test_runner::TestCaseResult;
+use tract_core::ndarray::*;
+use tract_core::prelude::*;
+use tract_tensorflow::conform::*;
+use tract_tensorflow::tfpb;
+use tract_tensorflow::tfpb::tensorflow::DataType::{DtBool, DtFloat, DtInt32, DtInt64};
+
+fn input() -> Tensor {
+    Array::from_shape_fn((2, 3, 4), |(a, b, c)| ((a * 7 + b * 5 + c * 3) % 11) as f32).into_tensor()
+}
+
+fn with_const_input(
+    op: &str,
+    konst: Tensor,
+    attrs: Vec<(&str, tfpb::tensorflow::AttrValue)>,
+) -> TestCaseResult {
+    let mut node = tfpb::node().name("op").op(op).input("input").input("konst").attr("T", DtFloat);
+    for (k, v) in attrs {
+        node = node.attr(k, v);
+    }
+    let graph = tfpb::graph()
+        .node(placeholder_f32("input"))
+        .node(const_i32("konst", &konst))
+        .node(node)
+        .write_to_bytes()
+        .unwrap();
+    compare(&graph, vec![("input", input())], "op")
+}
+
+fn unary(op: &str) -> TestCaseResult {
+    let graph = tfpb::graph()
+        .node(placeholder_f32("input"))
+        .node(tfpb::node().name("op").op(op).input("input").attr("T", DtFloat))
+        .write_to_bytes()
+        .unwrap();
+    compare(&graph, vec![("input", input())], "op")
+}
+
+#[test]
+fn zeros_like() {
+    unary("ZerosLike").unwrap()
+}
+
+#[test]
+fn ones_like() {
+    unary("OnesLike").unwrap()
+}
+
+#[test]
+fn rank() {
+    unary("Rank").unwrap()
+}
+
+#[test]
+fn reverse_v2() {
+    with_const_input("ReverseV2", tensor1(&[0, -1]), vec![("Tidx", DtInt32.into())]).unwrap()
+}
+
+#[test]
+fn cumsum() {
+    with_const_input("Cumsum", tensor0(1), vec![("Tidx", DtInt32.into())]).unwrap()
+}
+
+#[test]
+fn cumsum_exclusive_reverse() {
+    with_const_input(
+        "Cumsum",
+        tensor0(-1),
+        vec![("Tidx", DtInt32.into()), ("exclusive", true.into()), ("reverse", true.into())],
+    )
+    .unwrap()
+}
+
+#[test]
+fn arg_max() {
+    with_const_input("ArgMax", tensor0(1), vec![("Tidx", DtInt32.into())]).unwrap()
+}
+
+#[test]
+fn arg_min_i32() {
+    with_const_input(
+        "ArgMin",
+        tensor0(-1),
+        vec![("Tidx", DtInt32.into()), ("output_type", DtInt32.into())],
+    )
+    .unwrap()
+}
+
+#[test]
+fn where_bool() {
+    let graph = tfpb::graph()
+        .node(placeholder("input", DtBool, None))
+        .node(tfpb::node().name("op").op("Where").input("input").attr("T", DtBool))
+        .write_to_bytes()
+        .unwrap();
+    let input = tensor2(&[[true, false, true], [false, false, true]]);
+    // the output shape depends on the input values: no typed model
+    compare_optim(&graph, &vec![("input", input)], "op", Mode::Infer).unwrap()
+}
+
+#[test]
+fn where_i64() {
+    let graph = tfpb::graph()
+        .node(placeholder("input", DtInt64, None))
+        .node(tfpb::node().name("op").op("Where").input("input").attr("T", DtInt64))
+        .write_to_bytes()
+        .unwrap();
+    let input = tensor1(&[0i64, 3, 0, -1]);
+    compare_optim(&graph, &vec![("input", input)], "op", Mode::Infer).unwrap()
+}
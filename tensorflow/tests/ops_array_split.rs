@@ -0,0 +1,116 @@
+#![cfg(feature = "conform")]
+#![allow(non_snake_case)]
+extern crate env_logger;
+#[macro_use]
+extern crate log;
+#[macro_use]
+extern crate proptest;
+extern crate tract_core;
+extern crate tract_tensorflow;
+
+mod utils;
+
+use crate::utils::*;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseResult;
+use tract_core::ndarray::*;
+use tract_core::prelude::*;
+use tract_tensorflow::conform::*;
+use tract_tensorflow::tfpb;
+use tract_tensorflow::tfpb::tensorflow::DataType::DtInt32;
+
+/// An input, an axis, and a number of splits dividing the axis.
+fn strat() -> BoxedStrategy<(Tensor, usize, usize)> {
+    (vec(1usize..4, 1..4), 1usize..4)
+        .prop_flat_map(|(shape, n)| (Just(shape.clone()), 0..shape.len(), Just(n)))
+        .prop_map(|(mut shape, axis, n)| {
+            shape[axis] *= n;
+            let len = shape.iter().product::<usize>() as i32;
+            let input = Array::from_shape_vec(shape, (0..len).collect()).unwrap();
+            (input.into_tensor(), axis, n)
+        })
+        .boxed()
+}
+
+/// Compare the ix-th output of the op, through an Identity node.
+fn output(ix: usize) -> tfpb::tensorflow::NodeDef {
+    tfpb::node().name("output").op("Identity").input(format!("op:{}", ix)).attr("T", DtInt32)
+}
+
+fn split(input: &Tensor, axis: usize, n: usize, ix: usize) -> TestCaseResult {
+    let graph = tfpb::graph()
+        .node(const_i32("axis", &tensor0(axis as i32)))
+        .node(placeholder_i32("input"))
+        .node(
+            tfpb::node()
+                .name("op")
+                .op("Split")
+                .input("axis")
+                .input("input")
+                .attr("T", DtInt32)
+                .attr("num_split", n as i64),
+        )
+        .node(output(ix))
+        .write_to_bytes()
+        .unwrap();
+    compare(&graph, vec![("input", input.clone())], "output")
+}
+
+fn split_v(input: &Tensor, sizes: &[i32], axis: i32, ix: usize) -> TestCaseResult {
+    let graph = tfpb::graph()
+        .node(placeholder_i32("input"))
+        .node(const_i32("sizes", &tensor1(sizes)))
+        .node(const_i32("axis", &tensor0(axis)))
+        .node(
+            tfpb::node()
+                .name("op")
+                .op("SplitV")
+                .input("input")
+                .input("sizes")
+                .input("axis")
+                .attr("T", DtInt32)
+                .attr("Tlen", DtInt32)
+                .attr("num_split", sizes.len() as i64),
+        )
+        .node(output(ix))
+        .write_to_bytes()
+        .unwrap();
+    compare(&graph, vec![("input", input.clone())], "output")
+}
+
+fn unpack(input: &Tensor, axis: usize, ix: usize) -> TestCaseResult {
+    let graph = tfpb::graph()
+        .node(placeholder_i32("input"))
+        .node(
+            tfpb::node()
+                .name("op")
+                .op("Unpack")
+                .input("input")
+                .attr("T", DtInt32)
+                .attr("num", input.shape()[axis] as i64)
+                .attr("axis", axis as i64),
+        )
+        .node(output(ix))
+        .write_to_bytes()
+        .unwrap();
+    compare(&graph, vec![("input", input.clone())], "output")
+}
+
+proptest! {
+    #[test]
+    fn split_prop((ref input, axis, n) in strat()) {
+        split(input, axis, n, n - 1)?
+    }
+
+    #[test]
+    fn unpack_prop((ref input, axis, _n) in strat()) {
+        unpack(input, axis, 0)?
+    }
+}
+
+#[test]
+fn split_v_inferred_size() {
+    let input = Array::from_shape_vec((2, 5), (0..10).collect()).unwrap().into_tensor();
+    split_v(&input, &[2, -1, 1], -1, 1).unwrap()
+}
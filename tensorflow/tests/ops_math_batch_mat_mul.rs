@@ -0,0 +1,74 @@
+#![cfg(feature = "conform")]
+#![allow(non_snake_case)]
+extern crate env_logger;
+#[macro_use]
+extern crate log;
+#[macro_use]
+extern crate proptest;
+extern crate tract_core;
+extern crate tract_tensorflow;
+
+mod utils;
+
+use crate::utils::*;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseResult;
+use tract_core::ndarray::*;
+use tract_core::prelude::*;
+use tract_tensorflow::conform::*;
+use tract_tensorflow::tfpb;
+use tract_tensorflow::tfpb::tensorflow::DataType::DtFloat;
+
+fn strat() -> BoxedStrategy<(Tensor, Tensor, bool, bool)> {
+    // batch dims, m, k, n, adj_x, adj_y
+    (vec(1usize..4, 0..3), 1usize..5, 1usize..5, 1usize..5, any::<bool>(), any::<bool>())
+        .prop_map(|(batch, m, k, n, adj_x, adj_y)| {
+            let mut x_shape = batch.clone();
+            x_shape.extend(if adj_x { [k, m] } else { [m, k] }.iter());
+            let mut y_shape = batch.clone();
+            y_shape.extend(if adj_y { [n, k] } else { [k, n] }.iter());
+            let x = Array::from_shape_fn(x_shape, |c| c.slice().iter().sum::<usize>() as f32);
+            let y = Array::from_shape_fn(y_shape, |c| c.slice().iter().product::<usize>() as f32);
+            (x.into_tensor(), y.into_tensor(), adj_x, adj_y)
+        })
+        .boxed()
+}
+
+fn batch_mat_mul(op: &str, x: &Tensor, y: &Tensor, adj_x: bool, adj_y: bool) -> TestCaseResult {
+    let graph = tfpb::graph()
+        .node(placeholder_f32("x"))
+        .node(placeholder_f32("y"))
+        .node(
+            tfpb::node()
+                .name("op")
+                .op(op)
+                .input("x")
+                .input("y")
+                .attr("T", DtFloat)
+                .attr("adj_x", adj_x)
+                .attr("adj_y", adj_y),
+        )
+        .write_to_bytes()
+        .unwrap();
+    compare(&graph, vec![("x", x.clone()), ("y", y.clone())], "op")
+}
+
+proptest! {
+    #[test]
+    fn batch_mat_mul_prop((ref x, ref y, adj_x, adj_y) in strat()) {
+        batch_mat_mul("BatchMatMul", x, y, adj_x, adj_y)?
+    }
+
+    #[test]
+    fn batch_mat_mul_v2_prop((ref x, ref y, adj_x, adj_y) in strat()) {
+        batch_mat_mul("BatchMatMulV2", x, y, adj_x, adj_y)?
+    }
+}
+
+#[test]
+fn batch_mat_mul_v2_broadcast() {
+    let x = Array::from_shape_fn((2, 1, 3, 4), |(a, b, c, d)| (a + b + c + d) as f32);
+    let y = Array::from_shape_fn((3, 4, 2), |(a, b, c)| (a * b + c) as f32);
+    batch_mat_mul("BatchMatMulV2", &x.into_tensor(), &y.into_tensor(), false, false).unwrap();
+}
@@ -0,0 +1,177 @@
+#![cfg(feature = "conform")]
+#![allow(non_snake_case)]
+extern crate env_logger;
+#[macro_use]
+extern crate log;
+#[macro_use]
+extern crate proptest;
+extern crate tract_core;
+extern crate tract_tensorflow;
+
+mod utils;
+
+use crate::utils::*;
+use proptest::collection::vec;
+use proptest::prelude::*;
+use proptest::test_runner::TestCaseResult;
+use tract_core::ndarray::*;
+use tract_core::prelude::*;
+use tract_tensorflow::conform::*;
+use tract_tensorflow::tfpb;
+use tract_tensorflow::tfpb::tensorflow::DataType::{DtBool, DtFloat, DtInt32};
+
+fn strat() -> BoxedStrategy<Tensor> {
+    vec(1usize..4, 0..4)
+        .prop_flat_map(|shape| {
+            let len = shape.iter().product::<usize>();
+            (Just(shape), vec(-10.0f32..10.0, len..=len))
+        })
+        .prop_map(|(shape, data)| Array::from_shape_vec(shape, data).unwrap().into_tensor())
+        .boxed()
+}
+
+fn unary(op: &str, x: &Tensor) -> TestCaseResult {
+    let graph = tfpb::graph()
+        .node(placeholder_f32("x"))
+        .node(tfpb::node().name("op").op(op).input("x").attr("T", DtFloat))
+        .write_to_bytes()
+        .unwrap();
+    compare(&graph, vec![("x", x.clone())], "op")
+}
+
+fn binary(op: &str, dt: tfpb::tensorflow::DataType, x: &Tensor, y: &Tensor) -> TestCaseResult {
+    let graph = tfpb::graph()
+        .node(placeholder("x", dt, None))
+        .node(placeholder("y", dt, None))
+        .node(tfpb::node().name("op").op(op).input("x").input("y").attr("T", dt))
+        .write_to_bytes()
+        .unwrap();
+    compare(&graph, vec![("x", x.clone()), ("y", y.clone())], "op")
+}
+
+proptest! {
+    #[test]
+    fn square(ref x in strat()) {
+        unary("Square", x)?
+    }
+
+    #[test]
+    fn exp(ref x in strat()) {
+        unary("Exp", x)?
+    }
+
+    #[test]
+    fn floor(ref x in strat()) {
+        unary("Floor", x)?
+    }
+
+    #[test]
+    fn round(ref x in strat()) {
+        unary("Round", x)?
+    }
+
+    #[test]
+    fn erf(ref x in strat()) {
+        unary("Erf", x)?
+    }
+
+    #[test]
+    fn softplus(ref x in strat()) {
+        unary("Softplus", x)?
+    }
+
+    #[test]
+    fn squared_difference(ref x in strat(), y in -10.0f32..10.0) {
+        binary("SquaredDifference", DtFloat, x, &tensor0(y))?
+    }
+}
+
+#[test]
+fn sqrt() {
+    unary("Sqrt", &tensor1(&[0.0f32, 1.0, 2.0, 9.0])).unwrap()
+}
+
+#[test]
+fn round_half_to_even() {
+    unary("Round", &tensor1(&[-2.5f32, -1.5, -0.5, 0.5, 1.5, 2.5, 2.6])).unwrap()
+}
+
+#[test]
+fn leaky_relu() {
+    let graph = tfpb::graph()
+        .node(placeholder_f32("x"))
+        .node(
+            tfpb::node()
+                .name("op")
+                .op("LeakyRelu")
+                .input("x")
+                .attr("T", DtFloat)
+                .attr("alpha", 0.1f32),
+        )
+        .write_to_bytes()
+        .unwrap();
+    compare(&graph, vec![("x", tensor1(&[-2.0f32, -0.5, 0.0, 3.0]))], "op").unwrap()
+}
+
+#[test]
+fn floor_div_i32() {
+    let x = tensor1(&[7i32, -7, 7, -7, 6]);
+    let y = tensor1(&[2i32, 2, -2, -2, 3]);
+    binary("FloorDiv", DtInt32, &x, &y).unwrap()
+}
+
+#[test]
+fn floor_div_f32() {
+    let x = tensor1(&[7.0f32, -7.0, 7.5, -7.5]);
+    let y = tensor1(&[2.0f32, 2.0, -2.0, -2.0]);
+    binary("FloorDiv", DtFloat, &x, &y).unwrap()
+}
+
+#[test]
+fn not_equal() {
+    let x = tensor2(&[[1.0f32, 2.0], [3.0, 4.0]]);
+    let y = tensor1(&[1.0f32, 3.0]);
+    binary("NotEqual", DtFloat, &x, &y).unwrap()
+}
+
+#[test]
+fn logical_not() {
+    let graph = tfpb::graph()
+        .node(placeholder("x", DtBool, None))
+        .node(tfpb::node().name("op").op("LogicalNot").input("x"))
+        .write_to_bytes()
+        .unwrap();
+    compare(&graph, vec![("x", tensor1(&[true, false]))], "op").unwrap()
+}
+
+fn select(op: &str, cond: Tensor, t: Tensor, f: Tensor) -> TestCaseResult {
+    let graph = tfpb::graph()
+        .node(placeholder("c", DtBool, None))
+        .node(placeholder_f32("t"))
+        .node(placeholder_f32("f"))
+        .node(tfpb::node().name("op").op(op).input("c").input("t").input("f").attr("T", DtFloat))
+        .write_to_bytes()
+        .unwrap();
+    compare(&graph, vec![("c", cond), ("t", t), ("f", f)], "op")
+}
+
+#[test]
+fn select_same_shape() {
+    let t = tensor2(&[[1.0f32, 2.0], [3.0, 4.0]]);
+    let f = tensor2(&[[5.0f32, 6.0], [7.0, 8.0]]);
+    select("Select", tensor2(&[[true, false], [false, true]]), t, f).unwrap()
+}
+
+#[test]
+fn select_rows() {
+    let t = tensor2(&[[1.0f32, 2.0], [3.0, 4.0]]);
+    let f = tensor2(&[[5.0f32, 6.0], [7.0, 8.0]]);
+    select("Select", tensor1(&[false, true]), t, f).unwrap()
+}
+
+#[test]
+fn select_v2_broadcast() {
+    let t = tensor2(&[[1.0f32, 2.0], [3.0, 4.0]]);
+    let f = tensor0(0.0f32);
+    select("SelectV2", tensor1(&[false, true]), t, f).unwrap()
+}
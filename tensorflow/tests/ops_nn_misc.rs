@@ -0,0 +1,100 @@
+#![cfg(feature = "conform")]
+#![allow(non_snake_case)]
+extern crate env_logger;
+#[macro_use]
+extern crate log;
+extern crate proptest;
+extern crate tract_core;
+extern crate tract_tensorflow;
+
+mod utils;
+
+use crate::utils::*;
+use tract_core::ndarray::*;
+use tract_core::prelude::*;
+use tract_tensorflow::conform::*;
+use tract_tensorflow::tfpb;
+use tract_tensorflow::tfpb::tensorflow::DataType::DtFloat;
+
+fn ramp(shape: &[usize]) -> Tensor {
+    let len = shape.iter().product::<usize>();
+    Array::from_shape_vec(shape, (0..len).map(|x| (x % 7) as f32 - 3.0).collect())
+        .unwrap()
+        .into_tensor()
+}
+
+#[test]
+fn lrn() {
+    let graph = tfpb::graph()
+        .node(placeholder_f32("input"))
+        .node(
+            tfpb::node()
+                .name("op")
+                .op("LRN")
+                .input("input")
+                .attr("T", DtFloat)
+                .attr("depth_radius", 2i64)
+                .attr("bias", 1.5f32)
+                .attr("alpha", 0.3f32)
+                .attr("beta", 0.75f32),
+        )
+        .write_to_bytes()
+        .unwrap();
+    compare(&graph, vec![("input", ramp(&[1, 2, 3, 7]))], "op").unwrap()
+}
+
+fn conv3d(padding: &str, strides: Vec<i64>) {
+    let graph = tfpb::graph()
+        .node(placeholder_f32("input"))
+        .node(const_f32("filter", &ramp(&[2, 2, 3, 2, 4])))
+        .node(
+            tfpb::node()
+                .name("op")
+                .op("Conv3D")
+                .input("input")
+                .input("filter")
+                .attr("T", DtFloat)
+                .attr("strides", strides)
+                .attr("padding", padding),
+        )
+        .write_to_bytes()
+        .unwrap();
+    compare(&graph, vec![("input", ramp(&[1, 4, 5, 6, 2]))], "op").unwrap()
+}
+
+#[test]
+fn conv3d_valid() {
+    conv3d("VALID", vec![1, 1, 1, 1, 1])
+}
+
+#[test]
+fn conv3d_same_strides() {
+    conv3d("SAME", vec![1, 2, 1, 2, 1])
+}
+
+#[test]
+fn fused_batch_norm_v3() {
+    let graph = tfpb::graph()
+        .node(placeholder_f32("input"))
+        .node(const_f32("scale", &tensor1(&[1.0f32, 2.0])))
+        .node(const_f32("offset", &tensor1(&[0.5f32, -0.5])))
+        .node(const_f32("mean", &tensor1(&[1.0f32, -1.0])))
+        .node(const_f32("variance", &tensor1(&[4.0f32, 0.25])))
+        .node(
+            tfpb::node()
+                .name("op")
+                .op("FusedBatchNormV3")
+                .input("input")
+                .input("scale")
+                .input("offset")
+                .input("mean")
+                .input("variance")
+                .attr("T", DtFloat)
+                .attr("U", DtFloat)
+                .attr("epsilon", 0.001f32)
+                .attr("is_training", false),
+        )
+        .write_to_bytes()
+        .unwrap();
+    compare(&graph, vec![("input", ramp(&[1, 2, 3, 2]))], "op").unwrap()
+}
@@ -0,0 +1,194 @@
+//! A minimal, bounds-checked reader for the flatbuffers wire format.
+//!
+//! All offsets are little endian. A table starts with a signed offset to its
+//! vtable, which gives, for each field slot, the position of the field in the
+//! table (or 0 if the field is absent).
+
+use std::convert::TryInto;
+
+use tract_core::internal::*;
+
+/// Scalars that can be read from a flatbuffer.
+pub trait Scalar: Copy {
+    const SIZE: usize;
+    fn from_le(bytes: &[u8]) -> Self;
+}
+
+macro_rules! scalar {
+    ($($t:ty),*) => {
+        $(impl Scalar for $t {
+            const SIZE: usize = std::mem::size_of::<$t>();
+            fn from_le(bytes: &[u8]) -> $t {
+                <$t>::from_le_bytes(bytes.try_into().unwrap())
+            }
+        })*
+    };
+}
+
+scalar!(u8, i8, u16, i16, u32, i32, u64, i64, f32, f64);
+
+impl Scalar for bool {
+    const SIZE: usize = 1;
+    fn from_le(bytes: &[u8]) -> bool {
+        bytes[0] != 0
+    }
+}
+
+fn read<T: Scalar>(buf: &[u8], pos: usize) -> TractResult<T> {
+    let bytes = pos
+        .checked_add(T::SIZE)
+        .and_then(|end| buf.get(pos..end))
+        .ok_or_else(|| format!("Flatbuffer access out of bounds at {}", pos))?;
+    Ok(T::from_le(bytes))
+}
+
+/// A table in a flatbuffer.
+#[derive(Clone, Copy, Debug)]
+pub struct Table<'a> {
+    buf: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Table<'a> {
+    /// The root table of `buf`, checking the file identifier if any is given.
+    pub fn root(buf: &'a [u8], identifier: Option<&[u8; 4]>) -> TractResult<Table<'a>> {
+        if let Some(id) = identifier {
+            if buf.get(4..8) != Some(&id[..]) {
+                bail!("Wrong file identifier, expected {:?}", std::str::from_utf8(id).unwrap())
+            }
+        }
+        let pos = read::<u32>(buf, 0)? as usize;
+        Ok(Table { buf, pos })
+    }
+
+    /// Position of the field in `slot`, if present.
+    fn field(&self, slot: usize) -> TractResult<Option<usize>> {
+        let vtable = self.pos as i64 - read::<i32>(self.buf, self.pos)? as i64;
+        if vtable < 0 {
+            bail!("Invalid vtable offset at {}", self.pos)
+        }
+        let vtable = vtable as usize;
+        let vtable_len = read::<u16>(self.buf, vtable)? as usize;
+        let entry = 4 + 2 * slot;
+        if entry + 2 > vtable_len {
+            return Ok(None);
+        }
+        let offset = read::<u16>(self.buf, vtable + entry)? as usize;
+        Ok(if offset == 0 { None } else { Some(self.pos + offset) })
+    }
+
+    /// Target of the offset stored at `pos`.
+    fn indirect(&self, pos: usize) -> TractResult<usize> {
+        Ok(pos + read::<u32>(self.buf, pos)? as usize)
+    }
+
+    /// Position of the first element and length of the vector in `slot`.
+    fn vector_start(&self, slot: usize, item_size: usize) -> TractResult<Option<(usize, usize)>> {
+        if let Some(field) = self.field(slot)? {
+            let vector = self.indirect(field)?;
+            let len = read::<u32>(self.buf, vector)? as usize;
+            let end = len.checked_mul(item_size).and_then(|l| l.checked_add(vector + 4));
+            if end.map(|end| end > self.buf.len()).unwrap_or(true) {
+                bail!("Flatbuffer vector out of bounds at {}", vector)
+            }
+            Ok(Some((vector + 4, len)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// The scalar in `slot`, or `default` if absent.
+    pub fn scalar<T: Scalar>(&self, slot: usize, default: T) -> TractResult<T> {
+        match self.field(slot)? {
+            Some(pos) => read(self.buf, pos),
+            None => Ok(default),
+        }
+    }
+
+    /// The sub-table (or union value) in `slot`.
+    pub fn table(&self, slot: usize) -> TractResult<Option<Table<'a>>> {
+        match self.field(slot)? {
+            Some(pos) => Ok(Some(Table { buf: self.buf, pos: self.indirect(pos)? })),
+            None => Ok(None),
+        }
+    }
+
+    /// The vector of bytes in `slot`, empty if absent.
+    pub fn bytes(&self, slot: usize) -> TractResult<&'a [u8]> {
+        match self.vector_start(slot, 1)? {
+            Some((start, len)) => Ok(&self.buf[start..start + len]),
+            None => Ok(&[]),
+        }
+    }
+
+    /// The string in `slot`, if present.
+    pub fn string(&self, slot: usize) -> TractResult<Option<&'a str>> {
+        if self.field(slot)?.is_none() {
+            return Ok(None);
+        }
+        let s = std::str::from_utf8(self.bytes(slot)?)
+            .map_err(|_| "Invalid utf-8 string in flatbuffer".to_string())?;
+        Ok(Some(s))
+    }
+
+    /// The vector of scalars in `slot`, empty if absent.
+    pub fn vector<T: Scalar>(&self, slot: usize) -> TractResult<Vec<T>> {
+        match self.vector_start(slot, T::SIZE)? {
+            Some((start, len)) => (0..len).map(|i| read(self.buf, start + i * T::SIZE)).collect(),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// The vector of tables in `slot`, empty if absent.
+    pub fn tables(&self, slot: usize) -> TractResult<Vec<Table<'a>>> {
+        match self.vector_start(slot, 4)? {
+            Some((start, len)) => (0..len)
+                .map(|i| Ok(Table { buf: self.buf, pos: self.indirect(start + 4 * i)? }))
+                .collect(),
+            None => Ok(vec![]),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // table { a: i32 = 7 (slot 0, absent), b: u8 (slot 1), s: string (slot 2),
+    // v: [i32] (slot 3) }
+    fn buffer() -> Vec<u8> {
+        let mut buf = vec![];
+        buf.extend_from_slice(&20u32.to_le_bytes()); // root table at 20
+        buf.extend_from_slice(b"TEST");
+        // vtable at 8: len 10, table len 12, slot 0 absent, 1 at +4, 2 at +8
+        buf.extend_from_slice(&[10, 0, 12, 0, 0, 0, 4, 0, 8, 0]);
+        buf.extend_from_slice(&[0, 0]); // padding
+        buf.extend_from_slice(&12i32.to_le_bytes()); // vtable at 20 - 12
+        buf.extend_from_slice(&[42, 0, 0, 0]);
+        buf.extend_from_slice(&8u32.to_le_bytes()); // string at 28 + 8
+        buf.extend_from_slice(&[0, 0, 0, 0]);
+        buf.extend_from_slice(&5u32.to_le_bytes());
+        buf.extend_from_slice(b"hello\0");
+        buf
+    }
+
+    #[test]
+    fn read_table() {
+        let buf = buffer();
+        let table = Table::root(&buf, Some(b"TEST")).unwrap();
+        assert_eq!(table.scalar::<i32>(0, 7).unwrap(), 7);
+        assert_eq!(table.scalar::<u8>(1, 0).unwrap(), 42);
+        assert_eq!(table.string(2).unwrap(), Some("hello"));
+        assert_eq!(table.vector::<i32>(3).unwrap(), Vec::<i32>::new());
+        assert!(table.table(3).unwrap().is_none());
+    }
+
+    #[test]
+    fn check_identifier_and_bounds() {
+        let mut buf = buffer();
+        assert!(Table::root(&buf, Some(b"TFL3")).is_err());
+        buf.truncate(42);
+        let table = Table::root(&buf, None).unwrap();
+        assert!(table.string(2).is_err());
+    }
+}
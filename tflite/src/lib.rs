@@ -0,0 +1,24 @@
+#[allow(unused_imports)]
+#[macro_use]
+extern crate derive_new;
+#[macro_use]
+extern crate error_chain;
+#[allow(unused_imports)]
+#[macro_use]
+extern crate log;
+#[allow(unused_imports)]
+#[macro_use]
+extern crate tract_core;
+
+pub mod flat;
+pub mod model;
+pub mod ops;
+pub mod schema;
+
+pub use model::{Tflite, TfliteOpRegister, TfliteProtoModel};
+
+pub fn tflite() -> Tflite {
+    let mut tflite = Tflite::default();
+    ops::register_all_ops(&mut tflite.op_register);
+    tflite
+}
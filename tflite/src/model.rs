@@ -0,0 +1,473 @@
+use std::fmt;
+
+use tract_core::internal::*;
+use tract_core::ops::quant::QParams;
+
+use crate::flat::Table;
+use crate::schema::{self, Activation, Model, TensorDesc};
+
+/// A TFLite model, as a flatbuffer. Its content is accessed lazily through
+/// `model()`.
+#[derive(Clone)]
+pub struct TfliteProtoModel {
+    pub data: Vec<u8>,
+}
+
+impl TfliteProtoModel {
+    pub fn new(data: Vec<u8>) -> TractResult<TfliteProtoModel> {
+        let proto = TfliteProtoModel { data };
+        let version = proto.model()?.version()?;
+        if version != 3 {
+            bail!("Unsupported TFLite schema version {}", version)
+        }
+        Ok(proto)
+    }
+
+    pub fn model(&self) -> TractResult<Model<'_>> {
+        Model::from_buffer(&self.data)
+    }
+}
+
+impl fmt::Debug for TfliteProtoModel {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        write!(fmt, "TfliteProtoModel ({} bytes)", self.data.len())
+    }
+}
+
+/// An operator of the model, with its resolved code and options.
+#[derive(Clone, Debug)]
+pub struct Node<'a> {
+    pub name: String,
+    pub code: i32,
+    pub custom_code: Option<&'a str>,
+    pub version: i32,
+    /// Input tensor indices, -1 marking an omitted optional input.
+    pub inputs: Vec<i32>,
+    pub outputs: Vec<i32>,
+    pub options: Option<Table<'a>>,
+    pub custom_options: &'a [u8],
+}
+
+impl<'a> Node<'a> {
+    /// Read the scalar in `slot` of the builtin options, or `default`.
+    pub fn option<T: crate::flat::Scalar>(&self, slot: usize, default: T) -> TractResult<T> {
+        match self.options {
+            Some(options) => options.scalar(slot, default),
+            None => Ok(default),
+        }
+    }
+
+    /// Read the vector in `slot` of the builtin options.
+    pub fn option_vector<T: crate::flat::Scalar>(&self, slot: usize) -> TractResult<Vec<T>> {
+        match self.options {
+            Some(options) => options.vector(slot),
+            None => Ok(vec![]),
+        }
+    }
+
+    /// The fused activation function found in `slot` of the options.
+    pub fn activation(&self, slot: usize) -> TractResult<Activation> {
+        Activation::from_code(self.option::<i8>(slot, 0)?)
+    }
+
+    /// The index of the input tensor `ix`, if present.
+    pub fn input(&self, ix: usize) -> Option<i32> {
+        self.inputs.get(ix).cloned().filter(|&t| t >= 0)
+    }
+}
+
+/// Quantization of a tensor: real = scale * (quantized - zero_point).
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Quant {
+    pub scale: f32,
+    pub zero_point: i32,
+}
+
+/// Per-axis quantization: a `Quant` for each slice of a tensor along `axis`,
+/// or a single one for the whole tensor.
+#[derive(Clone, Debug, PartialEq)]
+pub struct AxisQuant {
+    pub axis: usize,
+    pub quants: Vec<Quant>,
+}
+
+impl AxisQuant {
+    pub fn scales(&self) -> Vec<f32> {
+        self.quants.iter().map(|q| q.scale).collect()
+    }
+
+    /// The zero point, which must be the same for all slices.
+    pub fn zero_point(&self) -> TractResult<i32> {
+        let zero_point = self.quants[0].zero_point;
+        if self.quants.iter().any(|q| q.zero_point != zero_point) {
+            bail!("Per-axis zero points must all be the same")
+        }
+        Ok(zero_point)
+    }
+}
+
+pub struct ParsingContext<'a> {
+    pub framework: &'a Tflite,
+    pub model: InferenceModel,
+    pub tensors: Vec<TensorDesc<'a>>,
+    pub buffers: Vec<&'a [u8]>,
+    outlets: HashMap<i32, OutletId>,
+}
+
+impl<'a> ParsingContext<'a> {
+    pub fn tensor(&self, ix: i32) -> TractResult<&TensorDesc<'a>> {
+        self.tensors.get(ix as usize).ok_or_else(|| format!("No tensor {} in subgraph", ix).into())
+    }
+
+    pub fn tensor_name(&self, ix: i32) -> TractResult<String> {
+        Ok(self.tensor(ix)?.name()?.map(|s| s.to_string()).unwrap_or_else(|| ix.to_string()))
+    }
+
+    pub fn datum_type(&self, ix: i32) -> TractResult<DatumType> {
+        schema::datum_type(self.tensor(ix)?.tensor_type()?)
+    }
+
+    pub fn shape(&self, ix: i32) -> TractResult<TVec<usize>> {
+        Ok(self.tensor(ix)?.shape()?.iter().map(|&d| d as usize).collect())
+    }
+
+    /// The quantization parameters of a tensor, if it is quantized.
+    ///
+    /// Per-axis quantized tensors are only accepted by `axis_quant`.
+    pub fn quant(&self, ix: i32) -> TractResult<Option<Quant>> {
+        match self.axis_quant(ix)? {
+            Some(ref q) if q.quants.len() > 1 => bail!(
+                "Per-axis quantization of tensor {} is not supported here",
+                self.tensor_name(ix)?
+            ),
+            q => Ok(q.map(|q| q.quants[0])),
+        }
+    }
+
+    /// The quantization parameters of a tensor, per axis or not, if it is
+    /// quantized.
+    pub fn axis_quant(&self, ix: i32) -> TractResult<Option<AxisQuant>> {
+        let q = match self.tensor(ix)?.quantization()? {
+            Some(q) => q,
+            None => return Ok(None),
+        };
+        let scale = q.scale()?;
+        let zero_point = q.zero_point()?;
+        if scale.is_empty() {
+            return Ok(None);
+        }
+        if zero_point.len() > 1 && zero_point.len() != scale.len() {
+            bail!(
+                "Tensor {} has {} scales but {} zero points",
+                self.tensor_name(ix)?,
+                scale.len(),
+                zero_point.len()
+            )
+        }
+        let quants = scale
+            .iter()
+            .enumerate()
+            .map(|(ix, &scale)| {
+                let zero_point = zero_point.get(ix).or_else(|| zero_point.first());
+                Quant { scale, zero_point: zero_point.cloned().unwrap_or(0) as _ }
+            })
+            .collect();
+        Ok(Some(AxisQuant { axis: q.quantized_dimension()? as usize, quants }))
+    }
+
+    /// The value of a constant tensor (one backed by a non-empty buffer).
+    pub fn konst(&self, ix: i32) -> TractResult<Option<Tensor>> {
+        let tensor = self.tensor(ix)?;
+        let data = self.buffers.get(tensor.buffer()? as usize).cloned().unwrap_or(&[]);
+        if data.is_empty() {
+            return Ok(None);
+        }
+        let dt = self.datum_type(ix)?;
+        let shape = self.shape(ix)?;
+        if !dt.is_copy() {
+            bail!("Constant tensor {} of type {:?} is not supported", self.tensor_name(ix)?, dt)
+        }
+        if data.len() != shape.iter().product::<usize>() * dt.size_of() {
+            bail!("Inconsistent buffer size for tensor {}", self.tensor_name(ix)?)
+        }
+        Ok(Some(unsafe { Tensor::from_raw_dt(dt, &shape, data)? }))
+    }
+
+    /// The outlet computing a tensor. Constants and variables (initialized to
+    /// zero) are added to the model on first use.
+    pub fn outlet(&mut self, ix: i32) -> TractResult<OutletId> {
+        if let Some(outlet) = self.outlets.get(&ix) {
+            return Ok(*outlet);
+        }
+        let name = self.tensor_name(ix)?;
+        let value = if let Some(value) = self.konst(ix)? {
+            value
+        } else if self.tensor(ix)?.is_variable()? {
+            let dt = self.datum_type(ix)?;
+            let shape = self.shape(ix)?;
+            let zeros = vec![0u8; shape.iter().product::<usize>() * dt.size_of()];
+            unsafe { Tensor::from_raw_dt(dt, &shape, &zeros)? }
+        } else {
+            bail!("Tensor {} is neither an input, a constant, or computed", name)
+        };
+        let outlet = self.model.add_const(name, value)?;
+        self.outlets.insert(ix, outlet);
+        Ok(outlet)
+    }
+
+    /// Outlets for all the (present) inputs of a node.
+    pub fn inputs(&mut self, node: &Node) -> TractResult<TVec<OutletId>> {
+        node.inputs.iter().filter(|&&i| i >= 0).map(|&i| self.outlet(i)).collect()
+    }
+
+    pub fn wire(
+        &mut self,
+        name: impl Into<String>,
+        op: impl Into<Box<dyn InferenceOp>>,
+        inputs: &[OutletId],
+    ) -> TractResult<TVec<OutletId>> {
+        self.model.wire_node(name, op, inputs)
+    }
+
+    pub fn add_const(
+        &mut self,
+        name: impl Into<String>,
+        v: impl IntoArcTensor,
+    ) -> TractResult<OutletId> {
+        self.model.add_const(name, v)
+    }
+
+    /// Apply a fused activation function to a float wire.
+    pub fn activation(
+        &mut self,
+        node: &Node,
+        activation: Activation,
+        wire: OutletId,
+    ) -> TractResult<OutletId> {
+        use tract_core::ops::math;
+        let op: Box<dyn InferenceOp> = match activation {
+            Activation::None => return Ok(wire),
+            Activation::Relu => Box::new(math::scalar_max((0.0f32).into())),
+            Activation::ReluN1To1 => {
+                Box::new(math::scalar_min_max((1.0f32).into(), (-1.0f32).into()))
+            }
+            Activation::Relu6 => Box::new(math::scalar_min_max((6.0f32).into(), (0.0f32).into())),
+            Activation::Tanh => Box::new(math::tanh()),
+        };
+        Ok(self.wire(format!("{}-{:?}", node.name, activation), op, &[wire])?[0])
+    }
+
+    /// The input `ix` of a node as a float wire, dequantizing it if needed.
+    pub fn float_input(&mut self, node: &Node, ix: usize) -> TractResult<OutletId> {
+        let tensor =
+            node.input(ix).ok_or_else(|| format!("{}: missing input {}", node.name, ix))?;
+        if self.datum_type(tensor)? != f32::datum_type() {
+            match (self.axis_quant(tensor)?, self.konst(tensor)?) {
+                (Some(ref q), Some(value)) if q.quants.len() > 1 => {
+                    let value = crate::ops::quant::dequantize_tensor_per_axis(&value, q)?;
+                    return self.add_const(format!("{}-dequant-{}", node.name, ix), value);
+                }
+                _ => (),
+            }
+        }
+        let outlet = self.outlet(tensor)?;
+        match self.quant(tensor)? {
+            Some(q) if self.datum_type(tensor)? != f32::datum_type() => {
+                let name = format!("{}-dequant-{}", node.name, ix);
+                Ok(self.wire(
+                    name,
+                    crate::ops::quant::Dequantize::new(q.scale, q.zero_point),
+                    &[outlet],
+                )?[0])
+            }
+            _ => Ok(outlet),
+        }
+    }
+
+    /// Bring the float result of a node to the type of its output `ix`,
+    /// quantizing it if needed.
+    pub fn output_from_float(
+        &mut self,
+        node: &Node,
+        ix: usize,
+        wire: OutletId,
+    ) -> TractResult<OutletId> {
+        let tensor = node.outputs[ix];
+        let dt = self.datum_type(tensor)?;
+        match self.quant(tensor)? {
+            Some(q) if dt != f32::datum_type() => {
+                let op = crate::ops::quant::Quantize::new(q.scale.recip(), 0, q.zero_point, dt);
+                Ok(self.wire(format!("{}-quant-{}", node.name, ix), op, &[wire])?[0])
+            }
+            _ => Ok(wire),
+        }
+    }
+
+    /// Quantization parameters for an integer product of quantized inputs
+    /// `a` and `b` (by tensor index), accumulated in i32.
+    pub fn product_qparams(&self, a: i32, b: i32) -> TractResult<QParams> {
+        let mut qp = QParams::new(i32::datum_type());
+        for (side, &t) in [a, b].iter().enumerate() {
+            let q = self.axis_quant(t)?.ok_or_else(|| format!("Tensor {} is not quantized", t))?;
+            let zero_point = q.zero_point()?;
+            let dt = self.datum_type(t)?;
+            let zp = crate::ops::quant::zero_point_tensor(zero_point, dt)?;
+            if zero_point != 0 {
+                if side == 0 {
+                    qp.set_zero_point_a(&zp);
+                } else {
+                    qp.set_zero_point_b(&zp);
+                }
+            }
+        }
+        Ok(qp)
+    }
+}
+
+pub type TfliteOpBuilder =
+    Arc<dyn Fn(&mut ParsingContext, &Node) -> TractResult<TVec<OutletId>> + Send + Sync>;
+
+/// Operator builders, by builtin operator code and by custom operator name.
+#[derive(Clone, Default)]
+pub struct TfliteOpRegister {
+    pub builtin: HashMap<i32, TfliteOpBuilder>,
+    pub custom: HashMap<String, TfliteOpBuilder>,
+}
+
+impl TfliteOpRegister {
+    pub fn insert<B>(&mut self, code: i32, builder: B)
+    where
+        B: Fn(&mut ParsingContext, &Node) -> TractResult<TVec<OutletId>> + Send + Sync + 'static,
+    {
+        self.builtin.insert(code, Arc::new(builder));
+    }
+
+    pub fn insert_custom<B>(&mut self, name: &str, builder: B)
+    where
+        B: Fn(&mut ParsingContext, &Node) -> TractResult<TVec<OutletId>> + Send + Sync + 'static,
+    {
+        self.custom.insert(name.to_string(), Arc::new(builder));
+    }
+}
+
+#[derive(Clone, Default)]
+pub struct Tflite {
+    pub op_register: TfliteOpRegister,
+}
+
+impl Tflite {
+    /// Register the operators of a plugin, typically custom operators.
+    pub fn with_plugin(mut self, plugin: &dyn OpPlugin<TfliteOpRegister>) -> Tflite {
+        plugin.register_ops(&mut self.op_register);
+        self
+    }
+
+    fn source_fact(tensor: &TensorDesc) -> TractResult<InferenceFact> {
+        let dt = schema::datum_type(tensor.tensor_type()?)?;
+        let signature = tensor.shape_signature()?;
+        let shape = if !signature.is_empty() { signature } else { tensor.shape()? };
+        let shape: TVec<DimFact> = shape
+            .iter()
+            .map(
+                |&d| if d >= 0 { DimFact::from((d as usize).to_dim()) } else { DimFact::default() },
+            )
+            .collect();
+        Ok(InferenceFact::dt(dt).with_shape(ShapeFact::closed(shape)))
+    }
+}
+
+impl Framework<TfliteProtoModel> for Tflite {
+    fn proto_model_for_read(&self, r: &mut dyn std::io::Read) -> TractResult<TfliteProtoModel> {
+        let mut data = vec![];
+        r.read_to_end(&mut data)?;
+        TfliteProtoModel::new(data)
+    }
+
+    fn model_for_proto_model(&self, proto: &TfliteProtoModel) -> TractResult<InferenceModel> {
+        let model = proto.model()?;
+        let codes = model.operator_codes()?;
+        let subgraphs = model.subgraphs()?;
+        if subgraphs.is_empty() {
+            bail!("Model has no subgraph")
+        }
+        let graph = &subgraphs[0];
+        let mut ctx = ParsingContext {
+            framework: self,
+            model: InferenceModel::default(),
+            tensors: graph.tensors()?,
+            buffers: model.buffers()?,
+            outlets: HashMap::new(),
+        };
+        for &input in &graph.inputs()? {
+            let fact = Self::source_fact(ctx.tensor(input)?)?;
+            let outlet = ctx.model.add_source(ctx.tensor_name(input)?, fact)?;
+            ctx.outlets.insert(input, outlet);
+        }
+        for (ix, op) in graph.operators()?.iter().enumerate() {
+            let code = codes
+                .get(op.opcode_index()? as usize)
+                .ok_or_else(|| format!("Invalid opcode index for operator {}", ix))?;
+            let outputs = op.outputs()?;
+            let name = match outputs.first() {
+                Some(&o) => ctx.tensor_name(o)?,
+                None => format!("op-{}", ix),
+            };
+            let node = Node {
+                name,
+                code: code.builtin_code()?,
+                custom_code: code.custom_code()?,
+                version: code.version()?,
+                inputs: op.inputs()?,
+                outputs,
+                options: op.builtin_options()?,
+                custom_options: op.custom_options()?,
+            };
+            trace!("Creating node {} (code {})", node.name, node.code);
+            let builder = if node.code == schema::builtin::CUSTOM {
+                node.custom_code.and_then(|c| self.op_register.custom.get(c))
+            } else {
+                self.op_register.builtin.get(&node.code)
+            };
+            let wires = match builder {
+                Some(builder) => (builder.clone())(&mut ctx, &node)?,
+                None => {
+                    let op_name = match node.custom_code {
+                        Some(custom) => custom.to_string(),
+                        None => format!("builtin-{}", node.code),
+                    };
+                    let op = tract_core::ops::unimpl::UnimplementedOp::new(
+                        op_name,
+                        format!("{:?}", node),
+                    );
+                    let inputs = ctx.inputs(&node)?;
+                    let id = ctx.model.add_node(
+                        &*node.name,
+                        op,
+                        node.outputs.iter().map(|_| InferenceFact::default()).collect(),
+                    )?;
+                    for (ix, input) in inputs.iter().enumerate() {
+                        ctx.model.add_edge(*input, InletId::new(id, ix))?;
+                    }
+                    (0..node.outputs.len()).map(|ix| OutletId::new(id, ix)).collect()
+                }
+            };
+            if wires.len() != node.outputs.len() {
+                bail!(
+                    "{}: built {} outputs, expected {}",
+                    node.name,
+                    wires.len(),
+                    node.outputs.len()
+                )
+            }
+            for (&tensor, &wire) in node.outputs.iter().zip(wires.iter()) {
+                let name = ctx.tensor_name(tensor)?;
+                ctx.model.set_outlet_label(wire, name);
+                ctx.outlets.insert(tensor, wire);
+            }
+        }
+        let outputs =
+            graph.outputs()?.iter().map(|&o| ctx.outlet(o)).collect::<TractResult<Vec<_>>>()?;
+        ctx.model.set_output_outlets(&outputs)?;
+        Ok(ctx.model)
+    }
+}
@@ -0,0 +1,170 @@
+use tract_core::internal::*;
+use tract_core::ops::array::{
+    AddDims, Concat, Gather, Pad, PadMode, PermuteAxes, Reshape, Split, Squeeze, StridedSlice, Tile,
+};
+use tract_core::ops::cast::Cast;
+
+use crate::model::{Node, ParsingContext, TfliteOpRegister};
+use crate::schema::builtin::*;
+
+use super::{const_i64s, wire_float};
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    reg.insert(CONCATENATION, concatenation);
+    reg.insert(EXPAND_DIMS, expand_dims);
+    reg.insert(GATHER, gather);
+    reg.insert(MIRROR_PAD, |ctx, node| {
+        let mode = match node.option(0, 0i8)? {
+            0 => PadMode::Reflect,
+            1 => PadMode::Symmetric,
+            mode => bail!("Unsupported MIRROR_PAD mode {}", mode),
+        };
+        pad(ctx, node, mode)
+    });
+    reg.insert(PAD, |ctx, node| pad(ctx, node, PadMode::Constant(rctensor0(0.0f32))));
+    reg.insert(PADV2, padv2);
+    reg.insert(RESHAPE, reshape);
+    reg.insert(SPLIT, split);
+    reg.insert(SQUEEZE, squeeze);
+    reg.insert(STRIDED_SLICE, strided_slice);
+    reg.insert(TILE, tile);
+    reg.insert(TRANSPOSE, transpose);
+}
+
+/// Non-negative axis from a possibly negative one.
+fn axis(axis: i64, rank: usize) -> usize {
+    if axis < 0 {
+        (axis + rank as i64) as usize
+    } else {
+        axis as usize
+    }
+}
+
+/// Data movement operators keep the quantized values as they are (TFLite
+/// requires the same quantization on input and output).
+fn wire_raw(
+    ctx: &mut ParsingContext,
+    node: &Node,
+    op: impl Into<Box<dyn InferenceOp>>,
+    inputs: &[usize],
+) -> TractResult<TVec<OutletId>> {
+    let inputs = inputs
+        .iter()
+        .map(|&ix| {
+            let tensor =
+                node.input(ix).ok_or_else(|| format!("{}: missing input {}", node.name, ix))?;
+            ctx.outlet(tensor)
+        })
+        .collect::<TractResult<TVec<_>>>()?;
+    ctx.wire(&*node.name, op, &inputs)
+}
+
+/// ConcatenationOptions: axis, fused_activation_function.
+fn concatenation(ctx: &mut ParsingContext, node: &Node) -> TractResult<TVec<OutletId>> {
+    let axis = node.option::<i32>(0, 0)? as i64;
+    let activation = node.activation(1)?;
+    let count = node.inputs.len();
+    wire_float(ctx, node, count, Concat::new(axis), activation)
+}
+
+fn expand_dims(ctx: &mut ParsingContext, node: &Node) -> TractResult<TVec<OutletId>> {
+    let rank = ctx.shape(node.input(0).ok_or("EXPAND_DIMS expects an input")?)?.len();
+    let axes = const_i64s(ctx, node, 1)?;
+    let axes = axes.iter().map(|&a| axis(a, rank + 1)).collect();
+    wire_raw(ctx, node, AddDims::new(axes), &[0])
+}
+
+/// GatherOptions: axis.
+fn gather(ctx: &mut ParsingContext, node: &Node) -> TractResult<TVec<OutletId>> {
+    let axis = node.option::<i32>(0, 0)? as i64;
+    let data = ctx.outlet(node.input(0).ok_or("GATHER expects data")?)?;
+    let indices = node.input(1).ok_or("GATHER expects indices")?;
+    let indices = if let Some(value) = ctx.konst(indices)? {
+        let value = value.cast_to::<i64>()?.into_owned();
+        ctx.add_const(format!("{}-indices", node.name), value)?
+    } else {
+        let wire = ctx.outlet(indices)?;
+        ctx.wire(format!("{}-indices", node.name), Cast::new(i64::datum_type()), &[wire])?[0]
+    };
+    ctx.wire(&*node.name, Gather::new(axis), &[data, indices])
+}
+
+fn paddings(ctx: &ParsingContext, node: &Node) -> TractResult<Vec<(usize, usize)>> {
+    let pads = const_i64s(ctx, node, 1)?;
+    Ok(pads.chunks(2).map(|p| (p[0] as usize, p[1] as usize)).collect())
+}
+
+fn pad(ctx: &mut ParsingContext, node: &Node, mode: PadMode) -> TractResult<TVec<OutletId>> {
+    let pads = paddings(ctx, node)?;
+    wire_float(ctx, node, 1, Pad::new(pads, mode), crate::schema::Activation::None)
+}
+
+fn padv2(ctx: &mut ParsingContext, node: &Node) -> TractResult<TVec<OutletId>> {
+    let value = node.input(2).ok_or("PADV2 expects a constant value")?;
+    let value = ctx.konst(value)?.ok_or("PADV2 value must be constant")?;
+    let value = match ctx.quant(node.input(2).unwrap())? {
+        Some(q) if value.datum_type() != f32::datum_type() => {
+            super::quant::dequantize_tensor(&value, q)?
+        }
+        _ => value.cast_to::<f32>()?.into_owned(),
+    };
+    let value = unsafe { value.into_shape(&[])? };
+    pad(ctx, node, PadMode::Constant(value.into_arc_tensor()))
+}
+
+/// ReshapeOptions: new_shape, used when the shape is not given as an input.
+fn reshape(ctx: &mut ParsingContext, node: &Node) -> TractResult<TVec<OutletId>> {
+    let data = ctx.outlet(node.input(0).ok_or("RESHAPE expects an input")?)?;
+    let shape = match node.input(1) {
+        Some(shape) => ctx.outlet(shape)?,
+        None => {
+            let shape: Vec<i64> = node.option_vector::<i32>(0)?.iter().map(|&d| d as i64).collect();
+            ctx.add_const(format!("{}-shape", node.name), tensor1(&shape))?
+        }
+    };
+    ctx.wire(&*node.name, Reshape::new(), &[data, shape])
+}
+
+/// SplitOptions: num_splits. The axis is the first input.
+fn split(ctx: &mut ParsingContext, node: &Node) -> TractResult<TVec<OutletId>> {
+    let splits = node.option::<i32>(0, node.outputs.len() as i32)? as usize;
+    let rank = ctx.shape(node.input(1).ok_or("SPLIT expects an input")?)?.len();
+    let split_axis = axis(const_i64s(ctx, node, 0)?[0], rank);
+    wire_raw(ctx, node, Split::new(split_axis, splits, None), &[1])
+}
+
+/// SqueezeOptions: squeeze_dims, all the unit dims if empty.
+fn squeeze(ctx: &mut ParsingContext, node: &Node) -> TractResult<TVec<OutletId>> {
+    let shape = ctx.shape(node.input(0).ok_or("SQUEEZE expects an input")?)?;
+    let dims = node.option_vector::<i32>(0)?;
+    let mut axes: Vec<usize> = if !dims.is_empty() {
+        dims.iter().map(|&d| axis(d as i64, shape.len())).collect()
+    } else {
+        (0..shape.len()).filter(|&ax| shape[ax] == 1).collect()
+    };
+    axes.sort();
+    wire_raw(ctx, node, Squeeze::new(Some(axes)), &[0])
+}
+
+/// StridedSliceOptions: begin_mask, end_mask, ellipsis_mask, new_axis_mask,
+/// shrink_axis_mask.
+fn strided_slice(ctx: &mut ParsingContext, node: &Node) -> TractResult<TVec<OutletId>> {
+    if node.option(2, 0i32)? != 0 || node.option(3, 0i32)? != 0 {
+        bail!("Ellipsis and new axis masks are not supported in STRIDED_SLICE")
+    }
+    let op = StridedSlice::tensorflow(
+        node.option::<i32>(0, 0)? as i64,
+        node.option::<i32>(1, 0)? as i64,
+        node.option::<i32>(4, 0)? as i64,
+    );
+    wire_raw(ctx, node, op, &[0, 1, 2, 3])
+}
+
+fn tile(ctx: &mut ParsingContext, node: &Node) -> TractResult<TVec<OutletId>> {
+    wire_raw(ctx, node, Tile::new(), &[0, 1])
+}
+
+fn transpose(ctx: &mut ParsingContext, node: &Node) -> TractResult<TVec<OutletId>> {
+    let perm = const_i64s(ctx, node, 1)?.iter().map(|&a| a as usize).collect();
+    wire_raw(ctx, node, PermuteAxes::new(Some(perm)), &[0])
+}
@@ -0,0 +1,201 @@
+use tract_core::internal::*;
+use tract_core::ops::cnn::{AvgPool, Conv, MaxPool, PaddingSpec, PoolSpec};
+use tract_core::ops::math;
+use tract_core::ops::nn::DataFormat;
+
+use crate::model::{AxisQuant, Node, ParsingContext, TfliteOpRegister};
+use crate::schema::builtin::*;
+use crate::schema::Activation;
+
+use super::quant::{dequantize_tensor_per_axis, quantize_tensor, zero_point_tensor, Quantize};
+use super::wire_float;
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    reg.insert(AVERAGE_POOL_2D, |ctx, node| pool(ctx, node, false));
+    reg.insert(CONV_2D, conv2d);
+    reg.insert(DEPTHWISE_CONV_2D, depthwise_conv2d);
+    reg.insert(MAX_POOL_2D, |ctx, node| pool(ctx, node, true));
+}
+
+fn permute<T: Datum>(t: &Tensor, axes: &[usize]) -> TractResult<Tensor> {
+    Ok(t.to_array_view::<T>()?.permuted_axes(axes).to_owned().into_tensor())
+}
+
+fn padding(code: i8) -> TractResult<PaddingSpec> {
+    match code {
+        0 => Ok(PaddingSpec::SameUpper),
+        1 => Ok(PaddingSpec::Valid),
+        _ => bail!("Unsupported padding {}", code),
+    }
+}
+
+/// Conv2DOptions: padding, stride_w, stride_h, fused_activation_function,
+/// dilation_w_factor, dilation_h_factor.
+fn conv2d(ctx: &mut ParsingContext, node: &Node) -> TractResult<TVec<OutletId>> {
+    let kernel = node.input(1).ok_or("CONV_2D expects a kernel")?;
+    let kernel_value = ctx.konst(kernel)?.ok_or("CONV_2D kernel must be constant")?;
+    // OHWI -> HWIO
+    let kernel_value =
+        dispatch_datum!(permute(kernel_value.datum_type())(&kernel_value, &[1, 2, 3, 0]))?;
+    let op = Conv::default()
+        .nhwc()
+        .hwio()
+        .padding(padding(node.option(0, 0i8)?)?)
+        .strides(tvec!(node.option::<i32>(2, 1)? as usize, node.option::<i32>(1, 1)? as usize))
+        .dilations(tvec!(node.option::<i32>(5, 1)? as usize, node.option::<i32>(4, 1)? as usize));
+    wire_conv(ctx, node, op, kernel_value, 0, node.activation(3)?)
+}
+
+/// DepthwiseConv2DOptions: padding, stride_w, stride_h, depth_multiplier,
+/// fused_activation_function, dilation_w_factor, dilation_h_factor.
+fn depthwise_conv2d(ctx: &mut ParsingContext, node: &Node) -> TractResult<TVec<OutletId>> {
+    let input = node.input(0).ok_or("DEPTHWISE_CONV_2D expects an input")?;
+    let kernel = node.input(1).ok_or("DEPTHWISE_CONV_2D expects a kernel")?;
+    let kernel_value = ctx.konst(kernel)?.ok_or("DEPTHWISE_CONV_2D kernel must be constant")?;
+    let channels = *ctx.shape(input)?.last().ok_or("DEPTHWISE_CONV_2D input has no shape")?;
+    // [1, kh, kw, C * multiplier] -> HWIO for C groups: [kh, kw, 1, C * multiplier]
+    let kshape = kernel_value.shape();
+    if kshape.len() != 4 || kshape[0] != 1 {
+        bail!("Unexpected depthwise kernel shape {:?}", kshape)
+    }
+    let hwio = [kshape[1], kshape[2], 1, kshape[3]];
+    let kernel_value = unsafe { kernel_value.into_shape(&hwio)? };
+    let op = Conv::default()
+        .nhwc()
+        .hwio()
+        .group(channels)
+        .padding(padding(node.option(0, 0i8)?)?)
+        .strides(tvec!(node.option::<i32>(2, 1)? as usize, node.option::<i32>(1, 1)? as usize))
+        .dilations(tvec!(node.option::<i32>(6, 1)? as usize, node.option::<i32>(5, 1)? as usize));
+    wire_conv(ctx, node, op, kernel_value, 3, node.activation(4)?)
+}
+
+/// Wire a convolution of the node input by an HWIO kernel, adding the bias
+/// and activation.
+///
+/// Per-axis quantized kernels must be quantized along their output channels,
+/// axis `output_axis` of the TFLite kernel.
+///
+/// Quantized convolutions accumulate the product in i32, through the zero
+/// points of the convolution, before the bias is added and the result
+/// requantized. Int8 operands are offset to uint8 first, as the quantized
+/// convolution only works on uint8.
+fn wire_conv(
+    ctx: &mut ParsingContext,
+    node: &Node,
+    op: Conv,
+    kernel: Tensor,
+    output_axis: usize,
+    activation: Activation,
+) -> TractResult<TVec<OutletId>> {
+    let input = node.input(0).unwrap();
+    let kernel_ix = node.input(1).unwrap();
+    let input_dt = ctx.datum_type(input)?;
+    let kernel_name = format!("{}-kernel", node.name);
+    let qk = match ctx.axis_quant(kernel_ix)? {
+        Some(ref q) if q.quants.len() > 1 && q.axis != output_axis => bail!(
+            "{}: kernel quantized along axis {}, expected output channels (axis {})",
+            node.name,
+            q.axis,
+            output_axis
+        ),
+        // output channels are the last HWIO axis
+        Some(q) => Some(AxisQuant { axis: 3, ..q }),
+        None => None,
+    };
+    if input_dt == f32::datum_type() {
+        let kernel = match qk {
+            Some(q) if kernel.datum_type() != f32::datum_type() => {
+                dequantize_tensor_per_axis(&kernel, &q)?
+            }
+            _ => kernel,
+        };
+        let wire = ctx.outlet(input)?;
+        let mut inputs = tvec!(wire, ctx.add_const(kernel_name, kernel)?);
+        let mut op = op;
+        if let Some(bias) = node.input(2) {
+            inputs.push(ctx.outlet(bias)?);
+            op = op.bias_input(2);
+        }
+        let wire = ctx.wire(&*node.name, op, &inputs)?[0];
+        return Ok(tvec!(ctx.activation(node, activation, wire)?));
+    }
+    let qx = ctx.quant(input)?.ok_or("Integer convolution input must be quantized")?;
+    let qk = qk.ok_or("Integer convolution kernel must be quantized")?;
+    if kernel.datum_type() != input_dt {
+        bail!("Convolution of {:?} by {:?} is not supported", input_dt, kernel.datum_type())
+    }
+    let mut wire = ctx.outlet(input)?;
+    let (mut kernel, mut x_zp, mut k_zp) = (kernel, qx.zero_point, qk.zero_point()?);
+    if input_dt == i8::datum_type() {
+        let offset = Quantize::new(1.0, 0, 128, u8::datum_type());
+        kernel = quantize_tensor(&kernel, &offset)?;
+        wire = ctx.wire(format!("{}-offset", node.name), offset, &[wire])?[0];
+        x_zp += 128;
+        k_zp += 128;
+    } else if input_dt != u8::datum_type() {
+        bail!("Unsupported quantized convolution on {:?}", input_dt)
+    }
+    let kernel = ctx.add_const(kernel_name, kernel)?;
+    let x_zp = ctx.add_const(
+        format!("{}-x-zero-point", node.name),
+        zero_point_tensor(x_zp, u8::datum_type())?,
+    )?;
+    let k_zp = ctx.add_const(
+        format!("{}-k-zero-point", node.name),
+        zero_point_tensor(k_zp, u8::datum_type())?,
+    )?;
+    let op = op
+        .x_zero_point_input(2)
+        .k_zero_point_input(3)
+        .override_output_datum_type(i32::datum_type());
+    wire = ctx.wire(&*node.name, op, &[wire, kernel, x_zp, k_zp])?[0];
+    let scales = qk.scales().iter().map(|s| qx.scale * s).collect();
+    wire_requantize(ctx, node, wire, (3, scales), activation)
+}
+
+/// Add the i32 bias (input 2) to an i32 accumulator of scales
+/// `accumulator_scales` (one, or one per output channel along the given
+/// axis), and requantize the sum to the node output.
+pub fn wire_requantize(
+    ctx: &mut ParsingContext,
+    node: &Node,
+    mut wire: OutletId,
+    accumulator_scales: (usize, Vec<f32>),
+    activation: Activation,
+) -> TractResult<TVec<OutletId>> {
+    let output = node.outputs[0];
+    let qy =
+        ctx.quant(output)?.ok_or_else(|| format!("{}: output must be quantized", node.name))?;
+    if let Some(bias) = node.input(2) {
+        let bias = ctx.outlet(bias)?;
+        wire = ctx.wire(format!("{}-bias", node.name), math::add::bin(), &[wire, bias])?[0];
+    }
+    let (axis, scales) = accumulator_scales;
+    let multipliers: Vec<f32> = scales.iter().map(|s| s / qy.scale).collect();
+    let mut op = Quantize::new(multipliers[0], 0, qy.zero_point, ctx.datum_type(output)?)
+        .with_activation(activation, qy.scale)?;
+    if multipliers.len() > 1 {
+        op = op.per_axis(axis, multipliers);
+    }
+    ctx.wire(format!("{}-requant", node.name), op, &[wire])
+}
+
+/// Pool2DOptions: padding, stride_w, stride_h, filter_width, filter_height,
+/// fused_activation_function.
+fn pool(ctx: &mut ParsingContext, node: &Node, max: bool) -> TractResult<TVec<OutletId>> {
+    let spec = PoolSpec::new(
+        DataFormat::NHWC,
+        tvec!(node.option::<i32>(4, 1)? as usize, node.option::<i32>(3, 1)? as usize),
+        padding(node.option(0, 0i8)?)?,
+        None,
+        Some(tvec!(node.option::<i32>(2, 1)? as usize, node.option::<i32>(1, 1)? as usize)),
+        None,
+    );
+    let activation = node.activation(5)?;
+    if max {
+        wire_float(ctx, node, 1, MaxPool::new(spec, None), activation)
+    } else {
+        wire_float(ctx, node, 1, AvgPool::new(spec, false), activation)
+    }
+}
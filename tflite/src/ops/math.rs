@@ -0,0 +1,72 @@
+use tract_core::internal::*;
+use tract_core::ops::{logic, math};
+
+use crate::model::{Node, ParsingContext, TfliteOpRegister};
+use crate::schema::builtin::*;
+use crate::schema::Activation;
+
+use super::wire_float;
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    reg.insert(ADD, |ctx, node| fused_binary(ctx, node, math::add::bin()));
+    reg.insert(SUB, |ctx, node| fused_binary(ctx, node, math::sub::bin()));
+    reg.insert(MUL, |ctx, node| fused_binary(ctx, node, math::mul::bin()));
+    reg.insert(DIV, |ctx, node| fused_binary(ctx, node, math::div::bin()));
+    reg.insert(MAXIMUM, |ctx, node| binary(ctx, node, math::max::bin()));
+    reg.insert(MINIMUM, |ctx, node| binary(ctx, node, math::min::bin()));
+    reg.insert(POW, |ctx, node| binary(ctx, node, math::pow::bin()));
+    reg.insert(FLOOR_MOD, |ctx, node| binary(ctx, node, math::rem::bin()));
+    reg.insert(EQUAL, |ctx, node| binary(ctx, node, logic::equals::bin()));
+    reg.insert(LESS, |ctx, node| binary(ctx, node, logic::lesser::bin()));
+    reg.insert(LESS_EQUAL, |ctx, node| binary(ctx, node, logic::lesser_equal::bin()));
+    reg.insert(GREATER, |ctx, node| binary(ctx, node, logic::greater::bin()));
+    reg.insert(GREATER_EQUAL, |ctx, node| binary(ctx, node, logic::greater_equal::bin()));
+    reg.insert(LOGICAL_AND, |ctx, node| binary(ctx, node, logic::and::bin()));
+    reg.insert(LOGICAL_OR, |ctx, node| binary(ctx, node, logic::or::bin()));
+    reg.insert(ABS, |ctx, node| unary(ctx, node, math::abs()));
+    reg.insert(CEIL, |ctx, node| unary(ctx, node, math::ceil()));
+    reg.insert(COS, |ctx, node| unary(ctx, node, math::cos()));
+    reg.insert(EXP, |ctx, node| unary(ctx, node, math::exp()));
+    reg.insert(FLOOR, |ctx, node| unary(ctx, node, math::floor()));
+    reg.insert(LOG, |ctx, node| unary(ctx, node, math::ln()));
+    reg.insert(LOGICAL_NOT, |ctx, node| unary(ctx, node, logic::not()));
+    reg.insert(NEG, |ctx, node| unary(ctx, node, math::neg()));
+    reg.insert(RSQRT, |ctx, node| unary(ctx, node, math::rsqrt()));
+    reg.insert(SIN, |ctx, node| unary(ctx, node, math::sin()));
+    reg.insert(SQRT, |ctx, node| unary(ctx, node, math::sqrt()));
+    reg.insert(SQUARED_DIFFERENCE, squared_difference);
+}
+
+/// Binary operators with a fused activation (AddOptions, SubOptions...).
+fn fused_binary(
+    ctx: &mut ParsingContext,
+    node: &Node,
+    op: impl Into<Box<dyn InferenceOp>>,
+) -> TractResult<TVec<OutletId>> {
+    let activation = node.activation(0)?;
+    wire_float(ctx, node, 2, op, activation)
+}
+
+fn binary(
+    ctx: &mut ParsingContext,
+    node: &Node,
+    op: impl Into<Box<dyn InferenceOp>>,
+) -> TractResult<TVec<OutletId>> {
+    wire_float(ctx, node, 2, op, Activation::None)
+}
+
+fn unary(
+    ctx: &mut ParsingContext,
+    node: &Node,
+    op: impl Into<Box<dyn InferenceOp>>,
+) -> TractResult<TVec<OutletId>> {
+    wire_float(ctx, node, 1, op, Activation::None)
+}
+
+fn squared_difference(ctx: &mut ParsingContext, node: &Node) -> TractResult<TVec<OutletId>> {
+    let a = ctx.float_input(node, 0)?;
+    let b = ctx.float_input(node, 1)?;
+    let diff = ctx.wire(format!("{}-diff", node.name), math::sub::bin(), &[a, b])?[0];
+    let wire = ctx.wire(&*node.name, math::mul::bin(), &[diff, diff])?[0];
+    Ok(tvec!(ctx.output_from_float(node, 0, wire)?))
+}
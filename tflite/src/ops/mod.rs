@@ -0,0 +1,46 @@
+use tract_core::internal::*;
+
+use crate::model::{Node, ParsingContext, TfliteOpRegister};
+use crate::schema::Activation;
+
+mod array;
+mod cnn;
+mod math;
+mod nn;
+pub mod quant;
+pub mod rec;
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    array::register_all_ops(reg);
+    cnn::register_all_ops(reg);
+    math::register_all_ops(reg);
+    nn::register_all_ops(reg);
+    quant::register_all_ops(reg);
+    rec::register_all_ops(reg);
+}
+
+/// Wire a float op on the first `inputs` inputs of a node, followed by a
+/// fused activation. Quantized inputs are dequantized, and the result is
+/// quantized like the node output.
+pub fn wire_float(
+    ctx: &mut ParsingContext,
+    node: &Node,
+    inputs: usize,
+    op: impl Into<Box<dyn InferenceOp>>,
+    activation: Activation,
+) -> TractResult<TVec<OutletId>> {
+    let inputs =
+        (0..inputs).map(|ix| ctx.float_input(node, ix)).collect::<TractResult<TVec<_>>>()?;
+    let wire = ctx.wire(&*node.name, op, &inputs)?[0];
+    let wire = ctx.activation(node, activation, wire)?;
+    Ok(tvec!(ctx.output_from_float(node, 0, wire)?))
+}
+
+/// The value of a constant input, as a vector of i64.
+pub fn const_i64s(ctx: &ParsingContext, node: &Node, ix: usize) -> TractResult<Vec<i64>> {
+    let tensor = node.input(ix).ok_or_else(|| format!("{}: missing input {}", node.name, ix))?;
+    let value = ctx
+        .konst(tensor)?
+        .ok_or_else(|| format!("{}: input {} must be a constant", node.name, ix))?;
+    Ok(value.cast_to::<i64>()?.as_slice::<i64>()?.to_vec())
+}
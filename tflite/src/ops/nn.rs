@@ -0,0 +1,123 @@
+use tract_core::internal::*;
+use tract_core::ops::array::Reshape;
+use tract_core::ops::math::{self, MatMul};
+use tract_core::ops::nn::{self, LayerLogSoftmax, LayerSoftmax, Reduce, Reducer};
+
+use crate::model::{Node, ParsingContext, TfliteOpRegister};
+use crate::schema::builtin::*;
+use crate::schema::Activation;
+
+use super::cnn::wire_requantize;
+use super::{const_i64s, wire_float};
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    reg.insert(ELU, |ctx, node| activation(ctx, node, nn::elu(1.0)));
+    reg.insert(FULLY_CONNECTED, fully_connected);
+    reg.insert(LEAKY_RELU, |ctx, node| {
+        let alpha = node.option(0, 0.0f32)?;
+        activation(ctx, node, nn::leaky_relu(alpha))
+    });
+    reg.insert(LOG_SOFTMAX, |ctx, node| activation(ctx, node, LayerLogSoftmax::new(-1)));
+    reg.insert(LOGISTIC, |ctx, node| activation(ctx, node, nn::sigmoid()));
+    reg.insert(MEAN, |ctx, node| reduce(ctx, node, Reducer::Mean));
+    reg.insert(REDUCE_MAX, |ctx, node| reduce(ctx, node, Reducer::Max));
+    reg.insert(REDUCE_MIN, |ctx, node| reduce(ctx, node, Reducer::Min));
+    reg.insert(REDUCE_PROD, |ctx, node| reduce(ctx, node, Reducer::Prod));
+    reg.insert(RELU, |ctx, node| activation(ctx, node, math::scalar_max((0.0f32).into())));
+    reg.insert(RELU_N1_TO_1, |ctx, node| {
+        activation(ctx, node, math::scalar_min_max((1.0f32).into(), (-1.0f32).into()))
+    });
+    reg.insert(RELU6, |ctx, node| {
+        activation(ctx, node, math::scalar_min_max((6.0f32).into(), (0.0f32).into()))
+    });
+    reg.insert(SOFTMAX, softmax);
+    reg.insert(SUM, |ctx, node| reduce(ctx, node, Reducer::Sum));
+    reg.insert(TANH, |ctx, node| activation(ctx, node, math::tanh()));
+}
+
+fn activation(
+    ctx: &mut ParsingContext,
+    node: &Node,
+    op: impl Into<Box<dyn InferenceOp>>,
+) -> TractResult<TVec<OutletId>> {
+    wire_float(ctx, node, 1, op, Activation::None)
+}
+
+/// ReducerOptions: keep_dims. Axes come as a constant input.
+fn reduce(ctx: &mut ParsingContext, node: &Node, reducer: Reducer) -> TractResult<TVec<OutletId>> {
+    let axes = const_i64s(ctx, node, 1)?;
+    let keep_dims = node.option(0, false)?;
+    wire_float(ctx, node, 1, Reduce::new(Some(axes), keep_dims, reducer), Activation::None)
+}
+
+/// SoftmaxOptions: beta.
+fn softmax(ctx: &mut ParsingContext, node: &Node) -> TractResult<TVec<OutletId>> {
+    let beta = node.option(0, 0.0f32)?;
+    let mut wire = ctx.float_input(node, 0)?;
+    if beta != 1.0 {
+        let beta = ctx.add_const(format!("{}-beta", node.name), tensor0(beta))?;
+        wire = ctx.wire(format!("{}-scaled", node.name), math::mul::bin(), &[wire, beta])?[0];
+    }
+    let wire = ctx.wire(&*node.name, LayerSoftmax::new(-1), &[wire])?[0];
+    Ok(tvec!(ctx.output_from_float(node, 0, wire)?))
+}
+
+/// FullyConnectedOptions: fused_activation_function, weights_format,
+/// keep_num_dims.
+///
+/// The input is flattened to [batch, depth] and multiplied by the
+/// transposed [units, depth] weights. Quantized inputs and weights of the
+/// same type are multiplied in i32 before the bias is added and the result
+/// requantized.
+fn fully_connected(ctx: &mut ParsingContext, node: &Node) -> TractResult<TVec<OutletId>> {
+    let input = node.input(0).ok_or("FULLY_CONNECTED expects an input")?;
+    let weights = node.input(1).ok_or("FULLY_CONNECTED expects weights")?;
+    let activation = node.activation(0)?;
+    if node.option(1, 0i8)? != 0 {
+        bail!("Shuffled weights format for FULLY_CONNECTED is not supported")
+    }
+    let input_dt = ctx.datum_type(input)?;
+    let quantized = (input_dt == u8::datum_type() || input_dt == i8::datum_type())
+        && ctx.datum_type(weights)? == input_dt;
+    let mut wire = if quantized { ctx.outlet(input)? } else { ctx.float_input(node, 0)? };
+    let depth = *ctx.shape(weights)?.last().ok_or("FULLY_CONNECTED weights have no shape")?;
+    if ctx.shape(input)?.len() != 2 {
+        let shape = tensor1(&[-1i64, depth as i64]);
+        let shape = ctx.add_const(format!("{}-flat-shape", node.name), shape)?;
+        wire = ctx.wire(format!("{}-flat", node.name), Reshape::new(), &[wire, shape])?[0];
+    }
+    if quantized {
+        let qp = ctx.product_qparams(input, weights)?;
+        let qx = ctx.quant(input)?.unwrap();
+        let qw = ctx.axis_quant(weights)?.unwrap();
+        if qw.quants.len() > 1 && qw.axis != 0 {
+            bail!(
+                "{}: weights quantized along axis {}, expected units (axis 0)",
+                node.name,
+                qw.axis
+            )
+        }
+        let scales = qw.scales().iter().map(|s| qx.scale * s).collect();
+        let w = ctx.outlet(weights)?;
+        let op = MatMul::default().with_b_trans(true).with_q_params(qp);
+        wire = ctx.wire(&*node.name, op, &[wire, w])?[0];
+        wire = wire_requantize(ctx, node, wire, (1, scales), activation)?[0];
+    } else {
+        let w = ctx.float_input(node, 1)?;
+        wire = ctx.wire(&*node.name, MatMul::default().with_b_trans(true), &[wire, w])?[0];
+        if node.input(2).is_some() {
+            let bias = ctx.float_input(node, 2)?;
+            wire = ctx.wire(format!("{}-bias", node.name), math::add::bin(), &[wire, bias])?[0];
+        }
+        wire = ctx.activation(node, activation, wire)?;
+        wire = ctx.output_from_float(node, 0, wire)?;
+    }
+    let output_shape = ctx.shape(node.outputs[0])?;
+    if output_shape.len() != 2 {
+        let mut shape: Vec<i64> = output_shape.iter().map(|&d| d as i64).collect();
+        shape[0] = -1;
+        let shape = ctx.add_const(format!("{}-output-shape", node.name), tensor1(&shape))?;
+        wire = ctx.wire(format!("{}-output", node.name), Reshape::new(), &[wire, shape])?[0];
+    }
+    Ok(tvec!(wire))
+}
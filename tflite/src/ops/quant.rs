@@ -0,0 +1,325 @@
+use tract_core::internal::*;
+use tract_core::ndarray::ArrayD;
+
+use crate::model::{AxisQuant, Node, ParsingContext, Quant, TfliteOpRegister};
+use crate::schema::builtin::*;
+use crate::schema::Activation;
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    reg.insert(DEQUANTIZE, dequantize);
+    reg.insert(QUANTIZE, quantize);
+}
+
+fn dequantize(ctx: &mut ParsingContext, node: &Node) -> TractResult<TVec<OutletId>> {
+    Ok(tvec!(ctx.float_input(node, 0)?))
+}
+
+fn quantize(ctx: &mut ParsingContext, node: &Node) -> TractResult<TVec<OutletId>> {
+    let input = node.input(0).ok_or("QUANTIZE expects an input")?;
+    let output = node.outputs[0];
+    let qo = ctx.quant(output)?.ok_or("QUANTIZE output must be quantized")?;
+    let dt = ctx.datum_type(output)?;
+    let wire = ctx.outlet(input)?;
+    let op = match ctx.quant(input)? {
+        Some(qi) if ctx.datum_type(input)? != f32::datum_type() => {
+            Quantize::new(qi.scale / qo.scale, qi.zero_point, qo.zero_point, dt)
+        }
+        _ => Quantize::new(qo.scale.recip(), 0, qo.zero_point, dt),
+    };
+    ctx.wire(&*node.name, op, &[wire])
+}
+
+/// A scalar zero point tensor, of the quantized type `dt`.
+pub fn zero_point_tensor(zero_point: i32, dt: DatumType) -> TractResult<Arc<Tensor>> {
+    Ok(match dt {
+        DatumType::U8 => rctensor0(zero_point as u8),
+        DatumType::I8 => rctensor0(zero_point as i8),
+        DatumType::I16 => rctensor0(zero_point as i16),
+        DatumType::I32 => rctensor0(zero_point),
+        _ => bail!("{:?} is not a quantized type", dt),
+    })
+}
+
+fn values_as_f64(t: &Tensor) -> TractResult<Vec<f64>> {
+    Ok(match t.datum_type() {
+        DatumType::F32 => t.as_slice::<f32>()?.iter().map(|&x| x as f64).collect(),
+        DatumType::I32 => t.as_slice::<i32>()?.iter().map(|&x| x as f64).collect(),
+        DatumType::I16 => t.as_slice::<i16>()?.iter().map(|&x| x as f64).collect(),
+        DatumType::U8 => t.as_slice::<u8>()?.iter().map(|&x| x as f64).collect(),
+        DatumType::I8 => t.as_slice::<i8>()?.iter().map(|&x| x as f64).collect(),
+        dt => bail!("Can not quantize a tensor of type {:?}", dt),
+    })
+}
+
+fn quantized_tensor(shape: &[usize], values: Vec<i32>, dt: DatumType) -> TractResult<Tensor> {
+    fn build<T: Datum>(shape: &[usize], values: Vec<T>) -> TractResult<Tensor> {
+        Ok(ArrayD::from_shape_vec(shape, values)?.into_tensor())
+    }
+    match dt {
+        DatumType::U8 => build(shape, values.into_iter().map(|x| x as u8).collect()),
+        DatumType::I8 => build(shape, values.into_iter().map(|x| x as i8).collect()),
+        DatumType::I16 => build(shape, values.into_iter().map(|x| x as i16).collect()),
+        DatumType::I32 => build(shape, values),
+        _ => bail!("{:?} is not a quantized type", dt),
+    }
+}
+
+/// Maps the flat index of an element of a tensor of shape `shape` to the
+/// index of its slice along `axis`, out of `slices` (a single slice covering
+/// the whole tensor).
+fn slice_index(
+    shape: &[usize],
+    axis: usize,
+    slices: usize,
+) -> TractResult<impl Fn(usize) -> usize> {
+    if slices != 1 && shape.get(axis) != Some(&slices) {
+        bail!("Expected {} slices along axis {} of a {:?} tensor", slices, axis, shape)
+    }
+    let stride: usize = if slices == 1 { 1 } else { shape[axis + 1..].iter().product() };
+    Ok(move |ix: usize| ix / stride % slices)
+}
+
+/// Dequantize a constant tensor.
+pub fn dequantize_tensor(t: &Tensor, q: Quant) -> TractResult<Tensor> {
+    dequantize_tensor_per_axis(t, &AxisQuant { axis: 0, quants: vec![q] })
+}
+
+/// Dequantize a constant tensor, slice by slice along the quantization axis.
+pub fn dequantize_tensor_per_axis(t: &Tensor, q: &AxisQuant) -> TractResult<Tensor> {
+    let slice = slice_index(t.shape(), q.axis, q.quants.len())?;
+    let values = values_as_f64(t)?
+        .into_iter()
+        .enumerate()
+        .map(|(ix, x)| {
+            let q = q.quants[slice(ix)];
+            ((x - q.zero_point as f64) * q.scale as f64) as f32
+        })
+        .collect();
+    Ok(ArrayD::from_shape_vec(t.shape(), values)?.into_tensor())
+}
+
+/// Quantize a constant tensor, like the Quantize op would.
+pub fn quantize_tensor(t: &Tensor, op: &Quantize) -> TractResult<Tensor> {
+    let slice = slice_index(t.shape(), op.axis, op.multipliers.len())?;
+    let values = values_as_f64(t)?
+        .into_iter()
+        .enumerate()
+        .map(|(ix, x)| op.quantize(x, op.multipliers[slice(ix)]))
+        .collect();
+    quantized_tensor(t.shape(), values, op.dt)
+}
+
+/// Convert (and clamp) to a quantized type:
+/// `y = round((x - input_zero_point) * multiplier) + zero_point`.
+///
+/// Quantizes floats (with the reciprocal of the output scale as
+/// multiplier), requantizes accumulators or quantized tensors, and offsets
+/// int8 tensors to uint8.
+///
+/// Per-axis requantization uses one multiplier per slice along `axis`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Quantize {
+    pub multipliers: Vec<f32>,
+    pub axis: usize,
+    pub input_zero_point: i32,
+    pub zero_point: i32,
+    pub dt: DatumType,
+    pub min: i32,
+    pub max: i32,
+}
+
+impl Quantize {
+    pub fn new(multiplier: f32, input_zero_point: i32, zero_point: i32, dt: DatumType) -> Quantize {
+        let (min, max) = match dt {
+            DatumType::U8 => (0, 255),
+            DatumType::I8 => (-128, 127),
+            DatumType::I16 => (std::i16::MIN as i32, std::i16::MAX as i32),
+            _ => (std::i32::MIN, std::i32::MAX),
+        };
+        Quantize {
+            multipliers: vec![multiplier],
+            axis: 0,
+            input_zero_point,
+            zero_point,
+            dt,
+            min,
+            max,
+        }
+    }
+
+    /// Use a multiplier per slice of the input along `axis`.
+    pub fn per_axis(self, axis: usize, multipliers: Vec<f32>) -> Quantize {
+        Quantize { multipliers, axis, ..self }
+    }
+
+    /// Restrict the output range to the one of a fused activation, for an
+    /// output of scale `scale`.
+    pub fn with_activation(self, activation: Activation, scale: f32) -> TractResult<Quantize> {
+        let q = |x: f32| self.zero_point + (x / scale).round() as i32;
+        let (min, max) = match activation {
+            Activation::None => return Ok(self),
+            Activation::Relu => (q(0.0), self.max),
+            Activation::Relu6 => (q(0.0), q(6.0)),
+            Activation::ReluN1To1 => (q(-1.0), q(1.0)),
+            Activation::Tanh => bail!("Fused tanh on quantized outputs is not supported"),
+        };
+        Ok(Quantize { min: min.max(self.min), max: max.min(self.max), ..self })
+    }
+
+    fn quantize(&self, x: f64, multiplier: f32) -> i32 {
+        let y = ((x - self.input_zero_point as f64) * multiplier as f64).round();
+        (y + self.zero_point as f64).max(self.min as f64).min(self.max as f64) as i32
+    }
+}
+
+impl Op for Quantize {
+    fn name(&self) -> Cow<str> {
+        "tflite.Quantize".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        let multiplier = if self.multipliers.len() > 1 {
+            format!("multipliers: {:?} on axis {}", self.multipliers, self.axis)
+        } else {
+            format!("multiplier: {}", self.multipliers[0])
+        };
+        Ok(vec![format!(
+            "{} zero points: {} -> {} {:?} in [{}, {}]",
+            multiplier, self.input_zero_point, self.zero_point, self.dt, self.min, self.max
+        )])
+    }
+
+    fn validation(&self) -> Validation {
+        Validation::Rounding
+    }
+
+    op_as_typed_op!();
+}
+
+impl StatelessOp for Quantize {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        Ok(tvec!(quantize_tensor(&input, self)?.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Quantize {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, self.dt)?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Quantize {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(TypedFact::dt_shape(self.dt, inputs[0].shape.clone())?))
+    }
+}
+
+/// Convert a quantized tensor to float: `y = (x - zero_point) * scale`.
+#[derive(Clone, Debug, new, PartialEq)]
+pub struct Dequantize {
+    pub scale: f32,
+    pub zero_point: i32,
+}
+
+impl Op for Dequantize {
+    fn name(&self) -> Cow<str> {
+        "tflite.Dequantize".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!("scale: {} zero point: {}", self.scale, self.zero_point)])
+    }
+
+    op_as_typed_op!();
+}
+
+impl StatelessOp for Dequantize {
+    fn eval(&self, mut inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let input = args_1!(inputs);
+        let q = Quant { scale: self.scale, zero_point: self.zero_point };
+        Ok(tvec!(dequantize_tensor(&input, q)?.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Dequantize {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, 1)?;
+        check_output_arity(&outputs, 1)?;
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&inputs[0].shape, &outputs[0].shape)?;
+        Ok(())
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Dequantize {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), inputs[0].shape.clone())?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quantize_and_clamp() {
+        let op = Quantize::new(2.0, 0, 10, DatumType::U8);
+        let t = quantize_tensor(&tensor1(&[-10.0f32, 0.0, 1.26, 200.0]), &op).unwrap();
+        assert_eq!(t, tensor1(&[0u8, 10, 13, 255]));
+        let relu = op.with_activation(Activation::Relu6, 0.5).unwrap();
+        let t = quantize_tensor(&tensor1(&[-10.0f32, 0.0, 1.26, 200.0]), &relu).unwrap();
+        assert_eq!(t, tensor1(&[10u8, 10, 13, 22]));
+    }
+
+    #[test]
+    fn offset_int8_to_uint8() {
+        let op = Quantize::new(1.0, 0, 128, DatumType::U8);
+        let t = quantize_tensor(&tensor1(&[-128i8, 0, 127]), &op).unwrap();
+        assert_eq!(t, tensor1(&[0u8, 128, 255]));
+    }
+
+    #[test]
+    fn dequantize() {
+        let q = Quant { scale: 0.5, zero_point: 128 };
+        let t = dequantize_tensor(&tensor1(&[128u8, 130, 0]), q).unwrap();
+        assert_eq!(t, tensor1(&[0.0f32, 1.0, -64.0]));
+    }
+
+    #[test]
+    fn per_axis() {
+        let q = AxisQuant {
+            axis: 1,
+            quants: vec![Quant { scale: 0.5, zero_point: 0 }, Quant { scale: 2.0, zero_point: 1 }],
+        };
+        let t = dequantize_tensor_per_axis(&tensor2(&[[2i8, 2], [4, 4]]), &q).unwrap();
+        assert_eq!(t, tensor2(&[[1.0f32, 2.0], [2.0, 6.0]]));
+        let op = Quantize::new(1.0, 0, 0, DatumType::I8).per_axis(1, vec![2.0, 0.5]);
+        let t = quantize_tensor(&tensor2(&[[1i32, 8], [3, 4]]), &op).unwrap();
+        assert_eq!(t, tensor2(&[[2i8, 4], [6, 2]]));
+    }
+}
@@ -0,0 +1,269 @@
+use tract_core::internal::*;
+use tract_core::ndarray::*;
+
+use crate::model::{Node, ParsingContext, TfliteOpRegister};
+use crate::schema::builtin::*;
+use crate::schema::Activation;
+
+pub fn register_all_ops(reg: &mut TfliteOpRegister) {
+    reg.insert(LSTM, |ctx, node| lstm(ctx, node, None));
+    reg.insert(UNIDIRECTIONAL_SEQUENCE_LSTM, |ctx, node| {
+        let time_major = node.option(3, false)?;
+        lstm(ctx, node, Some(time_major))
+    });
+}
+
+// Input slots of the LSTM operators.
+const INPUT: usize = 0;
+const INPUT_TO_GATES: [usize; 4] = [1, 2, 3, 4];
+const RECURRENT_TO_GATES: [usize; 4] = [5, 6, 7, 8];
+const CELL_TO_GATES: [usize; 3] = [9, 10, 11];
+const GATES_BIAS: [usize; 4] = [12, 13, 14, 15];
+const PROJECTION_WEIGHTS: usize = 16;
+const PROJECTION_BIAS: usize = 17;
+const OUTPUT_STATE: usize = 18;
+const CELL_STATE: usize = 19;
+const LAYER_NORM: [usize; 4] = [20, 21, 22, 23];
+
+/// LSTMOptions: fused_activation_function, cell_clip, proj_clip (then
+/// time_major for the sequence version).
+fn lstm(
+    ctx: &mut ParsingContext,
+    node: &Node,
+    time_major: Option<bool>,
+) -> TractResult<TVec<OutletId>> {
+    if LAYER_NORM.iter().any(|&slot| node.input(slot).is_some()) {
+        bail!("{}: layer normalized LSTM is not supported", node.name)
+    }
+    if node.outputs.len() != 1 {
+        bail!("{}: only LSTM with a single output are supported", node.name)
+    }
+    if ctx.datum_type(node.input(INPUT).ok_or("LSTM expects an input")?)? != f32::datum_type() {
+        bail!("{}: only float LSTM are supported", node.name)
+    }
+    let mut slots = vec![];
+    let mut inputs = tvec!();
+    for slot in 0..node.inputs.len().min(20) {
+        if node.input(slot).is_some() {
+            inputs.push(ctx.float_input(node, slot)?);
+            slots.push(slot);
+        }
+    }
+    let op = Lstm {
+        slots,
+        activation: node.activation(0)?,
+        cell_clip: node.option(1, 0.0f32)?,
+        proj_clip: node.option(2, 0.0f32)?,
+        time_major,
+    };
+    ctx.wire(&*node.name, op, &inputs)
+}
+
+/// A TFLite LSTM, with optional input gate (CIFG when absent), peepholes and
+/// projection.
+///
+/// `time_major` is None for the single step LSTM operator, which input is
+/// [batch, input]. Otherwise the input is a sequence, [time, batch, input]
+/// or [batch, time, input]. The state starts from the state inputs at each
+/// evaluation and is not kept between evaluations.
+#[derive(Clone, Debug)]
+pub struct Lstm {
+    /// TFLite input slot of each input of the op.
+    pub slots: Vec<usize>,
+    pub activation: Activation,
+    pub cell_clip: f32,
+    pub proj_clip: f32,
+    pub time_major: Option<bool>,
+}
+
+impl Lstm {
+    fn input_ix(&self, slot: usize) -> Option<usize> {
+        self.slots.iter().position(|&s| s == slot)
+    }
+
+    fn output_shape<D: DimLike>(&self, input: &[D], n_output: D) -> TractResult<TVec<D>> {
+        let expected = if self.time_major.is_some() { 3 } else { 2 };
+        if input.len() != expected {
+            bail!("LSTM input must be of rank {}, found {:?}", expected, input)
+        }
+        let mut shape: TVec<D> = input.into();
+        *shape.last_mut().unwrap() = n_output;
+        Ok(shape)
+    }
+
+    /// Number of outputs, from the recurrent weights ([cell, output]).
+    fn recurrent_weights(&self) -> TractResult<usize> {
+        self.input_ix(RECURRENT_TO_GATES[3]).ok_or_else(|| "LSTM expects recurrent weights".into())
+    }
+
+    fn activate(&self, x: &mut Array2<f32>) -> TractResult<()> {
+        match self.activation {
+            Activation::None => (),
+            Activation::Relu => x.mapv_inplace(|x| x.max(0.0)),
+            Activation::ReluN1To1 => x.mapv_inplace(|x| x.max(-1.0).min(1.0)),
+            Activation::Relu6 => x.mapv_inplace(|x| x.max(0.0).min(6.0)),
+            Activation::Tanh => x.mapv_inplace(|x| x.tanh()),
+        }
+        Ok(())
+    }
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+fn clip(x: &mut Array2<f32>, bound: f32) {
+    if bound > 0.0 {
+        x.mapv_inplace(|x| x.max(-bound).min(bound))
+    }
+}
+
+impl Op for Lstm {
+    fn name(&self) -> Cow<str> {
+        "tflite.Lstm".into()
+    }
+
+    fn info(&self) -> TractResult<Vec<String>> {
+        Ok(vec![format!(
+            "inputs: {:?} activation: {:?} clips: {} {} time major: {:?}",
+            self.slots, self.activation, self.cell_clip, self.proj_clip, self.time_major
+        )])
+    }
+
+    fn validation(&self) -> Validation {
+        Validation::Rounding
+    }
+
+    op_as_typed_op!();
+}
+
+impl StatelessOp for Lstm {
+    fn eval(&self, inputs: TVec<Arc<Tensor>>) -> TractResult<TVec<Arc<Tensor>>> {
+        let get = |slot: usize| -> TractResult<Option<ArrayView2<f32>>> {
+            match self.input_ix(slot) {
+                Some(ix) => Ok(Some(inputs[ix].to_array_view::<f32>()?.into_dimensionality()?)),
+                None => Ok(None),
+            }
+        };
+        let get1 = |slot: usize| -> TractResult<Option<ArrayView1<f32>>> {
+            match self.input_ix(slot) {
+                Some(ix) => Ok(Some(inputs[ix].to_array_view::<f32>()?.into_dimensionality()?)),
+                None => Ok(None),
+            }
+        };
+        let input = inputs[self.input_ix(INPUT).unwrap()].to_array_view::<f32>()?;
+        let input: Array3<f32> = match self.time_major {
+            None => input.insert_axis(Axis(0)).into_dimensionality()?.to_owned(),
+            Some(true) => input.into_dimensionality()?.to_owned(),
+            Some(false) => input.into_dimensionality::<Ix3>()?.permuted_axes([1, 0, 2]).to_owned(),
+        };
+        let (time, batch) = (input.shape()[0], input.shape()[1]);
+        let w_x: Vec<_> = INPUT_TO_GATES.iter().map(|&s| get(s)).collect::<TractResult<_>>()?;
+        let w_h: Vec<_> = RECURRENT_TO_GATES.iter().map(|&s| get(s)).collect::<TractResult<_>>()?;
+        let w_c: Vec<_> = CELL_TO_GATES.iter().map(|&s| get1(s)).collect::<TractResult<_>>()?;
+        let bias: Vec<_> = GATES_BIAS.iter().map(|&s| get1(s)).collect::<TractResult<_>>()?;
+        let projection = get(PROJECTION_WEIGHTS)?;
+        let projection_bias = get1(PROJECTION_BIAS)?;
+        let w_h_o = w_h[3].ok_or("LSTM expects recurrent weights")?;
+        let (n_cell, n_output) = (w_h_o.shape()[0], w_h_o.shape()[1]);
+        let mut h = match get(OUTPUT_STATE)? {
+            Some(h) => h.to_owned(),
+            None => Array2::zeros((batch, n_output)),
+        };
+        let mut c = match get(CELL_STATE)? {
+            Some(c) => c.to_owned(),
+            None => Array2::zeros((batch, n_cell)),
+        };
+        let mut output = Array3::<f32>::zeros((time, batch, n_output));
+        for t in 0..time {
+            let x = input.index_axis(Axis(0), t);
+            // gate pre-activations, in order: input, forget, cell, output
+            let mut gates = vec![];
+            for g in 0..4 {
+                let mut gate = match (w_x[g], w_h[g]) {
+                    (Some(w_x), Some(w_h)) => x.dot(&w_x.t()) + h.dot(&w_h.t()),
+                    (None, None) if g == 0 => Array2::zeros((batch, n_cell)),
+                    _ => bail!("Inconsistent LSTM weights for gate {}", g),
+                };
+                if let Some(b) = bias[g] {
+                    gate += &b;
+                }
+                gates.push(gate);
+            }
+            let cifg = w_x[0].is_none();
+            if let Some(w) = w_c[1] {
+                gates[1] += &(&c * &w);
+            }
+            gates[1].mapv_inplace(sigmoid);
+            if cifg {
+                gates[0] = gates[1].mapv(|f| 1.0 - f);
+            } else {
+                if let Some(w) = w_c[0] {
+                    gates[0] += &(&c * &w);
+                }
+                gates[0].mapv_inplace(sigmoid);
+            }
+            self.activate(&mut gates[2])?;
+            c = &gates[1] * &c + &gates[0] * &gates[2];
+            clip(&mut c, self.cell_clip);
+            if let Some(w) = w_c[2] {
+                gates[3] += &(&c * &w);
+            }
+            gates[3].mapv_inplace(sigmoid);
+            let mut activated = c.clone();
+            self.activate(&mut activated)?;
+            let cell_output = &gates[3] * &activated;
+            h = if let Some(w) = projection {
+                let mut h = cell_output.dot(&w.t());
+                if let Some(b) = projection_bias {
+                    h += &b;
+                }
+                clip(&mut h, self.proj_clip);
+                h
+            } else {
+                cell_output
+            };
+            output.index_axis_mut(Axis(0), t).assign(&h);
+        }
+        let output = match self.time_major {
+            None => output.index_axis_move(Axis(0), 0).into_dyn(),
+            Some(true) => output.into_dyn(),
+            Some(false) => output.permuted_axes([1, 0, 2]).into_dyn(),
+        };
+        Ok(tvec!(output.into_arc_tensor()))
+    }
+}
+
+impl InferenceRulesOp for Lstm {
+    fn rules<'r, 'p: 'r, 's: 'r>(
+        &'s self,
+        s: &mut Solver<'r>,
+        inputs: &'p [TensorProxy],
+        outputs: &'p [TensorProxy],
+    ) -> InferenceResult {
+        check_input_arity(&inputs, self.slots.len())?;
+        check_output_arity(&outputs, 1)?;
+        let recurrent = &inputs[self.recurrent_weights()?];
+        let input = &inputs[self.input_ix(INPUT).unwrap()];
+        s.equals(&outputs[0].datum_type, f32::datum_type())?;
+        s.equals(&input.datum_type, f32::datum_type())?;
+        s.equals(&recurrent.rank, 2)?;
+        s.given_2(&input.shape, &recurrent.shape[1], move |s, shape, n_output| {
+            s.equals(&outputs[0].shape, ShapeFact::from(self.output_shape(&shape, n_output)?))
+        })?;
+        Ok(())
+    }
+
+    inference_op_as_op!();
+    to_typed!();
+}
+
+impl TypedOp for Lstm {
+    typed_op_as_op!();
+
+    fn output_facts(&self, inputs: &[&TypedFact]) -> TractResult<TVec<TypedFact>> {
+        let input = inputs[self.input_ix(INPUT).unwrap()].shape.to_tvec();
+        let n_output = inputs[self.recurrent_weights()?].shape.dim(1);
+        Ok(tvec!(TypedFact::dt_shape(f32::datum_type(), &*self.output_shape(&input, n_output)?)?))
+    }
+}
@@ -0,0 +1,333 @@
+//! Accessors for the tables of the TFLite schema (schema.fbs, version 3).
+
+use tract_core::internal::*;
+
+use crate::flat::Table;
+
+pub const FILE_IDENTIFIER: &[u8; 4] = b"TFL3";
+
+macro_rules! table {
+    ($name: ident) => {
+        #[derive(Clone, Copy, Debug)]
+        pub struct $name<'a>(pub Table<'a>);
+    };
+}
+
+table!(Model);
+table!(OperatorCode);
+table!(SubGraph);
+table!(TensorDesc);
+table!(QuantizationParameters);
+table!(Operator);
+
+impl<'a> Model<'a> {
+    pub fn from_buffer(buf: &'a [u8]) -> TractResult<Model<'a>> {
+        Ok(Model(Table::root(buf, Some(FILE_IDENTIFIER))?))
+    }
+
+    pub fn version(&self) -> TractResult<u32> {
+        self.0.scalar(0, 0)
+    }
+
+    pub fn operator_codes(&self) -> TractResult<Vec<OperatorCode<'a>>> {
+        Ok(self.0.tables(1)?.into_iter().map(OperatorCode).collect())
+    }
+
+    pub fn subgraphs(&self) -> TractResult<Vec<SubGraph<'a>>> {
+        Ok(self.0.tables(2)?.into_iter().map(SubGraph).collect())
+    }
+
+    pub fn description(&self) -> TractResult<Option<&'a str>> {
+        self.0.string(3)
+    }
+
+    /// Data of the buffers, the first one being always empty.
+    pub fn buffers(&self) -> TractResult<Vec<&'a [u8]>> {
+        self.0.tables(4)?.into_iter().map(|b| b.bytes(0)).collect()
+    }
+}
+
+impl<'a> OperatorCode<'a> {
+    /// The builtin operator code. Codes above 127 are only found in the
+    /// newer int32 field, the deprecated byte field holding a placeholder.
+    pub fn builtin_code(&self) -> TractResult<i32> {
+        let deprecated = self.0.scalar::<i8>(0, 0)? as i32;
+        Ok(deprecated.max(self.0.scalar::<i32>(3, 0)?))
+    }
+
+    pub fn custom_code(&self) -> TractResult<Option<&'a str>> {
+        self.0.string(1)
+    }
+
+    pub fn version(&self) -> TractResult<i32> {
+        self.0.scalar(2, 1)
+    }
+}
+
+impl<'a> SubGraph<'a> {
+    pub fn tensors(&self) -> TractResult<Vec<TensorDesc<'a>>> {
+        Ok(self.0.tables(0)?.into_iter().map(TensorDesc).collect())
+    }
+
+    pub fn inputs(&self) -> TractResult<Vec<i32>> {
+        self.0.vector(1)
+    }
+
+    pub fn outputs(&self) -> TractResult<Vec<i32>> {
+        self.0.vector(2)
+    }
+
+    pub fn operators(&self) -> TractResult<Vec<Operator<'a>>> {
+        Ok(self.0.tables(3)?.into_iter().map(Operator).collect())
+    }
+
+    pub fn name(&self) -> TractResult<Option<&'a str>> {
+        self.0.string(4)
+    }
+}
+
+impl<'a> TensorDesc<'a> {
+    pub fn shape(&self) -> TractResult<Vec<i32>> {
+        self.0.vector(0)
+    }
+
+    pub fn tensor_type(&self) -> TractResult<u8> {
+        self.0.scalar(1, 0)
+    }
+
+    pub fn buffer(&self) -> TractResult<u32> {
+        self.0.scalar(2, 0)
+    }
+
+    pub fn name(&self) -> TractResult<Option<&'a str>> {
+        self.0.string(3)
+    }
+
+    pub fn quantization(&self) -> TractResult<Option<QuantizationParameters<'a>>> {
+        Ok(self.0.table(4)?.map(QuantizationParameters))
+    }
+
+    pub fn is_variable(&self) -> TractResult<bool> {
+        self.0.scalar(5, false)
+    }
+
+    /// The shape, with -1 for unknown dimensions.
+    pub fn shape_signature(&self) -> TractResult<Vec<i32>> {
+        self.0.vector(7)
+    }
+}
+
+impl<'a> QuantizationParameters<'a> {
+    pub fn scale(&self) -> TractResult<Vec<f32>> {
+        self.0.vector(2)
+    }
+
+    pub fn zero_point(&self) -> TractResult<Vec<i64>> {
+        self.0.vector(3)
+    }
+
+    pub fn quantized_dimension(&self) -> TractResult<i32> {
+        self.0.scalar(6, 0)
+    }
+}
+
+impl<'a> Operator<'a> {
+    pub fn opcode_index(&self) -> TractResult<u32> {
+        self.0.scalar(0, 0)
+    }
+
+    /// Input tensor indices, -1 marking an omitted optional input.
+    pub fn inputs(&self) -> TractResult<Vec<i32>> {
+        self.0.vector(1)
+    }
+
+    pub fn outputs(&self) -> TractResult<Vec<i32>> {
+        self.0.vector(2)
+    }
+
+    /// The BuiltinOptions table, whatever its type.
+    pub fn builtin_options(&self) -> TractResult<Option<Table<'a>>> {
+        self.0.table(4)
+    }
+
+    pub fn custom_options(&self) -> TractResult<&'a [u8]> {
+        self.0.bytes(5)
+    }
+}
+
+/// TensorType enum values.
+pub mod tensor_type {
+    pub const FLOAT32: u8 = 0;
+    pub const FLOAT16: u8 = 1;
+    pub const INT32: u8 = 2;
+    pub const UINT8: u8 = 3;
+    pub const INT64: u8 = 4;
+    pub const STRING: u8 = 5;
+    pub const BOOL: u8 = 6;
+    pub const INT16: u8 = 7;
+    pub const COMPLEX64: u8 = 8;
+    pub const INT8: u8 = 9;
+    pub const FLOAT64: u8 = 10;
+}
+
+pub fn datum_type(tensor_type: u8) -> TractResult<DatumType> {
+    use self::tensor_type::*;
+    Ok(match tensor_type {
+        FLOAT32 => f32::datum_type(),
+        FLOAT16 => f16::datum_type(),
+        INT32 => i32::datum_type(),
+        UINT8 => u8::datum_type(),
+        INT64 => i64::datum_type(),
+        STRING => String::datum_type(),
+        BOOL => bool::datum_type(),
+        INT16 => i16::datum_type(),
+        INT8 => i8::datum_type(),
+        FLOAT64 => f64::datum_type(),
+        _ => bail!("Unsupported tensor type {}", tensor_type),
+    })
+}
+
+/// Fused activation function, as found in many operator options.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Activation {
+    None,
+    Relu,
+    ReluN1To1,
+    Relu6,
+    Tanh,
+}
+
+impl Activation {
+    pub fn from_code(code: i8) -> TractResult<Activation> {
+        Ok(match code {
+            0 => Activation::None,
+            1 => Activation::Relu,
+            2 => Activation::ReluN1To1,
+            3 => Activation::Relu6,
+            4 => Activation::Tanh,
+            _ => bail!("Unsupported fused activation function {}", code),
+        })
+    }
+}
+
+/// BuiltinOperator enum values.
+pub mod builtin {
+    pub const ADD: i32 = 0;
+    pub const AVERAGE_POOL_2D: i32 = 1;
+    pub const CONCATENATION: i32 = 2;
+    pub const CONV_2D: i32 = 3;
+    pub const DEPTHWISE_CONV_2D: i32 = 4;
+    pub const DEPTH_TO_SPACE: i32 = 5;
+    pub const DEQUANTIZE: i32 = 6;
+    pub const EMBEDDING_LOOKUP: i32 = 7;
+    pub const FLOOR: i32 = 8;
+    pub const FULLY_CONNECTED: i32 = 9;
+    pub const HASHTABLE_LOOKUP: i32 = 10;
+    pub const L2_NORMALIZATION: i32 = 11;
+    pub const L2_POOL_2D: i32 = 12;
+    pub const LOCAL_RESPONSE_NORMALIZATION: i32 = 13;
+    pub const LOGISTIC: i32 = 14;
+    pub const LSH_PROJECTION: i32 = 15;
+    pub const LSTM: i32 = 16;
+    pub const MAX_POOL_2D: i32 = 17;
+    pub const MUL: i32 = 18;
+    pub const RELU: i32 = 19;
+    pub const RELU_N1_TO_1: i32 = 20;
+    pub const RELU6: i32 = 21;
+    pub const RESHAPE: i32 = 22;
+    pub const RESIZE_BILINEAR: i32 = 23;
+    pub const RNN: i32 = 24;
+    pub const SOFTMAX: i32 = 25;
+    pub const SPACE_TO_DEPTH: i32 = 26;
+    pub const SVDF: i32 = 27;
+    pub const TANH: i32 = 28;
+    pub const CONCAT_EMBEDDINGS: i32 = 29;
+    pub const SKIP_GRAM: i32 = 30;
+    pub const CALL: i32 = 31;
+    pub const CUSTOM: i32 = 32;
+    pub const EMBEDDING_LOOKUP_SPARSE: i32 = 33;
+    pub const PAD: i32 = 34;
+    pub const UNIDIRECTIONAL_SEQUENCE_RNN: i32 = 35;
+    pub const GATHER: i32 = 36;
+    pub const BATCH_TO_SPACE_ND: i32 = 37;
+    pub const SPACE_TO_BATCH_ND: i32 = 38;
+    pub const TRANSPOSE: i32 = 39;
+    pub const MEAN: i32 = 40;
+    pub const SUB: i32 = 41;
+    pub const DIV: i32 = 42;
+    pub const SQUEEZE: i32 = 43;
+    pub const UNIDIRECTIONAL_SEQUENCE_LSTM: i32 = 44;
+    pub const STRIDED_SLICE: i32 = 45;
+    pub const BIDIRECTIONAL_SEQUENCE_RNN: i32 = 46;
+    pub const EXP: i32 = 47;
+    pub const TOPK_V2: i32 = 48;
+    pub const SPLIT: i32 = 49;
+    pub const LOG_SOFTMAX: i32 = 50;
+    pub const DELEGATE: i32 = 51;
+    pub const BIDIRECTIONAL_SEQUENCE_LSTM: i32 = 52;
+    pub const CAST: i32 = 53;
+    pub const PRELU: i32 = 54;
+    pub const MAXIMUM: i32 = 55;
+    pub const ARG_MAX: i32 = 56;
+    pub const MINIMUM: i32 = 57;
+    pub const LESS: i32 = 58;
+    pub const NEG: i32 = 59;
+    pub const PADV2: i32 = 60;
+    pub const GREATER: i32 = 61;
+    pub const GREATER_EQUAL: i32 = 62;
+    pub const LESS_EQUAL: i32 = 63;
+    pub const SELECT: i32 = 64;
+    pub const SLICE: i32 = 65;
+    pub const SIN: i32 = 66;
+    pub const TRANSPOSE_CONV: i32 = 67;
+    pub const SPARSE_TO_DENSE: i32 = 68;
+    pub const TILE: i32 = 69;
+    pub const EXPAND_DIMS: i32 = 70;
+    pub const EQUAL: i32 = 71;
+    pub const NOT_EQUAL: i32 = 72;
+    pub const LOG: i32 = 73;
+    pub const SUM: i32 = 74;
+    pub const SQRT: i32 = 75;
+    pub const RSQRT: i32 = 76;
+    pub const SHAPE: i32 = 77;
+    pub const POW: i32 = 78;
+    pub const ARG_MIN: i32 = 79;
+    pub const FAKE_QUANT: i32 = 80;
+    pub const REDUCE_PROD: i32 = 81;
+    pub const REDUCE_MAX: i32 = 82;
+    pub const PACK: i32 = 83;
+    pub const LOGICAL_OR: i32 = 84;
+    pub const ONE_HOT: i32 = 85;
+    pub const LOGICAL_AND: i32 = 86;
+    pub const LOGICAL_NOT: i32 = 87;
+    pub const UNPACK: i32 = 88;
+    pub const REDUCE_MIN: i32 = 89;
+    pub const FLOOR_DIV: i32 = 90;
+    pub const REDUCE_ANY: i32 = 91;
+    pub const SQUARE: i32 = 92;
+    pub const ZEROS_LIKE: i32 = 93;
+    pub const FILL: i32 = 94;
+    pub const FLOOR_MOD: i32 = 95;
+    pub const RANGE: i32 = 96;
+    pub const RESIZE_NEAREST_NEIGHBOR: i32 = 97;
+    pub const LEAKY_RELU: i32 = 98;
+    pub const SQUARED_DIFFERENCE: i32 = 99;
+    pub const MIRROR_PAD: i32 = 100;
+    pub const ABS: i32 = 101;
+    pub const SPLIT_V: i32 = 102;
+    pub const UNIQUE: i32 = 103;
+    pub const CEIL: i32 = 104;
+    pub const REVERSE_V2: i32 = 105;
+    pub const ADD_N: i32 = 106;
+    pub const GATHER_ND: i32 = 107;
+    pub const COS: i32 = 108;
+    pub const WHERE: i32 = 109;
+    pub const RANK: i32 = 110;
+    pub const ELU: i32 = 111;
+    pub const REVERSE_SEQUENCE: i32 = 112;
+    pub const MATRIX_DIAG: i32 = 113;
+    pub const QUANTIZE: i32 = 114;
+    pub const MATRIX_SET_DIAG: i32 = 115;
+    pub const ROUND: i32 = 116;
+    pub const HARD_SWISH: i32 = 117;
+}
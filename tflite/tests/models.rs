@@ -0,0 +1,129 @@
+extern crate tract_core;
+extern crate tract_tflite;
+
+mod utils;
+
+use tract_core::ndarray::*;
+use tract_core::prelude::*;
+use tract_tflite::schema::builtin::*;
+use tract_tflite::schema::tensor_type::*;
+
+use utils::*;
+
+fn load(builder: ModelBuilder) -> InferenceModel {
+    let buf = builder.finish();
+    tract_tflite::tflite().model_for_read(&mut &*buf).unwrap()
+}
+
+/// Run the model, both as is and optimized.
+fn run(model: InferenceModel, input: Tensor) -> Vec<Arc<Tensor>> {
+    let plain = SimplePlan::new(&model).unwrap().run(tvec!(input.clone())).unwrap();
+    let optimized = model.into_optimized().unwrap();
+    let opt = SimplePlan::new(&optimized).unwrap().run(tvec!(input)).unwrap();
+    assert_eq!(plain, opt);
+    plain.into_vec()
+}
+
+#[test]
+fn conv_relu_add() {
+    let mut b = ModelBuilder::new();
+    let x = b.input("x", FLOAT32, &[1, 3, 3, 1], None);
+    // OHWI: first filter sums a 2x2 window, second one negates it
+    let k: Vec<f32> = vec![1.0, 1.0, 1.0, 1.0, -1.0, -1.0, -1.0, -1.0];
+    let k = b.konst("k", FLOAT32, &[2, 2, 2, 1], &k, None);
+    let bias = b.konst("bias", FLOAT32, &[2], &[0.5f32, 0.5], None);
+    let conv = b.tensor("conv", FLOAT32, &[1, 2, 2, 2], None, None);
+    // valid padding, strides 1, relu
+    b.operator(
+        CONV_2D,
+        &[x, k, bias],
+        &[conv],
+        vec![scalar(1i8), scalar(1i32), scalar(1i32), scalar(1i8)],
+    );
+    let one = b.konst("one", FLOAT32, &[], &[1.0f32], None);
+    let y = b.tensor("y", FLOAT32, &[1, 2, 2, 2], None, None);
+    b.operator(ADD, &[conv, one], &[y], vec![]);
+    b.output(y);
+    let input = Array::from_shape_vec((1, 3, 3, 1), (0..9).map(|x| x as f32).collect()).unwrap();
+    let outputs = run(load(b), input.into_tensor());
+    let expected = arr4(&[[[[9.5f32, 1.0], [13.5, 1.0]], [[21.5, 1.0], [25.5, 1.0]]]]);
+    assert_eq!(*outputs[0], expected.into_tensor());
+}
+
+#[test]
+fn quantized_fully_connected() {
+    let mut b = ModelBuilder::new();
+    let x = b.input("x", UINT8, &[1, 2], Some((0.5, 128)));
+    let w = b.konst("w", UINT8, &[1, 2], &[132u8, 134], Some((0.5, 128)));
+    let bias = b.konst("bias", INT32, &[1], &[4i32], Some((0.25, 0)));
+    let y = b.tensor("y", UINT8, &[1, 1], None, Some((1.0, 10)));
+    b.operator(FULLY_CONNECTED, &[x, w, bias], &[y], vec![]);
+    b.output(y);
+    // x = [1, 2], w = [2, 3], bias = 1
+    let outputs = run(load(b), tensor2(&[[130u8, 132]]));
+    assert_eq!(*outputs[0], tensor2(&[[19u8]]));
+}
+
+#[test]
+fn quantized_conv() {
+    let mut b = ModelBuilder::new();
+    let x = b.input("x", UINT8, &[1, 2, 2, 1], Some((0.5, 100)));
+    let k = b.konst("k", UINT8, &[1, 2, 2, 1], &[11u8, 12, 13, 14], Some((0.25, 10)));
+    let y = b.tensor("y", UINT8, &[1, 1, 1, 1], None, Some((0.125, 0)));
+    b.operator(CONV_2D, &[x, k, -1], &[y], vec![scalar(1i8)]);
+    b.output(y);
+    // x = [0.5, 1, 1.5, 2], k = [0.25, 0.5, 0.75, 1] => 3.75
+    let outputs = run(load(b), tensor4(&[[[[101u8], [102]], [[103], [104]]]]));
+    assert_eq!(*outputs[0], tensor4(&[[[[30u8]]]]));
+}
+
+#[test]
+fn per_channel_quantized_conv() {
+    let mut b = ModelBuilder::new();
+    let x = b.input("x", INT8, &[1, 2, 2, 1], Some((0.5, 0)));
+    let k = b.konst("k", INT8, &[2, 2, 2, 1], &[1i8, 2, 3, 4, 1, 1, 1, 1], None);
+    b.per_axis_quant(k, 0, &[(0.25, 0), (0.5, 0)]);
+    let bias = b.konst("bias", INT32, &[2], &[8i32, 4], None);
+    b.per_axis_quant(bias, 0, &[(0.125, 0), (0.25, 0)]);
+    let y = b.tensor("y", INT8, &[1, 1, 1, 2], None, Some((0.125, 0)));
+    b.operator(CONV_2D, &[x, k, bias], &[y], vec![scalar(1i8)]);
+    b.output(y);
+    // x = [0.5, 1, 1.5, 2], k = [[0.25, 0.5, 0.75, 1], [0.5; 4]], bias = [1, 1]
+    // => [4.75, 3.5]
+    let outputs = run(load(b), tensor4(&[[[[1i8], [2]], [[3], [4]]]]));
+    assert_eq!(*outputs[0], tensor4(&[[[[38i8, 28]]]]));
+}
+
+#[test]
+fn sequence_lstm() {
+    let mut b = ModelBuilder::new();
+    let x = b.input("x", FLOAT32, &[2, 1, 1], None);
+    let zero = b.konst("zero", FLOAT32, &[1, 1], &[0.0f32], None);
+    let biases: Vec<i32> = [0.5f32, 1.0, 2.0, -1.0]
+        .iter()
+        .enumerate()
+        .map(|(ix, &v)| b.konst(&format!("bias-{}", ix), FLOAT32, &[1], &[v], None))
+        .collect();
+    let h = b.variable("h", &[1, 1]);
+    let c = b.variable("c", &[1, 1]);
+    let y = b.tensor("y", FLOAT32, &[2, 1, 1], None, None);
+    let mut inputs = vec![x, zero, zero, zero, zero, zero, zero, zero, zero, -1, -1, -1];
+    inputs.extend(biases);
+    inputs.extend(&[-1, -1, h, c]);
+    // tanh activation, time major
+    b.operator(
+        UNIDIRECTIONAL_SEQUENCE_LSTM,
+        &inputs,
+        &[y],
+        vec![scalar(4i8), None, None, scalar(true)],
+    );
+    b.output(y);
+    let outputs = run(load(b), tensor3(&[[[3.0f32]], [[4.0]]]));
+    // with null weights, the gates only depend on the biases
+    let sigmoid = |x: f32| 1.0 / (1.0 + (-x).exp());
+    let (i, f, g, o) = (sigmoid(0.5), sigmoid(1.0), 2.0f32.tanh(), sigmoid(-1.0));
+    let c1 = i * g;
+    let c2 = f * c1 + i * g;
+    let expected = tensor3(&[[[o * c1.tanh()]], [[o * c2.tanh()]]]);
+    outputs[0].close_enough(&expected, true).unwrap();
+}
@@ -0,0 +1,268 @@
+#![allow(dead_code)]
+
+//! A naive flatbuffer writer, building TFLite models for the tests.
+//!
+//! Parents are written before their children, so that all offsets point
+//! forward as in the buffers written by the flatbuffers library.
+
+pub enum Value {
+    Scalar(Vec<u8>),
+    Table(Vec<Option<Value>>),
+    /// The bytes of the elements, and the size of an element.
+    Vector(Vec<u8>, usize),
+    Tables(Vec<Value>),
+    String(String),
+}
+
+pub fn scalar<T: Copy>(v: T) -> Option<Value> {
+    let bytes = unsafe {
+        std::slice::from_raw_parts(&v as *const T as *const u8, std::mem::size_of::<T>())
+    };
+    Some(Value::Scalar(bytes.to_vec()))
+}
+
+pub fn vector<T: Copy>(v: &[T]) -> Option<Value> {
+    let bytes = unsafe {
+        std::slice::from_raw_parts(v.as_ptr() as *const u8, v.len() * std::mem::size_of::<T>())
+    };
+    Some(Value::Vector(bytes.to_vec(), std::mem::size_of::<T>()))
+}
+
+fn align(buf: &mut Vec<u8>, n: usize) {
+    while buf.len() % n != 0 {
+        buf.push(0)
+    }
+}
+
+fn write(buf: &mut Vec<u8>, value: &Value) -> usize {
+    match value {
+        Value::Scalar(_) => panic!("scalars are stored inline"),
+        Value::Table(fields) => {
+            align(buf, 2);
+            let vtable = buf.len();
+            buf.extend(&(4 + 2 * fields.len() as u16).to_le_bytes());
+            buf.resize(vtable + 4 + 2 * fields.len(), 0);
+            align(buf, 8);
+            let table = buf.len();
+            buf.extend(&((table - vtable) as i32).to_le_bytes());
+            let mut children = vec![];
+            for (slot, field) in fields.iter().enumerate() {
+                let field = if let Some(field) = field { field } else { continue };
+                let pos = buf.len();
+                match field {
+                    Value::Scalar(bytes) => buf.extend(bytes),
+                    child => {
+                        buf.extend(&[0u8; 4]);
+                        children.push((pos, child));
+                    }
+                }
+                let offset = ((pos - table) as u16).to_le_bytes();
+                buf[vtable + 4 + 2 * slot..][..2].copy_from_slice(&offset);
+            }
+            let len = ((buf.len() - table) as u16).to_le_bytes();
+            buf[vtable + 2..][..2].copy_from_slice(&len);
+            for (pos, child) in children {
+                let child = write(buf, child);
+                buf[pos..][..4].copy_from_slice(&((child - pos) as u32).to_le_bytes());
+            }
+            table
+        }
+        Value::Vector(bytes, elem) => {
+            // keep 8 bytes elements aligned after the length
+            align(buf, 8);
+            buf.extend(&[0u8; 4]);
+            let pos = buf.len();
+            buf.extend(&((bytes.len() / elem) as u32).to_le_bytes());
+            buf.extend(bytes);
+            pos
+        }
+        Value::Tables(tables) => {
+            align(buf, 4);
+            let pos = buf.len();
+            buf.extend(&(tables.len() as u32).to_le_bytes());
+            let slots = buf.len();
+            buf.resize(slots + 4 * tables.len(), 0);
+            for (ix, table) in tables.iter().enumerate() {
+                let slot = slots + 4 * ix;
+                let child = write(buf, table);
+                buf[slot..][..4].copy_from_slice(&((child - slot) as u32).to_le_bytes());
+            }
+            pos
+        }
+        Value::String(s) => {
+            align(buf, 4);
+            let pos = buf.len();
+            buf.extend(&(s.len() as u32).to_le_bytes());
+            buf.extend(s.as_bytes());
+            buf.push(0);
+            pos
+        }
+    }
+}
+
+/// A single subgraph TFLite model.
+#[derive(Default)]
+pub struct ModelBuilder {
+    codes: Vec<i32>,
+    buffers: Vec<Vec<u8>>,
+    tensors: Vec<Value>,
+    operators: Vec<Value>,
+    inputs: Vec<i32>,
+    outputs: Vec<i32>,
+}
+
+impl ModelBuilder {
+    pub fn new() -> ModelBuilder {
+        ModelBuilder { buffers: vec![vec![]], ..ModelBuilder::default() }
+    }
+
+    /// Add a tensor, with data if it is a constant, and (scale, zero point)
+    /// if it is quantized.
+    pub fn tensor(
+        &mut self,
+        name: &str,
+        tensor_type: u8,
+        shape: &[i32],
+        data: Option<Vec<u8>>,
+        quant: Option<(f32, i64)>,
+    ) -> i32 {
+        self.tensor_desc(name, tensor_type, shape, data, quant, false)
+    }
+
+    /// A variable tensor, zero initialized.
+    pub fn variable(&mut self, name: &str, shape: &[i32]) -> i32 {
+        self.tensor_desc(name, 0, shape, None, None, true)
+    }
+
+    fn tensor_desc(
+        &mut self,
+        name: &str,
+        tensor_type: u8,
+        shape: &[i32],
+        data: Option<Vec<u8>>,
+        quant: Option<(f32, i64)>,
+        variable: bool,
+    ) -> i32 {
+        let buffer = if let Some(data) = data {
+            self.buffers.push(data);
+            self.buffers.len() as u32 - 1
+        } else {
+            0
+        };
+        let quant = quant
+            .map(|(scale, zp)| Value::Table(vec![None, None, vector(&[scale]), vector(&[zp])]));
+        self.tensors.push(Value::Table(vec![
+            vector(shape),
+            scalar(tensor_type),
+            scalar(buffer),
+            Some(Value::String(name.to_string())),
+            quant,
+            scalar(variable),
+        ]));
+        self.tensors.len() as i32 - 1
+    }
+
+    /// Quantize tensor `t` per axis, with a (scale, zero point) for each
+    /// slice along `axis`.
+    pub fn per_axis_quant(&mut self, t: i32, axis: i32, quant: &[(f32, i64)]) {
+        let scales: Vec<f32> = quant.iter().map(|q| q.0).collect();
+        let zero_points: Vec<i64> = quant.iter().map(|q| q.1).collect();
+        if let Value::Table(ref mut fields) = self.tensors[t as usize] {
+            fields[4] = Some(Value::Table(vec![
+                None,
+                None,
+                vector(&scales),
+                vector(&zero_points),
+                None,
+                None,
+                scalar(axis),
+            ]));
+        }
+    }
+
+    pub fn input(
+        &mut self,
+        name: &str,
+        tensor_type: u8,
+        shape: &[i32],
+        quant: Option<(f32, i64)>,
+    ) -> i32 {
+        let t = self.tensor(name, tensor_type, shape, None, quant);
+        self.inputs.push(t);
+        t
+    }
+
+    pub fn konst<T: Copy>(
+        &mut self,
+        name: &str,
+        tensor_type: u8,
+        shape: &[i32],
+        data: &[T],
+        quant: Option<(f32, i64)>,
+    ) -> i32 {
+        let bytes = unsafe {
+            std::slice::from_raw_parts(
+                data.as_ptr() as *const u8,
+                data.len() * std::mem::size_of::<T>(),
+            )
+        };
+        self.tensor(name, tensor_type, shape, Some(bytes.to_vec()), quant)
+    }
+
+    pub fn output(&mut self, t: i32) {
+        self.outputs.push(t)
+    }
+
+    pub fn operator(
+        &mut self,
+        code: i32,
+        inputs: &[i32],
+        outputs: &[i32],
+        options: Vec<Option<Value>>,
+    ) {
+        let opcode = match self.codes.iter().position(|&c| c == code) {
+            Some(ix) => ix,
+            None => {
+                self.codes.push(code);
+                self.codes.len() - 1
+            }
+        };
+        self.operators.push(Value::Table(vec![
+            scalar(opcode as u32),
+            vector(inputs),
+            vector(outputs),
+            None,
+            Some(Value::Table(options)),
+        ]));
+    }
+
+    pub fn finish(self) -> Vec<u8> {
+        let codes = self
+            .codes
+            .iter()
+            .map(|&c| {
+                let deprecated = if c < 127 { c as i8 } else { 127 };
+                Value::Table(vec![scalar(deprecated), None, scalar(1i32), scalar(c)])
+            })
+            .collect();
+        let graph = Value::Table(vec![
+            Some(Value::Tables(self.tensors)),
+            vector(&self.inputs),
+            vector(&self.outputs),
+            Some(Value::Tables(self.operators)),
+        ]);
+        let buffers = self.buffers.iter().map(|b| Value::Table(vec![vector(&b[..])])).collect();
+        let model = Value::Table(vec![
+            scalar(3u32),
+            Some(Value::Tables(codes)),
+            Some(Value::Tables(vec![graph])),
+            None,
+            Some(Value::Tables(buffers)),
+        ]);
+        let mut buf = vec![0u8; 4];
+        buf.extend(b"TFL3");
+        let root = write(&mut buf, &model);
+        buf[0..4].copy_from_slice(&(root as u32).to_le_bytes());
+        buf
+    }
+}